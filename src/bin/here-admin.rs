@@ -0,0 +1,374 @@
+//! Operator tooling that works directly against the database.
+//!
+//! Uses the same configuration as the server (`AppConfig::from_env`) and the
+//! services layer, so the rules are the same as for the HTTP API. Pass
+//! `--json` to any command for machine-readable output.
+//!
+//! ```bash
+//! cargo run --bin here-admin -- create-admin --username root --email root@example.com
+//! cargo run --bin here-admin -- --json upcoming-events --limit 5
+//! cargo run --bin here-admin -- export --output backup.json
+//! cargo run --bin here-admin -- config check
+//! ```
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+use here::core::cache::Cache;
+use here::core::configs::AppConfig;
+use here::core::startup::{check_migrations, connect_database, create_redis_pool};
+use here::entity::AccountType;
+use here::entity::prelude::*;
+use here::schemas::user::SignUp;
+use here::services::events::list_upcoming_events;
+use here::services::export::{DataExport, export_data, import_data};
+use here::services::reference_data::seed_reference_data;
+use here::services::users::{
+    create_user, find_user_by_identifier, promote_to_host, set_password, set_user_active,
+    set_user_admin,
+};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use validator::Validate;
+
+#[derive(Parser)]
+#[command(name = "here-admin", about = "Administer a Here backend database")]
+struct Cli {
+    /// Print results as JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a user with admin rights
+    CreateAdmin {
+        #[arg(long)]
+        username: String,
+        #[arg(long)]
+        email: String,
+        /// Read from HERE_ADMIN_PASSWORD when not given
+        #[arg(long, env = "HERE_ADMIN_PASSWORD", hide_env_values = true)]
+        password: String,
+        #[arg(long)]
+        first_name: Option<String>,
+        #[arg(long)]
+        last_name: Option<String>,
+    },
+    /// Set a new password and revoke the user's sessions
+    ResetPassword {
+        /// Username or email
+        user: String,
+        #[arg(long, env = "HERE_ADMIN_PASSWORD", hide_env_values = true)]
+        password: String,
+    },
+    /// Block a user from logging in and revoke their sessions
+    Deactivate {
+        /// Username or email
+        user: String,
+    },
+    /// Allow a deactivated user to log in again
+    Activate {
+        /// Username or email
+        user: String,
+    },
+    /// Turn a user into a host
+    PromoteHost {
+        /// Username or email
+        user: String,
+        #[arg(long)]
+        organization: Option<String>,
+    },
+    /// Insert missing event category and motivation rows
+    Seed,
+    /// List events that have not started yet
+    UpcomingEvents {
+        #[arg(long, default_value_t = 20)]
+        limit: u64,
+    },
+    /// Dump all domain data as JSON (sessions and API keys are not included)
+    Export {
+        /// Write to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Load a JSON export into an empty database
+    Import {
+        /// Export file, or `-` for stdin
+        input: PathBuf,
+    },
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Validate the configuration and print the effective values, secrets redacted
+    Check,
+}
+
+/// What the commands print about a user; never includes the password hash.
+#[derive(Serialize)]
+struct UserSummary {
+    id: i32,
+    username: String,
+    email: String,
+    account_type: AccountType,
+    is_active: bool,
+    is_admin: bool,
+}
+
+impl From<UserModel> for UserSummary {
+    fn from(user: UserModel) -> Self {
+        UserSummary {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            account_type: user.account_type,
+            is_active: user.is_active,
+            is_admin: user.is_admin,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct EventSummary {
+    id: i32,
+    title: String,
+    host_id: i32,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let settings = match AppConfig::from_env() {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("Failed to load configuration: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    // Needs no database, so it works before one is reachable.
+    if let Command::Config {
+        action: ConfigCommand::Check,
+    } = cli.command
+    {
+        return match print_config(cli.json, &settings) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    let db = match connect_database(&settings).await {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("Failed to connect to the database: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = check_migrations(&db, false).await {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
+    }
+
+    // Writes go through the same invalidation as the API so the server's
+    // cache never serves users or events changed here
+    let cache = if settings.cache_enabled {
+        match create_redis_pool(&settings) {
+            Ok(pool) => Cache::new(pool, None),
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        Cache::disabled()
+    };
+
+    match run(cli, &db, &cache, &settings).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(
+    cli: Cli,
+    db: &DatabaseConnection,
+    cache: &Cache,
+    settings: &AppConfig,
+) -> Result<(), Box<dyn Error>> {
+    let json = cli.json;
+
+    match cli.command {
+        Command::CreateAdmin {
+            username,
+            email,
+            password,
+            first_name,
+            last_name,
+        } => {
+            let signup = SignUp {
+                username,
+                first_name,
+                last_name,
+                email,
+                avatar_url: None,
+                password,
+            };
+            signup.validate()?;
+            let created = create_user(db, signup, settings.hash_rounds).await?;
+            let user = set_user_admin(db, cache, created.id, true).await?;
+            print_user(json, "Created admin", user)
+        }
+        Command::ResetPassword { user, password } => {
+            if password.len() < 8 {
+                return Err("Password must be at least 8 characters".into());
+            }
+            let user = find_user_by_identifier(db, &user).await?;
+            let user = set_password(db, cache, user.id, &password, settings.hash_rounds).await?;
+            print_user(json, "Reset password for", user)
+        }
+        Command::Deactivate { user } => {
+            let user = find_user_by_identifier(db, &user).await?;
+            let user = set_user_active(db, cache, user.id, false).await?;
+            print_user(json, "Deactivated", user)
+        }
+        Command::Activate { user } => {
+            let user = find_user_by_identifier(db, &user).await?;
+            let user = set_user_active(db, cache, user.id, true).await?;
+            print_user(json, "Activated", user)
+        }
+        Command::PromoteHost { user, organization } => {
+            let user = find_user_by_identifier(db, &user).await?;
+            let host = promote_to_host(db, cache, user.id, organization).await?;
+            if json {
+                print_json(&host)
+            } else {
+                println!("Promoted {} (id {}) to host", user.username, user.id);
+                Ok(())
+            }
+        }
+        Command::Seed => {
+            let report = seed_reference_data(db, cache).await?;
+            if json {
+                print_json(&report)
+            } else {
+                println!(
+                    "Inserted {} event categories and {} motivations",
+                    report.event_categories.len(),
+                    report.motivations.len()
+                );
+                Ok(())
+            }
+        }
+        Command::UpcomingEvents { limit } => {
+            let events: Vec<EventSummary> = list_upcoming_events(db, limit)
+                .await?
+                .into_iter()
+                .map(|event| EventSummary {
+                    id: event.id,
+                    title: event.title,
+                    host_id: event.host_id,
+                    start_time: event.start_time,
+                    end_time: event.end_time,
+                })
+                .collect();
+            if json {
+                return print_json(&events);
+            }
+            if events.is_empty() {
+                println!("No upcoming events");
+            }
+            for event in events {
+                println!(
+                    "{:>6}  {}  {}  (host {})",
+                    event.id,
+                    event.start_time.format("%Y-%m-%d %H:%M UTC"),
+                    event.title,
+                    event.host_id
+                );
+            }
+            Ok(())
+        }
+        Command::Export { output } => {
+            let data = export_data(db).await?;
+            let body = serde_json::to_string_pretty(&data)?;
+            match output {
+                Some(path) => {
+                    fs::write(&path, body)?;
+                    eprintln!("Exported {} users to {}", data.users.len(), path.display());
+                }
+                None => println!("{}", body),
+            }
+            Ok(())
+        }
+        Command::Import { input } => {
+            let body = if input.as_os_str() == "-" {
+                let mut body = String::new();
+                io::stdin().read_to_string(&mut body)?;
+                body
+            } else {
+                fs::read_to_string(&input)?
+            };
+            let data: DataExport = serde_json::from_str(&body)?;
+            let report = import_data(db, cache, data).await?;
+            if json {
+                print_json(&report)
+            } else {
+                println!(
+                    "Imported {} users, {} events and {} attendance records",
+                    report.users, report.events, report.attendance
+                );
+                Ok(())
+            }
+        }
+        Command::Config { .. } => unreachable!("handled before connecting to the database"),
+    }
+}
+
+fn print_config(json: bool, settings: &AppConfig) -> Result<(), Box<dyn Error>> {
+    let entries = settings.redacted_entries();
+    if json {
+        let map: serde_json::Map<String, serde_json::Value> = entries
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.into()))
+            .collect();
+        return print_json(&map);
+    }
+    println!("Configuration is valid (profile: {})", settings.profile);
+    for (name, value) in entries {
+        println!("{} = {}", name, value);
+    }
+    Ok(())
+}
+
+fn print_user(json: bool, action: &str, user: UserModel) -> Result<(), Box<dyn Error>> {
+    if json {
+        return print_json(&UserSummary::from(user));
+    }
+    println!("{} {} (id {})", action, user.username, user.id);
+    Ok(())
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//! Apply, roll back and inspect database migrations.
+//!
+//! Only `HERE_DATABASE_URL` is needed (from the environment or `.env`):
+//!
+//! ```bash
+//! cargo run --bin here-migrate -- status
+//! cargo run --bin here-migrate -- up        # apply all pending
+//! cargo run --bin here-migrate -- up 1      # apply the next one
+//! cargo run --bin here-migrate -- down      # roll back the latest
+//! cargo run --bin here-migrate -- down 2    # roll back the latest two
+//! cargo run --bin here-migrate -- baseline  # adopt a schema from before migrations
+//! ```
+use std::process::ExitCode;
+
+use here::migration;
+use sea_orm::Database;
+
+const USAGE: &str = "usage: here-migrate <up [N] | down [N] | status | baseline>";
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    dotenv::dotenv().ok();
+    tracing_subscriber::fmt().with_target(false).init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = args.first().map(String::as_str);
+    let steps = match args.get(1).map(|n| n.parse::<usize>()) {
+        None => None,
+        Some(Ok(n)) => Some(n),
+        Some(Err(_)) => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Ok(database_url) = std::env::var("HERE_DATABASE_URL") else {
+        eprintln!("HERE_DATABASE_URL is not set");
+        return ExitCode::FAILURE;
+    };
+    let db = match Database::connect(&database_url).await {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("Failed to connect to the database: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = match command {
+        Some("up") => migration::up(&db, steps).await.map(|ran| {
+            if ran.is_empty() {
+                println!("Nothing to apply.");
+            }
+            for name in ran {
+                println!("Applied  {}", name);
+            }
+        }),
+        Some("down") => migration::down(&db, steps.unwrap_or(1))
+            .await
+            .map(|reverted| {
+                if reverted.is_empty() {
+                    println!("Nothing to roll back.");
+                }
+                for name in reverted {
+                    println!("Reverted {}", name);
+                }
+            }),
+        Some("baseline") => migration::baseline(&db).await.map(|adopted| {
+            if adopted.is_empty() {
+                println!("Nothing to baseline.");
+            }
+            for name in adopted {
+                println!("Recorded {}", name);
+            }
+        }),
+        Some("status") => migration::status(&db).await.map(|statuses| {
+            for status in statuses {
+                match status.applied_at {
+                    Some(at) => println!("Applied  {}  ({})", status.name, at),
+                    None => println!("Pending  {}", status.name),
+                }
+            }
+        }),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Migration failed: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Standalone server entrypoint that runs without the Shuttle runtime.
+//!
+//! Configuration comes from `AppConfig::from_env` (`.env`, `Secrets.toml`
+//! and `HERE_*` environment variables). `HERE_DATABASE_URL` may point at
+//! Postgres or SQLite, e.g. `sqlite://./here.db?mode=rwc`. Set
+//! `HERE_LOG_FORMAT=json` for structured logs. Background jobs run on a thread
+//! inside this process unless `HERE_JOBS_WORKER=false`, e.g. when
+//! `here-worker` runs separately.
+//!
+//! ```bash
+//! cargo run --bin here-server
+//! ```
+use actix_web::HttpServer;
+use here::core::configs::AppConfig;
+use here::core::startup::{build_app_state, check_migrations, connect_database};
+use here::core::telemetry::init_telemetry;
+use here::jobs::worker::spawn_worker;
+use tracing::info;
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    let settings = AppConfig::from_env().expect("Failed to load configuration");
+    let _telemetry = init_telemetry(&settings).expect("Failed to initialise logging");
+
+    let db = connect_database(&settings)
+        .await
+        .expect("Failed to connect to the database");
+    check_migrations(&db, settings.auto_migrate)
+        .await
+        .expect("Database is not migrated");
+
+    let bind_address = (settings.host.clone(), settings.port);
+    let shutdown_timeout = settings.shutdown_timeout_seconds;
+    let app_state = build_app_state(db, settings).expect("Failed to build application state");
+
+    let worker = if app_state.config.jobs_worker {
+        Some(spawn_worker(app_state.clone())?)
+    } else {
+        None
+    };
+
+    info!("Listening on {}:{}", bind_address.0, bind_address.1);
+
+    // actix-web stops accepting connections on SIGTERM/SIGINT and waits up to
+    // `shutdown_timeout` seconds for in-flight requests before exiting.
+    HttpServer::new(move || here::build_app(app_state.clone()))
+        .bind(bind_address)?
+        .shutdown_timeout(shutdown_timeout)
+        .run()
+        .await?;
+
+    // Let the worker finish its current job before exiting
+    if let Some(worker) = worker {
+        worker.stop();
+    }
+
+    info!("Server stopped.");
+    Ok(())
+}
@@ -0,0 +1,57 @@
+//! Standalone background job worker.
+//!
+//! Runs the same jobs as the worker built into `here-server`; use it to
+//! scale jobs separately and set `JOBS_WORKER=false` on the servers. Any
+//! number of workers may run against one Redis. On SIGTERM/SIGINT the job
+//! in progress is finished before exiting.
+//!
+//! ```bash
+//! cargo run --bin here-worker
+//! ```
+use here::core::configs::AppConfig;
+use here::core::startup::{build_app_state, check_migrations, connect_database};
+use here::core::telemetry::init_telemetry;
+use here::jobs::worker::run_worker;
+use tokio::signal;
+use tokio::sync::watch;
+use tracing::info;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let settings = AppConfig::from_env().expect("Failed to load configuration");
+    let _telemetry = init_telemetry(&settings).expect("Failed to initialise logging");
+
+    let db = connect_database(&settings)
+        .await
+        .expect("Failed to connect to the database");
+    // Never migrates: that is the servers' or here-migrate's job
+    check_migrations(&db, false)
+        .await
+        .expect("Database is not migrated");
+    let app_state = build_app_state(db, settings).expect("Failed to build application state");
+
+    let (shutdown, receiver) = watch::channel(false);
+    tokio::spawn(async move {
+        wait_for_signal().await;
+        info!("Shutdown requested, finishing the current job.");
+        let _ = shutdown.send(true);
+    });
+
+    run_worker(app_state, receiver).await;
+}
+
+async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        let mut terminate = signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM");
+        tokio::select! {
+            _ = signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = signal::ctrl_c().await;
+    }
+}
@@ -0,0 +1,358 @@
+//! Redis read-through cache.
+//!
+//! Values are stored as JSON under
+//! `here:cache:v<CACHE_VERSION>:<namespace>:<generation>:<id>`. Bumping
+//! [`CACHE_VERSION`] orphans every entry written by older builds, and
+//! [`Cache::invalidate_namespace`] bumps a namespace's generation to drop all
+//! of its entries at once (e.g. every cached event listing).
+//!
+//! Redis is an optimisation only: on any Redis error or timeout the value is
+//! loaded from the database, and Redis is skipped for a few seconds so a dead
+//! server does not add latency to every request.
+use std::error::Error;
+use std::future::Future;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use deadpool_redis::Pool as RedisPool;
+use rand::Rng;
+use redis::FromRedisValue;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::sync::Mutex;
+use tokio::time::{sleep, timeout};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::core::metrics::Metrics;
+
+/// Bump when the shape of any cached value changes.
+pub const CACHE_VERSION: u32 = 6;
+
+const KEY_PREFIX: &str = "here:cache";
+
+/// Upper bound for a single Redis round trip.
+const OPERATION_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// How long Redis is bypassed after it failed.
+const BYPASS_AFTER_ERROR: Duration = Duration::from_secs(5);
+
+/// Expiry of the cross-instance loader lock; bounds how long a crashed
+/// loader can hold up others.
+const LOCK_TTL: Duration = Duration::from_secs(5);
+
+/// How long a request waits for another instance's loader before loading
+/// the value itself.
+const LOCK_WAIT: Duration = Duration::from_millis(500);
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Number of in-process loader locks keys are spread over.
+const LOCK_STRIPES: usize = 64;
+
+/// Deletes the lock only if it still holds our token.
+const UNLOCK_SCRIPT: &str = "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) else return 0 end";
+
+/// A family of cached values sharing a TTL.
+#[derive(Debug, Clone, Copy)]
+pub struct Namespace {
+    pub name: &'static str,
+    pub ttl: Duration,
+}
+
+/// Users by id, without their password hash, for the `CurrentUser` extractor.
+pub const USERS: Namespace = Namespace {
+    name: "user",
+    ttl: Duration::from_secs(300),
+};
+
+/// Public event details by id.
+pub const EVENTS: Namespace = Namespace {
+    name: "event",
+    ttl: Duration::from_secs(300),
+};
+
+/// Public event listings by page; invalidated as a whole whenever any event changes.
+pub const EVENT_LISTS: Namespace = Namespace {
+    name: "event-list",
+    ttl: Duration::from_secs(60),
+};
+
+/// Event categories and motivations.
+pub const REFERENCE_DATA: Namespace = Namespace {
+    name: "reference",
+    ttl: Duration::from_secs(3600),
+};
+
+const ALL_NAMESPACES: [Namespace; 4] = [USERS, EVENTS, EVENT_LISTS, REFERENCE_DATA];
+
+/// Handle to the cache; cheap to clone. [`Cache::disabled`] always loads.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    inner: Option<Arc<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    pool: RedisPool,
+    metrics: Option<Arc<Metrics>>,
+    locks: Vec<Mutex<()>>,
+    /// Unix time in milliseconds until which Redis is skipped.
+    bypass_until: AtomicU64,
+}
+
+impl Cache {
+    pub fn new(pool: RedisPool, metrics: Option<Arc<Metrics>>) -> Self {
+        Cache {
+            inner: Some(Arc::new(Inner {
+                pool,
+                metrics,
+                locks: (0..LOCK_STRIPES).map(|_| Mutex::new(())).collect(),
+                bypass_until: AtomicU64::new(0),
+            })),
+        }
+    }
+
+    /// A cache that never stores anything, for tools and tests without Redis.
+    pub fn disabled() -> Self {
+        Cache { inner: None }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Return the cached value for `id`, or run `load` and cache its result.
+    ///
+    /// Concurrent misses for the same key are coalesced: within a process
+    /// through a striped lock, across instances through a short Redis lock,
+    /// so only one caller hits the database while the others wait for its
+    /// result. Errors from `load` are returned and never cached, and neither
+    /// is `None`, so rows created later show up immediately.
+    pub async fn get_or_load<T, F, Fut>(
+        &self,
+        namespace: Namespace,
+        id: &str,
+        load: F,
+    ) -> Result<T, Box<dyn Error>>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, Box<dyn Error>>>,
+    {
+        let Some(inner) = self.inner.as_deref().filter(|inner| inner.available()) else {
+            return load().await;
+        };
+
+        let key = match inner.key(namespace, id).await {
+            Ok(key) => key,
+            Err(e) => {
+                inner.failed(namespace, e);
+                return load().await;
+            }
+        };
+        match inner.get::<T>(&key).await {
+            Ok(Some(value)) => {
+                inner.record(namespace, "hit");
+                return Ok(value);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                inner.failed(namespace, e);
+                return load().await;
+            }
+        }
+
+        let _local = inner.stripe(&key).lock().await;
+        if let Ok(Some(value)) = inner.get::<T>(&key).await {
+            inner.record(namespace, "hit");
+            return Ok(value);
+        }
+
+        let lock_key = format!("{}:lock", key);
+        let token = Uuid::new_v4().to_string();
+        let locked = inner.try_lock(&lock_key, &token).await.unwrap_or(true);
+        if !locked && let Some(value) = inner.wait_for::<T>(&key).await {
+            inner.record(namespace, "hit");
+            return Ok(value);
+        }
+
+        inner.record(namespace, "miss");
+        let result = load().await;
+        if let Ok(value) = &result
+            && let Err(e) = inner.set(&key, value, namespace.ttl).await
+        {
+            inner.failed(namespace, e);
+        }
+        if locked {
+            let _ = inner
+                .query::<i64>(
+                    redis::cmd("EVAL")
+                        .arg(UNLOCK_SCRIPT)
+                        .arg(1)
+                        .arg(&lock_key)
+                        .arg(&token),
+                )
+                .await;
+        }
+        result
+    }
+
+    /// Drop the cached value for `id`. Failures are logged; the entry then
+    /// expires with its TTL.
+    pub async fn invalidate(&self, namespace: Namespace, id: &str) {
+        let Some(inner) = self.inner.as_deref() else {
+            return;
+        };
+        let result = match inner.key(namespace, id).await {
+            Ok(key) => inner.query::<i64>(redis::cmd("DEL").arg(&key)).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            warn!(
+                "Failed to invalidate {} cache entry {}: {}",
+                namespace.name, id, e
+            );
+        }
+    }
+
+    /// Drop every cached value in `namespace` by moving to a new generation.
+    pub async fn invalidate_namespace(&self, namespace: Namespace) {
+        let Some(inner) = self.inner.as_deref() else {
+            return;
+        };
+        if let Err(e) = inner
+            .query::<u64>(redis::cmd("INCR").arg(generation_key(namespace)))
+            .await
+        {
+            warn!("Failed to invalidate {} cache: {}", namespace.name, e);
+        }
+    }
+
+    /// Drop everything, e.g. after a bulk import.
+    pub async fn invalidate_all(&self) {
+        for namespace in ALL_NAMESPACES {
+            self.invalidate_namespace(namespace).await;
+        }
+    }
+}
+
+impl Inner {
+    fn available(&self) -> bool {
+        now_millis() >= self.bypass_until.load(Ordering::Relaxed)
+    }
+
+    fn failed(&self, namespace: Namespace, error: Box<dyn Error>) {
+        warn!(
+            "Redis cache unavailable ({}), reading {} from the database: {}",
+            namespace.name, namespace.name, error
+        );
+        self.record(namespace, "error");
+        self.bypass_until.store(
+            now_millis() + BYPASS_AFTER_ERROR.as_millis() as u64,
+            Ordering::Relaxed,
+        );
+    }
+
+    fn record(&self, namespace: Namespace, result: &str) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_cache(namespace.name, result);
+        }
+    }
+
+    fn stripe(&self, key: &str) -> &Mutex<()> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.locks[hasher.finish() as usize % self.locks.len()]
+    }
+
+    async fn query<T: FromRedisValue>(&self, cmd: &mut redis::Cmd) -> Result<T, Box<dyn Error>> {
+        let run = async {
+            let mut conn = self.pool.get().await?;
+            Ok::<T, Box<dyn Error>>(cmd.query_async(&mut conn).await?)
+        };
+        timeout(OPERATION_TIMEOUT, run)
+            .await
+            .map_err(|_| "Redis operation timed out")?
+    }
+
+    async fn key(&self, namespace: Namespace, id: &str) -> Result<String, Box<dyn Error>> {
+        let generation: Option<u64> = self
+            .query(redis::cmd("GET").arg(generation_key(namespace)))
+            .await?;
+        Ok(format!(
+            "{}:v{}:{}:{}:{}",
+            KEY_PREFIX,
+            CACHE_VERSION,
+            namespace.name,
+            generation.unwrap_or_default(),
+            id
+        ))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, Box<dyn Error>> {
+        let raw: Option<String> = self.query(redis::cmd("GET").arg(key)).await?;
+        // An entry that no longer deserializes is treated as a miss and overwritten
+        Ok(raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+    }
+
+    async fn set<T: Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+        ttl: Duration,
+    ) -> Result<(), Box<dyn Error>> {
+        let value = serde_json::to_string(value)?;
+        if value == "null" {
+            return Ok(());
+        }
+        // Up to 10% jitter so entries written together do not expire together
+        let ttl_ms = ttl.as_millis() as u64;
+        let ttl_ms = ttl_ms + rand::rng().random_range(0..=ttl_ms / 10);
+        self.query::<()>(redis::cmd("SET").arg(key).arg(value).arg("PX").arg(ttl_ms))
+            .await
+    }
+
+    async fn try_lock(&self, lock_key: &str, token: &str) -> Result<bool, Box<dyn Error>> {
+        let reply: Option<String> = self
+            .query(
+                redis::cmd("SET")
+                    .arg(lock_key)
+                    .arg(token)
+                    .arg("NX")
+                    .arg("PX")
+                    .arg(LOCK_TTL.as_millis() as u64),
+            )
+            .await?;
+        Ok(reply.is_some())
+    }
+
+    /// Poll for a value another instance is loading.
+    async fn wait_for<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let polls = LOCK_WAIT.as_millis() / LOCK_POLL_INTERVAL.as_millis();
+        for _ in 0..polls {
+            sleep(LOCK_POLL_INTERVAL).await;
+            match self.get::<T>(key).await {
+                Ok(Some(value)) => return Some(value),
+                Ok(None) => {}
+                Err(_) => return None,
+            }
+        }
+        None
+    }
+}
+
+fn generation_key(namespace: Namespace) -> String {
+    format!(
+        "{}:v{}:{}:generation",
+        KEY_PREFIX, CACHE_VERSION, namespace.name
+    )
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
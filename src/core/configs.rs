@@ -1,58 +1,335 @@
-use config::{Config, ConfigError, Environment, File, FileFormat};
+use chrono::{DateTime, Utc};
+use config::{Config, ConfigError, Environment, File, FileFormat, Map, Source, Value};
 use deadpool_redis::Pool as RedisPool;
 use sea_orm::DatabaseConnection;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
 use tracing::info;
+use url::Url;
+use validator::ValidateEmail;
 
-#[derive(Debug, Deserialize, Clone)]
+use crate::core::cache::Cache;
+use crate::core::domain_events::DomainEvents;
+use crate::core::metrics::Metrics;
+use crate::jobs::JobQueue;
+use crate::services::email::Mailer;
+use crate::services::payments::PaymentProvider;
+use crate::utils::jwt::{DEFAULT_KID, JwtKeys};
+use crate::utils::redact::{REDACTED, redact_url};
+
+#[derive(Deserialize, Clone)]
 pub struct AppConfig {
+    /// Name of the profile file that was layered in (`dev`, `test`, `prod`, ...)
+    #[serde(default = "default_profile")]
+    pub profile: String,
     pub secret_key: String,
+    #[serde(default = "default_hash_rounds")]
     pub hash_rounds: u32,
     pub redis_url: String,
     pub smtp_host: String,
+    #[serde(default = "default_smtp_port")]
     pub smtp_port: u16,
     pub smtp_username: String,
     pub smtp_password: String,
     pub smtp_from_email: String,
+    // `starttls` (default), `tls` for implicit TLS (port 465) or `none` for local catchers
+    #[serde(default = "default_smtp_tls")]
+    pub smtp_tls: String,
     pub database_url: String,
+    /// Debug-level logs unless `RUST_LOG` says otherwise; refused in `prod`
+    #[serde(default)]
     pub debug: bool,
+    // --- JWT signing ---
+    #[serde(default = "default_jwt_algorithm")]
+    pub jwt_algorithm: String,
+    #[serde(default = "default_jwt_kid")]
+    pub jwt_kid: String,
+    #[serde(default)]
+    pub jwt_private_key: Option<String>,
+    #[serde(default)]
+    pub jwt_public_keys: Option<String>,
+    #[serde(default)]
+    pub jwt_previous_secrets: Option<String>,
+    #[serde(default)]
+    pub jwt_issuer: Option<String>,
+    #[serde(default)]
+    pub jwt_audience: Option<String>,
+    #[serde(default = "default_jwt_leeway_seconds")]
+    pub jwt_leeway_seconds: u64,
+    // --- Standalone server (ignored under Shuttle) ---
+    #[serde(default = "default_host")]
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default = "default_shutdown_timeout_seconds")]
+    pub shutdown_timeout_seconds: u64,
+    // Apply pending migrations on startup instead of refusing to start
+    #[serde(default)]
+    pub auto_migrate: bool,
+    // Include SMTP reachability in `/health/ready` (never fails readiness)
+    #[serde(default)]
+    pub health_check_smtp: bool,
+    // Bearer token required by `/metrics`; the endpoint is open when unset
+    #[serde(default)]
+    pub metrics_token: Option<String>,
+    // --- Logging and tracing (standalone server) ---
+    #[serde(default = "default_log_format")]
+    pub log_format: String,
+    #[serde(default)]
+    pub otel_exporter_otlp_endpoint: Option<String>,
+    #[serde(default = "default_otel_service_name")]
+    pub otel_service_name: String,
+    // Base URL of the web app, used for links in emails
+    #[serde(default = "default_public_url")]
+    pub public_url: String,
+    // Base URL clients reach this API on, used in calendar links; defaults
+    // to `public_url`. Never taken from request headers, which anyone can set
+    #[serde(default)]
+    pub api_url: Option<String>,
+    // --- Background jobs ---
+    // Run the job worker inside the server process; turn off when running `here-worker`
+    #[serde(default = "default_jobs_worker")]
+    pub jobs_worker: bool,
+    #[serde(default = "default_job_max_attempts")]
+    pub job_max_attempts: u32,
+    // Cache hot reads in Redis; when false every read goes to the database
+    #[serde(default = "default_cache_enabled")]
+    pub cache_enabled: bool,
+    // --- HTTP hardening ---
+    // Comma-separated origins allowed to make cross-origin requests, or `*`;
+    // cross-origin requests are refused when unset
+    #[serde(default)]
+    pub cors_allowed_origins: Option<String>,
+    #[serde(default = "default_cors_allowed_methods")]
+    pub cors_allowed_methods: String,
+    #[serde(default)]
+    pub cors_allow_credentials: bool,
+    #[serde(default = "default_cors_max_age_seconds")]
+    pub cors_max_age_seconds: usize,
+    // `Strict-Transport-Security` max-age; 0 disables the header
+    #[serde(default = "default_hsts_max_age_seconds")]
+    pub hsts_max_age_seconds: u64,
+    // Largest accepted request body (JSON, forms and raw payloads)
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+    // --- Payments ---
+    // `fake` takes no money, so paid orders stay pending; `paystack` needs
+    // `PAYSTACK_SECRET_KEY`, which also signs its webhooks
+    #[serde(default = "default_payment_provider")]
+    pub payment_provider: String,
+    #[serde(default)]
+    pub paystack_secret_key: Option<String>,
+    #[serde(default = "default_paystack_base_url")]
+    pub paystack_base_url: String,
+}
+
+fn default_profile() -> String {
+    DEFAULT_PROFILE.to_string()
+}
+
+fn default_payment_provider() -> String {
+    "fake".to_string()
+}
+
+fn default_paystack_base_url() -> String {
+    "https://api.paystack.co".to_string()
+}
+
+fn default_smtp_tls() -> String {
+    "starttls".to_string()
+}
+
+fn default_public_url() -> String {
+    "http://localhost:8000".to_string()
+}
+
+fn default_jobs_worker() -> bool {
+    true
+}
+
+fn default_job_max_attempts() -> u32 {
+    5
+}
+
+fn default_cache_enabled() -> bool {
+    true
+}
+
+fn default_cors_allowed_methods() -> String {
+    "GET,POST,PUT,PATCH,DELETE".to_string()
+}
+
+fn default_cors_max_age_seconds() -> usize {
+    3600
+}
+
+fn default_hsts_max_age_seconds() -> u64 {
+    31_536_000
+}
+
+fn default_max_body_bytes() -> usize {
+    256 * 1024
+}
+
+fn default_hash_rounds() -> u32 {
+    12
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+fn default_jwt_algorithm() -> String {
+    "HS256".to_string()
+}
+
+fn default_jwt_kid() -> String {
+    DEFAULT_KID.to_string()
+}
+
+fn default_jwt_leeway_seconds() -> u64 {
+    60
+}
+
+fn default_host() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_port() -> u16 {
+    8000
+}
+
+fn default_shutdown_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_log_format() -> String {
+    "text".to_string()
+}
+
+fn default_otel_service_name() -> String {
+    "here-backend".to_string()
+}
+
+/// Profile used when `APP_PROFILE` is not set, so a deployment that forgets
+/// it gets production settings; local development opts into `dev`.
+const DEFAULT_PROFILE: &str = "prod";
+
+/// Directory holding the per-profile files (`config/dev.toml`, ...).
+const PROFILE_DIR: &str = "config";
+
+/// Prefix environment variables need to be read into the config
+/// (`HERE_DATABASE_URL`), so generic names such as `HOST` or `PORT` set by
+/// the platform cannot override it.
+const ENV_PREFIX: &str = "HERE";
+
+/// Shortest accepted `SECRET_KEY`, in characters.
+const MIN_SECRET_KEY_LEN: usize = 32;
+
+/// Minimum estimated entropy of `SECRET_KEY`, in bits.
+const MIN_SECRET_KEY_BITS: f64 = 128.0;
+
+/// Key/value pairs whose keys are lowercased to match the field names, so
+/// `SECRET_KEY` in `Secrets.toml` or the Shuttle secret store sets
+/// `secret_key` just like the environment variable does.
+#[derive(Debug, Clone)]
+struct UppercaseKeys(Map<String, Value>);
+
+impl UppercaseKeys {
+    fn new(entries: impl IntoIterator<Item = (String, Value)>) -> Self {
+        Self(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.to_lowercase(), value))
+                .collect(),
+        )
+    }
+}
+
+impl Source for UppercaseKeys {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        Ok(self.0.clone())
+    }
 }
 
 impl AppConfig {
-    /// Create AppConfig from environment variables (for local development and Docker)
-    pub fn from_env() -> Result<Self, ConfigError> {
-        // 1. Load .env file (if it exists) into the environment
-        dotenv::dotenv().ok();
-        info!("Attempting to load configuration...");
-
-        // 2. Build the config
-        let builder = Config::builder()
-            // 3. Add `Secrets.toml` as a low-priority source.
-            //    This is for `cargo shuttle run` (local dev).
-            //    We make it `required(false)` so the app doesn't
-            //    crash if it's not present (e.g., in Docker).
-            .add_source(File::new("Secrets.toml", FileFormat::Toml).required(false))
-            // 4. Add environment variables as the highest-priority source.
-            //    This reads from the real environment, which includes
-            //    vars from:
-            //    - `dotenv()` (from step 1)
-            //    - Docker (`--env-file` or `-e`)
-            //    - Shuttle production (which injects secrets as env vars)
-            .add_source(Environment::default());
-
-        // 5. Build and deserialize
-        builder.build()?.try_deserialize()
-    }
-
-    /// Create AppConfig from Shuttle secrets (for Shuttle deployment)
+    /// Load configuration from every layer, lowest priority first:
     ///
-    /// This method accepts a HashMap of secrets from Shuttle and uses them
-    /// to populate the configuration fields. This is more flexible than
-    /// relying on environment variables alone.
+    /// 1. built-in defaults
+    /// 2. the profile file `config/<APP_PROFILE>.toml` (profile defaults to `prod`)
+    /// 3. `Secrets.toml`
+    /// 4. environment variables prefixed with `HERE_` (including `.env`)
+    /// 5. `secrets`, e.g. the Shuttle secret store
+    ///
+    /// The result is validated; all problems are reported together.
+    pub fn load(secrets: Option<HashMap<String, String>>) -> Result<Self, ConfigError> {
+        Self::load_from(Path::new("."), secrets)
+    }
+
+    /// [`load`](Self::load) with the profile directory and `Secrets.toml`
+    /// looked up under `base_dir`.
+    pub fn load_from(
+        base_dir: &Path,
+        secrets: Option<HashMap<String, String>>,
+    ) -> Result<Self, ConfigError> {
+        dotenv::dotenv().ok();
+        let secrets = secrets.unwrap_or_default();
+
+        let profile = secrets
+            .get("APP_PROFILE")
+            .cloned()
+            .or_else(|| std::env::var("APP_PROFILE").ok())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+        info!("Loading configuration (profile: {})...", profile);
+
+        let profile_file = base_dir.join(PROFILE_DIR).join(format!("{}.toml", profile));
+        let secrets_file = File::from(base_dir.join("Secrets.toml"))
+            .format(FileFormat::Toml)
+            .required(false)
+            .collect()?;
+
+        let config: AppConfig = Config::builder()
+            .add_source(
+                File::from(profile_file)
+                    .format(FileFormat::Toml)
+                    .required(profile != DEFAULT_PROFILE),
+            )
+            .add_source(UppercaseKeys::new(secrets_file))
+            .add_source(Environment::with_prefix(ENV_PREFIX))
+            .add_source(UppercaseKeys::new(
+                secrets
+                    .into_iter()
+                    .map(|(key, value)| (key, Value::from(value))),
+            ))
+            .set_override("profile", profile)?
+            .build()?
+            .try_deserialize()?;
+
+        config.validate().map_err(|errors| {
+            ConfigError::Message(format!(
+                "Invalid configuration:\n  - {}",
+                errors.join("\n  - ")
+            ))
+        })?;
+        Ok(config)
+    }
+
+    /// Load from `.env`, `Secrets.toml` and `HERE_*` environment variables (local development and Docker).
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::load(None)
+    }
+
+    /// Load with Shuttle secrets as the highest-priority layer.
     ///
     /// # Example
-    /// ```rust
+    /// ```rust,ignore
     /// #[shuttle_runtime::main]
     /// async fn main(
     ///     #[shuttle_runtime::Secrets] secrets: shuttle_runtime::SecretStore,
@@ -63,72 +340,309 @@ impl AppConfig {
     /// }
     /// ```
     pub fn from_secrets(secrets: HashMap<String, String>) -> Result<Self, ConfigError> {
-        info!("Loading configuration from Shuttle secrets...");
-
-        // Helper to get required secret
-        let get_secret = |key: &str| -> Result<String, ConfigError> {
-            secrets
-                .get(key)
-                .cloned()
-                .ok_or_else(|| ConfigError::Message(format!("Missing required secret: {}", key)))
-        };
-
-        // Helper to parse numeric values
-        let parse_secret = |key: &str, default: u32| -> u32 {
-            secrets
-                .get(key)
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(default)
-        };
-
-        // Helper to parse boolean values
-        let parse_bool = |key: &str, default: bool| -> bool {
-            secrets
-                .get(key)
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(default)
-        };
-
-        Ok(Self {
-            secret_key: get_secret("SECRET_KEY")?,
-            hash_rounds: parse_secret("HASH_ROUNDS", 12),
-            redis_url: get_secret("REDIS_URL")?,
-            smtp_host: get_secret("SMTP_HOST")?,
-            smtp_port: secrets
-                .get("SMTP_PORT")
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(587),
-            smtp_username: get_secret("SMTP_USERNAME")?,
-            smtp_password: get_secret("SMTP_PASSWORD")?,
-            smtp_from_email: get_secret("SMTP_FROM_EMAIL")?,
-            database_url: get_secret("DATABASE_URL")?,
-            debug: parse_bool("DEBUG", false),
-        })
+        Self::load(Some(secrets))
     }
 
-    /// Create AppConfig with fallback: try Shuttle secrets first, then environment
-    ///
-    /// This is useful for flexible deployment scenarios where you might use
-    /// either Shuttle secrets or environment variables.
+    /// Kept for existing callers; equivalent to [`load`](Self::load).
     pub fn from_secrets_or_env(
         secrets: Option<HashMap<String, String>>,
     ) -> Result<Self, ConfigError> {
-        match secrets {
-            Some(secrets) if !secrets.is_empty() => {
-                info!("Using Shuttle secrets for configuration");
-                Self::from_secrets(secrets)
+        Self::load(secrets)
+    }
+
+    /// [`api_url`](Self::api_url), or [`public_url`](Self::public_url) when
+    /// unset, without a trailing slash.
+    pub fn api_base_url(&self) -> &str {
+        self.api_url
+            .as_deref()
+            .unwrap_or(&self.public_url)
+            .trim_end_matches('/')
+    }
+
+    /// Check every setting and return one message per problem.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.secret_key.chars().count() < MIN_SECRET_KEY_LEN {
+            errors.push(format!(
+                "SECRET_KEY must be at least {} characters",
+                MIN_SECRET_KEY_LEN
+            ));
+        } else if estimated_entropy_bits(&self.secret_key) < MIN_SECRET_KEY_BITS {
+            errors.push(
+                "SECRET_KEY is too predictable; use a long random value (e.g. `openssl rand -hex 32`)"
+                    .to_string(),
+            );
+        }
+        if !(4..=31).contains(&self.hash_rounds) {
+            errors.push("HASH_ROUNDS must be between 4 and 31".to_string());
+        }
+        check_url(
+            &mut errors,
+            "REDIS_URL",
+            &self.redis_url,
+            &["redis", "rediss", "redis+unix", "unix"],
+        );
+        check_url(
+            &mut errors,
+            "DATABASE_URL",
+            &self.database_url,
+            &["postgres", "postgresql", "sqlite"],
+        );
+        if self.smtp_host.trim().is_empty() {
+            errors.push("SMTP_HOST must not be empty".to_string());
+        }
+        if self.smtp_port == 0 {
+            errors.push("SMTP_PORT must be between 1 and 65535".to_string());
+        }
+        if !self.smtp_from_email.validate_email() {
+            errors.push("SMTP_FROM_EMAIL must be an email address".to_string());
+        }
+        if !["starttls", "tls", "none"].contains(&self.smtp_tls.as_str()) {
+            errors.push("SMTP_TLS must be starttls, tls or none".to_string());
+        }
+        check_url(
+            &mut errors,
+            "PUBLIC_URL",
+            &self.public_url,
+            &["http", "https"],
+        );
+        if let Some(api_url) = &self.api_url {
+            check_url(&mut errors, "API_URL", api_url, &["http", "https"]);
+        }
+        if self.job_max_attempts == 0 {
+            errors.push("JOB_MAX_ATTEMPTS must be at least 1".to_string());
+        }
+        if !["HS256", "RS256", "EdDSA"].contains(&self.jwt_algorithm.as_str()) {
+            errors.push("JWT_ALGORITHM must be HS256, RS256 or EdDSA".to_string());
+        }
+        if self.port == 0 {
+            errors.push("PORT must be between 1 and 65535".to_string());
+        }
+        if !["text", "json"].contains(&self.log_format.as_str()) {
+            errors.push("LOG_FORMAT must be text or json".to_string());
+        }
+        if let Some(endpoint) = &self.otel_exporter_otlp_endpoint {
+            check_url(
+                &mut errors,
+                "OTEL_EXPORTER_OTLP_ENDPOINT",
+                endpoint,
+                &["http", "https"],
+            );
+        }
+        for origin in self.cors_origins() {
+            if origin == "*" {
+                if self.cors_allow_credentials {
+                    errors.push(
+                        "CORS_ALLOWED_ORIGINS cannot be `*` when CORS_ALLOW_CREDENTIALS is true"
+                            .to_string(),
+                    );
+                }
+                continue;
+            }
+            match Url::parse(&origin) {
+                Ok(url) if ["http", "https"].contains(&url.scheme()) && url.path() == "/" => {}
+                _ => errors.push(format!(
+                    "CORS_ALLOWED_ORIGINS entry `{}` must be an origin such as https://app.example.com",
+                    origin
+                )),
             }
-            _ => {
-                info!("Using environment variables for configuration");
-                Self::from_env()
+        }
+        for method in self.cors_methods() {
+            if method.parse::<actix_web::http::Method>().is_err() {
+                errors.push(format!(
+                    "CORS_ALLOWED_METHODS entry `{}` is not a method",
+                    method
+                ));
+            }
+        }
+        if self.max_body_bytes == 0 {
+            errors.push("MAX_BODY_BYTES must be greater than 0".to_string());
+        }
+        match self.payment_provider.as_str() {
+            "fake" => {}
+            "paystack" => {
+                if self
+                    .paystack_secret_key
+                    .as_deref()
+                    .is_none_or(|key| key.trim().is_empty())
+                {
+                    errors.push(
+                        "PAYSTACK_SECRET_KEY is required when PAYMENT_PROVIDER is paystack"
+                            .to_string(),
+                    );
+                }
+                check_url(
+                    &mut errors,
+                    "PAYSTACK_BASE_URL",
+                    &self.paystack_base_url,
+                    &["http", "https"],
+                );
             }
+            _ => errors.push("PAYMENT_PROVIDER must be fake or paystack".to_string()),
+        }
+        if self.profile == "prod" && self.debug {
+            errors.push("DEBUG must be false in the prod profile".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// `CORS_ALLOWED_ORIGINS` split into its entries.
+    pub fn cors_origins(&self) -> Vec<String> {
+        split_list(self.cors_allowed_origins.as_deref().unwrap_or_default())
+    }
+
+    /// `CORS_ALLOWED_METHODS` split into its entries, upper-cased.
+    pub fn cors_methods(&self) -> Vec<String> {
+        split_list(&self.cors_allowed_methods.to_uppercase())
+    }
+
+    /// Every setting as `(name, value)` with secrets and URL credentials
+    /// redacted, for `Debug` and `here-admin config check`.
+    pub fn redacted_entries(&self) -> Vec<(&'static str, String)> {
+        let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+        let secret = |value: &Option<String>| {
+            value
+                .as_ref()
+                .map(|_| REDACTED.to_string())
+                .unwrap_or_default()
+        };
+
+        vec![
+            ("profile", self.profile.clone()),
+            ("secret_key", REDACTED.to_string()),
+            ("hash_rounds", self.hash_rounds.to_string()),
+            ("redis_url", redact_url(&self.redis_url)),
+            ("smtp_host", self.smtp_host.clone()),
+            ("smtp_port", self.smtp_port.to_string()),
+            ("smtp_username", self.smtp_username.clone()),
+            ("smtp_password", REDACTED.to_string()),
+            ("smtp_from_email", self.smtp_from_email.clone()),
+            ("smtp_tls", self.smtp_tls.clone()),
+            ("database_url", redact_url(&self.database_url)),
+            ("debug", self.debug.to_string()),
+            ("jwt_algorithm", self.jwt_algorithm.clone()),
+            ("jwt_kid", self.jwt_kid.clone()),
+            ("jwt_private_key", secret(&self.jwt_private_key)),
+            ("jwt_public_keys", optional(&self.jwt_public_keys)),
+            ("jwt_previous_secrets", secret(&self.jwt_previous_secrets)),
+            ("jwt_issuer", optional(&self.jwt_issuer)),
+            ("jwt_audience", optional(&self.jwt_audience)),
+            ("jwt_leeway_seconds", self.jwt_leeway_seconds.to_string()),
+            ("host", self.host.clone()),
+            ("port", self.port.to_string()),
+            (
+                "shutdown_timeout_seconds",
+                self.shutdown_timeout_seconds.to_string(),
+            ),
+            ("auto_migrate", self.auto_migrate.to_string()),
+            ("health_check_smtp", self.health_check_smtp.to_string()),
+            ("metrics_token", secret(&self.metrics_token)),
+            ("log_format", self.log_format.clone()),
+            (
+                "otel_exporter_otlp_endpoint",
+                optional(&self.otel_exporter_otlp_endpoint),
+            ),
+            ("otel_service_name", self.otel_service_name.clone()),
+            ("public_url", self.public_url.clone()),
+            ("api_url", optional(&self.api_url)),
+            ("jobs_worker", self.jobs_worker.to_string()),
+            ("job_max_attempts", self.job_max_attempts.to_string()),
+            ("cache_enabled", self.cache_enabled.to_string()),
+            ("cors_allowed_origins", optional(&self.cors_allowed_origins)),
+            ("cors_allowed_methods", self.cors_allowed_methods.clone()),
+            (
+                "cors_allow_credentials",
+                self.cors_allow_credentials.to_string(),
+            ),
+            (
+                "cors_max_age_seconds",
+                self.cors_max_age_seconds.to_string(),
+            ),
+            (
+                "hsts_max_age_seconds",
+                self.hsts_max_age_seconds.to_string(),
+            ),
+            ("max_body_bytes", self.max_body_bytes.to_string()),
+            ("payment_provider", self.payment_provider.clone()),
+            ("paystack_secret_key", secret(&self.paystack_secret_key)),
+            ("paystack_base_url", self.paystack_base_url.clone()),
+        ]
+    }
+}
+
+/// Secrets and URL credentials are redacted so the config can be logged.
+impl fmt::Debug for AppConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("AppConfig");
+        for (name, value) in self.redacted_entries() {
+            debug.field(name, &value);
         }
+        debug.finish()
+    }
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn check_url(errors: &mut Vec<String>, name: &str, value: &str, schemes: &[&str]) {
+    match Url::parse(value) {
+        Ok(url) if schemes.contains(&url.scheme()) => {}
+        Ok(url) => errors.push(format!(
+            "{} has unsupported scheme `{}` (expected one of: {})",
+            name,
+            url.scheme(),
+            schemes.join(", ")
+        )),
+        Err(e) => errors.push(format!("{} is not a valid URL: {}", name, e)),
     }
 }
 
+/// Shannon entropy of `value` times its length: a rough upper bound on the
+/// bits an attacker has to guess. Catches keys such as `aaaa...` or
+/// `changeme` repeated.
+fn estimated_entropy_bits(value: &str) -> f64 {
+    let len = value.chars().count() as f64;
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let per_char: f64 = counts
+        .values()
+        .map(|&n| {
+            let p = n as f64 / len;
+            -p * p.log2()
+        })
+        .sum();
+    per_char * len
+}
+
 #[derive(Clone, Debug)]
 pub struct AppState {
     pub db: DatabaseConnection,
     pub redis_pool: RedisPool,
+    pub cache: Cache,
+    pub jobs: JobQueue,
+    pub domain_events: DomainEvents,
+    /// Sends email for jobs; tests swap in a `RecordingMailer`
+    pub mailer: Arc<dyn Mailer>,
+    /// Takes payment for tickets; tests swap in a `FakePaymentProvider`
+    /// they settle themselves
+    pub payments: Arc<dyn PaymentProvider>,
     pub config: AppConfig,
+    pub jwt_keys: Arc<JwtKeys>,
+    pub metrics: Arc<Metrics>,
+    /// When this process built its state; reported as uptime by `/health`
+    pub started_at: DateTime<Utc>,
 }
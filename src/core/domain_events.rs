@@ -0,0 +1,73 @@
+//! In-process domain events.
+//!
+//! Services publish a [`DomainEvent`] after a state change has been
+//! committed. Every event is logged; other parts of the process can
+//! [`subscribe`](DomainEvents::subscribe) to react to them. Delivery is
+//! best effort and local to the process that made the change: a subscriber
+//! that falls behind misses events, so anything that must happen belongs in
+//! the same transaction or a job.
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::info;
+
+use crate::entity::EventStatus;
+
+/// Events a slow subscriber may fall behind by before it starts missing some.
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DomainEvent {
+    /// An event moved between lifecycle states
+    EventStatusChanged {
+        event_id: i32,
+        from: EventStatus,
+        to: EventStatus,
+        /// User who made the change; `None` for the scheduler
+        changed_by: Option<i32>,
+        at: DateTime<Utc>,
+    },
+    /// A host cancelled an event; `notified` attendees got an in-app notification
+    EventCancelled {
+        event_id: i32,
+        cancelled_by: i32,
+        reason: String,
+        notified: u64,
+    },
+    /// Registrations nobody checked in for were marked `NoShow` when the event completed
+    AttendeesMarkedNoShow { event_id: i32, count: u64 },
+}
+
+/// Publisher for [`DomainEvent`]s; cheap to clone.
+#[derive(Debug, Clone)]
+pub struct DomainEvents {
+    sender: broadcast::Sender<DomainEvent>,
+}
+
+impl Default for DomainEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DomainEvents {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        DomainEvents { sender }
+    }
+
+    pub fn publish(&self, event: DomainEvent) {
+        info!(
+            domain_event = %serde_json::to_string(&event).unwrap_or_default(),
+            "Domain event"
+        );
+        // No subscribers is fine
+        let _ = self.sender.send(event);
+    }
+
+    /// Receive every event published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<DomainEvent> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,219 @@
+//! Prometheus metrics.
+//!
+//! Every `AppState` owns its own [`Metrics`] registry. Request, query and
+//! login metrics are recorded as they happen. Pool and business gauges are
+//! refreshed when `/metrics` is scraped.
+use std::time::Instant;
+
+use actix_web::Error;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::web::Data;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use sea_orm::metric::Info;
+
+use crate::core::configs::AppState;
+
+/// Route label for requests that matched no route, so unknown paths cannot
+/// blow up label cardinality.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+#[derive(Debug)]
+pub struct Metrics {
+    registry: Registry,
+    http_requests_total: IntCounterVec,
+    http_request_duration_seconds: HistogramVec,
+    db_query_duration_seconds: HistogramVec,
+    login_attempts_total: IntCounterVec,
+    cache_requests_total: IntCounterVec,
+    jobs_processed_total: IntCounterVec,
+    redis_pool_connections: IntGaugeVec,
+    active_users: IntGauge,
+    upcoming_events: IntGauge,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self, prometheus::Error> {
+        let registry = Registry::new_custom(Some("here".to_string()), None)?;
+
+        let http_requests_total = IntCounterVec::new(
+            Opts::new(
+                "http_requests_total",
+                "HTTP requests by route, method and status",
+            ),
+            &["method", "route", "status"],
+        )?;
+        let http_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "HTTP request latency by route, method and status",
+            ),
+            &["method", "route", "status"],
+        )?;
+        let db_query_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "db_query_duration_seconds",
+                "Database query latency by statement type",
+            )
+            .buckets(vec![
+                0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0,
+            ]),
+            &["operation", "outcome"],
+        )?;
+        let login_attempts_total = IntCounterVec::new(
+            Opts::new("login_attempts_total", "Login attempts by outcome"),
+            &["outcome"],
+        )?;
+        let cache_requests_total = IntCounterVec::new(
+            Opts::new(
+                "cache_requests_total",
+                "Cache lookups by cache and result (hit, miss, error)",
+            ),
+            &["cache", "result"],
+        )?;
+        let jobs_processed_total = IntCounterVec::new(
+            Opts::new(
+                "jobs_processed_total",
+                "Background job runs by job and outcome (success, retry, dead)",
+            ),
+            &["job", "outcome"],
+        )?;
+        let redis_pool_connections = IntGaugeVec::new(
+            Opts::new(
+                "redis_pool_connections",
+                "Redis pool connections (max, open, idle, waiting)",
+            ),
+            &["state"],
+        )?;
+        let active_users = IntGauge::new(
+            "active_users",
+            "Users with a login session seen in the last 24 hours",
+        )?;
+        let upcoming_events = IntGauge::new(
+            "upcoming_events",
+            "Scheduled events that have not started yet",
+        )?;
+
+        registry.register(Box::new(http_requests_total.clone()))?;
+        registry.register(Box::new(http_request_duration_seconds.clone()))?;
+        registry.register(Box::new(db_query_duration_seconds.clone()))?;
+        registry.register(Box::new(login_attempts_total.clone()))?;
+        registry.register(Box::new(cache_requests_total.clone()))?;
+        registry.register(Box::new(jobs_processed_total.clone()))?;
+        registry.register(Box::new(redis_pool_connections.clone()))?;
+        registry.register(Box::new(active_users.clone()))?;
+        registry.register(Box::new(upcoming_events.clone()))?;
+
+        Ok(Self {
+            registry,
+            http_requests_total,
+            http_request_duration_seconds,
+            db_query_duration_seconds,
+            login_attempts_total,
+            cache_requests_total,
+            jobs_processed_total,
+            redis_pool_connections,
+            active_users,
+            upcoming_events,
+        })
+    }
+
+    pub fn observe_request(&self, method: &str, route: &str, status: u16, seconds: f64) {
+        let status = status.to_string();
+        let labels = [method, route, status.as_str()];
+        self.http_requests_total.with_label_values(&labels).inc();
+        self.http_request_duration_seconds
+            .with_label_values(&labels)
+            .observe(seconds);
+    }
+
+    /// Record one query; installed as the SeaORM metric callback.
+    pub fn observe_query(&self, info: &Info<'_>) {
+        let operation = info
+            .statement
+            .sql
+            .split_whitespace()
+            .next()
+            .map(str::to_ascii_uppercase)
+            .unwrap_or_default();
+        let outcome = if info.failed { "error" } else { "ok" };
+        self.db_query_duration_seconds
+            .with_label_values(&[operation.as_str(), outcome])
+            .observe(info.elapsed.as_secs_f64());
+    }
+
+    pub fn record_login(&self, success: bool) {
+        let outcome = if success { "success" } else { "failure" };
+        self.login_attempts_total
+            .with_label_values(&[outcome])
+            .inc();
+    }
+
+    pub fn record_cache(&self, cache: &str, result: &str) {
+        self.cache_requests_total
+            .with_label_values(&[cache, result])
+            .inc();
+    }
+
+    pub fn record_job(&self, job: &str, outcome: &str) {
+        self.jobs_processed_total
+            .with_label_values(&[job, outcome])
+            .inc();
+    }
+
+    pub fn set_redis_pool_status(&self, status: deadpool_redis::Status) {
+        for (state, value) in [
+            ("max", status.max_size),
+            ("open", status.size),
+            ("idle", status.available),
+            ("waiting", status.waiting),
+        ] {
+            self.redis_pool_connections
+                .with_label_values(&[state])
+                .set(value as i64);
+        }
+    }
+
+    pub fn set_business_gauges(&self, active_users: u64, upcoming_events: u64) {
+        self.active_users.set(active_users as i64);
+        self.upcoming_events.set(upcoming_events as i64);
+    }
+
+    /// Encode every metric in the Prometheus text format.
+    pub fn render(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+}
+
+/// Middleware recording request count and latency per route template.
+pub async fn track_requests(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let started = Instant::now();
+    let method = req.method().to_string();
+    let state = req.app_data::<Data<AppState>>().cloned();
+
+    let res = next.call(req).await?;
+
+    if let Some(state) = state {
+        let route = res
+            .request()
+            .match_pattern()
+            .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+        state.metrics.observe_request(
+            &method,
+            &route,
+            res.status().as_u16(),
+            started.elapsed().as_secs_f64(),
+        );
+    }
+
+    Ok(res)
+}
@@ -1 +1,7 @@
+pub mod cache;
 pub mod configs;
+pub mod domain_events;
+pub mod metrics;
+pub mod security;
+pub mod startup;
+pub mod telemetry;
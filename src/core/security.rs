@@ -0,0 +1,95 @@
+use actix_cors::Cors;
+use actix_web::Error;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::http::header::{
+    CONTENT_SECURITY_POLICY, HeaderValue, REFERRER_POLICY, STRICT_TRANSPORT_SECURITY,
+    X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+};
+use actix_web::middleware::Next;
+use actix_web::web::Data;
+
+use crate::core::configs::{AppConfig, AppState};
+use crate::utils::request_id::REQUEST_ID_HEADER;
+
+/// Path prefix of the Swagger UI, which gets a looser CSP than the API.
+const DOCS_PREFIX: &str = "/docs";
+
+/// The API only returns JSON, so nothing may be loaded or framed.
+const API_CSP: &str = "default-src 'none'; frame-ancestors 'none'";
+
+/// Swagger UI loads its own scripts and styles, injects inline styles and
+/// uses `data:` images, and fetches the OpenAPI document from this origin.
+const DOCS_CSP: &str = "default-src 'self'; script-src 'self'; style-src 'self' 'unsafe-inline'; \
+     img-src 'self' data:; connect-src 'self'; frame-ancestors 'none'";
+
+/// Build the CORS middleware from `CORS_*` settings.
+///
+/// With no `CORS_ALLOWED_ORIGINS`, cross-origin requests are refused; `*`
+/// allows any origin (only without credentials, enforced by validation).
+pub fn cors(config: &AppConfig) -> Cors {
+    let mut cors = Cors::default()
+        .allowed_methods(
+            config
+                .cors_methods()
+                .iter()
+                .filter_map(|method| method.parse::<Method>().ok()),
+        )
+        .allow_any_header()
+        .expose_headers([REQUEST_ID_HEADER])
+        .max_age(config.cors_max_age_seconds);
+
+    for origin in config.cors_origins() {
+        cors = if origin == "*" {
+            cors.allow_any_origin().send_wildcard()
+        } else {
+            cors.allowed_origin(&origin)
+        };
+    }
+    if config.cors_allow_credentials {
+        cors = cors.supports_credentials();
+    }
+    cors
+}
+
+/// Middleware that adds the default security headers to every response.
+///
+/// Headers already set by a handler are left alone.
+pub async fn security_headers(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let hsts_max_age = req
+        .app_data::<Data<AppState>>()
+        .map(|state| state.config.hsts_max_age_seconds)
+        .unwrap_or_default();
+    let csp = if req.path().starts_with(DOCS_PREFIX) {
+        DOCS_CSP
+    } else {
+        API_CSP
+    };
+
+    let mut res = next.call(req).await?;
+
+    let headers = res.headers_mut();
+    let defaults = [
+        (X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")),
+        (X_FRAME_OPTIONS, HeaderValue::from_static("DENY")),
+        (REFERRER_POLICY, HeaderValue::from_static("no-referrer")),
+        (CONTENT_SECURITY_POLICY, HeaderValue::from_static(csp)),
+    ];
+    for (name, value) in defaults {
+        if !headers.contains_key(&name) {
+            headers.insert(name, value);
+        }
+    }
+    if hsts_max_age > 0 && !headers.contains_key(STRICT_TRANSPORT_SECURITY) {
+        let value = format!("max-age={}; includeSubDomains", hsts_max_age);
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(STRICT_TRANSPORT_SECURITY, value);
+        }
+    }
+
+    Ok(res)
+}
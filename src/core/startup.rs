@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use deadpool_redis::{Config as RedisConfig, Pool as RedisPool, Runtime};
+use sea_orm::{Database, DatabaseConnection, DbErr};
+use tracing::info;
+
+use crate::core::cache::Cache;
+use crate::core::configs::{AppConfig, AppState};
+use crate::core::domain_events::DomainEvents;
+use crate::core::metrics::Metrics;
+use crate::jobs::JobQueue;
+use crate::migration;
+use crate::services::email::SmtpMailer;
+use crate::services::payments::{FakePaymentProvider, PaymentProvider};
+use crate::services::paystack::PaystackProvider;
+use crate::utils::jwt::JwtKeys;
+
+/// Shared startup steps used by both the Shuttle entrypoint and the
+/// standalone server binary.
+pub fn create_redis_pool(config: &AppConfig) -> Result<RedisPool, String> {
+    let pool = RedisConfig::from_url(&config.redis_url)
+        .create_pool(Some(Runtime::Tokio1))
+        .map_err(|e| format!("Failed to create Redis pool: {}", e))?;
+    info!("Redis connection pool created.");
+    Ok(pool)
+}
+
+/// Connect to `database_url`. Both `postgres://` and `sqlite://` URLs work.
+pub async fn connect_database(config: &AppConfig) -> Result<DatabaseConnection, DbErr> {
+    let db = Database::connect(&config.database_url).await?;
+    info!(
+        "Database connection established ({:?}).",
+        db.get_database_backend()
+    );
+    Ok(db)
+}
+
+/// Refuse to start against a database that is missing migrations.
+///
+/// With `auto_migrate` set, a database from before migrations is
+/// baselined and pending migrations are applied first; this is meant for
+/// platforms such as Shuttle where running `here-migrate` by hand is
+/// awkward.
+pub async fn check_migrations(db: &DatabaseConnection, auto_migrate: bool) -> Result<(), String> {
+    if auto_migrate {
+        let adopted = migration::baseline(db)
+            .await
+            .map_err(|e| format!("Failed to baseline the existing schema: {}", e))?;
+        if !adopted.is_empty() {
+            info!("Adopted existing schema as {}", adopted.join(", "));
+        }
+        let ran = migration::up(db, None)
+            .await
+            .map_err(|e| format!("Failed to apply migrations: {}", e))?;
+        if !ran.is_empty() {
+            info!("Applied {} migration(s): {}", ran.len(), ran.join(", "));
+        }
+    }
+    migration::ensure_up_to_date(db).await?;
+    info!("Database schema is up to date.");
+    Ok(())
+}
+
+/// Assemble the shared application state from an open database connection.
+pub fn build_app_state(mut db: DatabaseConnection, config: AppConfig) -> Result<AppState, String> {
+    let redis_pool = create_redis_pool(&config)?;
+
+    let metrics =
+        Arc::new(Metrics::new().map_err(|e| format!("Failed to register metrics: {}", e))?);
+    let query_metrics = metrics.clone();
+    db.set_metric_callback(move |info| query_metrics.observe_query(info));
+
+    let cache = if config.cache_enabled {
+        Cache::new(redis_pool.clone(), Some(metrics.clone()))
+    } else {
+        info!("Redis cache disabled.");
+        Cache::disabled()
+    };
+
+    let jobs = JobQueue::new(redis_pool.clone(), config.job_max_attempts);
+    let mailer = Arc::new(SmtpMailer::from_config(&config)?);
+    let payments: Arc<dyn PaymentProvider> = match config.payment_provider.as_str() {
+        "paystack" => Arc::new(PaystackProvider::from_config(&config)?),
+        // Takes no money, so paid orders stay pending
+        "fake" => Arc::new(FakePaymentProvider::default()),
+        other => return Err(format!("Unknown payment provider: {}", other)),
+    };
+    info!("Payment provider: {}.", payments.name());
+
+    let jwt_keys = JwtKeys::from_config(&config)?;
+    info!("JWT signing key loaded (kid: {}).", jwt_keys.kid);
+
+    Ok(AppState {
+        db,
+        redis_pool,
+        cache,
+        jobs,
+        domain_events: DomainEvents::new(),
+        mailer,
+        payments,
+        config,
+        jwt_keys: Arc::new(jwt_keys),
+        metrics,
+        started_at: Utc::now(),
+    })
+}
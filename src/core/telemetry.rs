@@ -0,0 +1,231 @@
+//! Log output, request spans and optional OpenTelemetry export.
+//!
+//! `LOG_FORMAT` picks human-readable (`text`, the default) or `json` logs.
+//! Built with the `otel` feature and given `OTEL_EXPORTER_OTLP_ENDPOINT`,
+//! spans are also exported over OTLP/HTTP and incoming `traceparent`
+//! headers are honoured.
+use std::time::Instant;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::{Error, HttpMessage};
+use tracing::{Span, info};
+use tracing_actix_web::{DefaultRootSpanBuilder, RootSpanBuilder};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, fmt};
+
+use crate::core::configs::AppConfig;
+use crate::utils::redact::redact_path_and_query;
+use crate::utils::request_id::RequestId;
+
+/// Flushes pending spans when dropped; keep it alive for the whole process.
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider.take()
+            && let Err(e) = provider.shutdown()
+        {
+            eprintln!("Failed to flush OpenTelemetry spans: {}", e);
+        }
+    }
+}
+
+/// Install the global tracing subscriber described by `config`.
+pub fn init_telemetry(config: &AppConfig) -> Result<TelemetryGuard, String> {
+    let json = match config.log_format.as_str() {
+        "text" => false,
+        "json" => true,
+        other => return Err(format!("Unknown LOG_FORMAT `{}` (use text or json)", other)),
+    };
+    // `RUST_LOG` wins; otherwise `DEBUG` turns on debug-level logs
+    let default_level = if config.debug { "debug" } else { "info" };
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| default_level.into());
+
+    #[cfg(feature = "otel")]
+    let (otel_layer, provider) = match otel::tracer_provider(config)? {
+        Some(provider) => (Some(otel::layer(&provider)), Some(provider)),
+        None => (None, None),
+    };
+    #[cfg(not(feature = "otel"))]
+    let otel_layer: Option<tracing_subscriber::layer::Identity> = None;
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(json.then(|| {
+            fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(false)
+        }))
+        .with((!json).then(fmt::layer))
+        .with(otel_layer)
+        .try_init()
+        .map_err(|e| format!("Failed to install tracing subscriber: {}", e))?;
+
+    #[cfg(not(feature = "otel"))]
+    if config.otel_exporter_otlp_endpoint.is_some() {
+        tracing::warn!(
+            "OTEL_EXPORTER_OTLP_ENDPOINT is set but this build lacks the `otel` feature"
+        );
+    }
+
+    Ok(TelemetryGuard {
+        #[cfg(feature = "otel")]
+        provider,
+    })
+}
+
+/// When the request started, for the access log line.
+struct RequestStart(Instant);
+
+/// Root span for every request, carrying the request ID from
+/// [`request_id`](crate::utils::request_id::request_id) so that every log
+/// line emitted while handling the request can be correlated.
+///
+/// Unlike the default builder the target is recorded with sensitive query
+/// parameters redacted.
+pub struct RequestSpanBuilder;
+
+impl RootSpanBuilder for RequestSpanBuilder {
+    fn on_request_start(request: &ServiceRequest) -> Span {
+        request
+            .extensions_mut()
+            .insert(RequestStart(Instant::now()));
+
+        let request_id = request
+            .extensions()
+            .get::<RequestId>()
+            .map(|id| id.0.clone())
+            .unwrap_or_default();
+        let route = request
+            .match_pattern()
+            .unwrap_or_else(|| "default".to_string());
+        let target = request
+            .uri()
+            .path_and_query()
+            .map(|p| redact_path_and_query(p.as_str()))
+            .unwrap_or_default();
+        let user_agent = request
+            .headers()
+            .get("User-Agent")
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or("");
+
+        let span = tracing::info_span!(
+            "HTTP request",
+            http.method = %request.method(),
+            http.route = %route,
+            http.target = %target,
+            http.client_ip = %request.connection_info().realip_remote_addr().unwrap_or(""),
+            http.user_agent = %user_agent,
+            http.status_code = tracing::field::Empty,
+            otel.name = %format!("{} {}", request.method(), route),
+            otel.kind = "server",
+            otel.status_code = tracing::field::Empty,
+            trace_id = tracing::field::Empty,
+            request_id = %request_id,
+            exception.message = tracing::field::Empty,
+            exception.details = tracing::field::Empty,
+        );
+
+        #[cfg(feature = "otel")]
+        otel::set_parent(request, &span);
+
+        span
+    }
+
+    fn on_request_end<B: MessageBody>(span: Span, outcome: &Result<ServiceResponse<B>, Error>) {
+        if let Ok(response) = outcome {
+            let latency_ms = response
+                .request()
+                .extensions()
+                .get::<RequestStart>()
+                .map(|start| start.0.elapsed().as_secs_f64() * 1000.0)
+                .unwrap_or_default();
+            span.in_scope(|| {
+                info!(
+                    status = response.status().as_u16(),
+                    latency_ms, "request completed"
+                )
+            });
+        }
+        DefaultRootSpanBuilder::on_request_end(span, outcome);
+    }
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use actix_web::dev::ServiceRequest;
+    use actix_web::http::header::HeaderMap;
+    use opentelemetry::propagation::Extractor;
+    use opentelemetry::trace::{TraceContextExt, TracerProvider};
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::Resource;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use tracing::Span;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    use crate::core::configs::AppConfig;
+
+    pub(super) fn tracer_provider(config: &AppConfig) -> Result<Option<SdkTracerProvider>, String> {
+        let Some(endpoint) = &config.otel_exporter_otlp_endpoint else {
+            return Ok(None);
+        };
+
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+            .build()
+            .map_err(|e| format!("Failed to create OTLP exporter: {}", e))?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(
+                Resource::builder()
+                    .with_service_name(config.otel_service_name.clone())
+                    .build(),
+            )
+            .build();
+
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        opentelemetry::global::set_tracer_provider(provider.clone());
+        Ok(Some(provider))
+    }
+
+    pub(super) fn layer<S>(
+        provider: &SdkTracerProvider,
+    ) -> tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+    where
+        S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer().with_tracer(provider.tracer("here"))
+    }
+
+    struct HeaderExtractor<'a>(&'a HeaderMap);
+
+    impl Extractor for HeaderExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|v| v.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(|k| k.as_str()).collect()
+        }
+    }
+
+    /// Continue the caller's trace when the request carries `traceparent`.
+    pub(super) fn set_parent(request: &ServiceRequest, span: &Span) {
+        let parent = opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(&HeaderExtractor(request.headers()))
+        });
+        let _ = span.set_parent(parent);
+        let trace_id = span.context().span().span_context().trace_id();
+        span.record("trace_id", tracing::field::display(trace_id));
+    }
+}
@@ -1,6 +1,37 @@
+use crate::entity::api_key::ApiScope;
+use crate::entity::{
+    AttendanceStatus, DiscountType, EventCategory, EventRole, EventStatus, EventType,
+    EventVisibility, InvitationStatus, LedgerEntryKind, Motivation, OrderStatus, StaffRole,
+};
+use crate::handlers::api_keys::*;
 use crate::handlers::auth::*;
+use crate::handlers::calendar::*;
+use crate::handlers::event_staff::*;
+use crate::handlers::events::*;
+use crate::handlers::health::*;
+use crate::handlers::invitations::*;
+use crate::handlers::jobs::*;
+use crate::handlers::metrics::*;
+use crate::handlers::notifications::*;
+use crate::handlers::payments::*;
+use crate::handlers::reference_data::*;
+use crate::handlers::search::*;
+use crate::handlers::tags::*;
+use crate::handlers::tickets::*;
 use crate::handlers::users::*;
+use crate::schemas::api_keys::*;
 use crate::schemas::auth::*;
+use crate::schemas::calendar::*;
+use crate::schemas::error::ErrorResponse;
+use crate::schemas::event::*;
+use crate::schemas::event_staff::*;
+use crate::schemas::health::*;
+use crate::schemas::invitation::*;
+use crate::schemas::jobs::*;
+use crate::schemas::notification::*;
+use crate::schemas::search::*;
+use crate::schemas::tag::*;
+use crate::schemas::ticket::*;
 use crate::schemas::user::*;
 use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
 use utoipa::{Modify, OpenApi};
@@ -11,10 +42,149 @@ use utoipa::{Modify, OpenApi};
         signup,
         login,
         get_me,
-        health_check
+        update_timezone,
+        live,
+        ready,
+        metrics,
+        list_sessions,
+        delete_session,
+        jwks,
+        request_email_verification,
+        verify_email,
+        request_password_reset,
+        reset_password,
+        create_key,
+        list_keys,
+        delete_key,
+        list_events,
+        get_event,
+        update_event_status,
+        edit_event,
+        create_event_series,
+        get_event_series,
+        event_ics,
+        create_calendar_feed,
+        delete_calendar_feed,
+        calendar_feed,
+        cancel,
+        create_rsvp,
+        invite,
+        list_event_invitations,
+        delete_invitation,
+        create_link,
+        list_links,
+        delete_link,
+        list_mine,
+        accept,
+        decline,
+        redeem,
+        add_event_staff,
+        list_event_staff,
+        delete_event_staff,
+        list_event_attendees,
+        check_in_attendee,
+        list_managed_events,
+        list_ticket_tiers,
+        create_ticket_tier,
+        update_ticket_tier,
+        delete_ticket_tier,
+        list_event_promo_codes,
+        create_event_promo_code,
+        delete_event_promo_code,
+        checkout_tickets,
+        list_ticket_orders,
+        refund_ticket_order,
+        event_ledger,
+        payment_webhook,
+        list_my_orders,
+        get_my_order,
+        confirm_my_order,
+        cancel_my_order,
+        list_my_roles,
+        accept_role,
+        decline_role,
+        list,
+        mark_read,
+        reference_data,
+        autocomplete_tags,
+        search,
+        list_jobs,
+        retry_job,
+        delete_job
     ),
     components(
-        schemas(SignUp, SignShow, LoginRequest, LoginResponse, UserMeResponse)
+        schemas(
+            SignUp,
+            TimezoneUpdate,
+            SignShow,
+            LoginRequest,
+            LoginResponse,
+            UserMeResponse,
+            SessionResponse,
+            VerifyEmailRequest,
+            PasswordResetRequest,
+            PasswordResetConfirm,
+            ApiScope,
+            CreateApiKeyRequest,
+            ApiKeyResponse,
+            CreatedApiKeyResponse,
+            CheckStatus,
+            DependencyCheck,
+            LivenessResponse,
+            ReadinessResponse,
+            ErrorResponse,
+            EventResponse,
+            EventStatusUpdate,
+            EventUpdate,
+            EditScope,
+            EventWindow,
+            LabelMatch,
+            CreateEventSeriesRequest,
+            EventSeriesResponse,
+            CalendarFeedResponse,
+            CancelEventRequest,
+            AttendanceResponse,
+            AttendanceStatus,
+            InviteUsersRequest,
+            InvitationResponse,
+            InvitationStatus,
+            UserInvitationResponse,
+            CreateInviteLinkRequest,
+            InviteLinkResponse,
+            CreatedInviteLinkResponse,
+            RedeemInviteLinkRequest,
+            AddStaffRequest,
+            StaffResponse,
+            StaffRole,
+            UserStaffRoleResponse,
+            ManagedEventResponse,
+            CreateTicketTierRequest,
+            UpdateTicketTierRequest,
+            TicketTierResponse,
+            CreatePromoCodeRequest,
+            PromoCodeResponse,
+            DiscountType,
+            CheckoutRequest,
+            OrderResponse,
+            OrderStatus,
+            LedgerEntryResponse,
+            LedgerEntryKind,
+            EventRole,
+            NotificationResponse,
+            ReferenceDataResponse,
+            TagResponse,
+            SearchResponse,
+            HostSearchResult,
+            UserSearchResult,
+            EventType,
+            EventCategory,
+            EventStatus,
+            EventVisibility,
+            Motivation,
+            JobResponse,
+            QueueStatsResponse,
+            JobsOverviewResponse
+        )
     ),
     modifiers(&SecurityAddon),
     tags(
@@ -33,7 +203,10 @@ impl Modify for SecurityAddon {
                 SecurityScheme::Http(
                     HttpBuilder::new()
                         .scheme(HttpAuthScheme::Bearer)
-                        .bearer_format("JWT")
+                        .bearer_format("JWT or API key")
+                        .description(Some(
+                            "Either an access token from /auth/login or a personal API key (here_...)",
+                        ))
                         .build(),
                 ),
             )
@@ -0,0 +1,74 @@
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_keys")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    // Public identifier shown to the user, e.g. `here_1a2b3c4d`
+    #[sea_orm(unique)]
+    pub prefix: String,
+    // SHA-256 hex digest of the full key; the key itself is never stored
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    // Space-separated list of scopes, e.g. `profile:read events:write`
+    pub scopes: String,
+    pub expires_at: Option<DateTimeUtc>,
+    pub last_used_at: Option<DateTimeUtc>,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub created_at: DateTimeUtc,
+
+    #[sea_orm(belongs_to, from = "user_id", to = "id", on_delete = "Cascade")]
+    pub user: HasOne<super::user::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Permission granted to an API key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+pub enum ApiScope {
+    #[serde(rename = "profile:read")]
+    ProfileRead,
+    #[serde(rename = "events:read")]
+    EventsRead,
+    #[serde(rename = "events:write")]
+    EventsWrite,
+}
+
+impl ApiScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiScope::ProfileRead => "profile:read",
+            ApiScope::EventsRead => "events:read",
+            ApiScope::EventsWrite => "events:write",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "profile:read" => Some(ApiScope::ProfileRead),
+            "events:read" => Some(ApiScope::EventsRead),
+            "events:write" => Some(ApiScope::EventsWrite),
+            _ => None,
+        }
+    }
+}
+
+impl Model {
+    /// Scopes granted to this key. Unknown values are ignored.
+    pub fn scope_list(&self) -> Vec<ApiScope> {
+        self.scopes
+            .split_whitespace()
+            .filter_map(ApiScope::parse)
+            .collect()
+    }
+
+    pub fn has_scope(&self, scope: ApiScope) -> bool {
+        self.scope_list().contains(&scope)
+    }
+}
@@ -20,6 +20,22 @@ pub struct Model {
     pub host: HasOne<super::host::Entity>,
     pub start_time: DateTimeUtc,
     pub end_time: DateTimeUtc,
+    // IANA name of the time zone the event takes place in
+    #[sea_orm(default_value = "UTC")]
+    pub timezone: String,
+    // Set when the host cancels the event
+    pub cancelled_at: Option<DateTimeUtc>,
+    pub cancelled_by: Option<i32>,
+    pub cancellation_reason: Option<String>,
+    // Occurrences of a recurring series only
+    #[sea_orm(foreign_key = "ForeignKey::event_series")]
+    pub series_id: Option<i32>,
+    #[sea_orm(belongs_to, from = "series_id", to = "id")]
+    pub series: HasOne<super::event_series::Entity>,
+    // Start time the series rule gave this occurrence (RFC 5545 RECURRENCE-ID)
+    pub recurrence_id: Option<DateTimeUtc>,
+    // Edited on its own, so series-wide edits leave it alone
+    pub is_exception: bool,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
@@ -0,0 +1,33 @@
+use super::InvitationStatus;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "event_invitations")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub event_id: i32,
+    pub user_id: i32,
+    // Host who invited the user by name; unset when they came through a link
+    pub invited_by: Option<i32>,
+    // Link the user redeemed, if any
+    pub invite_link_id: Option<i32>,
+    #[sea_orm(default_value = "Pending")]
+    pub status: InvitationStatus,
+    pub responded_at: Option<DateTimeUtc>,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub created_at: DateTimeUtc,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub updated_at: DateTimeUtc,
+
+    #[sea_orm(belongs_to, from = "event_id", to = "id", on_delete = "Cascade")]
+    pub event: HasOne<super::event::Entity>,
+
+    #[sea_orm(belongs_to, from = "user_id", to = "id", on_delete = "Cascade")]
+    pub user: HasOne<super::user::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,41 @@
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "event_invite_links")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub event_id: i32,
+    pub created_by: i32,
+    // SHA-256 hex digest of the token in the link
+    #[sea_orm(unique)]
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    // Unlimited when unset
+    pub max_uses: Option<i32>,
+    #[sea_orm(default_value = 0)]
+    pub use_count: i32,
+    pub expires_at: Option<DateTimeUtc>,
+    pub revoked_at: Option<DateTimeUtc>,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub created_at: DateTimeUtc,
+
+    #[sea_orm(belongs_to, from = "event_id", to = "id", on_delete = "Cascade")]
+    pub event: HasOne<super::event::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Whether the link can still be redeemed at `now`.
+    pub fn is_usable(&self, now: DateTimeUtc) -> bool {
+        self.revoked_at.is_none()
+            && self.expires_at.is_none_or(|expires_at| expires_at > now)
+            && self
+                .max_uses
+                .is_none_or(|max_uses| self.use_count < max_uses)
+    }
+}
@@ -0,0 +1,40 @@
+use super::{EventCategory, EventType, EventVisibility};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A recurring event. Its occurrences are ordinary `events` rows pointing
+/// back here through `series_id`, created ahead of time up to a horizon.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "event_series")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(foreign_key = "ForeignKey::hosts")]
+    pub host_id: i32,
+    #[sea_orm(belongs_to, from = "host_id", to = "user_id")]
+    pub host: HasOne<super::host::Entity>,
+    pub title: String,
+    pub description: String,
+    pub location: String,
+    pub event_type: EventType,
+    pub category: EventCategory,
+    pub visibility: EventVisibility,
+    // RFC 5545 RRULE value, e.g. `FREQ=WEEKLY;BYDAY=TU`
+    pub rrule: String,
+    // IANA time zone the rule is expanded in, e.g. `Europe/London`
+    pub timezone: String,
+    // Wall-clock start of the first occurrence in `timezone`
+    pub start_local: DateTime,
+    pub duration_minutes: i32,
+    // Comma-separated local start times removed from the series (EXDATE)
+    pub exdates: String,
+    // Start of the latest occurrence created so far
+    pub materialized_until: Option<DateTimeUtc>,
+    #[sea_orm(has_many)]
+    pub events: HasMany<super::event::Entity>,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
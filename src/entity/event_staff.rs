@@ -0,0 +1,32 @@
+use super::{InvitationStatus, StaffRole};
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "event_staff")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub event_id: i32,
+    pub user_id: i32,
+    pub role: StaffRole,
+    // The role only takes effect once accepted
+    #[sea_orm(default_value = "Pending")]
+    pub status: InvitationStatus,
+    pub invited_by: Option<i32>,
+    pub responded_at: Option<DateTimeUtc>,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub created_at: DateTimeUtc,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub updated_at: DateTimeUtc,
+
+    #[sea_orm(belongs_to, from = "event_id", to = "id", on_delete = "Cascade")]
+    pub event: HasOne<super::event::Entity>,
+
+    #[sea_orm(belongs_to, from = "user_id", to = "id", on_delete = "Cascade")]
+    pub user: HasOne<super::user::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
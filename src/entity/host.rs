@@ -19,6 +19,8 @@ pub struct Model {
     pub user: HasOne<super::user::Entity>,
     #[sea_orm(has_many)]
     pub events: HasMany<super::event::Entity>,
+    #[sea_orm(has_many)]
+    pub event_series: HasMany<super::event_series::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
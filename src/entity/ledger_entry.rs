@@ -0,0 +1,38 @@
+use super::LedgerEntryKind;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// One movement of money. Entries are only ever added; the one exception
+/// is a refund, which is written as `pending` when claimed and completed
+/// with the provider's reference once the provider confirms it.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "ledger_entries")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    // Kept when the order or event is deleted
+    pub order_id: Option<i32>,
+    pub event_id: Option<i32>,
+    pub kind: LedgerEntryKind,
+    // In the currency's minor unit: positive for charges, negative for refunds
+    pub amount: i64,
+    pub currency: String,
+    pub provider: String,
+    // The provider's id for the payment or refund
+    pub provider_reference: String,
+    pub description: String,
+    // A refund claimed here that the provider has not confirmed yet
+    pub pending: bool,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub created_at: DateTimeUtc,
+
+    #[sea_orm(belongs_to, from = "order_id", to = "id", on_delete = "SetNull")]
+    pub order: HasOne<super::ticket_order::Entity>,
+
+    #[sea_orm(belongs_to, from = "event_id", to = "id", on_delete = "SetNull")]
+    pub event: HasOne<super::event::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
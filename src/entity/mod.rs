@@ -1,17 +1,34 @@
+pub mod api_key;
 pub mod attendance;
 pub mod attendee;
 pub mod attendee_motivations;
+pub mod calendar_feed;
 pub mod categories_join;
 pub mod event;
 pub mod event_categories;
+pub mod event_category_link;
+pub mod event_invitation;
+pub mod event_invite_link;
+pub mod event_series;
+pub mod event_staff;
+pub mod event_tag;
 pub mod host;
+pub mod ledger_entry;
 pub mod location;
 pub mod motivation;
+pub mod notification;
+pub mod payment_webhook_event;
 pub mod prelude;
+pub mod promo_code;
+pub mod session;
 pub mod skills;
+pub mod tag;
+pub mod ticket_order;
+pub mod ticket_tier;
 pub mod types;
 pub mod user;
 pub mod user_motivations;
+pub mod user_token;
 
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -36,6 +53,7 @@ pub enum AccountType {
     DeriveActiveEnum,
     serde::Serialize,
     serde::Deserialize,
+    utoipa::ToSchema,
 )]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "event_type")] // Tell SeaORM its DB type
 pub enum EventType {
@@ -55,6 +73,7 @@ pub enum EventType {
     DeriveActiveEnum,
     serde::Serialize,
     serde::Deserialize,
+    utoipa::ToSchema,
 )]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "event_category")]
 pub enum EventCategory {
@@ -112,6 +131,7 @@ pub enum Skill {
     DeriveActiveEnum,
     serde::Serialize,
     serde::Deserialize,
+    utoipa::ToSchema,
 )]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "event_status")]
 pub enum EventStatus {
@@ -125,6 +145,25 @@ pub enum EventStatus {
     Cancelled,
 }
 
+impl EventStatus {
+    /// Whether an event may move from `self` to `next`. Events advance
+    /// Scheduled -> Ongoing -> Completed and may be cancelled until they
+    /// complete; Completed and Cancelled are final.
+    pub fn can_transition_to(self, next: EventStatus) -> bool {
+        matches!(
+            (self, next),
+            (EventStatus::Scheduled, EventStatus::Ongoing)
+                | (EventStatus::Scheduled, EventStatus::Cancelled)
+                | (EventStatus::Ongoing, EventStatus::Completed)
+                | (EventStatus::Ongoing, EventStatus::Cancelled)
+        )
+    }
+
+    pub fn is_final(self) -> bool {
+        matches!(self, EventStatus::Completed | EventStatus::Cancelled)
+    }
+}
+
 #[derive(
     Debug,
     Clone,
@@ -135,6 +174,7 @@ pub enum EventStatus {
     DeriveActiveEnum,
     serde::Serialize,
     serde::Deserialize,
+    utoipa::ToSchema,
 )]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "attendance_status")]
 pub enum AttendanceStatus {
@@ -144,6 +184,9 @@ pub enum AttendanceStatus {
     CheckedIn,
     #[sea_orm(string_value = "NoShow")]
     NoShow,
+    /// Waiting for a place to free up
+    #[sea_orm(string_value = "Waitlisted")]
+    Waitlisted,
 }
 
 #[derive(
@@ -156,6 +199,7 @@ pub enum AttendanceStatus {
     DeriveActiveEnum,
     serde::Serialize,
     serde::Deserialize,
+    utoipa::ToSchema,
 )]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "event_visibility")]
 pub enum EventVisibility {
@@ -165,6 +209,162 @@ pub enum EventVisibility {
     Private,
 }
 
+/// Role of someone helping to run an event besides its host.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "staff_role")]
+pub enum StaffRole {
+    /// Edits the event and manages its invitations, like the host
+    #[sea_orm(string_value = "CoHost")]
+    CoHost,
+    /// Checks attendees in
+    #[sea_orm(string_value = "Staff")]
+    Staff,
+}
+
+/// What a user may do with an event, from least to most. Not stored: the
+/// host comes from `events.host_id` and the rest from `event_staff`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+pub enum EventRole {
+    Staff,
+    CoHost,
+    /// The host, or an administrator
+    Host,
+}
+
+impl From<StaffRole> for EventRole {
+    fn from(role: StaffRole) -> Self {
+        match role {
+            StaffRole::CoHost => EventRole::CoHost,
+            StaffRole::Staff => EventRole::Staff,
+        }
+    }
+}
+
+/// An invitee's answer to an invitation to a private event.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "invitation_status")]
+pub enum InvitationStatus {
+    #[sea_orm(string_value = "Pending")]
+    Pending,
+    #[sea_orm(string_value = "Accepted")]
+    Accepted,
+    #[sea_orm(string_value = "Declined")]
+    Declined,
+}
+
+/// How a promo code takes money off an order.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "discount_type")]
+pub enum DiscountType {
+    /// `amount` percent off the order
+    #[sea_orm(string_value = "Percentage")]
+    Percentage,
+    /// `amount` minor units off the order, never below zero
+    #[sea_orm(string_value = "Fixed")]
+    Fixed,
+}
+
+/// Where a ticket order is in checkout.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "order_status")]
+pub enum OrderStatus {
+    /// Tickets held while the buyer pays
+    #[sea_orm(string_value = "Pending")]
+    Pending,
+    #[sea_orm(string_value = "Paid")]
+    Paid,
+    /// The payment was declined; the hold is released
+    #[sea_orm(string_value = "Failed")]
+    Failed,
+    /// Not paid in time; the hold is released
+    #[sea_orm(string_value = "Expired")]
+    Expired,
+    /// Given up by the buyer; the hold is released
+    #[sea_orm(string_value = "Cancelled")]
+    Cancelled,
+    /// Paid, then the money was given back
+    #[sea_orm(string_value = "Refunded")]
+    Refunded,
+}
+
+/// Which way money moved in a [`ledger_entry`].
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "ledger_entry_kind")]
+pub enum LedgerEntryKind {
+    /// Money taken from a buyer
+    #[sea_orm(string_value = "Charge")]
+    Charge,
+    /// Money given back to a buyer
+    #[sea_orm(string_value = "Refund")]
+    Refund,
+}
+
 #[derive(
     Debug,
     Clone,
@@ -175,6 +375,7 @@ pub enum EventVisibility {
     DeriveActiveEnum,
     serde::Serialize,
     serde::Deserialize,
+    utoipa::ToSchema,
 )]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "motivation")]
 pub enum Motivation {
@@ -0,0 +1,44 @@
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "notifications")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub user_id: i32,
+    // What happened, e.g. `event_cancelled`; see `NotificationKind`
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    // The event the notification is about, if any
+    pub event_id: Option<i32>,
+    pub read_at: Option<DateTimeUtc>,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub created_at: DateTimeUtc,
+
+    #[sea_orm(belongs_to, from = "user_id", to = "id", on_delete = "Cascade")]
+    pub user: HasOne<super::user::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Values of [`Model::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    EventCancelled,
+    EventInvitation,
+    StaffInvitation,
+}
+
+impl NotificationKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotificationKind::EventCancelled => "event_cancelled",
+            NotificationKind::EventInvitation => "event_invitation",
+            NotificationKind::StaffInvitation => "staff_invitation",
+        }
+    }
+}
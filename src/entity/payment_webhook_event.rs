@@ -0,0 +1,21 @@
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A payment provider webhook that has been processed, so a redelivery is
+/// recognised and skipped.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "payment_webhook_events")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub provider: String,
+    // The provider's id for the event
+    pub event_id: String,
+    pub event_type: String,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub processed_at: DateTimeUtc,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -8,6 +8,10 @@
 pub use sea_orm::entity::prelude::*;
 
 // Re-export generated entity types for easy single-import usage.
+pub use super::api_key::{
+    ActiveModel as ApiKeyActiveModel, Column as ApiKeyColumn, Entity as ApiKey,
+    Model as ApiKeyModel, Relation as ApiKeyRelation,
+};
 pub use super::attendance::{
     ActiveModel as AttendanceActiveModel, Column as AttendanceColumn, Entity as Attendance,
     Model as AttendanceModel, Relation as AttendanceRelation,
@@ -21,6 +25,10 @@ pub use super::attendee_motivations::{
     Entity as AttendeeMotivations, Model as AttendeeMotivationsModel,
     Relation as AttendeeMotivationsRelation,
 };
+pub use super::calendar_feed::{
+    ActiveModel as CalendarFeedActiveModel, Column as CalendarFeedColumn, Entity as CalendarFeed,
+    Model as CalendarFeedModel, Relation as CalendarFeedRelation,
+};
 pub use super::categories_join::{
     ActiveModel as CategoriesJoinActiveModel, Column as CategoriesJoinColumn,
     Entity as CategoriesJoin, Model as CategoriesJoinModel, Relation as CategoriesJoinRelation,
@@ -33,10 +41,39 @@ pub use super::event_categories::{
     ActiveModel as EventCategoriesActiveModel, Column as EventCategoriesColumn,
     Entity as EventCategories, Model as EventCategoriesModel, Relation as EventCategoriesRelation,
 };
+pub use super::event_category_link::{
+    ActiveModel as EventCategoryLinkActiveModel, Column as EventCategoryLinkColumn,
+    Entity as EventCategoryLink, Model as EventCategoryLinkModel,
+    Relation as EventCategoryLinkRelation,
+};
+pub use super::event_invitation::{
+    ActiveModel as EventInvitationActiveModel, Column as EventInvitationColumn,
+    Entity as EventInvitation, Model as EventInvitationModel, Relation as EventInvitationRelation,
+};
+pub use super::event_invite_link::{
+    ActiveModel as EventInviteLinkActiveModel, Column as EventInviteLinkColumn,
+    Entity as EventInviteLink, Model as EventInviteLinkModel, Relation as EventInviteLinkRelation,
+};
+pub use super::event_series::{
+    ActiveModel as EventSeriesActiveModel, Column as EventSeriesColumn, Entity as EventSeries,
+    Model as EventSeriesModel, Relation as EventSeriesRelation,
+};
+pub use super::event_staff::{
+    ActiveModel as EventStaffActiveModel, Column as EventStaffColumn, Entity as EventStaff,
+    Model as EventStaffModel, Relation as EventStaffRelation,
+};
+pub use super::event_tag::{
+    ActiveModel as EventTagActiveModel, Column as EventTagColumn, Entity as EventTag,
+    Model as EventTagModel, Relation as EventTagRelation,
+};
 pub use super::host::{
     ActiveModel as HostActiveModel, Column as HostColumn, Entity as Host, Model as HostModel,
     Relation as HostRelation,
 };
+pub use super::ledger_entry::{
+    ActiveModel as LedgerEntryActiveModel, Column as LedgerEntryColumn, Entity as LedgerEntry,
+    Model as LedgerEntryModel, Relation as LedgerEntryRelation,
+};
 pub use super::location::{
     ActiveModel as LocationActiveModel, Column as LocationColumn, Entity as Location,
     Model as LocationModel, Relation as LocationRelation,
@@ -45,10 +82,39 @@ pub use super::motivation::{
     ActiveModel as MotivationActiveModel, Column as MotivationColumn, Entity as Motivation,
     Model as MotivationModel, Relation as MotivationRelation,
 };
+pub use super::notification::{
+    ActiveModel as NotificationActiveModel, Column as NotificationColumn, Entity as Notification,
+    Model as NotificationModel, Relation as NotificationRelation,
+};
+pub use super::payment_webhook_event::{
+    ActiveModel as PaymentWebhookEventActiveModel, Column as PaymentWebhookEventColumn,
+    Entity as PaymentWebhookEvent, Model as PaymentWebhookEventModel,
+    Relation as PaymentWebhookEventRelation,
+};
+pub use super::promo_code::{
+    ActiveModel as PromoCodeActiveModel, Column as PromoCodeColumn, Entity as PromoCode,
+    Model as PromoCodeModel, Relation as PromoCodeRelation,
+};
+pub use super::session::{
+    ActiveModel as SessionActiveModel, Column as SessionColumn, Entity as Session,
+    Model as SessionModel, Relation as SessionRelation,
+};
 pub use super::skills::{
     ActiveModel as SkillsActiveModel, Column as SkillsColumn, Entity as Skills,
     Model as SkillsModel, Relation as SkillsRelation,
 };
+pub use super::tag::{
+    ActiveModel as TagActiveModel, Column as TagColumn, Entity as Tag, Model as TagModel,
+    Relation as TagRelation,
+};
+pub use super::ticket_order::{
+    ActiveModel as TicketOrderActiveModel, Column as TicketOrderColumn, Entity as TicketOrder,
+    Model as TicketOrderModel, Relation as TicketOrderRelation,
+};
+pub use super::ticket_tier::{
+    ActiveModel as TicketTierActiveModel, Column as TicketTierColumn, Entity as TicketTier,
+    Model as TicketTierModel, Relation as TicketTierRelation,
+};
 pub use super::user::{
     ActiveModel as UserActiveModel, Column as UserColumn, Entity as User, Model as UserModel,
     Relation as UserRelation,
@@ -57,6 +123,10 @@ pub use super::user_motivations::{
     ActiveModel as UserMotivationsActiveModel, Column as UserMotivationsColumn,
     Entity as UserMotivations, Model as UserMotivationsModel, Relation as UserMotivationsRelation,
 };
+pub use super::user_token::{
+    ActiveModel as UserTokenActiveModel, Column as UserTokenColumn, Entity as UserToken,
+    Model as UserTokenModel, Relation as UserTokenRelation,
+};
 
 // Re-export DB-specific types implemented in `types.rs` (PgPoint wrapper used by Location)
 pub use super::types::PgPoint;
@@ -0,0 +1,30 @@
+use super::DiscountType;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "promo_codes")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub event_id: i32,
+    // Stored upper case
+    pub code: String,
+    pub discount_type: DiscountType,
+    // Percent off, or an amount off each order in the tier's minor unit
+    pub amount: i64,
+    pub max_uses: Option<i32>,
+    // Orders paid or in checkout with the code
+    #[sea_orm(default_value = 0)]
+    pub uses: i32,
+    pub expires_at: Option<DateTimeUtc>,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub created_at: DateTimeUtc,
+
+    #[sea_orm(belongs_to, from = "event_id", to = "id", on_delete = "Cascade")]
+    pub event: HasOne<super::event::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,52 @@
+use super::OrderStatus;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "ticket_orders")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    // Sent to the payment provider to tie its payment back to the order
+    #[sea_orm(unique)]
+    pub reference: String,
+    pub event_id: i32,
+    pub tier_id: i32,
+    pub user_id: i32,
+    pub quantity: i32,
+    pub promo_code_id: Option<i32>,
+    // Amounts in the tier's minor unit
+    pub subtotal: i64,
+    pub discount: i64,
+    pub total: i64,
+    pub currency: String,
+    #[sea_orm(default_value = "Pending")]
+    pub status: OrderStatus,
+    // The tickets are held for the buyer until then
+    pub expires_at: DateTimeUtc,
+    // The provider taking the payment, its id for it, and where the buyer pays
+    pub payment_provider: Option<String>,
+    pub payment_reference: Option<String>,
+    pub checkout_url: Option<String>,
+    // Set once paid
+    pub attendance_id: Option<i32>,
+    pub paid_at: Option<DateTimeUtc>,
+    pub refunded_at: Option<DateTimeUtc>,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub created_at: DateTimeUtc,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub updated_at: DateTimeUtc,
+
+    #[sea_orm(belongs_to, from = "event_id", to = "id", on_delete = "Cascade")]
+    pub event: HasOne<super::event::Entity>,
+
+    #[sea_orm(belongs_to, from = "tier_id", to = "id", on_delete = "Cascade")]
+    pub tier: HasOne<super::ticket_tier::Entity>,
+
+    #[sea_orm(belongs_to, from = "user_id", to = "id", on_delete = "Cascade")]
+    pub user: HasOne<super::user::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,34 @@
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "ticket_tiers")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub event_id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    // In the currency's minor unit; 0 for free tickets
+    pub price: i64,
+    // ISO 4217 code, e.g. NGN
+    pub currency: String,
+    pub quantity: i32,
+    // Tickets sold or held for a checkout in progress
+    #[sea_orm(default_value = 0)]
+    pub reserved: i32,
+    // On sale from/until these times when set
+    pub sales_start_at: Option<DateTimeUtc>,
+    pub sales_end_at: Option<DateTimeUtc>,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub created_at: DateTimeUtc,
+    #[sea_orm(default_expr = "Utc::now()")]
+    pub updated_at: DateTimeUtc,
+
+    #[sea_orm(belongs_to, from = "event_id", to = "id", on_delete = "Cascade")]
+    pub event: HasOne<super::event::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
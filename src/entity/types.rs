@@ -54,22 +54,22 @@ mod postgres_types {
             index: I,
         ) -> Result<Self, TryGetError> {
             // Try getting as a string (WKT format) or parse from other formats
-            match res.try_get_by::<Option<String>, I>(index.clone()) {
+            match res.try_get_by::<Option<String>, I>(index) {
                 Ok(Some(s)) => {
                     // Parse WKT format: "POINT(x y)"
                     let s = s.trim();
                     if s.starts_with("POINT(") && s.ends_with(")") {
                         let coords = &s[6..s.len() - 1];
                         let parts: Vec<&str> = coords.split_whitespace().collect();
-                        if parts.len() == 2 {
-                            if let (Ok(x), Ok(y)) = (parts[0].parse(), parts[1].parse()) {
-                                return Ok(PgPoint { x, y });
-                            }
+                        if parts.len() == 2
+                            && let (Ok(x), Ok(y)) = (parts[0].parse(), parts[1].parse())
+                        {
+                            return Ok(PgPoint { x, y });
                         }
                     }
                     Err(TryGetError::Null(format!("Invalid POINT format: {}", s)))
                 }
-                Ok(None) => Err(TryGetError::Null(format!("NULL value for PgPoint"))),
+                Ok(None) => Err(TryGetError::Null("NULL value for PgPoint".to_string())),
                 Err(e) => Err(TryGetError::DbErr(e)),
             }
         }
@@ -85,10 +85,10 @@ mod postgres_types {
                     if s.starts_with("POINT(") && s.ends_with(")") {
                         let coords = &s[6..s.len() - 1];
                         let parts: Vec<&str> = coords.split_whitespace().collect();
-                        if parts.len() == 2 {
-                            if let (Ok(x), Ok(y)) = (parts[0].parse(), parts[1].parse()) {
-                                return Ok(PgPoint { x, y });
-                            }
+                        if parts.len() == 2
+                            && let (Ok(x), Ok(y)) = (parts[0].parse(), parts[1].parse())
+                        {
+                            return Ok(PgPoint { x, y });
                         }
                     }
                     Err(ValueTypeErr)
@@ -154,22 +154,22 @@ mod sqlite_types {
             res: &QueryResult,
             index: I,
         ) -> Result<Self, TryGetError> {
-            match res.try_get_by::<Option<String>, I>(index.clone()) {
+            match res.try_get_by::<Option<String>, I>(index) {
                 Ok(Some(s)) => {
                     // Parse WKT format: "POINT(x y)"
                     let s = s.trim();
                     if s.starts_with("POINT(") && s.ends_with(")") {
                         let coords = &s[6..s.len() - 1];
                         let parts: Vec<&str> = coords.split_whitespace().collect();
-                        if parts.len() == 2 {
-                            if let (Ok(x), Ok(y)) = (parts[0].parse(), parts[1].parse()) {
-                                return Ok(PgPoint { x, y });
-                            }
+                        if parts.len() == 2
+                            && let (Ok(x), Ok(y)) = (parts[0].parse(), parts[1].parse())
+                        {
+                            return Ok(PgPoint { x, y });
                         }
                     }
                     Err(TryGetError::Null(format!("Invalid POINT format: {}", s)))
                 }
-                Ok(None) => Err(TryGetError::Null(format!("NULL value for PgPoint"))),
+                Ok(None) => Err(TryGetError::Null("NULL value for PgPoint".to_string())),
                 Err(e) => Err(TryGetError::DbErr(e)),
             }
         }
@@ -184,10 +184,10 @@ mod sqlite_types {
                     if s.starts_with("POINT(") && s.ends_with(")") {
                         let coords = &s[6..s.len() - 1];
                         let parts: Vec<&str> = coords.split_whitespace().collect();
-                        if parts.len() == 2 {
-                            if let (Ok(x), Ok(y)) = (parts[0].parse(), parts[1].parse()) {
-                                return Ok(PgPoint { x, y });
-                            }
+                        if parts.len() == 2
+                            && let (Ok(x), Ok(y)) = (parts[0].parse(), parts[1].parse())
+                        {
+                            return Ok(PgPoint { x, y });
                         }
                     }
                     Err(ValueTypeErr)
@@ -22,12 +22,21 @@ pub struct Model {
 
     pub avatar_url: Option<String>,
 
+    // IANA name; event times are also shown in this zone when set
+    pub timezone: Option<String>,
+
     #[sea_orm(has_many)]
     pub skills: HasMany<super::skills::Entity>,
 
-    #[sea_orm(default_value = "true")]
+    #[sea_orm(default_value = true)]
     pub is_active: bool,
 
+    #[sea_orm(default_value = false)]
+    pub is_admin: bool,
+
+    // Set once the user followed the link from the verification email
+    pub email_verified_at: Option<DateTimeUtc>,
+
     #[sea_orm(default_expr = "Utc::now()")]
     pub created_at: DateTimeUtc,
 
@@ -39,6 +48,21 @@ pub struct Model {
 
     #[sea_orm(has_one)]
     pub host: HasOne<super::host::Entity>,
+
+    #[sea_orm(has_many)]
+    pub sessions: HasMany<super::session::Entity>,
+
+    #[sea_orm(has_many)]
+    pub api_keys: HasMany<super::api_key::Entity>,
+
+    #[sea_orm(has_many)]
+    pub tokens: HasMany<super::user_token::Entity>,
+
+    #[sea_orm(has_many)]
+    pub notifications: HasMany<super::notification::Entity>,
+
+    #[sea_orm(has_one)]
+    pub calendar_feed: HasOne<super::calendar_feed::Entity>,
 }
 
 // NO MORE `enum Relation` or `impl Related` blocks.
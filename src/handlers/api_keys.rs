@@ -0,0 +1,139 @@
+use actix_web::{
+    Error, HttpResponse, Result, delete, error, get, post,
+    web::{Data, Json, Path},
+};
+use chrono::Utc;
+use tracing::error;
+use validator::Validate;
+
+use crate::core::configs::AppState;
+use crate::schemas::api_keys::{ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse};
+use crate::services::api_keys::{create_api_key, delete_api_key, list_api_keys};
+use crate::utils::auth_extractor::CurrentUser;
+use crate::utils::redact::validation_summary;
+
+#[utoipa::path(
+    post,
+    path = "/users/me/api-keys",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "API key created; the key is only shown once", body = CreatedApiKeyResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API keys cannot create API keys"),
+        (status = 422, description = "Validation error"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/me/api-keys")]
+pub async fn create_key(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    payload: Json<CreateApiKeyRequest>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_session()?;
+
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+
+    let request = payload.into_inner();
+    if request
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= Utc::now())
+    {
+        return Err(error::ErrorUnprocessableEntity(
+            "Validation error: expires_at must be in the future",
+        ));
+    }
+
+    let (api_key, key) = create_api_key(
+        &data.db,
+        current_user.0.id,
+        request.name,
+        &request.scopes,
+        request.expires_at,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to create API key: {}", e);
+        error::ErrorInternalServerError("Failed to create API key")
+    })?;
+
+    Ok(HttpResponse::Created().json(CreatedApiKeyResponse {
+        api_key: api_key.into(),
+        key,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/me/api-keys",
+    responses(
+        (status = 200, description = "API keys for the current user", body = Vec<ApiKeyResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API keys cannot list API keys"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/me/api-keys")]
+pub async fn list_keys(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+) -> Result<Json<Vec<ApiKeyResponse>>, Error> {
+    current_user.require_session()?;
+
+    let keys = list_api_keys(&data.db, current_user.0.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to list API keys: {}", e);
+            error::ErrorInternalServerError("Failed to list API keys")
+        })?;
+
+    Ok(Json(keys.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/me/api-keys/{id}",
+    params(
+        ("id" = i32, Path, description = "API key ID"),
+    ),
+    responses(
+        (status = 204, description = "API key deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API keys cannot delete API keys"),
+        (status = 404, description = "API key not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[delete("/me/api-keys/{id}")]
+pub async fn delete_key(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_session()?;
+
+    let deleted = delete_api_key(&data.db, current_user.0.id, path.into_inner())
+        .await
+        .map_err(|e| {
+            error!("Failed to delete API key: {}", e);
+            error::ErrorInternalServerError("Failed to delete API key")
+        })?;
+
+    if !deleted {
+        return Err(error::ErrorNotFound("API key not found"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
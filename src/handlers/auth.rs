@@ -1,14 +1,29 @@
 use actix_web::{
-    Error, Result, error, get, post,
-    web::{Data, Json},
+    Error, HttpRequest, HttpResponse, Result, delete, error, get,
+    http::header,
+    post,
+    web::{Data, Json, Path},
 };
-use tracing::error;
+use chrono::Utc;
+use jsonwebtoken::jwk::JwkSet;
+use tracing::{error, info};
 use validator::Validate;
 
 use crate::core::configs::AppState;
-use crate::schemas::auth::{LoginRequest, LoginResponse, UserMeResponse};
-use crate::services::users::authenticate_user;
+use crate::entity::api_key::ApiScope;
+use crate::entity::user_token::TokenPurpose;
+use crate::jobs::{EnqueueOptions, Job};
+use crate::schemas::auth::{
+    LoginRequest, LoginResponse, PasswordResetConfirm, PasswordResetRequest, SessionResponse,
+    UserMeResponse, VerifyEmailRequest,
+};
+use crate::services::sessions::{create_session, list_active_sessions, revoke_session};
+use crate::services::user_tokens::consume_token;
+use crate::services::users::{
+    authenticate_user, find_user_by_identifier, mark_email_verified, set_password,
+};
 use crate::utils::auth_extractor::CurrentUser;
+use crate::utils::redact::validation_summary;
 use crate::utils::utils::generate_jwt;
 
 #[utoipa::path(
@@ -24,13 +39,14 @@ use crate::utils::utils::generate_jwt;
 )]
 #[post("/login")]
 pub async fn login(
+    req: HttpRequest,
     data: Data<AppState>,
     payload: Json<LoginRequest>,
 ) -> Result<Json<LoginResponse>, Error> {
     // Validate request
     payload.validate().map_err(|e| {
-        error!("Validation error: {}", e);
-        error::ErrorBadRequest(format!("Validation error: {}", e))
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorBadRequest(format!("Validation error: {}", validation_summary(&e)))
     })?;
 
     let login_data = payload.into_inner();
@@ -40,11 +56,30 @@ pub async fn login(
         .await
         .map_err(|e| {
             error!("Authentication error: {}", e);
+            data.metrics.record_login(false);
             error::ErrorUnauthorized("Invalid credentials")
         })?;
+    data.metrics.record_login(true);
+
+    // Record the session for this device
+    let user_agent = req
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_owned);
+    let ip_address = req
+        .connection_info()
+        .realip_remote_addr()
+        .map(str::to_owned);
+    let session = create_session(&data.db, user.id, user_agent, ip_address)
+        .await
+        .map_err(|e| {
+            error!("Session creation error: {}", e);
+            error::ErrorInternalServerError("Failed to create session")
+        })?;
 
     // Generate JWT token
-    let token = generate_jwt(user.id, &data.config.secret_key).map_err(|e| {
+    let token = generate_jwt(user.id, session.id, &data.jwt_keys).map_err(|e| {
         error!("JWT generation error: {}", e);
         error::ErrorInternalServerError("Failed to generate token")
     })?;
@@ -71,14 +106,267 @@ pub async fn login(
 )]
 #[get("/me")]
 pub async fn get_me(current_user: CurrentUser) -> Result<Json<UserMeResponse>, Error> {
+    current_user.require_scope(ApiScope::ProfileRead)?;
+
+    Ok(Json(current_user.0.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/sessions",
+    responses(
+        (status = 200, description = "Active sessions for the current user", body = Vec<SessionResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/sessions")]
+pub async fn list_sessions(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+) -> Result<Json<Vec<SessionResponse>>, Error> {
+    let current_session_id = current_user.require_session()?.id;
     let user = current_user.0;
 
-    Ok(Json(UserMeResponse {
-        id: user.id,
-        username: user.username,
-        email: user.email,
-        first_name: user.first_name,
-        last_name: user.last_name,
-        avatar_url: user.avatar_url,
-    }))
+    let sessions = list_active_sessions(&data.db, user.id).await.map_err(|e| {
+        error!("Failed to list sessions: {}", e);
+        error::ErrorInternalServerError("Failed to list sessions")
+    })?;
+
+    Ok(Json(
+        sessions
+            .into_iter()
+            .map(|session| SessionResponse {
+                current: session.id == current_session_id,
+                id: session.id,
+                user_agent: session.user_agent,
+                ip_address: session.ip_address,
+                created_at: session.created_at,
+                last_seen_at: session.last_seen_at,
+            })
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/auth/sessions/{id}",
+    params(
+        ("id" = i32, Path, description = "Session ID"),
+    ),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[delete("/sessions/{id}")]
+pub async fn delete_session(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_session()?;
+    let session_id = path.into_inner();
+
+    let revoked = revoke_session(&data.db, current_user.0.id, session_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to revoke session: {}", e);
+            error::ErrorInternalServerError("Failed to revoke session")
+        })?;
+
+    if !revoked {
+        return Err(error::ErrorNotFound("Session not found"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    responses(
+        (status = 200, description = "Public keys for verifying access tokens (empty when HS256 is used)"),
+    )
+)]
+#[get("/.well-known/jwks.json")]
+pub async fn jwks(data: Data<AppState>) -> Json<JwkSet> {
+    Json(data.jwt_keys.public_jwks().clone())
+}
+
+/// Repeat requests for the same email within this many seconds queue a
+/// single email.
+const EMAIL_REQUEST_WINDOW_SECONDS: i64 = 300;
+
+#[utoipa::path(
+    post,
+    path = "/auth/verify-email/request",
+    responses(
+        (status = 202, description = "Verification email queued"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Email already verified"),
+        (status = 503, description = "Job queue unavailable"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/verify-email/request")]
+pub async fn request_email_verification(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    current_user.require_session()?;
+    let user = current_user.0;
+    if user.email_verified_at.is_some() {
+        return Err(error::ErrorConflict("Email already verified"));
+    }
+
+    data.jobs
+        .enqueue(
+            Job::SendVerificationEmail { user_id: user.id },
+            EnqueueOptions::default().idempotency_key(email_request_key("verify-email", user.id)),
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to queue verification email: {}", e);
+            error::ErrorServiceUnavailable("Email delivery is temporarily unavailable")
+        })?;
+
+    Ok(HttpResponse::Accepted().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/verify-email",
+    request_body = VerifyEmailRequest,
+    responses(
+        (status = 204, description = "Email address verified"),
+        (status = 400, description = "Invalid or expired token"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+#[post("/verify-email")]
+pub async fn verify_email(
+    data: Data<AppState>,
+    payload: Json<VerifyEmailRequest>,
+) -> Result<HttpResponse, Error> {
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorBadRequest(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+
+    let user_id = consume_token(&data.db, &payload.token, TokenPurpose::VerifyEmail)
+        .await
+        .map_err(|e| {
+            error!("Email verification failed: {}", e);
+            error::ErrorBadRequest("Invalid or expired token")
+        })?;
+    mark_email_verified(&data.db, &data.cache, user_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to mark email as verified: {}", e);
+            error::ErrorInternalServerError("Failed to verify email")
+        })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/password-reset/request",
+    request_body = PasswordResetRequest,
+    responses(
+        (status = 202, description = "If the address belongs to an account, a reset email is on its way"),
+        (status = 400, description = "Bad request"),
+    )
+)]
+#[post("/password-reset/request")]
+pub async fn request_password_reset(
+    data: Data<AppState>,
+    payload: Json<PasswordResetRequest>,
+) -> Result<HttpResponse, Error> {
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorBadRequest(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+
+    // Always 202, so the response does not reveal which addresses have accounts
+    match find_user_by_identifier(&data.db, &payload.email).await {
+        Ok(user) if user.is_active && user.email == payload.email => {
+            if let Err(e) = data
+                .jobs
+                .enqueue(
+                    Job::SendPasswordResetEmail { user_id: user.id },
+                    EnqueueOptions::default()
+                        .idempotency_key(email_request_key("password-reset", user.id)),
+                )
+                .await
+            {
+                error!("Failed to queue password reset email: {}", e);
+            }
+        }
+        _ => info!("Password reset requested for an unknown or inactive account"),
+    }
+
+    Ok(HttpResponse::Accepted().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/password-reset",
+    request_body = PasswordResetConfirm,
+    responses(
+        (status = 204, description = "Password changed; every session is signed out"),
+        (status = 400, description = "Invalid or expired token"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+#[post("/password-reset")]
+pub async fn reset_password(
+    data: Data<AppState>,
+    payload: Json<PasswordResetConfirm>,
+) -> Result<HttpResponse, Error> {
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorBadRequest(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+
+    let user_id = consume_token(&data.db, &payload.token, TokenPurpose::PasswordReset)
+        .await
+        .map_err(|e| {
+            error!("Password reset failed: {}", e);
+            error::ErrorBadRequest("Invalid or expired token")
+        })?;
+    set_password(
+        &data.db,
+        &data.cache,
+        user_id,
+        &payload.password,
+        data.config.hash_rounds,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to reset password: {}", e);
+        error::ErrorInternalServerError("Failed to reset password")
+    })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+fn email_request_key(kind: &str, user_id: i32) -> String {
+    format!(
+        "{}:{}:{}",
+        kind,
+        user_id,
+        Utc::now().timestamp() / EMAIL_REQUEST_WINDOW_SECONDS
+    )
 }
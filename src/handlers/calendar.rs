@@ -0,0 +1,178 @@
+use actix_web::{
+    Error, HttpResponse, Result, delete, error, get,
+    http::header,
+    post,
+    web::{Data, Path},
+};
+use tracing::error;
+
+use crate::core::configs::AppState;
+use crate::schemas::calendar::CalendarFeedResponse;
+use crate::services::calendar::{
+    delete_feed, feed_owner, registered_events, render_calendar, rotate_feed_token, with_organizers,
+};
+use crate::services::invitations::get_visible_event;
+use crate::utils::auth_extractor::{CurrentUser, MaybeCurrentUser};
+
+const CALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+#[utoipa::path(
+    get,
+    path = "/events/{id}.ics",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    responses(
+        (status = 200, description = "The event as an iCalendar file", content_type = "text/calendar"),
+        (status = 404, description = "Event not found, or private and the caller is not invited"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+#[get("/{id}.ics")]
+pub async fn event_ics(
+    data: Data<AppState>,
+    current_user: MaybeCurrentUser,
+    path: Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let event = get_visible_event(
+        &data.db,
+        &data.cache,
+        path.into_inner(),
+        current_user.0.as_ref(),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch event: {}", e);
+        error::ErrorInternalServerError("Failed to fetch event")
+    })?
+    .ok_or_else(|| error::ErrorNotFound("Event not found"))?;
+
+    let event_id = event.id;
+    let name = event.title.clone();
+    let events = with_organizers(&data.db, vec![event]).await.map_err(|e| {
+        error!("Failed to fetch event host: {}", e);
+        error::ErrorInternalServerError("Failed to fetch event")
+    })?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(CALENDAR_CONTENT_TYPE)
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"event-{}.ics\"", event_id),
+        ))
+        .body(render_calendar(&name, &events, data.config.api_base_url())))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/calendar-feed",
+    responses(
+        (status = 201, description = "Feed created; any previous feed URL stops working", body = CalendarFeedResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API keys cannot manage calendar feeds"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/me/calendar-feed")]
+pub async fn create_calendar_feed(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    current_user.require_session()?;
+
+    let token = rotate_feed_token(&data.db, current_user.0.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to create calendar feed: {}", e);
+            error::ErrorInternalServerError("Failed to create calendar feed")
+        })?;
+
+    let url = format!("{}/calendar/{}.ics", data.config.api_base_url(), token);
+    let webcal_url = format!(
+        "webcal://{}",
+        url.split_once("://").map_or(url.as_str(), |(_, rest)| rest)
+    );
+    Ok(HttpResponse::Created().json(CalendarFeedResponse { url, webcal_url }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/me/calendar-feed",
+    responses(
+        (status = 204, description = "Feed turned off"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API keys cannot manage calendar feeds"),
+        (status = 404, description = "No calendar feed"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[delete("/me/calendar-feed")]
+pub async fn delete_calendar_feed(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    current_user.require_session()?;
+
+    let deleted = delete_feed(&data.db, current_user.0.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete calendar feed: {}", e);
+            error::ErrorInternalServerError("Failed to delete calendar feed")
+        })?;
+    if !deleted {
+        return Err(error::ErrorNotFound("No calendar feed"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    get,
+    path = "/calendar/{token}.ics",
+    params(
+        ("token" = String, Path, description = "Secret feed token"),
+    ),
+    responses(
+        (status = 200, description = "Events the feed's owner registered for", content_type = "text/calendar"),
+        (status = 404, description = "Unknown feed"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+#[get("/{token}.ics")]
+pub async fn calendar_feed(
+    data: Data<AppState>,
+    path: Path<String>,
+) -> Result<HttpResponse, Error> {
+    let user = feed_owner(&data.db, &path.into_inner())
+        .await
+        .map_err(|e| {
+            error!("Failed to look up calendar feed: {}", e);
+            error::ErrorInternalServerError("Failed to load calendar feed")
+        })?
+        .ok_or_else(|| error::ErrorNotFound("Calendar feed not found"))?;
+
+    let events = registered_events(&data.db, user.id).await.map_err(|e| {
+        error!("Failed to load calendar feed events: {}", e);
+        error::ErrorInternalServerError("Failed to load calendar feed")
+    })?;
+    let events = with_organizers(&data.db, events).await.map_err(|e| {
+        error!("Failed to load calendar feed hosts: {}", e);
+        error::ErrorInternalServerError("Failed to load calendar feed")
+    })?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(CALENDAR_CONTENT_TYPE)
+        // Always current: apps should not see a stale copy after a change
+        .insert_header((header::CACHE_CONTROL, "no-cache, private"))
+        .body(render_calendar(
+            "Here events",
+            &events,
+            data.config.api_base_url(),
+        )))
+}
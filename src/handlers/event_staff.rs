@@ -0,0 +1,392 @@
+use actix_web::{
+    Error, HttpResponse, Result, delete, error, get, post,
+    web::{Data, Json, Path},
+};
+use tracing::error;
+use validator::Validate;
+
+use crate::core::configs::AppState;
+use crate::entity::EventRole;
+use crate::entity::api_key::ApiScope;
+use crate::handlers::events::managed_event;
+use crate::handlers::invitations::closed;
+use crate::schemas::event::{AttendanceResponse, EventResponse};
+use crate::schemas::event_staff::{
+    AddStaffRequest, ManagedEventResponse, StaffResponse, UserStaffRoleResponse,
+};
+use crate::services::event_staff::{
+    CheckIn, StaffInvite, StaffReply, add_staff, check_in, list_attendees, list_staff,
+    managed_events, remove_staff, respond_to_staff_invite, user_staff_roles,
+};
+use crate::utils::auth_extractor::CurrentUser;
+use crate::utils::redact::validation_summary;
+
+#[utoipa::path(
+    post,
+    path = "/events/{id}/staff",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    request_body = AddStaffRequest,
+    responses(
+        (status = 201, description = "User asked to help run the event and notified", body = StaffResponse),
+        (status = 200, description = "User was already on the team or already asked; role updated", body = StaffResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not the host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 409, description = "The event has been cancelled or has completed, or the user is its host"),
+        (status = 422, description = "Validation error or unknown user"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/staff")]
+pub async fn add_event_staff(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+    payload: Json<AddStaffRequest>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::Host).await?;
+    if event.status.is_final() {
+        return Err(closed(event.status));
+    }
+
+    let outcome = add_staff(
+        &data.db,
+        &event,
+        current_user.0.id,
+        payload.user.trim(),
+        payload.role,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to add event staff: {}", e);
+        error::ErrorInternalServerError("Failed to add event staff")
+    })?;
+
+    match outcome {
+        StaffInvite::Invited(staff) => Ok(HttpResponse::Created().json(StaffResponse::from(staff))),
+        StaffInvite::Updated(staff) => Ok(HttpResponse::Ok().json(StaffResponse::from(staff))),
+        StaffInvite::UnknownUser => Err(error::ErrorUnprocessableEntity(format!(
+            "No active user found for: {}",
+            payload.user.trim()
+        ))),
+        StaffInvite::IsHost => Err(error::ErrorConflict("The host already runs this event")),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{id}/staff",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    responses(
+        (status = 200, description = "The event's co-hosts and staff, including pending and declined requests", body = Vec<StaffResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:read` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/{id}/staff")]
+pub async fn list_event_staff(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<Vec<StaffResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsRead)?;
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::CoHost).await?;
+
+    let staff = list_staff(&data.db, event.id).await.map_err(|e| {
+        error!("Failed to list event staff: {}", e);
+        error::ErrorInternalServerError("Failed to list event staff")
+    })?;
+
+    Ok(Json(staff.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/events/{id}/staff/{staff_id}",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+        ("staff_id" = i32, Path, description = "Staff membership ID"),
+    ),
+    responses(
+        (status = 204, description = "Removed from the event's team"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not the host"),
+        (status = 404, description = "Event or staff membership not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[delete("/{id}/staff/{staff_id}")]
+pub async fn delete_event_staff(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<(i32, i32)>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    let (event_id, staff_id) = path.into_inner();
+    let event = managed_event(&data, &current_user, event_id, EventRole::Host).await?;
+
+    let removed = remove_staff(&data.db, event.id, staff_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to remove event staff: {}", e);
+            error::ErrorInternalServerError("Failed to remove event staff")
+        })?;
+
+    if removed {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(error::ErrorNotFound("Staff membership not found"))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{id}/attendees",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    responses(
+        (status = 200, description = "Everyone registered, checked in or waitlisted, in sign-up order", body = Vec<AttendanceResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:read` scope"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/{id}/attendees")]
+pub async fn list_event_attendees(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<Vec<AttendanceResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsRead)?;
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::Staff).await?;
+
+    let attendees = list_attendees(&data.db, event.id).await.map_err(|e| {
+        error!("Failed to list attendees: {}", e);
+        error::ErrorInternalServerError("Failed to list attendees")
+    })?;
+
+    Ok(Json(attendees.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{id}/attendees/{user_id}/check-in",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+        ("user_id" = i32, Path, description = "Attendee's user ID"),
+    ),
+    responses(
+        (status = 200, description = "Attendee checked in, or already was", body = AttendanceResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 409, description = "The event has been cancelled or has completed"),
+        (status = 422, description = "The user has no place at the event"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/attendees/{user_id}/check-in")]
+pub async fn check_in_attendee(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<(i32, i32)>,
+) -> Result<Json<AttendanceResponse>, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    let (event_id, user_id) = path.into_inner();
+    let event = managed_event(&data, &current_user, event_id, EventRole::Staff).await?;
+
+    let outcome = check_in(&data.db, &event, user_id).await.map_err(|e| {
+        error!("Failed to check attendee in: {}", e);
+        error::ErrorInternalServerError("Failed to check attendee in")
+    })?;
+
+    match outcome {
+        CheckIn::CheckedIn(attendance) | CheckIn::AlreadyCheckedIn(attendance) => {
+            Ok(Json(attendance.into()))
+        }
+        CheckIn::NotRegistered => Err(error::ErrorUnprocessableEntity(
+            "This user is not registered for the event",
+        )),
+        CheckIn::Closed(status) => Err(closed(status)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/managed",
+    responses(
+        (status = 200, description = "Every event the current user hosts or has accepted a role on, soonest first", body = Vec<ManagedEventResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:read` scope"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/managed")]
+pub async fn list_managed_events(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+) -> Result<Json<Vec<ManagedEventResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsRead)?;
+
+    let events = managed_events(&data.db, current_user.0.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to list managed events: {}", e);
+            error::ErrorInternalServerError("Failed to list managed events")
+        })?;
+
+    Ok(Json(
+        events
+            .into_iter()
+            .map(|(event, role)| ManagedEventResponse {
+                event: event.into(),
+                role,
+            })
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/event-roles",
+    responses(
+        (status = 200, description = "The current user's pending and accepted roles on events that have not ended, soonest first", body = Vec<UserStaffRoleResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:read` scope"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("")]
+pub async fn list_my_roles(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+) -> Result<Json<Vec<UserStaffRoleResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsRead)?;
+
+    let roles = user_staff_roles(&data.db, current_user.0.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to list event roles: {}", e);
+            error::ErrorInternalServerError("Failed to list event roles")
+        })?;
+
+    Ok(Json(
+        roles
+            .into_iter()
+            .map(|(staff, event)| UserStaffRoleResponse {
+                staff: staff.into(),
+                event: EventResponse::from(event),
+            })
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/event-roles/{id}/accept",
+    params(
+        ("id" = i32, Path, description = "Staff membership ID"),
+    ),
+    responses(
+        (status = 200, description = "Role accepted and in effect", body = StaffResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope"),
+        (status = 404, description = "Role not found"),
+        (status = 409, description = "The event has been cancelled or has completed"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/accept")]
+pub async fn accept_role(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<StaffResponse>, Error> {
+    respond(&data, &current_user, path.into_inner(), true).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/event-roles/{id}/decline",
+    params(
+        ("id" = i32, Path, description = "Staff membership ID"),
+    ),
+    responses(
+        (status = 200, description = "Role declined, or given up if it had been accepted", body = StaffResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope"),
+        (status = 404, description = "Role not found"),
+        (status = 409, description = "The event has been cancelled or has completed"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/decline")]
+pub async fn decline_role(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<StaffResponse>, Error> {
+    respond(&data, &current_user, path.into_inner(), false).await
+}
+
+async fn respond(
+    data: &AppState,
+    current_user: &CurrentUser,
+    staff_id: i32,
+    accepting: bool,
+) -> Result<Json<StaffResponse>, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+
+    let reply = respond_to_staff_invite(&data.db, staff_id, current_user.0.id, accepting)
+        .await
+        .map_err(|e| {
+            error!("Failed to answer event role: {}", e);
+            error::ErrorInternalServerError("Failed to answer event role")
+        })?;
+
+    match reply {
+        StaffReply::Responded(staff) => Ok(Json(staff.into())),
+        StaffReply::NotFound => Err(error::ErrorNotFound("Role not found")),
+        StaffReply::Closed(status) => Err(closed(status)),
+    }
+}
@@ -0,0 +1,577 @@
+use actix_web::{
+    Error, HttpResponse, Result, error, get, patch, post,
+    web::{Data, Json, Path, Query},
+};
+use chrono_tz::Tz;
+use tracing::error;
+use validator::Validate;
+
+use crate::core::configs::AppState;
+use crate::entity::api_key::ApiScope;
+use crate::entity::prelude::EventModel;
+use crate::entity::{EventRole, EventStatus};
+use crate::jobs::{EnqueueOptions, Job, JobQueue, schedule_event_jobs};
+use crate::schemas::event::{
+    AttendanceResponse, CancelEventRequest, CreateEventSeriesRequest, EventListQuery,
+    EventResponse, EventSeriesResponse, EventStatusUpdate, EventUpdate,
+};
+use crate::services::attendance::{RsvpOutcome, rsvp};
+use crate::services::event_lifecycle::{StatusChange, cancel_event, transition_event_status};
+use crate::services::event_series::{
+    EventEdit, NewSeries, SeriesCreation, create_series, get_series, series_occurrences,
+    update_event,
+};
+use crate::services::event_staff::event_role;
+use crate::services::events::{
+    get_event_by_id, list_public_events, list_public_events_in, list_public_events_matching,
+};
+use crate::services::invitations::{can_view_series, get_visible_event};
+use crate::services::tags::{event_labels, set_event_labels};
+use crate::utils::auth_extractor::{CurrentUser, MaybeCurrentUser};
+use crate::utils::recurrence::{Recurrence, RecurrenceRule};
+use crate::utils::redact::validation_summary;
+use crate::utils::tags::normalize_tags;
+use crate::utils::timezone::{parse_timezone, stored_timezone};
+
+/// Page size when `limit` is not given.
+const DEFAULT_PAGE_SIZE: u64 = 20;
+
+#[utoipa::path(
+    get,
+    path = "/events",
+    params(EventListQuery),
+    responses(
+        (status = 200, description = "Public events that have not ended, soonest first", body = Vec<EventResponse>),
+        (status = 422, description = "Validation error"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+#[get("")]
+pub async fn list_events(
+    data: Data<AppState>,
+    current_user: MaybeCurrentUser,
+    query: Query<EventListQuery>,
+) -> Result<Json<Vec<EventResponse>>, Error> {
+    query.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+
+    let filter = query
+        .label_filter()
+        .map_err(error::ErrorUnprocessableEntity)?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    let offset = query.offset.unwrap_or_default();
+    let events = match query.when {
+        Some(window) => list_public_events_in(&data.db, window, &filter, limit, offset).await,
+        None if !filter.is_empty() => {
+            list_public_events_matching(&data.db, &filter, limit, offset).await
+        }
+        None => list_public_events(&data.db, &data.cache, limit, offset).await,
+    }
+    .map_err(|e| {
+        error!("Failed to list events: {}", e);
+        error::ErrorInternalServerError("Failed to list events")
+    })?;
+
+    Ok(Json(
+        labelled(&data, events, viewer_timezone(&current_user)).await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{id}",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    responses(
+        (status = 200, description = "Event details", body = EventResponse),
+        (status = 404, description = "Event not found, or private and the caller is not invited"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+#[get("/{id}")]
+pub async fn get_event(
+    data: Data<AppState>,
+    current_user: MaybeCurrentUser,
+    path: Path<i32>,
+) -> Result<Json<EventResponse>, Error> {
+    let event = get_visible_event(
+        &data.db,
+        &data.cache,
+        path.into_inner(),
+        current_user.0.as_ref(),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch event: {}", e);
+        error::ErrorInternalServerError("Failed to fetch event")
+    })?
+    .ok_or_else(|| error::ErrorNotFound("Event not found"))?;
+
+    let mut events = labelled(&data, vec![event], viewer_timezone(&current_user)).await?;
+    Ok(Json(events.remove(0)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/events/{id}/status",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    request_body = EventStatusUpdate,
+    responses(
+        (status = 200, description = "Status changed", body = EventResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 409, description = "The event cannot move to this status"),
+        (status = 422, description = "Cancellation must use POST /events/{id}/cancel"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[patch("/{id}/status")]
+pub async fn update_event_status(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+    payload: Json<EventStatusUpdate>,
+) -> Result<Json<EventResponse>, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    if payload.status == EventStatus::Cancelled {
+        return Err(error::ErrorUnprocessableEntity(
+            "Use POST /events/{id}/cancel to cancel an event",
+        ));
+    }
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::CoHost).await?;
+    let user = &current_user.0;
+
+    let change = transition_event_status(
+        &data.db,
+        &data.cache,
+        &data.domain_events,
+        event.id,
+        payload.status,
+        Some(user.id),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to change event status: {}", e);
+        error::ErrorInternalServerError("Failed to change event status")
+    })?;
+
+    match change {
+        StatusChange::Changed(event) => Ok(Json((*event).into())),
+        StatusChange::NotFound => Err(error::ErrorNotFound("Event not found")),
+        StatusChange::Rejected { from } => Err(error::ErrorConflict(format!(
+            "Cannot change a {:?} event to {:?}",
+            from, payload.status
+        ))),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{id}/cancel",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    request_body = CancelEventRequest,
+    responses(
+        (status = 200, description = "Event cancelled; attendees are notified and paid orders refunded", body = EventResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not the host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 409, description = "The event has already completed or been cancelled"),
+        (status = 422, description = "Validation error"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/cancel")]
+pub async fn cancel(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+    payload: Json<CancelEventRequest>,
+) -> Result<Json<EventResponse>, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::Host).await?;
+
+    let change = cancel_event(
+        &data.db,
+        &data.cache,
+        &data.domain_events,
+        event.id,
+        current_user.0.id,
+        payload.reason.trim(),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to cancel event: {}", e);
+        error::ErrorInternalServerError("Failed to cancel event")
+    })?;
+
+    match change {
+        StatusChange::Changed(event) => {
+            // Attendees already have the in-app notification, so a queue
+            // outage only costs the emails
+            if let Err(e) = data
+                .jobs
+                .enqueue(
+                    Job::EventCancelledEmails { event_id: event.id },
+                    EnqueueOptions::default()
+                        .idempotency_key(format!("event-cancelled:{}", event.id)),
+                )
+                .await
+            {
+                error!("Failed to queue cancellation emails: {}", e);
+            }
+            // A queue outage leaves the refunds for the host to issue
+            // through `POST /events/{id}/orders/{order_id}/refund`
+            if let Err(e) = data
+                .jobs
+                .enqueue(
+                    Job::RefundEventOrders { event_id: event.id },
+                    EnqueueOptions::default()
+                        .idempotency_key(format!("refund-event-orders:{}", event.id)),
+                )
+                .await
+            {
+                error!("Failed to queue refunds for event {}: {}", event.id, e);
+            }
+            Ok(Json((*event).into()))
+        }
+        StatusChange::NotFound => Err(error::ErrorNotFound("Event not found")),
+        StatusChange::Rejected { from } => Err(error::ErrorConflict(format!(
+            "Cannot cancel a {:?} event",
+            from
+        ))),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{id}/rsvp",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    responses(
+        (status = 201, description = "Registered for the event", body = AttendanceResponse),
+        (status = 200, description = "Already registered", body = AttendanceResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope"),
+        (status = 404, description = "Event not found"),
+        (status = 409, description = "The event has been cancelled or has completed, or needs a ticket"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/rsvp")]
+pub async fn create_rsvp(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+
+    let outcome = rsvp(&data.db, path.into_inner(), &current_user.0)
+        .await
+        .map_err(|e| {
+            error!("Failed to RSVP: {}", e);
+            error::ErrorInternalServerError("Failed to RSVP")
+        })?;
+
+    match outcome {
+        RsvpOutcome::Registered(attendance) => {
+            Ok(HttpResponse::Created().json(AttendanceResponse::from(attendance)))
+        }
+        RsvpOutcome::AlreadyRegistered(attendance) => {
+            Ok(HttpResponse::Ok().json(AttendanceResponse::from(attendance)))
+        }
+        RsvpOutcome::NotFound => Err(error::ErrorNotFound("Event not found")),
+        RsvpOutcome::Closed(EventStatus::Cancelled) => {
+            Err(error::ErrorConflict("This event has been cancelled"))
+        }
+        RsvpOutcome::Closed(_) => Err(error::ErrorConflict("This event is no longer taking RSVPs")),
+        RsvpOutcome::TicketRequired => Err(error::ErrorConflict(
+            "This event needs a ticket; buy one with POST /events/{id}/checkout",
+        )),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/events/{id}",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    request_body = EventUpdate,
+    responses(
+        (status = 200, description = "Every event that changed, the edited one first", body = Vec<EventResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 409, description = "The event has completed or been cancelled"),
+        (status = 422, description = "Validation error"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[patch("/{id}")]
+pub async fn edit_event(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+    payload: Json<EventUpdate>,
+) -> Result<Json<Vec<EventResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    let tags = match &payload.tags {
+        Some(tags) => Some(normalize_tags(tags).map_err(error::ErrorUnprocessableEntity)?),
+        None => None,
+    };
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::CoHost).await?;
+
+    let edit = update_event(&data.db, &data.cache, event, &payload)
+        .await
+        .map_err(|e| {
+            error!("Failed to update event: {}", e);
+            error::ErrorInternalServerError("Failed to update event")
+        })?;
+
+    match edit {
+        EventEdit::Updated(events) => {
+            schedule_jobs(&data.jobs, &events).await;
+            // Categories and tags go to every event the edit reached
+            let mut labelled_events = Vec::with_capacity(events.len());
+            for event in events {
+                let event = set_event_labels(
+                    &data.db,
+                    &data.cache,
+                    event,
+                    payload.categories.as_deref(),
+                    tags.as_deref(),
+                )
+                .await
+                .map_err(|e| {
+                    error!("Failed to update event labels: {}", e);
+                    error::ErrorInternalServerError("Failed to update event")
+                })?;
+                labelled_events.push(event);
+            }
+            Ok(Json(labelled(&data, labelled_events, None).await?))
+        }
+        EventEdit::Closed(status) => Err(error::ErrorConflict(format!(
+            "Cannot edit a {:?} event",
+            status
+        ))),
+        EventEdit::Invalid(message) => Err(error::ErrorUnprocessableEntity(message)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/series",
+    request_body = CreateEventSeriesRequest,
+    responses(
+        (status = 201, description = "Series created with its first occurrences", body = EventSeriesResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Not a host, or API key is missing the `events:write` scope"),
+        (status = 422, description = "Validation error, invalid rule or time zone"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/series")]
+pub async fn create_event_series(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    payload: Json<CreateEventSeriesRequest>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    let payload = payload.into_inner();
+    let rule: RecurrenceRule = payload
+        .rrule
+        .parse()
+        .map_err(|e| error::ErrorUnprocessableEntity(format!("Invalid rrule: {}", e)))?;
+    let timezone = parse_timezone(&payload.timezone).map_err(error::ErrorUnprocessableEntity)?;
+
+    let creation = create_series(
+        &data.db,
+        &data.cache,
+        current_user.0.id,
+        NewSeries {
+            title: payload.title,
+            description: payload.description,
+            location: payload.location,
+            event_type: payload.event_type,
+            category: payload.category,
+            visibility: payload.visibility,
+            recurrence: Recurrence {
+                rule,
+                start: payload.start_time,
+                timezone,
+                exdates: payload.exdates,
+            },
+            duration: chrono::Duration::minutes(payload.duration_minutes.into()),
+        },
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to create event series: {}", e);
+        error::ErrorInternalServerError("Failed to create event series")
+    })?;
+
+    match creation {
+        SeriesCreation::Created {
+            series,
+            occurrences,
+        } => {
+            schedule_jobs(&data.jobs, &occurrences).await;
+            Ok(HttpResponse::Created().json(EventSeriesResponse::new(*series, occurrences)))
+        }
+        SeriesCreation::NotAHost => Err(error::ErrorForbidden("Only hosts can create events")),
+        SeriesCreation::NoOccurrences => Err(error::ErrorUnprocessableEntity(
+            "The rule has no upcoming occurrences",
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/series/{id}",
+    params(
+        ("id" = i32, Path, description = "Event series ID"),
+    ),
+    responses(
+        (status = 200, description = "Series with the occurrences created so far", body = EventSeriesResponse),
+        (status = 404, description = "Series not found"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+#[get("/series/{id}")]
+pub async fn get_event_series(
+    data: Data<AppState>,
+    current_user: MaybeCurrentUser,
+    path: Path<i32>,
+) -> Result<Json<EventSeriesResponse>, Error> {
+    let series = get_series(&data.db, path.into_inner())
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch event series: {}", e);
+            error::ErrorInternalServerError("Failed to fetch event series")
+        })?
+        .ok_or_else(|| error::ErrorNotFound("Event series not found"))?;
+
+    let occurrences = series_occurrences(&data.db, series.id).await.map_err(|e| {
+        error!("Failed to fetch event series: {}", e);
+        error::ErrorInternalServerError("Failed to fetch event series")
+    })?;
+    let visible = can_view_series(&data.db, &series, &occurrences, current_user.0.as_ref())
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch event series: {}", e);
+            error::ErrorInternalServerError("Failed to fetch event series")
+        })?;
+    if !visible {
+        return Err(error::ErrorNotFound("Event series not found"));
+    }
+
+    Ok(Json(EventSeriesResponse::new(series, occurrences)))
+}
+
+/// Responses for `events` with their categories and tags.
+pub(crate) async fn labelled(
+    data: &AppState,
+    events: Vec<EventModel>,
+    viewer: Option<Tz>,
+) -> Result<Vec<EventResponse>, Error> {
+    let mut labels = event_labels(&data.db, &events).await.map_err(|e| {
+        error!("Failed to fetch event labels: {}", e);
+        error::ErrorInternalServerError("Failed to fetch event labels")
+    })?;
+    Ok(events
+        .into_iter()
+        .map(|event| {
+            let event_labels = labels.remove(&event.id).unwrap_or_default();
+            EventResponse::localized(event, viewer).with_labels(event_labels)
+        })
+        .collect())
+}
+
+/// The signed-in user's preferred time zone, if any.
+pub(crate) fn viewer_timezone(current_user: &MaybeCurrentUser) -> Option<Tz> {
+    current_user
+        .0
+        .as_ref()
+        .and_then(|user| user.timezone.as_deref())
+        .map(stored_timezone)
+}
+
+/// Queue reminders and status changes for new or moved events. Best effort:
+/// the lifecycle sweep still moves events along if the queue is down.
+async fn schedule_jobs(queue: &JobQueue, events: &[EventModel]) {
+    for event in events {
+        if let Err(e) = schedule_event_jobs(queue, event).await {
+            error!("Failed to schedule jobs for event {}: {}", event.id, e);
+            break;
+        }
+    }
+}
+
+/// The event, if the current user's role on it is at least `required`:
+/// 404 if they have none, so other people's private events stay invisible,
+/// and 403 if it is too low.
+pub(crate) async fn managed_event(
+    data: &AppState,
+    current_user: &CurrentUser,
+    event_id: i32,
+    required: EventRole,
+) -> Result<EventModel, Error> {
+    let event = get_event_by_id(&data.db, event_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch event: {}", e);
+            error::ErrorInternalServerError("Failed to fetch event")
+        })?
+        .ok_or_else(|| error::ErrorNotFound("Event not found"))?;
+    let role = event_role(&data.db, &event, &current_user.0)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch event role: {}", e);
+            error::ErrorInternalServerError("Failed to fetch event")
+        })?
+        .ok_or_else(|| error::ErrorNotFound("Event not found"))?;
+    if role < required {
+        return Err(error::ErrorForbidden(format!(
+            "This requires the {:?} role on the event",
+            required
+        )));
+    }
+    Ok(event)
+}
@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use actix_web::{HttpResponse, Responder, get, web::Data};
+use chrono::Utc;
+
+use crate::core::configs::AppState;
+use crate::schemas::health::{CheckStatus, LivenessResponse, ReadinessResponse};
+use crate::services::health::{check_database, check_redis, check_smtp};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    responses(
+        (status = 200, description = "The process is running", body = LivenessResponse),
+    )
+)]
+#[get("/live")]
+pub async fn live(data: Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(LivenessResponse {
+        status: "ok".to_string(),
+        version: VERSION.to_string(),
+        started_at: data.started_at,
+        uptime_seconds: (Utc::now() - data.started_at).num_seconds(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    responses(
+        (status = 200, description = "All critical dependencies are reachable", body = ReadinessResponse),
+        (status = 503, description = "A critical dependency is down", body = ReadinessResponse),
+    )
+)]
+#[get("/ready")]
+pub async fn ready(data: Data<AppState>) -> impl Responder {
+    let smtp = async {
+        if data.config.health_check_smtp {
+            Some(check_smtp(&data.config.smtp_host, data.config.smtp_port).await)
+        } else {
+            None
+        }
+    };
+    let (database, redis, smtp) = futures::join!(
+        check_database(&data.db),
+        check_redis(&data.redis_pool),
+        smtp
+    );
+
+    let mut checks = BTreeMap::from([
+        ("database".to_string(), database),
+        ("redis".to_string(), redis),
+    ]);
+    if let Some(smtp) = smtp {
+        checks.insert("smtp".to_string(), smtp);
+    }
+
+    let down = |critical: bool| {
+        checks
+            .values()
+            .any(|c| c.critical == critical && c.status == CheckStatus::Down)
+    };
+    let (status, mut response) = if down(true) {
+        ("unavailable", HttpResponse::ServiceUnavailable())
+    } else if down(false) {
+        ("degraded", HttpResponse::Ok())
+    } else {
+        ("ok", HttpResponse::Ok())
+    };
+
+    response.json(ReadinessResponse {
+        status: status.to_string(),
+        version: VERSION.to_string(),
+        started_at: data.started_at,
+        uptime_seconds: (Utc::now() - data.started_at).num_seconds(),
+        checks,
+    })
+}
@@ -0,0 +1,469 @@
+use actix_web::{
+    Error, HttpResponse, Result, delete, error, get, post,
+    web::{Data, Json, Path},
+};
+use tracing::error;
+use validator::Validate;
+
+use crate::core::configs::AppState;
+use crate::entity::api_key::ApiScope;
+use crate::entity::{EventRole, EventStatus};
+use crate::handlers::events::managed_event;
+use crate::schemas::event::EventResponse;
+use crate::schemas::invitation::{
+    CreateInviteLinkRequest, CreatedInviteLinkResponse, InvitationResponse, InviteLinkResponse,
+    InviteUsersRequest, RedeemInviteLinkRequest, UserInvitationResponse,
+};
+use crate::services::invitations::{
+    InvitationReply, InviteOutcome, LinkRedemption, NewInviteLink, create_invite_link,
+    invite_users, list_invitations, list_invite_links, redeem_invite_link, respond_to_invitation,
+    revoke_invitation, revoke_invite_link, user_invitations,
+};
+use crate::utils::auth_extractor::CurrentUser;
+use crate::utils::redact::validation_summary;
+
+#[utoipa::path(
+    post,
+    path = "/events/{id}/invitations",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    request_body = InviteUsersRequest,
+    responses(
+        (status = 200, description = "Invitations for everyone named, existing ones included", body = Vec<InvitationResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 409, description = "The event has been cancelled or has completed"),
+        (status = 422, description = "Validation error or unknown users"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/invitations")]
+pub async fn invite(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+    payload: Json<InviteUsersRequest>,
+) -> Result<Json<Vec<InvitationResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::CoHost).await?;
+    if event.status.is_final() {
+        return Err(closed(event.status));
+    }
+
+    let outcome = invite_users(&data.db, &event, current_user.0.id, &payload.invitees)
+        .await
+        .map_err(|e| {
+            error!("Failed to invite users: {}", e);
+            error::ErrorInternalServerError("Failed to invite users")
+        })?;
+
+    match outcome {
+        InviteOutcome::Invited(invitations) => {
+            Ok(Json(invitations.into_iter().map(Into::into).collect()))
+        }
+        InviteOutcome::UnknownUsers(unknown) => Err(error::ErrorUnprocessableEntity(format!(
+            "No active user found for: {}",
+            unknown.join(", ")
+        ))),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{id}/invitations",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    responses(
+        (status = 200, description = "Everyone invited to the event and their answers", body = Vec<InvitationResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:read` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/{id}/invitations")]
+pub async fn list_event_invitations(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<Vec<InvitationResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsRead)?;
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::CoHost).await?;
+
+    let invitations = list_invitations(&data.db, event.id).await.map_err(|e| {
+        error!("Failed to list invitations: {}", e);
+        error::ErrorInternalServerError("Failed to list invitations")
+    })?;
+
+    Ok(Json(invitations.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/events/{id}/invitations/{invitation_id}",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+        ("invitation_id" = i32, Path, description = "Invitation ID"),
+    ),
+    responses(
+        (status = 204, description = "Invitation withdrawn, along with the invitee's RSVP"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event or invitation not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[delete("/{id}/invitations/{invitation_id}")]
+pub async fn delete_invitation(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<(i32, i32)>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    let (event_id, invitation_id) = path.into_inner();
+    let event = managed_event(&data, &current_user, event_id, EventRole::CoHost).await?;
+
+    let revoked = revoke_invitation(&data.db, event.id, invitation_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to revoke invitation: {}", e);
+            error::ErrorInternalServerError("Failed to revoke invitation")
+        })?;
+
+    if revoked {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(error::ErrorNotFound("Invitation not found"))
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{id}/invite-links",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    request_body = CreateInviteLinkRequest,
+    responses(
+        (status = 201, description = "Invite link created", body = CreatedInviteLinkResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 409, description = "The event has been cancelled or has completed"),
+        (status = 422, description = "Validation error"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/invite-links")]
+pub async fn create_link(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+    payload: Json<CreateInviteLinkRequest>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    if payload
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= chrono::Utc::now())
+    {
+        return Err(error::ErrorUnprocessableEntity(
+            "expires_at must be in the future",
+        ));
+    }
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::CoHost).await?;
+    if event.status.is_final() {
+        return Err(closed(event.status));
+    }
+
+    let payload = payload.into_inner();
+    let (link, token) = create_invite_link(
+        &data.db,
+        event.id,
+        current_user.0.id,
+        NewInviteLink {
+            max_uses: payload.max_uses,
+            expires_at: payload.expires_at,
+        },
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to create invite link: {}", e);
+        error::ErrorInternalServerError("Failed to create invite link")
+    })?;
+
+    Ok(HttpResponse::Created().json(CreatedInviteLinkResponse {
+        link: link.into(),
+        url: format!(
+            "{}/invite/{}",
+            data.config.public_url.trim_end_matches('/'),
+            token
+        ),
+        token,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{id}/invite-links",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    responses(
+        (status = 200, description = "The event's invite links, newest first", body = Vec<InviteLinkResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:read` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/{id}/invite-links")]
+pub async fn list_links(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<Vec<InviteLinkResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsRead)?;
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::CoHost).await?;
+
+    let links = list_invite_links(&data.db, event.id).await.map_err(|e| {
+        error!("Failed to list invite links: {}", e);
+        error::ErrorInternalServerError("Failed to list invite links")
+    })?;
+
+    Ok(Json(links.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/events/{id}/invite-links/{link_id}",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+        ("link_id" = i32, Path, description = "Invite link ID"),
+    ),
+    responses(
+        (status = 204, description = "Link revoked; invitations made through it stay"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event or link not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[delete("/{id}/invite-links/{link_id}")]
+pub async fn delete_link(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<(i32, i32)>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    let (event_id, link_id) = path.into_inner();
+    let event = managed_event(&data, &current_user, event_id, EventRole::CoHost).await?;
+
+    let revoked = revoke_invite_link(&data.db, event.id, link_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to revoke invite link: {}", e);
+            error::ErrorInternalServerError("Failed to revoke invite link")
+        })?;
+
+    if revoked {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(error::ErrorNotFound("Invite link not found"))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/invitations",
+    responses(
+        (status = 200, description = "The current user's invitations to events that have not ended, soonest first", body = Vec<UserInvitationResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:read` scope"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("")]
+pub async fn list_mine(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+) -> Result<Json<Vec<UserInvitationResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsRead)?;
+
+    let invitations = user_invitations(&data.db, current_user.0.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to list invitations: {}", e);
+            error::ErrorInternalServerError("Failed to list invitations")
+        })?;
+
+    Ok(Json(
+        invitations
+            .into_iter()
+            .map(|(invitation, event)| UserInvitationResponse {
+                invitation: invitation.into(),
+                event: EventResponse::from(event),
+            })
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/invitations/{id}/accept",
+    params(
+        ("id" = i32, Path, description = "Invitation ID"),
+    ),
+    responses(
+        (status = 200, description = "Invitation accepted and the user registered for the event", body = InvitationResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope"),
+        (status = 404, description = "Invitation not found"),
+        (status = 409, description = "The event has been cancelled or has completed"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/accept")]
+pub async fn accept(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<InvitationResponse>, Error> {
+    respond(&data, &current_user, path.into_inner(), true).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/invitations/{id}/decline",
+    params(
+        ("id" = i32, Path, description = "Invitation ID"),
+    ),
+    responses(
+        (status = 200, description = "Invitation declined and any RSVP given up", body = InvitationResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope"),
+        (status = 404, description = "Invitation not found"),
+        (status = 409, description = "The event has been cancelled or has completed"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/decline")]
+pub async fn decline(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<InvitationResponse>, Error> {
+    respond(&data, &current_user, path.into_inner(), false).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/invitations/redeem",
+    request_body = RedeemInviteLinkRequest,
+    responses(
+        (status = 200, description = "The current user's invitation to the event", body = InvitationResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope"),
+        (status = 404, description = "Unknown invite link"),
+        (status = 409, description = "The event has been cancelled or has completed"),
+        (status = 410, description = "The link has been revoked, has expired or has been used up"),
+        (status = 422, description = "Validation error"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/redeem")]
+pub async fn redeem(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    payload: Json<RedeemInviteLinkRequest>,
+) -> Result<Json<InvitationResponse>, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+
+    let redemption = redeem_invite_link(&data.db, &payload.token, current_user.0.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to redeem invite link: {}", e);
+            error::ErrorInternalServerError("Failed to redeem invite link")
+        })?;
+
+    match redemption {
+        LinkRedemption::Redeemed(invitation) => Ok(Json(invitation.into())),
+        LinkRedemption::NotFound => Err(error::ErrorNotFound("Invite link not found")),
+        LinkRedemption::Unusable => Err(error::ErrorGone("This invite link is no longer valid")),
+        LinkRedemption::Closed(status) => Err(closed(status)),
+    }
+}
+
+async fn respond(
+    data: &AppState,
+    current_user: &CurrentUser,
+    invitation_id: i32,
+    accepting: bool,
+) -> Result<Json<InvitationResponse>, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+
+    let reply = respond_to_invitation(&data.db, invitation_id, &current_user.0, accepting)
+        .await
+        .map_err(|e| {
+            error!("Failed to answer invitation: {}", e);
+            error::ErrorInternalServerError("Failed to answer invitation")
+        })?;
+
+    match reply {
+        InvitationReply::Responded(invitation) => Ok(Json(invitation.into())),
+        InvitationReply::NotFound => Err(error::ErrorNotFound("Invitation not found")),
+        InvitationReply::Closed(status) => Err(closed(status)),
+    }
+}
+
+/// 409 for acting on an event that has been cancelled or has completed.
+pub(crate) fn closed(status: EventStatus) -> Error {
+    match status {
+        EventStatus::Cancelled => error::ErrorConflict("This event has been cancelled"),
+        _ => error::ErrorConflict("This event has already completed"),
+    }
+}
@@ -0,0 +1,136 @@
+use actix_web::{
+    Error, HttpResponse, Result, delete, error, get, post,
+    web::{Data, Json, Path, Query},
+};
+use tracing::error;
+use validator::Validate;
+
+use crate::core::configs::AppState;
+use crate::schemas::jobs::{JobListQuery, JobsOverviewResponse};
+use crate::utils::auth_extractor::CurrentUser;
+use crate::utils::redact::validation_summary;
+
+/// Dead jobs listed when `limit` is not given.
+const DEFAULT_DEAD_JOBS: usize = 50;
+
+#[utoipa::path(
+    get,
+    path = "/admin/jobs",
+    params(JobListQuery),
+    responses(
+        (status = 200, description = "Queue sizes and the most recent dead jobs", body = JobsOverviewResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Not an administrator"),
+        (status = 422, description = "Validation error"),
+        (status = 503, description = "Job queue unavailable"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/jobs")]
+pub async fn list_jobs(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    query: Query<JobListQuery>,
+) -> Result<Json<JobsOverviewResponse>, Error> {
+    current_user.require_session()?;
+    current_user.require_admin()?;
+    query.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+
+    let stats = data.jobs.stats().await.map_err(unavailable)?;
+    let dead = data
+        .jobs
+        .dead_jobs(query.limit.unwrap_or(DEFAULT_DEAD_JOBS))
+        .await
+        .map_err(unavailable)?;
+
+    Ok(Json(JobsOverviewResponse {
+        stats: stats.into(),
+        dead: dead.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/jobs/{id}/retry",
+    params(
+        ("id" = String, Path, description = "Job ID"),
+    ),
+    responses(
+        (status = 204, description = "Job queued again with a fresh set of attempts"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "No dead job with this ID"),
+        (status = 503, description = "Job queue unavailable"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/jobs/{id}/retry")]
+pub async fn retry_job(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<String>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_session()?;
+    current_user.require_admin()?;
+
+    let retried = data
+        .jobs
+        .retry_dead(&path.into_inner())
+        .await
+        .map_err(unavailable)?;
+    if !retried {
+        return Err(error::ErrorNotFound("Dead job not found"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/jobs/{id}",
+    params(
+        ("id" = String, Path, description = "Job ID"),
+    ),
+    responses(
+        (status = 204, description = "Dead job discarded"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "No dead job with this ID"),
+        (status = 503, description = "Job queue unavailable"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[delete("/jobs/{id}")]
+pub async fn delete_job(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<String>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_session()?;
+    current_user.require_admin()?;
+
+    let deleted = data
+        .jobs
+        .delete_dead(&path.into_inner())
+        .await
+        .map_err(unavailable)?;
+    if !deleted {
+        return Err(error::ErrorNotFound("Dead job not found"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+fn unavailable(e: Box<dyn std::error::Error>) -> Error {
+    error!("Job queue error: {}", e);
+    error::ErrorServiceUnavailable("Job queue unavailable")
+}
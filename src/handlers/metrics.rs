@@ -0,0 +1,55 @@
+use actix_web::{Error, HttpRequest, HttpResponse, Result, error, get, http::header, web::Data};
+use chrono::{Duration, Utc};
+use subtle::ConstantTimeEq;
+use tracing::error;
+
+use crate::core::configs::AppState;
+use crate::services::events::count_upcoming_events;
+use crate::services::sessions::count_active_users;
+
+/// Prometheus scrape endpoint.
+///
+/// When `METRICS_TOKEN` is set the scraper must send it as a bearer token.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses(
+        (status = 200, description = "Metrics in the Prometheus text format", content_type = "text/plain"),
+        (status = 401, description = "Missing or wrong metrics token"),
+    )
+)]
+#[get("/metrics")]
+pub async fn metrics(req: HttpRequest, data: Data<AppState>) -> Result<HttpResponse, Error> {
+    if let Some(token) = &data.config.metrics_token {
+        let provided = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .unwrap_or_default();
+        if !bool::from(provided.as_bytes().ct_eq(token.as_bytes())) {
+            return Err(error::ErrorUnauthorized("Invalid metrics token"));
+        }
+    }
+
+    data.metrics.set_redis_pool_status(data.redis_pool.status());
+
+    // Keep serving the other metrics if the database is down
+    let active_users = count_active_users(&data.db, Utc::now() - Duration::hours(24)).await;
+    let upcoming_events = count_upcoming_events(&data.db).await;
+    match (active_users, upcoming_events) {
+        (Ok(active_users), Ok(upcoming_events)) => data
+            .metrics
+            .set_business_gauges(active_users, upcoming_events),
+        (Err(e), _) | (_, Err(e)) => error!("Failed to refresh business metrics: {}", e),
+    }
+
+    let body = data.metrics.render().map_err(|e| {
+        error!("Failed to encode metrics: {}", e);
+        error::ErrorInternalServerError("Failed to encode metrics")
+    })?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(body))
+}
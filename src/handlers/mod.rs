@@ -1,2 +1,16 @@
+pub mod api_keys;
 pub mod auth;
+pub mod calendar;
+pub mod event_staff;
+pub mod events;
+pub mod health;
+pub mod invitations;
+pub mod jobs;
+pub mod metrics;
+pub mod notifications;
+pub mod payments;
+pub mod reference_data;
+pub mod search;
+pub mod tags;
+pub mod tickets;
 pub mod users;
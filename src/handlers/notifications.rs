@@ -0,0 +1,93 @@
+use actix_web::{
+    Error, HttpResponse, Result, error, get, post,
+    web::{Data, Json, Path, Query},
+};
+use tracing::error;
+use validator::Validate;
+
+use crate::core::configs::AppState;
+use crate::schemas::notification::{NotificationListQuery, NotificationResponse};
+use crate::services::notifications::{list_notifications, mark_notification_read};
+use crate::utils::auth_extractor::CurrentUser;
+use crate::utils::redact::validation_summary;
+
+/// Page size when `limit` is not given.
+const DEFAULT_PAGE_SIZE: u64 = 50;
+
+#[utoipa::path(
+    get,
+    path = "/notifications",
+    params(NotificationListQuery),
+    responses(
+        (status = 200, description = "The current user's notifications, newest first", body = Vec<NotificationResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Validation error"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("")]
+pub async fn list(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    query: Query<NotificationListQuery>,
+) -> Result<Json<Vec<NotificationResponse>>, Error> {
+    current_user.require_session()?;
+    query.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+
+    let notifications = list_notifications(
+        &data.db,
+        current_user.0.id,
+        query.unread.unwrap_or_default(),
+        query.limit.unwrap_or(DEFAULT_PAGE_SIZE),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to list notifications: {}", e);
+        error::ErrorInternalServerError("Failed to list notifications")
+    })?;
+
+    Ok(Json(notifications.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/notifications/{id}/read",
+    params(
+        ("id" = i32, Path, description = "Notification ID"),
+    ),
+    responses(
+        (status = 204, description = "Notification marked as read"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Notification not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/read")]
+pub async fn mark_read(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_session()?;
+
+    let found = mark_notification_read(&data.db, current_user.0.id, path.into_inner())
+        .await
+        .map_err(|e| {
+            error!("Failed to mark notification as read: {}", e);
+            error::ErrorInternalServerError("Failed to mark notification as read")
+        })?;
+    if !found {
+        return Err(error::ErrorNotFound("Notification not found"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
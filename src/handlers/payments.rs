@@ -0,0 +1,63 @@
+use actix_web::{
+    Error, HttpRequest, HttpResponse, Result, error, post,
+    web::{Bytes, Data},
+};
+use tracing::{error, info, warn};
+
+use crate::core::configs::AppState;
+use crate::services::payments::WebhookError;
+use crate::services::tickets::{WebhookOutcome, handle_webhook};
+
+#[utoipa::path(
+    post,
+    path = "/payments/webhook",
+    request_body(content = String, description = "The provider's event, exactly as sent", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Event processed, already processed or not one acted on"),
+        (status = 400, description = "Signed, but not an event the provider documents"),
+        (status = 401, description = "Missing or invalid signature"),
+        (status = 500, description = "Internal server error; the provider retries"),
+    )
+)]
+#[post("/webhook")]
+pub async fn payment_webhook(
+    data: Data<AppState>,
+    req: HttpRequest,
+    body: Bytes,
+) -> Result<HttpResponse, Error> {
+    let payments = data.payments.as_ref();
+    let signature = req
+        .headers()
+        .get(payments.signature_header())
+        .and_then(|value| value.to_str().ok());
+
+    let event = match payments.parse_webhook(&body, signature) {
+        Ok(Some(event)) => event,
+        Ok(None) => return Ok(HttpResponse::Ok().finish()),
+        Err(WebhookError::InvalidSignature) => {
+            warn!("Rejected {} webhook with a bad signature", payments.name());
+            return Err(error::ErrorUnauthorized("Invalid signature"));
+        }
+        Err(e) => {
+            warn!("Rejected {} webhook: {}", payments.name(), e);
+            return Err(error::ErrorBadRequest("Malformed webhook"));
+        }
+    };
+
+    let outcome = handle_webhook(&data.db, payments, &event)
+        .await
+        .map_err(|e| {
+            error!("Failed to process webhook {}: {}", event.id, e);
+            error::ErrorInternalServerError("Failed to process webhook")
+        })?;
+    match outcome {
+        WebhookOutcome::Processed => {}
+        WebhookOutcome::Duplicate => info!("Webhook {} was already processed", event.id),
+        WebhookOutcome::UnknownPayment => warn!(
+            "Webhook {} is about unknown payment {}",
+            event.id, event.payment_reference
+        ),
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
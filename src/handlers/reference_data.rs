@@ -0,0 +1,29 @@
+use actix_web::{
+    Error, Result, error, get,
+    web::{Data, Json},
+};
+use tracing::error;
+
+use crate::core::configs::AppState;
+use crate::schemas::event::ReferenceDataResponse;
+use crate::services::reference_data::list_reference_data;
+
+#[utoipa::path(
+    get,
+    path = "/reference-data",
+    responses(
+        (status = 200, description = "Event categories and motivations", body = ReferenceDataResponse),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+#[get("/reference-data")]
+pub async fn reference_data(data: Data<AppState>) -> Result<Json<ReferenceDataResponse>, Error> {
+    let reference_data = list_reference_data(&data.db, &data.cache)
+        .await
+        .map_err(|e| {
+            error!("Failed to load reference data: {}", e);
+            error::ErrorInternalServerError("Failed to load reference data")
+        })?;
+
+    Ok(Json(reference_data))
+}
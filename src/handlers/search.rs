@@ -0,0 +1,63 @@
+use actix_web::{
+    Error, Result, error, get,
+    web::{Data, Json, Query},
+};
+use tracing::error;
+use validator::Validate;
+
+use crate::core::configs::AppState;
+use crate::handlers::events::{labelled, viewer_timezone};
+use crate::schemas::search::{SearchQuery, SearchResponse};
+use crate::services::search::{search as search_all, search_terms};
+use crate::utils::auth_extractor::MaybeCurrentUser;
+use crate::utils::redact::validation_summary;
+
+/// Results of each kind when `limit` is not given.
+const DEFAULT_RESULTS: u64 = 10;
+
+#[utoipa::path(
+    get,
+    path = "/search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Matching public events, hosts and, for signed-in callers, users, best match first", body = SearchResponse),
+        (status = 422, description = "Validation error, or the query has no letters or digits"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+#[get("/search")]
+pub async fn search(
+    data: Data<AppState>,
+    current_user: MaybeCurrentUser,
+    query: Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, Error> {
+    query.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    let terms = search_terms(&query.q);
+    if terms.is_empty() {
+        return Err(error::ErrorUnprocessableEntity(
+            "Search for at least one letter or digit",
+        ));
+    }
+
+    // Only people who have signed in can look other people up
+    let results = search_all(
+        &data.db,
+        &terms,
+        current_user.0.is_some(),
+        query.limit.unwrap_or(DEFAULT_RESULTS),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to search: {}", e);
+        error::ErrorInternalServerError("Failed to search")
+    })?;
+
+    Ok(Json(SearchResponse {
+        events: labelled(&data, results.events, viewer_timezone(&current_user)).await?,
+        hosts: results.hosts.into_iter().map(Into::into).collect(),
+        users: results.users.into_iter().map(Into::into).collect(),
+    }))
+}
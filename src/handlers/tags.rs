@@ -0,0 +1,50 @@
+use actix_web::{
+    Error, Result, error, get,
+    web::{Data, Json, Query},
+};
+use tracing::error;
+use validator::Validate;
+
+use crate::core::configs::AppState;
+use crate::schemas::tag::{TagQuery, TagResponse};
+use crate::services::tags::suggest_tags;
+use crate::utils::redact::validation_summary;
+use crate::utils::tags::normalize_tag;
+
+/// Suggestions when `limit` is not given.
+const DEFAULT_SUGGESTIONS: u64 = 10;
+
+#[utoipa::path(
+    get,
+    path = "/tags",
+    params(TagQuery),
+    responses(
+        (status = 200, description = "Tags starting with the prefix, most used on public events first", body = Vec<TagResponse>),
+        (status = 422, description = "Validation error"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+#[get("/tags")]
+pub async fn autocomplete_tags(
+    data: Data<AppState>,
+    query: Query<TagQuery>,
+) -> Result<Json<Vec<TagResponse>>, Error> {
+    query.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    let prefix = normalize_tag(&query.prefix).map_err(error::ErrorUnprocessableEntity)?;
+
+    let tags = suggest_tags(
+        &data.db,
+        &prefix,
+        query.limit.unwrap_or(DEFAULT_SUGGESTIONS),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to suggest tags: {}", e);
+        error::ErrorInternalServerError("Failed to suggest tags")
+    })?;
+
+    Ok(Json(tags.into_iter().map(Into::into).collect()))
+}
@@ -0,0 +1,755 @@
+use actix_web::{
+    Error, HttpResponse, Result, delete, error, get, patch, post,
+    web::{Data, Json, Path},
+};
+use tracing::error;
+use validator::Validate;
+
+use crate::core::configs::AppState;
+use crate::entity::api_key::ApiScope;
+use crate::entity::{DiscountType, EventRole, OrderStatus};
+use crate::handlers::events::managed_event;
+use crate::handlers::invitations::closed;
+use crate::jobs::{EnqueueOptions, Job};
+use crate::schemas::ticket::{
+    CheckoutRequest, CreatePromoCodeRequest, CreateTicketTierRequest, LedgerEntryResponse,
+    OrderResponse, PromoCodeResponse, TicketTierResponse, UpdateTicketTierRequest,
+};
+use crate::services::invitations::get_visible_event;
+use crate::services::tickets::{
+    Checkout, NewPromoCode, NewTicketTier, OrderUpdate, PromoCreation, Refund, TicketTierChanges,
+    TierRemoval, TierSave, cancel_order, checkout, confirm_order, create_promo_code, create_tier,
+    delete_promo_code, delete_tier, get_order, list_event_orders, list_ledger, list_orders,
+    list_promo_codes, list_tiers, refund_order, update_tier,
+};
+use crate::utils::auth_extractor::{CurrentUser, MaybeCurrentUser};
+use crate::utils::redact::validation_summary;
+use crate::utils::tickets::{normalize_currency, normalize_promo_code};
+
+#[utoipa::path(
+    get,
+    path = "/events/{id}/ticket-tiers",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    responses(
+        (status = 200, description = "The event's ticket tiers, cheapest first; empty for free events", body = Vec<TicketTierResponse>),
+        (status = 404, description = "Event not found, or private and the caller is not invited"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+#[get("/{id}/ticket-tiers")]
+pub async fn list_ticket_tiers(
+    data: Data<AppState>,
+    current_user: MaybeCurrentUser,
+    path: Path<i32>,
+) -> Result<Json<Vec<TicketTierResponse>>, Error> {
+    let event = get_visible_event(
+        &data.db,
+        &data.cache,
+        path.into_inner(),
+        current_user.0.as_ref(),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch event: {}", e);
+        error::ErrorInternalServerError("Failed to fetch event")
+    })?
+    .ok_or_else(|| error::ErrorNotFound("Event not found"))?;
+
+    let tiers = list_tiers(&data.db, event.id).await.map_err(|e| {
+        error!("Failed to list ticket tiers: {}", e);
+        error::ErrorInternalServerError("Failed to list ticket tiers")
+    })?;
+
+    Ok(Json(tiers.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{id}/ticket-tiers",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    request_body = CreateTicketTierRequest,
+    responses(
+        (status = 201, description = "Tier created; the event now takes tickets instead of RSVPs", body = TicketTierResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 409, description = "The event has been cancelled or has completed, or already has a tier of that name"),
+        (status = 422, description = "Validation error"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/ticket-tiers")]
+pub async fn create_ticket_tier(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+    payload: Json<CreateTicketTierRequest>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    let currency =
+        normalize_currency(&payload.currency).map_err(error::ErrorUnprocessableEntity)?;
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::CoHost).await?;
+    if event.status.is_final() {
+        return Err(closed(event.status));
+    }
+
+    let payload = payload.into_inner();
+    let outcome = create_tier(
+        &data.db,
+        event.id,
+        NewTicketTier {
+            name: payload.name.trim().to_string(),
+            description: payload.description,
+            price: payload.price,
+            currency,
+            quantity: payload.quantity,
+            sales_start_at: payload.sales_start_at,
+            sales_end_at: payload.sales_end_at,
+        },
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to create ticket tier: {}", e);
+        error::ErrorInternalServerError("Failed to create ticket tier")
+    })?;
+
+    match outcome {
+        TierSave::Saved(tier) => Ok(HttpResponse::Created().json(TicketTierResponse::from(tier))),
+        other => Err(tier_error(other)),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/events/{id}/ticket-tiers/{tier_id}",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+        ("tier_id" = i32, Path, description = "Ticket tier ID"),
+    ),
+    request_body = UpdateTicketTierRequest,
+    responses(
+        (status = 200, description = "Tier updated", body = TicketTierResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event or tier not found"),
+        (status = 409, description = "The event already has a tier of that name, or more tickets are sold or held than the new quantity"),
+        (status = 422, description = "Validation error"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[patch("/{id}/ticket-tiers/{tier_id}")]
+pub async fn update_ticket_tier(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<(i32, i32)>,
+    payload: Json<UpdateTicketTierRequest>,
+) -> Result<Json<TicketTierResponse>, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    let (event_id, tier_id) = path.into_inner();
+    let event = managed_event(&data, &current_user, event_id, EventRole::CoHost).await?;
+
+    let payload = payload.into_inner();
+    let outcome = update_tier(
+        &data.db,
+        event.id,
+        tier_id,
+        TicketTierChanges {
+            name: payload.name.map(|name| name.trim().to_string()),
+            description: payload.description,
+            price: payload.price,
+            quantity: payload.quantity,
+            sales_start_at: payload.sales_start_at,
+            sales_end_at: payload.sales_end_at,
+        },
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to update ticket tier: {}", e);
+        error::ErrorInternalServerError("Failed to update ticket tier")
+    })?;
+
+    match outcome {
+        TierSave::Saved(tier) => Ok(Json(tier.into())),
+        other => Err(tier_error(other)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/events/{id}/ticket-tiers/{tier_id}",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+        ("tier_id" = i32, Path, description = "Ticket tier ID"),
+    ),
+    responses(
+        (status = 204, description = "Tier deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event or tier not found"),
+        (status = 409, description = "Tickets of the tier have been ordered"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[delete("/{id}/ticket-tiers/{tier_id}")]
+pub async fn delete_ticket_tier(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<(i32, i32)>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    let (event_id, tier_id) = path.into_inner();
+    let event = managed_event(&data, &current_user, event_id, EventRole::CoHost).await?;
+
+    let outcome = delete_tier(&data.db, event.id, tier_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete ticket tier: {}", e);
+            error::ErrorInternalServerError("Failed to delete ticket tier")
+        })?;
+
+    match outcome {
+        TierRemoval::Removed => Ok(HttpResponse::NoContent().finish()),
+        TierRemoval::NotFound => Err(error::ErrorNotFound("Ticket tier not found")),
+        TierRemoval::HasOrders => Err(error::ErrorConflict(
+            "Tickets of this tier have been ordered; lower its quantity instead",
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{id}/promo-codes",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    responses(
+        (status = 200, description = "The event's promo codes, newest first", body = Vec<PromoCodeResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:read` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/{id}/promo-codes")]
+pub async fn list_event_promo_codes(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<Vec<PromoCodeResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsRead)?;
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::CoHost).await?;
+
+    let codes = list_promo_codes(&data.db, event.id).await.map_err(|e| {
+        error!("Failed to list promo codes: {}", e);
+        error::ErrorInternalServerError("Failed to list promo codes")
+    })?;
+
+    Ok(Json(codes.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{id}/promo-codes",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    request_body = CreatePromoCodeRequest,
+    responses(
+        (status = 201, description = "Promo code created", body = PromoCodeResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 409, description = "The event has been cancelled or has completed, or already has the code"),
+        (status = 422, description = "Validation error"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/promo-codes")]
+pub async fn create_event_promo_code(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+    payload: Json<CreatePromoCodeRequest>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    let code = normalize_promo_code(&payload.code).map_err(error::ErrorUnprocessableEntity)?;
+    if payload.discount_type == DiscountType::Percentage && payload.amount > 100 {
+        return Err(error::ErrorUnprocessableEntity(
+            "Percentage discounts are at most 100",
+        ));
+    }
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::CoHost).await?;
+    if event.status.is_final() {
+        return Err(closed(event.status));
+    }
+
+    let outcome = create_promo_code(
+        &data.db,
+        event.id,
+        NewPromoCode {
+            code,
+            discount_type: payload.discount_type,
+            amount: payload.amount,
+            max_uses: payload.max_uses,
+            expires_at: payload.expires_at,
+        },
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to create promo code: {}", e);
+        error::ErrorInternalServerError("Failed to create promo code")
+    })?;
+
+    match outcome {
+        PromoCreation::Created(promo) => {
+            Ok(HttpResponse::Created().json(PromoCodeResponse::from(promo)))
+        }
+        PromoCreation::Duplicate => Err(error::ErrorConflict("The event already has this code")),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/events/{id}/promo-codes/{promo_id}",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+        ("promo_id" = i32, Path, description = "Promo code ID"),
+    ),
+    responses(
+        (status = 204, description = "Promo code deleted; orders that used it keep their discount"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event or promo code not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[delete("/{id}/promo-codes/{promo_id}")]
+pub async fn delete_event_promo_code(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<(i32, i32)>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    let (event_id, promo_id) = path.into_inner();
+    let event = managed_event(&data, &current_user, event_id, EventRole::CoHost).await?;
+
+    let deleted = delete_promo_code(&data.db, event.id, promo_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete promo code: {}", e);
+            error::ErrorInternalServerError("Failed to delete promo code")
+        })?;
+
+    if !deleted {
+        return Err(error::ErrorNotFound("Promo code not found"));
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{id}/checkout",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    request_body = CheckoutRequest,
+    responses(
+        (status = 201, description = "Tickets held until `expires_at`; pay at `checkout_url`. Free orders come back paid", body = OrderResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope"),
+        (status = 404, description = "Event or tier not found"),
+        (status = 409, description = "The event has been cancelled or has completed, the tier is not on sale or too few tickets are left"),
+        (status = 422, description = "Validation error, or the promo code is unknown, expired or used up"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/checkout")]
+pub async fn checkout_tickets(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+    payload: Json<CheckoutRequest>,
+) -> Result<HttpResponse, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    let promo_code = payload
+        .promo_code
+        .as_deref()
+        .map(normalize_promo_code)
+        .transpose()
+        .map_err(error::ErrorUnprocessableEntity)?;
+
+    let outcome = checkout(
+        &data.db,
+        data.payments.as_ref(),
+        &current_user.0,
+        path.into_inner(),
+        payload.tier_id,
+        payload.quantity,
+        promo_code.as_deref(),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to check out: {}", e);
+        error::ErrorInternalServerError("Failed to check out")
+    })?;
+
+    match outcome {
+        Checkout::Started(order) => {
+            if order.status == OrderStatus::Pending {
+                // Best effort: checkouts also release expired holds
+                if let Err(e) = data
+                    .jobs
+                    .enqueue(
+                        Job::ExpireTicketHold { order_id: order.id },
+                        EnqueueOptions::at(order.expires_at)
+                            .idempotency_key(format!("expire-ticket-hold:{}", order.id)),
+                    )
+                    .await
+                {
+                    error!(
+                        "Failed to schedule hold expiry for order {}: {}",
+                        order.id, e
+                    );
+                }
+            }
+            Ok(HttpResponse::Created().json(OrderResponse::from(*order)))
+        }
+        Checkout::EventNotFound => Err(error::ErrorNotFound("Event not found")),
+        Checkout::TierNotFound => Err(error::ErrorNotFound("Ticket tier not found")),
+        Checkout::Closed(status) => Err(closed(status)),
+        Checkout::NotOnSale => Err(error::ErrorConflict("These tickets are not on sale")),
+        Checkout::SoldOut { available: 0 } => Err(error::ErrorConflict("Sold out")),
+        Checkout::SoldOut { available } => Err(error::ErrorConflict(format!(
+            "Only {} ticket(s) left",
+            available
+        ))),
+        Checkout::InvalidPromoCode => Err(error::ErrorUnprocessableEntity(
+            "This promo code is not valid",
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{id}/orders",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    responses(
+        (status = 200, description = "Every ticket order for the event, newest first", body = Vec<OrderResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:read` scope, or the user is not a host or co-host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/{id}/orders")]
+pub async fn list_ticket_orders(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<Vec<OrderResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsRead)?;
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::CoHost).await?;
+
+    let orders = list_event_orders(&data.db, event.id).await.map_err(|e| {
+        error!("Failed to list orders: {}", e);
+        error::ErrorInternalServerError("Failed to list orders")
+    })?;
+
+    Ok(Json(orders.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{id}/orders/{order_id}/refund",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+        ("order_id" = i32, Path, description = "Order ID"),
+    ),
+    responses(
+        (status = 200, description = "Money given back; the buyer's registration is dropped and the tickets go back on sale", body = OrderResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope, or the user is not the host"),
+        (status = 404, description = "Event or order not found"),
+        (status = 409, description = "The order is not paid"),
+        (status = 500, description = "Internal server error, or the payment provider refused the refund; the order stays paid"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/orders/{order_id}/refund")]
+pub async fn refund_ticket_order(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<(i32, i32)>,
+) -> Result<Json<OrderResponse>, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+    let (event_id, order_id) = path.into_inner();
+    let event = managed_event(&data, &current_user, event_id, EventRole::Host).await?;
+
+    let outcome = refund_order(&data.db, data.payments.as_ref(), event.id, order_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to refund order {}: {}", order_id, e);
+            error::ErrorInternalServerError("Failed to refund order")
+        })?;
+
+    match outcome {
+        Refund::Refunded(order) => Ok(Json((*order).into())),
+        Refund::NotFound => Err(error::ErrorNotFound("Order not found")),
+        Refund::NotPaid(status) => Err(error::ErrorConflict(format!(
+            "Only paid orders can be refunded; this one is {:?}",
+            status
+        ))),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{id}/ledger",
+    params(
+        ("id" = i32, Path, description = "Event ID"),
+    ),
+    responses(
+        (status = 200, description = "Every charge and refund for the event, oldest first", body = Vec<LedgerEntryResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:read` scope, or the user is not the host"),
+        (status = 404, description = "Event not found or not managed by the current user"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/{id}/ledger")]
+pub async fn event_ledger(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<Vec<LedgerEntryResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsRead)?;
+    let event = managed_event(&data, &current_user, path.into_inner(), EventRole::Host).await?;
+
+    let entries = list_ledger(&data.db, event.id).await.map_err(|e| {
+        error!("Failed to list ledger entries: {}", e);
+        error::ErrorInternalServerError("Failed to list ledger entries")
+    })?;
+
+    Ok(Json(entries.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/orders",
+    responses(
+        (status = 200, description = "The current user's ticket orders, newest first", body = Vec<OrderResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:read` scope"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("")]
+pub async fn list_my_orders(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+) -> Result<Json<Vec<OrderResponse>>, Error> {
+    current_user.require_scope(ApiScope::EventsRead)?;
+
+    let orders = list_orders(&data.db, current_user.0.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to list orders: {}", e);
+            error::ErrorInternalServerError("Failed to list orders")
+        })?;
+
+    Ok(Json(orders.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/orders/{id}",
+    params(
+        ("id" = i32, Path, description = "Order ID"),
+    ),
+    responses(
+        (status = 200, description = "One of the current user's orders", body = OrderResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:read` scope"),
+        (status = 404, description = "Order not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/{id}")]
+pub async fn get_my_order(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<OrderResponse>, Error> {
+    current_user.require_scope(ApiScope::EventsRead)?;
+
+    let order = get_order(&data.db, current_user.0.id, path.into_inner())
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch order: {}", e);
+            error::ErrorInternalServerError("Failed to fetch order")
+        })?
+        .ok_or_else(|| error::ErrorNotFound("Order not found"))?;
+
+    Ok(Json(order.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/orders/{id}/confirm",
+    params(
+        ("id" = i32, Path, description = "Order ID"),
+    ),
+    responses(
+        (status = 200, description = "The order after checking its payment: paid and registered, still pending, failed or expired", body = OrderResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope"),
+        (status = 404, description = "Order not found"),
+        (status = 409, description = "The order was already cancelled, failed or expired"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/confirm")]
+pub async fn confirm_my_order(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<OrderResponse>, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+
+    let outcome = confirm_order(
+        &data.db,
+        data.payments.as_ref(),
+        &current_user.0,
+        path.into_inner(),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to confirm order: {}", e);
+        error::ErrorInternalServerError("Failed to confirm order")
+    })?;
+
+    order_update(outcome)
+}
+
+#[utoipa::path(
+    post,
+    path = "/orders/{id}/cancel",
+    params(
+        ("id" = i32, Path, description = "Order ID"),
+    ),
+    responses(
+        (status = 200, description = "Order cancelled and its tickets released", body = OrderResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "API key is missing the `events:write` scope"),
+        (status = 404, description = "Order not found"),
+        (status = 409, description = "The order is no longer pending"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{id}/cancel")]
+pub async fn cancel_my_order(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    path: Path<i32>,
+) -> Result<Json<OrderResponse>, Error> {
+    current_user.require_scope(ApiScope::EventsWrite)?;
+
+    let outcome = cancel_order(&data.db, &current_user.0, path.into_inner())
+        .await
+        .map_err(|e| {
+            error!("Failed to cancel order: {}", e);
+            error::ErrorInternalServerError("Failed to cancel order")
+        })?;
+
+    order_update(outcome)
+}
+
+fn order_update(outcome: OrderUpdate) -> Result<Json<OrderResponse>, Error> {
+    match outcome {
+        OrderUpdate::Updated(order) => Ok(Json((*order).into())),
+        OrderUpdate::NotFound => Err(error::ErrorNotFound("Order not found")),
+        OrderUpdate::Settled(status) => Err(error::ErrorConflict(format!(
+            "This order is already {:?}",
+            status
+        ))),
+    }
+}
+
+fn tier_error(outcome: TierSave) -> Error {
+    match outcome {
+        TierSave::Saved(_) => error::ErrorInternalServerError("Unexpected ticket tier outcome"),
+        TierSave::NotFound => error::ErrorNotFound("Ticket tier not found"),
+        TierSave::DuplicateName => {
+            error::ErrorConflict("The event already has a tier of that name")
+        }
+        TierSave::BelowReserved(reserved) => {
+            error::ErrorConflict(format!("{} ticket(s) are already sold or held", reserved))
+        }
+        TierSave::InvalidSaleWindow => {
+            error::ErrorUnprocessableEntity("Sales must end after they start")
+        }
+    }
+}
@@ -1,8 +1,13 @@
 use crate::core::configs::AppState;
-use crate::schemas::user::{SignShow, SignUp};
-use crate::services::users::create_user;
+use crate::jobs::{EnqueueOptions, Job};
+use crate::schemas::auth::UserMeResponse;
+use crate::schemas::user::{SignShow, SignUp, TimezoneUpdate};
+use crate::services::users::{create_user, set_user_timezone};
+use crate::utils::auth_extractor::CurrentUser;
+use crate::utils::redact::validation_summary;
+use crate::utils::timezone::parse_timezone;
 use actix_web::{
-    Error, Responder, Result, error, get, post,
+    Error, Result, error, post, put,
     web::{Data, Json},
 };
 use tracing::error;
@@ -22,32 +27,79 @@ use validator::Validate;
 pub async fn signup(data: Data<AppState>, payload: Json<SignUp>) -> Result<Json<SignShow>, Error> {
     // 1. Handle Validation Error (Client Error)
     payload.validate().map_err(|e| {
-        error!("Validation error: {}", e);
+        error!("Validation error: {}", validation_summary(&e));
         // This is okay, but a structured JSON error is even better.
         // We'll keep it for simplicity.
-        error::ErrorUnprocessableEntity(format!("Validation error: {}", e))
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
     })?;
 
     let signup_data: SignUp = payload.into_inner();
 
     // 2. Handle Service/Database Error (Server Error)
-    let user: SignShow = create_user(&data.db, signup_data).await.map_err(|e| {
-        error!("Database error during user creation: {}", e);
+    let user: SignShow = create_user(&data.db, signup_data, data.config.hash_rounds)
+        .await
+        .map_err(|e| {
+            error!("Database error during user creation: {}", e);
+
+            // Send a generic, safe error to the client
+            error::ErrorInternalServerError("An error occurred while creating the account.")
+        })?;
+
+    // The account is usable without verifying, so a queue outage must not fail signup;
+    // the user can ask for another email later
+    if let Err(e) = data
+        .jobs
+        .enqueue(
+            Job::SendVerificationEmail { user_id: user.id },
+            EnqueueOptions::default(),
+        )
+        .await
+    {
+        error!("Failed to queue verification email: {}", e);
+    }
 
-        // Send a generic, safe error to the client
-        error::ErrorInternalServerError("An error occurred while creating the account.")
-    })?;
     Ok(Json(user))
 }
 
 #[utoipa::path(
-    get,
-    path = "/users/health",
+    put,
+    path = "/users/me/timezone",
+    request_body = TimezoneUpdate,
     responses(
-        (status = 200, description = "Health check OK"),
+        (status = 200, description = "Preferred time zone saved", body = UserMeResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Requires a login session"),
+        (status = 422, description = "Unknown time zone"),
+        (status = 500, description = "Internal server error"),
+    ),
+    security(
+        ("bearer_auth" = [])
     )
 )]
-#[get("/health")]
-pub async fn health_check() -> impl Responder {
-    "OK"
+#[put("/me/timezone")]
+pub async fn update_timezone(
+    data: Data<AppState>,
+    current_user: CurrentUser,
+    payload: Json<TimezoneUpdate>,
+) -> Result<Json<UserMeResponse>, Error> {
+    current_user.require_session()?;
+    payload.validate().map_err(|e| {
+        error!("Validation error: {}", validation_summary(&e));
+        error::ErrorUnprocessableEntity(format!("Validation error: {}", validation_summary(&e)))
+    })?;
+    let timezone = payload
+        .into_inner()
+        .timezone
+        .map(|name| parse_timezone(&name).map(|tz| tz.name().to_string()))
+        .transpose()
+        .map_err(error::ErrorUnprocessableEntity)?;
+
+    let user = set_user_timezone(&data.db, &data.cache, current_user.0.id, timezone)
+        .await
+        .map_err(|e| {
+            error!("Failed to update time zone: {}", e);
+            error::ErrorInternalServerError("Failed to update time zone")
+        })?;
+
+    Ok(Json(user.into()))
 }
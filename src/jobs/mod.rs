@@ -0,0 +1,271 @@
+//! Background jobs.
+//!
+//! Work that should not hold up a request (sending email, reminders, event
+//! status changes) is described by a [`Job`], pushed onto the Redis-backed
+//! [`JobQueue`] and run by a [`worker`] inside the server process or the
+//! separate `here-worker` binary.
+pub mod queue;
+pub mod worker;
+
+use std::error::Error;
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::core::configs::AppState;
+use crate::entity::prelude::*;
+use crate::entity::user_token::TokenPurpose;
+use crate::entity::{AttendanceStatus, EventStatus};
+use crate::services::email::{
+    EmailMessage, event_cancelled_email, event_reminder_email, password_reset_email,
+    verification_email,
+};
+use crate::services::event_lifecycle::{
+    advance_due_events, advance_event_status, affected_attendees,
+};
+use crate::services::event_series::extend_all_series;
+use crate::services::tickets::{expire_order, refund_event_orders};
+use crate::services::user_tokens::issue_token;
+use crate::services::users::get_user_model_by_id;
+
+pub use queue::{EnqueueOptions, Enqueued, JobQueue};
+
+/// How long before an event starts its attendees are reminded.
+pub const REMINDER_LEAD_TIME: Duration = Duration::hours(24);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Job {
+    /// Deliver one email as is
+    SendEmail(EmailMessage),
+    /// Issue a verification token and email the link
+    SendVerificationEmail { user_id: i32 },
+    /// Issue a password reset token and email the link
+    SendPasswordResetEmail { user_id: i32 },
+    /// Queue a reminder email for every registered attendee, unless the
+    /// event has moved away from `start_time` since this was scheduled
+    EventReminder {
+        event_id: i32,
+        start_time: DateTime<Utc>,
+    },
+    /// Move an event to the status its start and end times call for
+    EventStatusTransition { event_id: i32 },
+    /// Move every event whose start or end time has passed; queued by the
+    /// workers every minute as a safety net for the per-event jobs
+    EventLifecycleSweep,
+    /// Email every registered and waitlisted attendee of a cancelled event
+    EventCancelledEmails { event_id: i32 },
+    /// Create the next occurrences of recurring series; queued by the
+    /// workers every hour
+    RecurringSeriesSweep,
+    /// Give back the tickets of an order that was not paid in time
+    ExpireTicketHold { order_id: i32 },
+    /// Refund every paid order of a cancelled event
+    RefundEventOrders { event_id: i32 },
+}
+
+impl Job {
+    /// Stable name used in logs and metrics.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Job::SendEmail(_) => "send_email",
+            Job::SendVerificationEmail { .. } => "send_verification_email",
+            Job::SendPasswordResetEmail { .. } => "send_password_reset_email",
+            Job::EventReminder { .. } => "event_reminder",
+            Job::EventStatusTransition { .. } => "event_status_transition",
+            Job::EventLifecycleSweep => "event_lifecycle_sweep",
+            Job::EventCancelledEmails { .. } => "event_cancelled_emails",
+            Job::RecurringSeriesSweep => "recurring_series_sweep",
+            Job::ExpireTicketHold { .. } => "expire_ticket_hold",
+            Job::RefundEventOrders { .. } => "refund_event_orders",
+        }
+    }
+}
+
+/// Run one job. An error makes the queue retry it later, so every job must
+/// be safe to run more than once.
+pub async fn perform(job: &Job, state: &AppState) -> Result<(), Box<dyn Error>> {
+    match job {
+        Job::SendEmail(message) => state.mailer.send(message).await,
+        Job::SendVerificationEmail { user_id } => {
+            let user = get_user_model_by_id(&state.db, *user_id).await?;
+            if user.email_verified_at.is_some() || !user.is_active {
+                return Ok(());
+            }
+            let token = issue_token(&state.db, user.id, TokenPurpose::VerifyEmail).await?;
+            let link = format!("{}/verify-email?token={}", public_url(state), token);
+            state.mailer.send(&verification_email(&user, &link)).await
+        }
+        Job::SendPasswordResetEmail { user_id } => {
+            let user = get_user_model_by_id(&state.db, *user_id).await?;
+            if !user.is_active {
+                return Ok(());
+            }
+            let token = issue_token(&state.db, user.id, TokenPurpose::PasswordReset).await?;
+            let link = format!("{}/reset-password?token={}", public_url(state), token);
+            state.mailer.send(&password_reset_email(&user, &link)).await
+        }
+        Job::EventReminder {
+            event_id,
+            start_time,
+        } => send_event_reminders(state, *event_id, *start_time).await,
+        Job::EventStatusTransition { event_id } => {
+            advance_event_status(&state.db, &state.cache, &state.domain_events, *event_id).await?;
+            Ok(())
+        }
+        Job::EventLifecycleSweep => {
+            let transitions =
+                advance_due_events(&state.db, &state.cache, &state.domain_events).await?;
+            if transitions > 0 {
+                info!("Applied {} scheduled event status change(s)", transitions);
+            }
+            Ok(())
+        }
+        Job::EventCancelledEmails { event_id } => send_cancellation_emails(state, *event_id).await,
+        Job::RecurringSeriesSweep => {
+            let occurrences = extend_all_series(&state.db, &state.cache).await?;
+            if !occurrences.is_empty() {
+                info!(
+                    "Created {} recurring event occurrence(s)",
+                    occurrences.len()
+                );
+            }
+            for occurrence in &occurrences {
+                schedule_event_jobs(&state.jobs, occurrence).await?;
+            }
+            Ok(())
+        }
+        Job::ExpireTicketHold { order_id } => {
+            expire_order(&state.db, *order_id).await?;
+            Ok(())
+        }
+        Job::RefundEventOrders { event_id } => {
+            let refunded =
+                refund_event_orders(&state.db, state.payments.as_ref(), *event_id).await?;
+            if refunded > 0 {
+                info!(
+                    "Refunded {} order(s) of cancelled event {}",
+                    refunded, event_id
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Like [`send_event_reminders`], one `SendEmail` job per attendee.
+async fn send_cancellation_emails(state: &AppState, event_id: i32) -> Result<(), Box<dyn Error>> {
+    let Some(event) = Event::find_by_id(event_id).one(&state.db).await? else {
+        return Ok(());
+    };
+    if event.status != EventStatus::Cancelled {
+        return Ok(());
+    }
+
+    for user_id in affected_attendees(&state.db, event_id).await? {
+        let user = get_user_model_by_id(&state.db, user_id).await?;
+        if !user.is_active {
+            continue;
+        }
+        state
+            .jobs
+            .enqueue(
+                Job::SendEmail(event_cancelled_email(&user, &event)),
+                EnqueueOptions::default()
+                    .idempotency_key(format!("event-cancelled:{}:{}", event.id, user.id)),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Fan out one `SendEmail` job per registered attendee, so a single bad
+/// address cannot hold up the rest. Idempotency keys keep a retried
+/// reminder from emailing anyone twice. A reminder scheduled for an
+/// earlier `start_time` does nothing; the event's current one covers it.
+async fn send_event_reminders(
+    state: &AppState,
+    event_id: i32,
+    start_time: DateTime<Utc>,
+) -> Result<(), Box<dyn Error>> {
+    let Some(event) = Event::find_by_id(event_id).one(&state.db).await? else {
+        return Ok(());
+    };
+    if event.start_time != start_time
+        || event.status != EventStatus::Scheduled
+        || event.start_time <= Utc::now()
+    {
+        return Ok(());
+    }
+
+    let attendees = Attendance::find()
+        .filter(AttendanceColumn::EventId.eq(event_id))
+        .filter(AttendanceColumn::Status.eq(AttendanceStatus::Registered))
+        .all(&state.db)
+        .await?;
+    for attendance in attendees {
+        let user = get_user_model_by_id(&state.db, attendance.attendee_id).await?;
+        if !user.is_active {
+            continue;
+        }
+        state
+            .jobs
+            .enqueue(
+                Job::SendEmail(event_reminder_email(&user, &event)),
+                EnqueueOptions::default().idempotency_key(format!(
+                    "event-reminder:{}:{}:{}",
+                    event.id,
+                    event.start_time.timestamp(),
+                    user.id
+                )),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Queue the reminder and the status transitions for an event. Keys include
+/// the times, so scheduling the same event again is a no-op while moving
+/// it schedules fresh jobs. Stale reminders carry the old start time and
+/// skip themselves; stale transitions find nothing to do.
+pub async fn schedule_event_jobs(
+    queue: &JobQueue,
+    event: &EventModel,
+) -> Result<(), Box<dyn Error>> {
+    let reminder_at = event.start_time - REMINDER_LEAD_TIME;
+    if reminder_at > Utc::now() {
+        queue
+            .enqueue(
+                Job::EventReminder {
+                    event_id: event.id,
+                    start_time: event.start_time,
+                },
+                EnqueueOptions::at(reminder_at).idempotency_key(format!(
+                    "event-reminder:{}:{}",
+                    event.id,
+                    event.start_time.timestamp()
+                )),
+            )
+            .await?;
+    }
+
+    for at in [event.start_time, event.end_time] {
+        queue
+            .enqueue(
+                Job::EventStatusTransition { event_id: event.id },
+                EnqueueOptions::at(at).idempotency_key(format!(
+                    "event-status:{}:{}",
+                    event.id,
+                    at.timestamp()
+                )),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+fn public_url(state: &AppState) -> &str {
+    state.config.public_url.trim_end_matches('/')
+}
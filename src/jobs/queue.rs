@@ -0,0 +1,548 @@
+//! Durable job queue on Redis.
+//!
+//! Keys (all under `here:jobs`):
+//!
+//! - `job:<id>`: the JSON [`JobEnvelope`]
+//! - `ready`: list of ids waiting for a worker
+//! - `delayed`: sorted set of ids scored by when they may run (delayed jobs and retries)
+//! - `processing`: list of ids a worker has reserved
+//! - `leases`: sorted set of reserved ids scored by when the reservation
+//!   lapses; lapsed jobs are retried, so a crashed worker loses nothing
+//! - `dead`: list of ids that failed `max_attempts` times
+//! - `idempotency:<key>`: id of the job enqueued with that key
+use std::error::Error;
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use deadpool_redis::{Connection, Pool as RedisPool};
+use redis::{FromRedisValue, Pipeline};
+use serde::{Deserialize, Serialize};
+use tokio::time::timeout;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use super::Job;
+
+const KEY_PREFIX: &str = "here:jobs";
+const READY: &str = "here:jobs:ready";
+const DELAYED: &str = "here:jobs:delayed";
+const PROCESSING: &str = "here:jobs:processing";
+const LEASES: &str = "here:jobs:leases";
+const DEAD: &str = "here:jobs:dead";
+
+/// Upper bound for one Redis round trip, so callers never hang on Redis.
+const OPERATION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a worker may hold a job before it is handed to another one.
+/// Must exceed the longest job run time.
+pub const LEASE_DURATION: Duration = Duration::from_secs(300);
+
+/// Minimum lifetime of an idempotency key.
+const IDEMPOTENCY_TTL: chrono::Duration = chrono::Duration::days(7);
+
+/// Dead jobs are kept this long for inspection.
+const DEAD_JOB_TTL: Duration = Duration::from_secs(30 * 24 * 3600);
+
+/// Jobs moved per maintenance pass.
+const BATCH_SIZE: usize = 100;
+
+/// Longest `last_error` kept on an envelope.
+const MAX_ERROR_LEN: usize = 500;
+
+/// Moves due ids from `delayed` to `ready` atomically, so two workers never
+/// promote the same job twice.
+const PROMOTE_SCRIPT: &str = r"
+local ids = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', ARGV[1], 'LIMIT', 0, ARGV[2])
+for _, id in ipairs(ids) do
+    redis.call('ZREM', KEYS[1], id)
+    redis.call('LPUSH', KEYS[2], id)
+end
+return #ids
+";
+
+/// Stores a new job and queues it in one step, so an idempotency key is
+/// never left claimed without its job. With an idempotency key (`KEYS[4]`)
+/// the id of the job that already holds it is returned instead.
+///
+/// KEYS: job key, `ready`, `delayed`, idempotency key (optional).
+/// ARGV: id, envelope, run at in ms or `''` to run now, key TTL in seconds.
+const ENQUEUE_SCRIPT: &str = r"
+if KEYS[4] then
+    local existing = redis.call('GET', KEYS[4])
+    if existing then
+        return existing
+    end
+    redis.call('SET', KEYS[4], ARGV[1], 'EX', ARGV[4])
+end
+redis.call('SET', KEYS[1], ARGV[2])
+if ARGV[3] == '' then
+    redis.call('LPUSH', KEYS[2], ARGV[1])
+else
+    redis.call('ZADD', KEYS[3], ARGV[3], ARGV[1])
+end
+return ARGV[1]
+";
+
+/// Takes the next ready id into `processing` together with its lease, so
+/// no job is ever in flight without one, and returns it with its envelope.
+///
+/// KEYS: `ready`, `processing`, `leases`. ARGV: lease end in ms, job key prefix.
+const RESERVE_SCRIPT: &str = r"
+local id = redis.call('RPOP', KEYS[1])
+if not id then
+    return false
+end
+redis.call('LPUSH', KEYS[2], id)
+redis.call('ZADD', KEYS[3], ARGV[1], id)
+return {id, redis.call('GET', ARGV[2] .. id)}
+";
+
+/// A job plus its bookkeeping, as stored in Redis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobEnvelope {
+    pub id: String,
+    pub job: Job,
+    /// Failed runs so far
+    pub attempts: u32,
+    pub max_attempts: u32,
+    pub enqueued_at: DateTime<Utc>,
+    /// Earliest time of the next run
+    pub run_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EnqueueOptions {
+    /// Run no earlier than this; `None` runs as soon as a worker is free
+    pub run_at: Option<DateTime<Utc>>,
+    /// Enqueueing again with the same key returns the first job instead of
+    /// adding another one, for at least seven days (or until `run_at`)
+    pub idempotency_key: Option<String>,
+}
+
+impl EnqueueOptions {
+    pub fn at(run_at: DateTime<Utc>) -> Self {
+        EnqueueOptions {
+            run_at: Some(run_at),
+            ..Default::default()
+        }
+    }
+
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enqueued {
+    pub id: String,
+    /// True when an earlier job with the same idempotency key was returned
+    pub duplicate: bool,
+}
+
+/// What happened to a failed job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailOutcome {
+    Retrying(DateTime<Utc>),
+    Dead,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QueueStats {
+    pub ready: u64,
+    pub delayed: u64,
+    pub processing: u64,
+    pub dead: u64,
+}
+
+/// Delay before retry number `attempts`: 30s doubling per attempt, capped at an hour.
+pub fn retry_delay(attempts: u32) -> chrono::Duration {
+    let seconds = 30i64.saturating_mul(1 << attempts.saturating_sub(1).min(16));
+    chrono::Duration::seconds(seconds.min(3600))
+}
+
+#[derive(Debug, Clone)]
+pub struct JobQueue {
+    pool: RedisPool,
+    max_attempts: u32,
+}
+
+impl JobQueue {
+    pub fn new(pool: RedisPool, max_attempts: u32) -> Self {
+        JobQueue { pool, max_attempts }
+    }
+
+    async fn conn(&self) -> Result<Connection, Box<dyn Error>> {
+        Ok(bounded(self.pool.get()).await??)
+    }
+
+    pub async fn enqueue(
+        &self,
+        job: Job,
+        options: EnqueueOptions,
+    ) -> Result<Enqueued, Box<dyn Error>> {
+        let mut conn = self.conn().await?;
+        let now = Utc::now();
+        let run_at = options.run_at.unwrap_or(now).max(now);
+        let id = Uuid::new_v4().to_string();
+
+        let envelope = JobEnvelope {
+            id: id.clone(),
+            job,
+            attempts: 0,
+            max_attempts: self.max_attempts,
+            enqueued_at: now,
+            run_at,
+            last_error: None,
+            idempotency_key: options.idempotency_key,
+        };
+        let mut script = redis::cmd("EVAL");
+        script
+            .arg(ENQUEUE_SCRIPT)
+            .arg(if envelope.idempotency_key.is_some() {
+                4
+            } else {
+                3
+            })
+            .arg(job_key(&id))
+            .arg(READY)
+            .arg(DELAYED);
+        if let Some(key) = &envelope.idempotency_key {
+            script.arg(idempotency_key(key));
+        }
+        let ttl = IDEMPOTENCY_TTL.max(run_at - now + chrono::Duration::days(1));
+        script
+            .arg(&id)
+            .arg(serde_json::to_string(&envelope)?)
+            .arg(if run_at > now {
+                run_at.timestamp_millis().to_string()
+            } else {
+                String::new()
+            })
+            .arg(ttl.num_seconds());
+        let queued: String = query(&mut script, &mut conn).await?;
+
+        Ok(Enqueued {
+            duplicate: queued != id,
+            id: queued,
+        })
+    }
+
+    /// Wait up to `block` for a ready job and lease it to the caller, who
+    /// must then call [`complete`](Self::complete) or [`fail`](Self::fail).
+    pub async fn reserve(&self, block: Duration) -> Result<Option<JobEnvelope>, Box<dyn Error>> {
+        let mut conn = self.conn().await?;
+        // Only waits for a job: moving the tail of `ready` onto its own tail
+        // leaves the list as it was
+        let mut blmove = redis::cmd("BLMOVE");
+        blmove
+            .arg(READY)
+            .arg(READY)
+            .arg("RIGHT")
+            .arg("RIGHT")
+            .arg(block.as_secs_f64());
+        let waited = blmove.query_async::<Option<String>>(&mut conn);
+        if timeout(block + OPERATION_TIMEOUT, waited)
+            .await
+            .map_err(|_| "Redis operation timed out")??
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let lease_until = Utc::now() + chrono::Duration::from_std(LEASE_DURATION)?;
+        // Another worker may have taken the job in the meantime
+        let Some((id, raw)): Option<(String, Option<String>)> = query(
+            redis::cmd("EVAL")
+                .arg(RESERVE_SCRIPT)
+                .arg(3)
+                .arg(READY)
+                .arg(PROCESSING)
+                .arg(LEASES)
+                .arg(lease_until.timestamp_millis())
+                .arg(job_key("")),
+            &mut conn,
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        match raw.map(|raw| serde_json::from_str::<JobEnvelope>(&raw)) {
+            Some(Ok(envelope)) => Ok(Some(envelope)),
+            Some(Err(e)) => {
+                // Written by an incompatible version; park it for an operator
+                error!(
+                    "Job {} cannot be decoded, moving it to the dead list: {}",
+                    id, e
+                );
+                let mut pipe = redis::pipe();
+                pipe.atomic();
+                release(&mut pipe, &id);
+                pipe.cmd("LPUSH").arg(DEAD).arg(&id).ignore();
+                run::<()>(&pipe, &mut conn).await?;
+                Ok(None)
+            }
+            None => {
+                warn!("Job {} vanished before it ran", id);
+                let mut pipe = redis::pipe();
+                release(&mut pipe, &id);
+                run::<()>(&pipe, &mut conn).await?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Remove a successfully run job.
+    pub async fn complete(&self, envelope: &JobEnvelope) -> Result<(), Box<dyn Error>> {
+        let mut conn = self.conn().await?;
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        release(&mut pipe, &envelope.id);
+        pipe.cmd("DEL").arg(job_key(&envelope.id)).ignore();
+        run(&pipe, &mut conn).await
+    }
+
+    /// Record a failed run: schedule a retry with backoff, or move the job
+    /// to the dead list once it has used up its attempts.
+    pub async fn fail(
+        &self,
+        envelope: &JobEnvelope,
+        error: &str,
+    ) -> Result<FailOutcome, Box<dyn Error>> {
+        let mut conn = self.conn().await?;
+        let now = Utc::now();
+        let mut envelope = envelope.clone();
+        envelope.attempts += 1;
+        envelope.last_error = Some(error.chars().take(MAX_ERROR_LEN).collect());
+
+        let outcome = if envelope.attempts >= envelope.max_attempts {
+            FailOutcome::Dead
+        } else {
+            envelope.run_at = now + retry_delay(envelope.attempts);
+            FailOutcome::Retrying(envelope.run_at)
+        };
+
+        let id = envelope.id.clone();
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .cmd("SET")
+            .arg(job_key(&id))
+            .arg(serde_json::to_string(&envelope)?)
+            .ignore();
+        release(&mut pipe, &id);
+        match outcome {
+            FailOutcome::Dead => {
+                pipe.cmd("EXPIRE")
+                    .arg(job_key(&id))
+                    .arg(DEAD_JOB_TTL.as_secs())
+                    .ignore()
+                    .cmd("LPUSH")
+                    .arg(DEAD)
+                    .arg(&id)
+                    .ignore();
+            }
+            FailOutcome::Retrying(run_at) => schedule(&mut pipe, &id, run_at, now),
+        }
+        run::<()>(&pipe, &mut conn).await?;
+
+        Ok(outcome)
+    }
+
+    /// Move delayed jobs that are due onto the ready list.
+    pub async fn promote_due(&self) -> Result<u64, Box<dyn Error>> {
+        let mut conn = self.conn().await?;
+        query(
+            redis::cmd("EVAL")
+                .arg(PROMOTE_SCRIPT)
+                .arg(2)
+                .arg(DELAYED)
+                .arg(READY)
+                .arg(Utc::now().timestamp_millis())
+                .arg(BATCH_SIZE),
+            &mut conn,
+        )
+        .await
+    }
+
+    /// Treat jobs whose lease lapsed (their worker died or hung) as failed
+    /// runs, so they are retried or dead-lettered like any other failure.
+    pub async fn recover_stalled(&self) -> Result<u64, Box<dyn Error>> {
+        let mut conn = self.conn().await?;
+        let ids: Vec<String> = query(
+            redis::cmd("ZRANGEBYSCORE")
+                .arg(LEASES)
+                .arg("-inf")
+                .arg(Utc::now().timestamp_millis())
+                .arg("LIMIT")
+                .arg(0)
+                .arg(BATCH_SIZE),
+            &mut conn,
+        )
+        .await?;
+
+        let mut recovered = 0;
+        for id in ids {
+            // Whoever removes the lease owns the recovery
+            let removed: u64 = query(redis::cmd("ZREM").arg(LEASES).arg(&id), &mut conn).await?;
+            if removed == 0 {
+                continue;
+            }
+            if let Some(envelope) = self.get(&id).await? {
+                warn!(
+                    "Job {} ({}) lease expired, retrying",
+                    id,
+                    envelope.job.name()
+                );
+                self.fail(&envelope, "Worker lease expired").await?;
+                recovered += 1;
+            }
+        }
+        Ok(recovered)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<JobEnvelope>, Box<dyn Error>> {
+        let mut conn = self.conn().await?;
+        let raw: Option<String> = query(redis::cmd("GET").arg(job_key(id)), &mut conn).await?;
+        Ok(raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+    }
+
+    pub async fn stats(&self) -> Result<QueueStats, Box<dyn Error>> {
+        let mut conn = self.conn().await?;
+        let mut pipe = redis::pipe();
+        pipe.cmd("LLEN")
+            .arg(READY)
+            .cmd("ZCARD")
+            .arg(DELAYED)
+            .cmd("LLEN")
+            .arg(PROCESSING)
+            .cmd("LLEN")
+            .arg(DEAD);
+        let (ready, delayed, processing, dead) = run(&pipe, &mut conn).await?;
+        Ok(QueueStats {
+            ready,
+            delayed,
+            processing,
+            dead,
+        })
+    }
+
+    /// The most recently dead-lettered jobs, newest first.
+    pub async fn dead_jobs(&self, limit: usize) -> Result<Vec<JobEnvelope>, Box<dyn Error>> {
+        let mut conn = self.conn().await?;
+        let ids: Vec<String> = query(
+            redis::cmd("LRANGE")
+                .arg(DEAD)
+                .arg(0)
+                .arg(limit.saturating_sub(1)),
+            &mut conn,
+        )
+        .await?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let keys: Vec<String> = ids.iter().map(|id| job_key(id)).collect();
+        let raw: Vec<Option<String>> = query(redis::cmd("MGET").arg(keys), &mut conn).await?;
+        Ok(raw
+            .into_iter()
+            .flatten()
+            .filter_map(|raw| serde_json::from_str(&raw).ok())
+            .collect())
+    }
+
+    /// Put a dead job back on the ready list with a fresh set of attempts.
+    /// Returns `false` when `id` is not dead.
+    pub async fn retry_dead(&self, id: &str) -> Result<bool, Box<dyn Error>> {
+        let Some(mut envelope) = self.get(id).await? else {
+            return Ok(false);
+        };
+        let mut conn = self.conn().await?;
+        let removed: u64 = query(redis::cmd("LREM").arg(DEAD).arg(1).arg(id), &mut conn).await?;
+        if removed == 0 {
+            return Ok(false);
+        }
+
+        envelope.attempts = 0;
+        envelope.run_at = Utc::now();
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .cmd("SET")
+            .arg(job_key(id))
+            .arg(serde_json::to_string(&envelope)?)
+            .ignore()
+            .cmd("LPUSH")
+            .arg(READY)
+            .arg(id)
+            .ignore();
+        run::<()>(&pipe, &mut conn).await?;
+        Ok(true)
+    }
+
+    /// Drop a dead job for good. Returns `false` when `id` is not dead.
+    pub async fn delete_dead(&self, id: &str) -> Result<bool, Box<dyn Error>> {
+        let mut conn = self.conn().await?;
+        let removed: u64 = query(redis::cmd("LREM").arg(DEAD).arg(1).arg(id), &mut conn).await?;
+        if removed > 0 {
+            query::<()>(redis::cmd("DEL").arg(job_key(id)), &mut conn).await?;
+        }
+        Ok(removed > 0)
+    }
+}
+
+fn job_key(id: &str) -> String {
+    format!("{}:job:{}", KEY_PREFIX, id)
+}
+
+fn idempotency_key(key: &str) -> String {
+    format!("{}:idempotency:{}", KEY_PREFIX, key)
+}
+
+/// Queue `id` on `ready`, or on `delayed` when it is not due yet.
+fn schedule(pipe: &mut Pipeline, id: &str, run_at: DateTime<Utc>, now: DateTime<Utc>) {
+    if run_at > now {
+        pipe.cmd("ZADD")
+            .arg(DELAYED)
+            .arg(run_at.timestamp_millis())
+            .arg(id)
+            .ignore();
+    } else {
+        pipe.cmd("LPUSH").arg(READY).arg(id).ignore();
+    }
+}
+
+/// Drop `id` from the in-flight bookkeeping.
+fn release(pipe: &mut Pipeline, id: &str) {
+    pipe.cmd("LREM")
+        .arg(PROCESSING)
+        .arg(1)
+        .arg(id)
+        .ignore()
+        .cmd("ZREM")
+        .arg(LEASES)
+        .arg(id)
+        .ignore();
+}
+
+async fn bounded<T>(operation: impl Future<Output = T>) -> Result<T, Box<dyn Error>> {
+    timeout(OPERATION_TIMEOUT, operation)
+        .await
+        .map_err(|_| "Redis operation timed out".into())
+}
+
+async fn query<T: FromRedisValue>(
+    cmd: &mut redis::Cmd,
+    conn: &mut Connection,
+) -> Result<T, Box<dyn Error>> {
+    Ok(bounded(cmd.query_async(conn)).await??)
+}
+
+async fn run<T: FromRedisValue>(
+    pipe: &Pipeline,
+    conn: &mut Connection,
+) -> Result<T, Box<dyn Error>> {
+    Ok(bounded(pipe.query_async(conn)).await??)
+}
@@ -0,0 +1,184 @@
+//! Job worker.
+//!
+//! A worker repeatedly promotes due delayed jobs, recovers jobs whose
+//! lease lapsed, reserves the next ready job and runs it. Once a minute it
+//! also queues an [`Job::EventLifecycleSweep`], and once an hour a
+//! [`Job::RecurringSeriesSweep`]. Any number of workers may share one
+//! queue: every step is safe to race.
+use std::error::Error;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+
+use tokio::sync::watch;
+use tokio::time::{sleep, timeout};
+use tracing::{Instrument, error, info, info_span, warn};
+
+use super::queue::{FailOutcome, JobEnvelope, LEASE_DURATION};
+use super::{EnqueueOptions, Job, perform};
+use crate::core::configs::AppState;
+
+/// How long one reserve call blocks waiting for a job. Bounds how long a
+/// delayed job can sit past its due time and how quickly shutdown is noticed.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest a single job may run; kept under the lease so a slow job is not
+/// handed to a second worker while the first is still on it.
+const JOB_TIMEOUT: Duration = Duration::from_secs(LEASE_DURATION.as_secs() * 4 / 5);
+
+/// How often the event lifecycle sweep is queued.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the recurring series sweep is queued.
+const SERIES_SWEEP_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Pause after a Redis error, doubling up to the maximum while Redis is down.
+const MIN_ERROR_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_ERROR_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Run jobs from `state.jobs` until `shutdown` turns true. A job that is
+/// running when shutdown is requested is finished first.
+pub async fn run_worker(state: AppState, mut shutdown: watch::Receiver<bool>) {
+    info!("Job worker started.");
+    let mut backoff = MIN_ERROR_BACKOFF;
+    let mut sweeps = Sweeps {
+        lifecycle: Instant::now(),
+        series: Instant::now(),
+    };
+
+    while !*shutdown.borrow() {
+        match tick(&state, &mut sweeps).await {
+            Ok(()) => backoff = MIN_ERROR_BACKOFF,
+            Err(e) => {
+                warn!("Job queue unavailable, retrying in {:?}: {}", backoff, e);
+                tokio::select! {
+                    _ = sleep(backoff) => {}
+                    Ok(()) = shutdown.changed() => {}
+                }
+                backoff = (backoff * 2).min(MAX_ERROR_BACKOFF);
+            }
+        }
+    }
+
+    info!("Job worker stopped.");
+}
+
+/// When each periodic sweep is next queued.
+struct Sweeps {
+    lifecycle: Instant,
+    series: Instant,
+}
+
+/// One round of maintenance plus at most one job.
+async fn tick(state: &AppState, sweeps: &mut Sweeps) -> Result<(), Box<dyn Error>> {
+    state.jobs.promote_due().await?;
+    state.jobs.recover_stalled().await?;
+
+    if Instant::now() >= sweeps.lifecycle {
+        // Keyed by minute, so however many workers run only one sweep is queued
+        let minute = Utc::now().timestamp() / SWEEP_INTERVAL.as_secs() as i64;
+        state
+            .jobs
+            .enqueue(
+                Job::EventLifecycleSweep,
+                EnqueueOptions::default()
+                    .idempotency_key(format!("event-lifecycle-sweep:{}", minute)),
+            )
+            .await?;
+        sweeps.lifecycle = Instant::now() + SWEEP_INTERVAL;
+    }
+    if Instant::now() >= sweeps.series {
+        let hour = Utc::now().timestamp() / SERIES_SWEEP_INTERVAL.as_secs() as i64;
+        state
+            .jobs
+            .enqueue(
+                Job::RecurringSeriesSweep,
+                EnqueueOptions::default()
+                    .idempotency_key(format!("recurring-series-sweep:{}", hour)),
+            )
+            .await?;
+        sweeps.series = Instant::now() + SERIES_SWEEP_INTERVAL;
+    }
+
+    // Not raced against shutdown: a reserve cancelled after Redis handed
+    // out the job would leave it idle until its lease lapsed
+    if let Some(envelope) = state.jobs.reserve(POLL_INTERVAL).await? {
+        let span = info_span!(
+            "job",
+            job.id = %envelope.id,
+            job.name = envelope.job.name(),
+            job.attempt = envelope.attempts + 1
+        );
+        run_job(state, envelope).instrument(span).await?;
+    }
+    Ok(())
+}
+
+async fn run_job(state: &AppState, envelope: JobEnvelope) -> Result<(), Box<dyn Error>> {
+    let name = envelope.job.name();
+    let result = match timeout(JOB_TIMEOUT, perform(&envelope.job, state)).await {
+        Ok(result) => result,
+        Err(_) => Err(format!("Timed out after {:?}", JOB_TIMEOUT).into()),
+    };
+
+    match result {
+        Ok(()) => {
+            state.jobs.complete(&envelope).await?;
+            state.metrics.record_job(name, "success");
+        }
+        Err(e) => match state.jobs.fail(&envelope, &e.to_string()).await? {
+            FailOutcome::Retrying(run_at) => {
+                warn!(
+                    "Job {} ({}) failed, retrying at {}: {}",
+                    envelope.id, name, run_at, e
+                );
+                state.metrics.record_job(name, "retry");
+            }
+            FailOutcome::Dead => {
+                error!(
+                    "Job {} ({}) failed {} times, moved to the dead list: {}",
+                    envelope.id,
+                    name,
+                    envelope.attempts + 1,
+                    e
+                );
+                state.metrics.record_job(name, "dead");
+            }
+        },
+    }
+    Ok(())
+}
+
+/// A worker running on its own thread; see [`spawn_worker`]. Dropping the
+/// handle detaches the worker, which then runs until the process exits.
+#[derive(Debug)]
+pub struct WorkerHandle {
+    shutdown: watch::Sender<bool>,
+    thread: thread::JoinHandle<()>,
+}
+
+impl WorkerHandle {
+    /// Ask the worker to stop and wait until it has, which includes
+    /// finishing the job it is running.
+    pub fn stop(self) {
+        let _ = self.shutdown.send(true);
+        if self.thread.join().is_err() {
+            error!("Job worker thread panicked");
+        }
+    }
+}
+
+/// Run a worker on a dedicated thread with its own single-threaded runtime,
+/// so jobs never compete with request handling for executor time.
+pub fn spawn_worker(state: AppState) -> std::io::Result<WorkerHandle> {
+    let (shutdown, receiver) = watch::channel(false);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let thread = thread::Builder::new()
+        .name("here-jobs".to_string())
+        .spawn(move || runtime.block_on(run_worker(state, receiver)))?;
+
+    Ok(WorkerHandle { shutdown, thread })
+}
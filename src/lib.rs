@@ -2,7 +2,92 @@ pub mod core;
 pub mod docs;
 pub mod entity;
 pub mod handlers;
+pub mod jobs;
+pub mod migration;
 pub mod routes;
 pub mod schemas;
 pub mod services;
 pub mod utils;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::middleware::from_fn;
+use actix_web::web::{self, Data, ServiceConfig};
+use actix_web::{App, Error};
+use tracing_actix_web::TracingLogger;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::core::configs::{AppConfig, AppState};
+use crate::core::metrics::track_requests;
+use crate::core::security::{cors, security_headers};
+use crate::core::telemetry::RequestSpanBuilder;
+use crate::docs::ApiDoc;
+use crate::utils::payload::{json_config, path_config, payload_config, query_config};
+use crate::utils::request_id::request_id;
+
+/// Register every route, the request middleware and the Swagger UI.
+///
+/// `AppState` must be registered as app data by the caller; the Shuttle
+/// entrypoint does this itself, everything else should use [`build_app`].
+pub fn configure(config: &AppConfig) -> impl FnOnce(&mut ServiceConfig) + use<> {
+    let cors = cors(config);
+    let max_body_bytes = config.max_body_bytes;
+
+    move |cfg: &mut ServiceConfig| {
+        cfg.app_data(json_config(max_body_bytes))
+            .app_data(payload_config(max_body_bytes))
+            .app_data(query_config())
+            .app_data(path_config())
+            .service(
+                // Create a single root scope
+                web::scope("")
+                    // Apply the middleware to this scope; preflight requests
+                    // are answered by CORS but still traced and counted
+                    .wrap(cors)
+                    .wrap(TracingLogger::<RequestSpanBuilder>::new())
+                    .wrap(from_fn(track_requests))
+                    .wrap(from_fn(security_headers))
+                    // Outermost, so the ID exists before the request span is opened
+                    .wrap(from_fn(request_id))
+                    .configure(routes::health::init)
+                    .configure(routes::metrics::init)
+                    .configure(routes::users::init)
+                    .configure(routes::auth::init)
+                    .configure(routes::events::init)
+                    .configure(routes::reference_data::init)
+                    .configure(routes::tags::init)
+                    .configure(routes::search::init)
+                    .configure(routes::notifications::init)
+                    .configure(routes::invitations::init)
+                    .configure(routes::event_staff::init)
+                    .configure(routes::calendar::init)
+                    .configure(routes::tickets::init)
+                    .configure(routes::payments::init)
+                    .configure(routes::admin::init)
+                    .service(
+                        SwaggerUi::new("/docs/{_:.*}")
+                            .url("/api-docs/openapi.json", ApiDoc::openapi()),
+                    ),
+            );
+    }
+}
+
+/// Build the actix `App` for the given state.
+///
+/// Shared by the standalone server and the integration tests so that both
+/// exercise exactly the same wiring.
+pub fn build_app(
+    state: AppState,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = Error,
+        InitError = (),
+    >,
+> {
+    let configure = configure(&state.config);
+    App::new().app_data(Data::new(state)).configure(configure)
+}
@@ -1,21 +1,16 @@
-use actix_web::web;
 // rely on the library crate for modules (declared in src/lib.rs)
 // top-level modules are provided by the `here` crate
-use actix_web::middleware::Logger;
 use actix_web::web::Data;
 use actix_web::web::ServiceConfig;
-use deadpool_redis::{Config as RedisConfig, Runtime};
-use here::core::configs::{AppConfig, AppState};
-use here::docs::ApiDoc;
+use here::core::configs::AppConfig;
+use here::core::startup::{build_app_state, check_migrations};
+use here::jobs::worker::spawn_worker;
 use sea_orm::DatabaseConnection;
-use sea_orm::Schema;
 use sea_orm::SqlxPostgresConnector;
 use shuttle_actix_web::ShuttleActixWeb;
 use sqlx::PgPool;
 use std::collections::HashMap;
 use tracing::info;
-use utoipa::OpenApi;
-use utoipa_swagger_ui::SwaggerUi;
 
 #[shuttle_runtime::main]
 async fn main(
@@ -28,45 +23,22 @@ async fn main(
     // Load configuration from Shuttle secrets with fallback to environment
     let settings: AppConfig =
         AppConfig::from_secrets_or_env(Some(secrets_map)).expect("Failed to load configuration");
-    let redis_cfg: RedisConfig = RedisConfig::from_url(&settings.redis_url);
-    let redis_pool = redis_cfg
-        .create_pool(Some(Runtime::Tokio1))
-        .expect("Failed to create Redis pool");
-    info!("Redis connection pool created.");
 
     let db: DatabaseConnection = SqlxPostgresConnector::from_sqlx_postgres_pool(pool);
     info!("Database connection established.");
 
-    let _schema = Schema::new(db.get_database_backend());
-
-    db.get_schema_registry("here::entity::*")
-        .sync(&db)
+    check_migrations(&db, settings.auto_migrate)
         .await
-        .expect("Failed to sync schema registry");
-
-    info!("Database schema synchronized.");
+        .expect("Database is not migrated");
 
-    let app_state = AppState {
-        db,
-        redis_pool,
-        config: settings.clone(),
-    };
+    let app_state = build_app_state(db, settings).expect("Failed to build application state");
+    if app_state.config.jobs_worker {
+        // Detached: Shuttle gives no hook to stop it, so it runs until the process exits
+        spawn_worker(app_state.clone()).expect("Failed to start job worker");
+    }
     let config = move |cfg: &mut ServiceConfig| {
-        cfg.app_data(Data::new(app_state.clone())).service(
-            // Create a single root scope
-            web::scope("")
-                // Apply the middleware to this scope
-                .wrap(Logger::new(r#"%a - "%r" %s %b %T"#))
-                .configure(here::routes::users::init)
-                .configure(here::routes::auth::init)
-                .service(
-                    SwaggerUi::new("/docs/{_:.*}").url("/api-docs/openapi.json", ApiDoc::openapi()))
-        );
-
-        // NOTE - Swagger UI
-        // cfg.service(
-        //     SwaggerUi::new("/docs/{_:.*}").url("/api-docs/openapi.json", ApiDoc::openapi()),
-        // );
+        cfg.app_data(Data::new(app_state.clone()))
+            .configure(here::configure(&app_state.config));
     };
     Ok(config.into())
 }
@@ -0,0 +1,589 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{Alias, ColumnDef, ForeignKey, ForeignKeyAction, Index, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+use super::{create_enum, drop_enum, drop_table, enum_column, is_postgres, timestamp_now};
+
+pub struct Migration;
+
+const ACCOUNT_TYPE: &[&str] = &["Attendee", "Host"];
+const EVENT_TYPE: &[&str] = &["Physical", "Virtual"];
+const EVENT_CATEGORY: &[&str] = &[
+    "Conference",
+    "Meetup",
+    "Workshop",
+    "Webinar",
+    "Religious",
+    "Social",
+    "Business",
+];
+const SKILL: &[&str] = &[
+    "Event Planning",
+    "Marketing",
+    "Sales",
+    "Management",
+    "Technical",
+    "Videography",
+    "Photography",
+];
+const EVENT_STATUS: &[&str] = &["Scheduled", "Ongoing", "Completed", "Cancelled"];
+const ATTENDANCE_STATUS: &[&str] = &["Registered", "CheckedIn", "NoShow"];
+const EVENT_VISIBILITY: &[&str] = &["Public", "Private"];
+const MOTIVATION: &[&str] = &["Networking", "Learning", "Business", "Socializing"];
+
+const ENUMS: &[(&str, &[&str])] = &[
+    ("account_type", ACCOUNT_TYPE),
+    ("event_type", EVENT_TYPE),
+    ("event_category", EVENT_CATEGORY),
+    ("skill", SKILL),
+    ("event_status", EVENT_STATUS),
+    ("attendance_status", ATTENDANCE_STATUS),
+    ("event_visibility", EVENT_VISIBILITY),
+    ("motivation", MOTIVATION),
+];
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000001_create_base_tables"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        if is_postgres(db) {
+            db.execute_unprepared("CREATE EXTENSION IF NOT EXISTS postgis")
+                .await?;
+        }
+        for (name, values) in ENUMS {
+            create_enum(db, name, values).await?;
+        }
+
+        db.execute(
+            &Table::create()
+                .table(Users::Table)
+                .col(
+                    ColumnDef::new(Users::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Users::Username)
+                        .string()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(
+                    ColumnDef::new(Users::Email)
+                        .string()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(ColumnDef::new(Users::Password).string().not_null())
+                .col(ColumnDef::new(Users::FirstName).string())
+                .col(ColumnDef::new(Users::LastName).string())
+                .col(
+                    enum_column(Users::AccountType, "account_type", ACCOUNT_TYPE)
+                        .default("Attendee")
+                        .to_owned(),
+                )
+                .col(ColumnDef::new(Users::AvatarUrl).string())
+                .col(
+                    ColumnDef::new(Users::IsActive)
+                        .boolean()
+                        .not_null()
+                        .default(true),
+                )
+                .col(timestamp_now(Users::CreatedAt))
+                .col(timestamp_now(Users::UpdatedAt))
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(Hosts::Table)
+                .col(
+                    ColumnDef::new(Hosts::UserId)
+                        .integer()
+                        .not_null()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Hosts::OrganizationName).string())
+                .col(
+                    ColumnDef::new(Hosts::EventsHostedCount)
+                        .integer()
+                        .not_null()
+                        .default(0),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Hosts::Table, Hosts::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(Attendees::Table)
+                .col(
+                    ColumnDef::new(Attendees::UserId)
+                        .integer()
+                        .not_null()
+                        .primary_key(),
+                )
+                .col(enum_column(
+                    Attendees::PreferredEventType,
+                    "event_type",
+                    EVENT_TYPE,
+                ))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Attendees::Table, Attendees::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(Events::Table)
+                .col(
+                    ColumnDef::new(Events::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Events::Title).string().not_null())
+                .col(ColumnDef::new(Events::Description).string().not_null())
+                .col(ColumnDef::new(Events::Location).string().not_null())
+                .col(enum_column(Events::EventType, "event_type", EVENT_TYPE))
+                .col(enum_column(
+                    Events::Category,
+                    "event_category",
+                    EVENT_CATEGORY,
+                ))
+                .col(enum_column(Events::Status, "event_status", EVENT_STATUS))
+                .col(enum_column(
+                    Events::Visibility,
+                    "event_visibility",
+                    EVENT_VISIBILITY,
+                ))
+                .col(ColumnDef::new(Events::HostId).integer().not_null())
+                .col(
+                    ColumnDef::new(Events::StartTime)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(Events::EndTime)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(timestamp_now(Events::CreatedAt))
+                .col(timestamp_now(Events::UpdatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Events::Table, Events::HostId)
+                        .to(Hosts::Table, Hosts::UserId),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-events-host_id")
+                .table(Events::Table)
+                .col(Events::HostId)
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-events-start_time")
+                .table(Events::Table)
+                .col(Events::StartTime)
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(Attendance::Table)
+                .col(
+                    ColumnDef::new(Attendance::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Attendance::EventId).integer().not_null())
+                .col(ColumnDef::new(Attendance::AttendeeId).integer().not_null())
+                .col(enum_column(
+                    Attendance::Status,
+                    "attendance_status",
+                    ATTENDANCE_STATUS,
+                ))
+                .col(timestamp_now(Attendance::CreatedAt))
+                .col(timestamp_now(Attendance::UpdatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Attendance::Table, Attendance::EventId)
+                        .to(Events::Table, Events::Id),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Attendance::Table, Attendance::AttendeeId)
+                        .to(Attendees::Table, Attendees::UserId),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-attendance-event_id")
+                .table(Attendance::Table)
+                .col(Attendance::EventId)
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-attendance-attendee_id")
+                .table(Attendance::Table)
+                .col(Attendance::AttendeeId)
+                .to_owned(),
+        )
+        .await?;
+
+        let mut coordinates = ColumnDef::new(Locations::Coordinates);
+        if is_postgres(db) {
+            coordinates.custom(Alias::new("geometry(Point, 4326)"));
+        } else {
+            coordinates.string();
+        }
+        db.execute(
+            &Table::create()
+                .table(Locations::Table)
+                .col(
+                    ColumnDef::new(Locations::Id)
+                        .integer()
+                        .not_null()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Locations::Name).string().not_null())
+                .col(coordinates.not_null())
+                .col(ColumnDef::new(Locations::Description).string())
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(Skills::Table)
+                .col(
+                    ColumnDef::new(Skills::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Skills::UserId).integer().not_null())
+                .col(enum_column(Skills::Name, "skill", SKILL))
+                .col(timestamp_now(Skills::CreatedAt))
+                .col(timestamp_now(Skills::UpdatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Skills::Table, Skills::UserId)
+                        .to(Users::Table, Users::Id),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(Motivations::Table)
+                .col(
+                    ColumnDef::new(Motivations::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    enum_column(Motivations::Motivation, "motivation", MOTIVATION)
+                        .unique_key()
+                        .to_owned(),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(EventCategories::Table)
+                .col(
+                    ColumnDef::new(EventCategories::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    enum_column(EventCategories::Name, "event_category", EVENT_CATEGORY)
+                        .unique_key()
+                        .to_owned(),
+                )
+                .col(ColumnDef::new(EventCategories::Description).string())
+                .col(timestamp_now(EventCategories::CreatedAt))
+                .col(timestamp_now(EventCategories::UpdatedAt))
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(UserMotivations::Table)
+                .col(ColumnDef::new(UserMotivations::UserId).integer().not_null())
+                .col(
+                    ColumnDef::new(UserMotivations::MotivationId)
+                        .integer()
+                        .not_null(),
+                )
+                .primary_key(
+                    Index::create()
+                        .col(UserMotivations::UserId)
+                        .col(UserMotivations::MotivationId),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(UserMotivations::Table, UserMotivations::UserId)
+                        .to(Users::Table, Users::Id),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(UserMotivations::Table, UserMotivations::MotivationId)
+                        .to(Motivations::Table, Motivations::Id),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(AttendeeMotivations::Table)
+                .col(
+                    ColumnDef::new(AttendeeMotivations::AttendeeId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(AttendeeMotivations::MotivationId)
+                        .integer()
+                        .not_null(),
+                )
+                .primary_key(
+                    Index::create()
+                        .col(AttendeeMotivations::AttendeeId)
+                        .col(AttendeeMotivations::MotivationId),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(AttendeeMotivations::Table, AttendeeMotivations::AttendeeId)
+                        .to(Attendees::Table, Attendees::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(
+                            AttendeeMotivations::Table,
+                            AttendeeMotivations::MotivationId,
+                        )
+                        .to(Motivations::Table, Motivations::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(CategoriesJoin::Table)
+                .col(
+                    ColumnDef::new(CategoriesJoin::AttendeeId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(CategoriesJoin::CategoryId)
+                        .integer()
+                        .not_null(),
+                )
+                .primary_key(
+                    Index::create()
+                        .col(CategoriesJoin::AttendeeId)
+                        .col(CategoriesJoin::CategoryId),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(CategoriesJoin::Table, CategoriesJoin::AttendeeId)
+                        .to(Attendees::Table, Attendees::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(CategoriesJoin::Table, CategoriesJoin::CategoryId)
+                        .to(EventCategories::Table, EventCategories::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        drop_table(db, CategoriesJoin::Table).await?;
+        drop_table(db, AttendeeMotivations::Table).await?;
+        drop_table(db, UserMotivations::Table).await?;
+        drop_table(db, EventCategories::Table).await?;
+        drop_table(db, Motivations::Table).await?;
+        drop_table(db, Skills::Table).await?;
+        drop_table(db, Locations::Table).await?;
+        drop_table(db, Attendance::Table).await?;
+        drop_table(db, Events::Table).await?;
+        drop_table(db, Attendees::Table).await?;
+        drop_table(db, Hosts::Table).await?;
+        drop_table(db, Users::Table).await?;
+        for (name, _) in ENUMS.iter().rev() {
+            drop_enum(db, name).await?;
+        }
+        // The PostGIS extension is left installed; other schemas may use it.
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+    Username,
+    Email,
+    Password,
+    FirstName,
+    LastName,
+    AccountType,
+    AvatarUrl,
+    IsActive,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Hosts {
+    Table,
+    UserId,
+    OrganizationName,
+    EventsHostedCount,
+}
+
+#[derive(DeriveIden)]
+enum Attendees {
+    Table,
+    UserId,
+    PreferredEventType,
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    Id,
+    Title,
+    Description,
+    Location,
+    EventType,
+    Category,
+    Status,
+    Visibility,
+    HostId,
+    StartTime,
+    EndTime,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Attendance {
+    Table,
+    Id,
+    EventId,
+    AttendeeId,
+    Status,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Locations {
+    Table,
+    Id,
+    Name,
+    Coordinates,
+    Description,
+}
+
+#[derive(DeriveIden)]
+enum Skills {
+    Table,
+    Id,
+    UserId,
+    Name,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Motivations {
+    Table,
+    Id,
+    Motivation,
+}
+
+#[derive(DeriveIden)]
+enum EventCategories {
+    Table,
+    Id,
+    Name,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum UserMotivations {
+    Table,
+    UserId,
+    MotivationId,
+}
+
+#[derive(DeriveIden)]
+enum AttendeeMotivations {
+    Table,
+    AttendeeId,
+    MotivationId,
+}
+
+#[derive(DeriveIden)]
+enum CategoriesJoin {
+    Table,
+    AttendeeId,
+    CategoryId,
+}
@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{ColumnDef, ForeignKey, ForeignKeyAction, Index, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+use super::{drop_table, timestamp_now};
+
+/// Login sessions and personal API keys.
+pub struct Migration;
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000002_create_auth_tables"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        db.execute(
+            &Table::create()
+                .table(Sessions::Table)
+                .col(
+                    ColumnDef::new(Sessions::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Sessions::UserId).integer().not_null())
+                .col(ColumnDef::new(Sessions::UserAgent).string())
+                .col(ColumnDef::new(Sessions::IpAddress).string())
+                .col(timestamp_now(Sessions::CreatedAt))
+                .col(timestamp_now(Sessions::LastSeenAt))
+                .col(ColumnDef::new(Sessions::RevokedAt).timestamp_with_time_zone())
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Sessions::Table, Sessions::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-sessions-user_id")
+                .table(Sessions::Table)
+                .col(Sessions::UserId)
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(ApiKeys::Table)
+                .col(
+                    ColumnDef::new(ApiKeys::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(ApiKeys::UserId).integer().not_null())
+                .col(ColumnDef::new(ApiKeys::Name).string().not_null())
+                .col(
+                    ColumnDef::new(ApiKeys::Prefix)
+                        .string()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(ColumnDef::new(ApiKeys::KeyHash).string().not_null())
+                .col(ColumnDef::new(ApiKeys::Scopes).string().not_null())
+                .col(ColumnDef::new(ApiKeys::ExpiresAt).timestamp_with_time_zone())
+                .col(ColumnDef::new(ApiKeys::LastUsedAt).timestamp_with_time_zone())
+                .col(timestamp_now(ApiKeys::CreatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(ApiKeys::Table, ApiKeys::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-api_keys-user_id")
+                .table(ApiKeys::Table)
+                .col(ApiKeys::UserId)
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        drop_table(db, ApiKeys::Table).await?;
+        drop_table(db, Sessions::Table).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Sessions {
+    Table,
+    Id,
+    UserId,
+    UserAgent,
+    IpAddress,
+    CreatedAt,
+    LastSeenAt,
+    RevokedAt,
+}
+
+#[derive(DeriveIden)]
+enum ApiKeys {
+    Table,
+    Id,
+    UserId,
+    Name,
+    Prefix,
+    KeyHash,
+    Scopes,
+    ExpiresAt,
+    LastUsedAt,
+    CreatedAt,
+}
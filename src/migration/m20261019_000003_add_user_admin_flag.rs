@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{ColumnDef, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+/// Operator accounts created with `here-admin`.
+pub struct Migration;
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000003_add_user_admin_flag"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        db.execute(
+            &Table::alter()
+                .table(Users::Table)
+                .add_column(
+                    ColumnDef::new(Users::IsAdmin)
+                        .boolean()
+                        .not_null()
+                        .default(false),
+                )
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        db.execute(
+            &Table::alter()
+                .table(Users::Table)
+                .drop_column(Users::IsAdmin)
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    IsAdmin,
+}
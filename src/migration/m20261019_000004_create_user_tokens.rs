@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{ColumnDef, ForeignKey, ForeignKeyAction, Index, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+use super::{drop_table, timestamp_now};
+
+/// One-time tokens for email verification and password resets.
+pub struct Migration;
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000004_create_user_tokens"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        db.execute(
+            &Table::alter()
+                .table(Users::Table)
+                .add_column(ColumnDef::new(Users::EmailVerifiedAt).timestamp_with_time_zone())
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(UserTokens::Table)
+                .col(
+                    ColumnDef::new(UserTokens::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(UserTokens::UserId).integer().not_null())
+                .col(ColumnDef::new(UserTokens::Purpose).string().not_null())
+                .col(
+                    ColumnDef::new(UserTokens::TokenHash)
+                        .string()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(
+                    ColumnDef::new(UserTokens::ExpiresAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(UserTokens::UsedAt).timestamp_with_time_zone())
+                .col(timestamp_now(UserTokens::CreatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(UserTokens::Table, UserTokens::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-user_tokens-user_id")
+                .table(UserTokens::Table)
+                .col(UserTokens::UserId)
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        drop_table(db, UserTokens::Table).await?;
+        db.execute(
+            &Table::alter()
+                .table(Users::Table)
+                .drop_column(Users::EmailVerifiedAt)
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+    EmailVerifiedAt,
+}
+
+#[derive(DeriveIden)]
+enum UserTokens {
+    Table,
+    Id,
+    UserId,
+    Purpose,
+    TokenHash,
+    ExpiresAt,
+    UsedAt,
+    CreatedAt,
+}
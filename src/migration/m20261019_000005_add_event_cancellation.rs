@@ -0,0 +1,137 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{Alias, ColumnDef, ForeignKey, ForeignKeyAction, Index, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+use super::{drop_table, is_postgres, timestamp_now};
+
+/// Who cancelled an event, when and why; a `Waitlisted` attendance status;
+/// and in-app notifications.
+pub struct Migration;
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000005_add_event_cancellation"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        // SQLite takes one column per ALTER TABLE
+        for column in [
+            ColumnDef::new(Events::CancelledAt)
+                .timestamp_with_time_zone()
+                .to_owned(),
+            ColumnDef::new(Events::CancelledBy).integer().to_owned(),
+            ColumnDef::new(Events::CancellationReason).text().to_owned(),
+        ] {
+            db.execute(
+                &Table::alter()
+                    .table(Events::Table)
+                    .add_column(column)
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        if is_postgres(db) {
+            // New values cannot be used in the transaction that adds them,
+            // which is fine as nothing here does
+            let stmt = sea_orm::sea_query::extension::postgres::Type::alter()
+                .name(Alias::new("attendance_status"))
+                .add_value(Alias::new("Waitlisted"))
+                .if_not_exists()
+                .to_owned();
+            db.execute(&stmt).await?;
+        }
+
+        db.execute(
+            &Table::create()
+                .table(Notifications::Table)
+                .col(
+                    ColumnDef::new(Notifications::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Notifications::UserId).integer().not_null())
+                .col(ColumnDef::new(Notifications::Kind).string().not_null())
+                .col(ColumnDef::new(Notifications::Title).string().not_null())
+                .col(ColumnDef::new(Notifications::Body).text().not_null())
+                .col(ColumnDef::new(Notifications::EventId).integer())
+                .col(ColumnDef::new(Notifications::ReadAt).timestamp_with_time_zone())
+                .col(timestamp_now(Notifications::CreatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Notifications::Table, Notifications::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Notifications::Table, Notifications::EventId)
+                        .to(Events::Table, Events::Id)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-notifications-user_id-created_at")
+                .table(Notifications::Table)
+                .col(Notifications::UserId)
+                .col(Notifications::CreatedAt)
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        drop_table(db, Notifications::Table).await?;
+        // Postgres cannot drop an enum value; `Waitlisted` stays in the type
+        for column in [
+            Events::CancellationReason,
+            Events::CancelledBy,
+            Events::CancelledAt,
+        ] {
+            db.execute(
+                &Table::alter()
+                    .table(Events::Table)
+                    .drop_column(column)
+                    .to_owned(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    Id,
+    CancelledAt,
+    CancelledBy,
+    CancellationReason,
+}
+
+#[derive(DeriveIden)]
+enum Notifications {
+    Table,
+    Id,
+    UserId,
+    Kind,
+    Title,
+    Body,
+    EventId,
+    ReadAt,
+    CreatedAt,
+}
@@ -0,0 +1,207 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{ColumnDef, Expr, ForeignKey, ForeignKeyAction, Index, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+use super::{drop_table, enum_column, is_postgres, timestamp_now};
+
+/// Recurring event series, and the link from each occurrence back to its
+/// series.
+pub struct Migration;
+
+// Values must match the enum types created by the base tables migration
+const EVENT_TYPE: &[&str] = &["Physical", "Virtual"];
+const EVENT_CATEGORY: &[&str] = &[
+    "Conference",
+    "Meetup",
+    "Workshop",
+    "Webinar",
+    "Religious",
+    "Social",
+    "Business",
+];
+const EVENT_VISIBILITY: &[&str] = &["Public", "Private"];
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000006_create_event_series"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        db.execute(
+            &Table::create()
+                .table(EventSeries::Table)
+                .col(
+                    ColumnDef::new(EventSeries::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(EventSeries::HostId).integer().not_null())
+                .col(ColumnDef::new(EventSeries::Title).string().not_null())
+                .col(ColumnDef::new(EventSeries::Description).string().not_null())
+                .col(ColumnDef::new(EventSeries::Location).string().not_null())
+                .col(enum_column(
+                    EventSeries::EventType,
+                    "event_type",
+                    EVENT_TYPE,
+                ))
+                .col(enum_column(
+                    EventSeries::Category,
+                    "event_category",
+                    EVENT_CATEGORY,
+                ))
+                .col(enum_column(
+                    EventSeries::Visibility,
+                    "event_visibility",
+                    EVENT_VISIBILITY,
+                ))
+                .col(ColumnDef::new(EventSeries::Rrule).string().not_null())
+                .col(ColumnDef::new(EventSeries::Timezone).string().not_null())
+                .col(
+                    ColumnDef::new(EventSeries::StartLocal)
+                        .timestamp()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(EventSeries::DurationMinutes)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(EventSeries::Exdates)
+                        .text()
+                        .not_null()
+                        .default(""),
+                )
+                .col(ColumnDef::new(EventSeries::MaterializedUntil).timestamp_with_time_zone())
+                .col(timestamp_now(EventSeries::CreatedAt))
+                .col(timestamp_now(EventSeries::UpdatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventSeries::Table, EventSeries::HostId)
+                        .to(Hosts::Table, Hosts::UserId),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        // SQLite takes one column per ALTER TABLE, and cannot add a foreign
+        // key to an existing table; the service keeps `series_id` valid there
+        db.execute(
+            &Table::alter()
+                .table(Events::Table)
+                .add_column(ColumnDef::new(Events::SeriesId).integer())
+                .to_owned(),
+        )
+        .await?;
+        if is_postgres(db) {
+            db.execute(
+                &ForeignKey::create()
+                    .name("fk-events-series_id")
+                    .from(Events::Table, Events::SeriesId)
+                    .to(EventSeries::Table, EventSeries::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+        }
+        db.execute(
+            &Table::alter()
+                .table(Events::Table)
+                .add_column(ColumnDef::new(Events::RecurrenceId).timestamp_with_time_zone())
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Table::alter()
+                .table(Events::Table)
+                .add_column(
+                    ColumnDef::new(Events::IsException)
+                        .boolean()
+                        .not_null()
+                        .default(Expr::value(false)),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-events-series_id-recurrence_id")
+                .table(Events::Table)
+                .col(Events::SeriesId)
+                .col(Events::RecurrenceId)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        db.execute(
+            &Index::drop()
+                .name("idx-events-series_id-recurrence_id")
+                .table(Events::Table)
+                .to_owned(),
+        )
+        .await?;
+        if is_postgres(db) {
+            db.execute(
+                &ForeignKey::drop()
+                    .name("fk-events-series_id")
+                    .table(Events::Table)
+                    .to_owned(),
+            )
+            .await?;
+        }
+        for column in [Events::IsException, Events::RecurrenceId, Events::SeriesId] {
+            db.execute(
+                &Table::alter()
+                    .table(Events::Table)
+                    .drop_column(column)
+                    .to_owned(),
+            )
+            .await?;
+        }
+        drop_table(db, EventSeries::Table).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Hosts {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    SeriesId,
+    RecurrenceId,
+    IsException,
+}
+
+#[derive(DeriveIden)]
+enum EventSeries {
+    Table,
+    Id,
+    HostId,
+    Title,
+    Description,
+    Location,
+    EventType,
+    Category,
+    Visibility,
+    Rrule,
+    Timezone,
+    StartLocal,
+    DurationMinutes,
+    Exdates,
+    MaterializedUntil,
+    CreatedAt,
+    UpdatedAt,
+}
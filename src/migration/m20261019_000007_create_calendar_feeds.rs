@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{ColumnDef, ForeignKey, ForeignKeyAction, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+use super::{drop_table, timestamp_now};
+
+/// Secret calendar feed URLs, at most one per user.
+pub struct Migration;
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000007_create_calendar_feeds"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        db.execute(
+            &Table::create()
+                .table(CalendarFeeds::Table)
+                .col(
+                    ColumnDef::new(CalendarFeeds::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(CalendarFeeds::UserId)
+                        .integer()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(
+                    ColumnDef::new(CalendarFeeds::TokenHash)
+                        .string()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(ColumnDef::new(CalendarFeeds::LastAccessedAt).timestamp_with_time_zone())
+                .col(timestamp_now(CalendarFeeds::CreatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(CalendarFeeds::Table, CalendarFeeds::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        drop_table(db, CalendarFeeds::Table).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum CalendarFeeds {
+    Table,
+    Id,
+    UserId,
+    TokenHash,
+    LastAccessedAt,
+    CreatedAt,
+}
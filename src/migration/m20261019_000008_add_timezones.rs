@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{ColumnDef, Expr, ExprTrait, Query, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+/// The IANA time zone each event takes place in, and a preferred time zone
+/// for users.
+pub struct Migration;
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000008_add_timezones"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        db.execute(
+            &Table::alter()
+                .table(Events::Table)
+                .add_column(
+                    ColumnDef::new(Events::Timezone)
+                        .string()
+                        .not_null()
+                        .default("UTC"),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Table::alter()
+                .table(Users::Table)
+                .add_column(ColumnDef::new(Users::Timezone).string())
+                .to_owned(),
+        )
+        .await?;
+
+        // Occurrences already know their zone from their series
+        let series_timezone = Query::select()
+            .column(EventSeries::Timezone)
+            .from(EventSeries::Table)
+            .and_where(
+                Expr::col((EventSeries::Table, EventSeries::Id))
+                    .equals((Events::Table, Events::SeriesId)),
+            )
+            .to_owned();
+        db.execute(
+            &Query::update()
+                .table(Events::Table)
+                .value(Events::Timezone, Expr::expr(series_timezone))
+                .and_where(Expr::col(Events::SeriesId).is_not_null())
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        db.execute(
+            &Table::alter()
+                .table(Users::Table)
+                .drop_column(Users::Timezone)
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Table::alter()
+                .table(Events::Table)
+                .drop_column(Events::Timezone)
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Timezone,
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    SeriesId,
+    Timezone,
+}
+
+#[derive(DeriveIden)]
+enum EventSeries {
+    Table,
+    Id,
+    Timezone,
+}
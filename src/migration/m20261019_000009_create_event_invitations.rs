@@ -0,0 +1,201 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{ColumnDef, ForeignKey, ForeignKeyAction, Index, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+use super::{create_enum, drop_enum, drop_table, enum_column, timestamp_now};
+
+const INVITATION_STATUS: &[&str] = &["Pending", "Accepted", "Declined"];
+
+/// Invitations to private events and shareable invite links.
+pub struct Migration;
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000009_create_event_invitations"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        create_enum(db, "invitation_status", INVITATION_STATUS).await?;
+
+        db.execute(
+            &Table::create()
+                .table(EventInviteLinks::Table)
+                .col(
+                    ColumnDef::new(EventInviteLinks::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(EventInviteLinks::EventId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(EventInviteLinks::CreatedBy)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(EventInviteLinks::TokenHash)
+                        .string()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(ColumnDef::new(EventInviteLinks::MaxUses).integer())
+                .col(
+                    ColumnDef::new(EventInviteLinks::UseCount)
+                        .integer()
+                        .not_null()
+                        .default(0),
+                )
+                .col(ColumnDef::new(EventInviteLinks::ExpiresAt).timestamp_with_time_zone())
+                .col(ColumnDef::new(EventInviteLinks::RevokedAt).timestamp_with_time_zone())
+                .col(timestamp_now(EventInviteLinks::CreatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventInviteLinks::Table, EventInviteLinks::EventId)
+                        .to(Events::Table, Events::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventInviteLinks::Table, EventInviteLinks::CreatedBy)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(EventInvitations::Table)
+                .col(
+                    ColumnDef::new(EventInvitations::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(EventInvitations::EventId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(EventInvitations::UserId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(EventInvitations::InvitedBy).integer())
+                .col(ColumnDef::new(EventInvitations::InviteLinkId).integer())
+                .col(
+                    enum_column(
+                        EventInvitations::Status,
+                        "invitation_status",
+                        INVITATION_STATUS,
+                    )
+                    .default("Pending")
+                    .to_owned(),
+                )
+                .col(ColumnDef::new(EventInvitations::RespondedAt).timestamp_with_time_zone())
+                .col(timestamp_now(EventInvitations::CreatedAt))
+                .col(timestamp_now(EventInvitations::UpdatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventInvitations::Table, EventInvitations::EventId)
+                        .to(Events::Table, Events::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventInvitations::Table, EventInvitations::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventInvitations::Table, EventInvitations::InvitedBy)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventInvitations::Table, EventInvitations::InviteLinkId)
+                        .to(EventInviteLinks::Table, EventInviteLinks::Id)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-event_invitations-event_id-user_id")
+                .table(EventInvitations::Table)
+                .col(EventInvitations::EventId)
+                .col(EventInvitations::UserId)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-event_invitations-user_id")
+                .table(EventInvitations::Table)
+                .col(EventInvitations::UserId)
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        drop_table(db, EventInvitations::Table).await?;
+        drop_table(db, EventInviteLinks::Table).await?;
+        drop_enum(db, "invitation_status").await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum EventInviteLinks {
+    Table,
+    Id,
+    EventId,
+    CreatedBy,
+    TokenHash,
+    MaxUses,
+    UseCount,
+    ExpiresAt,
+    RevokedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum EventInvitations {
+    Table,
+    Id,
+    EventId,
+    UserId,
+    InvitedBy,
+    InviteLinkId,
+    Status,
+    RespondedAt,
+    CreatedAt,
+    UpdatedAt,
+}
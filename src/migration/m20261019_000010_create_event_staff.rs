@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{ColumnDef, ForeignKey, ForeignKeyAction, Index, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+use super::{create_enum, drop_enum, drop_table, enum_column, timestamp_now};
+
+const STAFF_ROLE: &[&str] = &["CoHost", "Staff"];
+// Created by m20261019_000009_create_event_invitations
+const INVITATION_STATUS: &[&str] = &["Pending", "Accepted", "Declined"];
+
+/// Co-hosts and staff helping to run an event.
+pub struct Migration;
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000010_create_event_staff"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        create_enum(db, "staff_role", STAFF_ROLE).await?;
+
+        db.execute(
+            &Table::create()
+                .table(EventStaff::Table)
+                .col(
+                    ColumnDef::new(EventStaff::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(EventStaff::EventId).integer().not_null())
+                .col(ColumnDef::new(EventStaff::UserId).integer().not_null())
+                .col(enum_column(EventStaff::Role, "staff_role", STAFF_ROLE))
+                .col(
+                    enum_column(EventStaff::Status, "invitation_status", INVITATION_STATUS)
+                        .default("Pending")
+                        .to_owned(),
+                )
+                .col(ColumnDef::new(EventStaff::InvitedBy).integer())
+                .col(ColumnDef::new(EventStaff::RespondedAt).timestamp_with_time_zone())
+                .col(timestamp_now(EventStaff::CreatedAt))
+                .col(timestamp_now(EventStaff::UpdatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventStaff::Table, EventStaff::EventId)
+                        .to(Events::Table, Events::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventStaff::Table, EventStaff::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventStaff::Table, EventStaff::InvitedBy)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-event_staff-event_id-user_id")
+                .table(EventStaff::Table)
+                .col(EventStaff::EventId)
+                .col(EventStaff::UserId)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-event_staff-user_id")
+                .table(EventStaff::Table)
+                .col(EventStaff::UserId)
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        drop_table(db, EventStaff::Table).await?;
+        drop_enum(db, "staff_role").await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum EventStaff {
+    Table,
+    Id,
+    EventId,
+    UserId,
+    Role,
+    Status,
+    InvitedBy,
+    RespondedAt,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{ColumnDef, ForeignKey, ForeignKeyAction, Index, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+use super::{drop_table, timestamp_now};
+
+/// Extra categories for events and free-form tags. An event's `category`
+/// column stays its primary category and counts as one of its categories
+/// whether or not it has a row in `event_category_links`.
+pub struct Migration;
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000011_create_event_tags"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        db.execute(
+            &Table::create()
+                .table(EventCategoryLinks::Table)
+                .col(
+                    ColumnDef::new(EventCategoryLinks::EventId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(EventCategoryLinks::CategoryId)
+                        .integer()
+                        .not_null(),
+                )
+                .primary_key(
+                    Index::create()
+                        .col(EventCategoryLinks::EventId)
+                        .col(EventCategoryLinks::CategoryId),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventCategoryLinks::Table, EventCategoryLinks::EventId)
+                        .to(Events::Table, Events::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventCategoryLinks::Table, EventCategoryLinks::CategoryId)
+                        .to(EventCategories::Table, EventCategories::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-event_category_links-category_id")
+                .table(EventCategoryLinks::Table)
+                .col(EventCategoryLinks::CategoryId)
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(Tags::Table)
+                .col(
+                    ColumnDef::new(Tags::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Tags::Name).string().not_null().unique_key())
+                .col(timestamp_now(Tags::CreatedAt))
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(EventTags::Table)
+                .col(ColumnDef::new(EventTags::EventId).integer().not_null())
+                .col(ColumnDef::new(EventTags::TagId).integer().not_null())
+                .primary_key(
+                    Index::create()
+                        .col(EventTags::EventId)
+                        .col(EventTags::TagId),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventTags::Table, EventTags::EventId)
+                        .to(Events::Table, Events::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(EventTags::Table, EventTags::TagId)
+                        .to(Tags::Table, Tags::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-event_tags-tag_id")
+                .table(EventTags::Table)
+                .col(EventTags::TagId)
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        drop_table(db, EventTags::Table).await?;
+        drop_table(db, Tags::Table).await?;
+        drop_table(db, EventCategoryLinks::Table).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum EventCategories {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum EventCategoryLinks {
+    Table,
+    EventId,
+    CategoryId,
+}
+
+#[derive(DeriveIden)]
+enum Tags {
+    Table,
+    Id,
+    Name,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum EventTags {
+    Table,
+    EventId,
+    TagId,
+}
@@ -0,0 +1,145 @@
+use async_trait::async_trait;
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr};
+
+use super::is_postgres;
+
+/// Full-text search over events, hosts and users.
+///
+/// On Postgres each table gets a generated, weighted `search_vector` with a
+/// GIN index, plus `pg_trgm` indexes on the short fields for typo tolerance.
+/// SQLite has neither, so it gets external-content FTS5 tables kept in step
+/// by triggers; typo tolerance there is worked out in the search service.
+pub struct Migration;
+
+const POSTGRES_UP: &[&str] = &[
+    "CREATE EXTENSION IF NOT EXISTS pg_trgm",
+    "ALTER TABLE events ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', title), 'A') ||
+        setweight(to_tsvector('english', location), 'B') ||
+        setweight(to_tsvector('english', description), 'C')
+    ) STORED",
+    "CREATE INDEX \"idx-events-search_vector\" ON events USING GIN (search_vector)",
+    "CREATE INDEX \"idx-events-title-trgm\" ON events USING GIN (title gin_trgm_ops)",
+    // Names are not English words, so they are not stemmed
+    "ALTER TABLE hosts ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', coalesce(organization_name, '')), 'A')
+    ) STORED",
+    "CREATE INDEX \"idx-hosts-search_vector\" ON hosts USING GIN (search_vector)",
+    "CREATE INDEX \"idx-hosts-organization_name-trgm\" ON hosts
+        USING GIN (organization_name gin_trgm_ops)",
+    "ALTER TABLE users ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', username), 'A') ||
+        setweight(to_tsvector('simple',
+            coalesce(first_name, '') || ' ' || coalesce(last_name, '')), 'B')
+    ) STORED",
+    "CREATE INDEX \"idx-users-search_vector\" ON users USING GIN (search_vector)",
+    "CREATE INDEX \"idx-users-username-trgm\" ON users USING GIN (username gin_trgm_ops)",
+    "CREATE INDEX \"idx-users-full_name-trgm\" ON users
+        USING GIN ((coalesce(first_name, '') || ' ' || coalesce(last_name, '')) gin_trgm_ops)",
+];
+
+const POSTGRES_DOWN: &[&str] = &[
+    // Dropping the columns drops their indexes; pg_trgm may have other users
+    "DROP INDEX IF EXISTS \"idx-users-full_name-trgm\"",
+    "DROP INDEX IF EXISTS \"idx-users-username-trgm\"",
+    "ALTER TABLE users DROP COLUMN IF EXISTS search_vector",
+    "DROP INDEX IF EXISTS \"idx-hosts-organization_name-trgm\"",
+    "ALTER TABLE hosts DROP COLUMN IF EXISTS search_vector",
+    "DROP INDEX IF EXISTS \"idx-events-title-trgm\"",
+    "ALTER TABLE events DROP COLUMN IF EXISTS search_vector",
+];
+
+/// Indexed table, its key column and the columns searched, most important
+/// first.
+const SQLITE_INDEXES: &[(&str, &str, &[&str], &str)] = &[
+    (
+        "events",
+        "id",
+        &["title", "location", "description"],
+        "porter unicode61 remove_diacritics 2",
+    ),
+    (
+        "hosts",
+        "user_id",
+        &["organization_name"],
+        "unicode61 remove_diacritics 2",
+    ),
+    (
+        "users",
+        "id",
+        &["username", "first_name", "last_name"],
+        "unicode61 remove_diacritics 2",
+    ),
+];
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000012_add_search_indexes"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        if is_postgres(db) {
+            for sql in POSTGRES_UP {
+                db.execute_unprepared(sql).await?;
+            }
+            return Ok(());
+        }
+
+        for (table, key, columns, tokenizer) in SQLITE_INDEXES {
+            let fts = format!("{table}_fts");
+            let list = columns.join(", ");
+            let new = columns
+                .iter()
+                .map(|column| format!("new.{column}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let old = columns
+                .iter()
+                .map(|column| format!("old.{column}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let insert = format!("INSERT INTO {fts} (rowid, {list}) VALUES (new.{key}, {new});");
+            let delete = format!(
+                "INSERT INTO {fts} ({fts}, rowid, {list}) VALUES ('delete', old.{key}, {old});"
+            );
+
+            for sql in [
+                format!(
+                    "CREATE VIRTUAL TABLE {fts} USING fts5({list}, content='{table}', \
+                     content_rowid='{key}', tokenize='{tokenizer}')"
+                ),
+                format!("CREATE TRIGGER {fts}_insert AFTER INSERT ON {table} BEGIN {insert} END"),
+                format!("CREATE TRIGGER {fts}_delete AFTER DELETE ON {table} BEGIN {delete} END"),
+                format!(
+                    "CREATE TRIGGER {fts}_update AFTER UPDATE OF {list} ON {table} \
+                     BEGIN {delete} {insert} END"
+                ),
+                format!("INSERT INTO {fts} ({fts}) VALUES ('rebuild')"),
+            ] {
+                db.execute_unprepared(&sql).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        if is_postgres(db) {
+            for sql in POSTGRES_DOWN {
+                db.execute_unprepared(sql).await?;
+            }
+            return Ok(());
+        }
+
+        for (table, _, _, _) in SQLITE_INDEXES.iter().rev() {
+            let fts = format!("{table}_fts");
+            for suffix in ["insert", "delete", "update"] {
+                db.execute_unprepared(&format!("DROP TRIGGER IF EXISTS {fts}_{suffix}"))
+                    .await?;
+            }
+            db.execute_unprepared(&format!("DROP TABLE IF EXISTS {fts}"))
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{ColumnDef, ForeignKey, ForeignKeyAction, Index, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+use super::{create_enum, drop_enum, drop_table, enum_column, timestamp_now};
+
+const DISCOUNT_TYPE: &[&str] = &["Percentage", "Fixed"];
+const ORDER_STATUS: &[&str] = &["Pending", "Paid", "Failed", "Expired", "Cancelled"];
+
+/// Paid tickets: tiers per event, promo codes and orders. Amounts are in
+/// the currency's minor unit (kobo, cents).
+pub struct Migration;
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000013_create_ticketing"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        create_enum(db, "discount_type", DISCOUNT_TYPE).await?;
+        create_enum(db, "order_status", ORDER_STATUS).await?;
+
+        db.execute(
+            &Table::create()
+                .table(TicketTiers::Table)
+                .col(
+                    ColumnDef::new(TicketTiers::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(TicketTiers::EventId).integer().not_null())
+                .col(ColumnDef::new(TicketTiers::Name).string().not_null())
+                .col(ColumnDef::new(TicketTiers::Description).text())
+                .col(ColumnDef::new(TicketTiers::Price).big_integer().not_null())
+                .col(
+                    ColumnDef::new(TicketTiers::Currency)
+                        .string_len(3)
+                        .not_null(),
+                )
+                .col(ColumnDef::new(TicketTiers::Quantity).integer().not_null())
+                .col(
+                    ColumnDef::new(TicketTiers::Reserved)
+                        .integer()
+                        .not_null()
+                        .default(0),
+                )
+                .col(ColumnDef::new(TicketTiers::SalesStartAt).timestamp_with_time_zone())
+                .col(ColumnDef::new(TicketTiers::SalesEndAt).timestamp_with_time_zone())
+                .col(timestamp_now(TicketTiers::CreatedAt))
+                .col(timestamp_now(TicketTiers::UpdatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(TicketTiers::Table, TicketTiers::EventId)
+                        .to(Events::Table, Events::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-ticket_tiers-event_id-name")
+                .table(TicketTiers::Table)
+                .col(TicketTiers::EventId)
+                .col(TicketTiers::Name)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(PromoCodes::Table)
+                .col(
+                    ColumnDef::new(PromoCodes::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(PromoCodes::EventId).integer().not_null())
+                .col(ColumnDef::new(PromoCodes::Code).string().not_null())
+                .col(enum_column(
+                    PromoCodes::DiscountType,
+                    "discount_type",
+                    DISCOUNT_TYPE,
+                ))
+                .col(ColumnDef::new(PromoCodes::Amount).big_integer().not_null())
+                .col(ColumnDef::new(PromoCodes::MaxUses).integer())
+                .col(
+                    ColumnDef::new(PromoCodes::Uses)
+                        .integer()
+                        .not_null()
+                        .default(0),
+                )
+                .col(ColumnDef::new(PromoCodes::ExpiresAt).timestamp_with_time_zone())
+                .col(timestamp_now(PromoCodes::CreatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(PromoCodes::Table, PromoCodes::EventId)
+                        .to(Events::Table, Events::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-promo_codes-event_id-code")
+                .table(PromoCodes::Table)
+                .col(PromoCodes::EventId)
+                .col(PromoCodes::Code)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(TicketOrders::Table)
+                .col(
+                    ColumnDef::new(TicketOrders::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(TicketOrders::Reference)
+                        .string()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(ColumnDef::new(TicketOrders::EventId).integer().not_null())
+                .col(ColumnDef::new(TicketOrders::TierId).integer().not_null())
+                .col(ColumnDef::new(TicketOrders::UserId).integer().not_null())
+                .col(ColumnDef::new(TicketOrders::Quantity).integer().not_null())
+                .col(ColumnDef::new(TicketOrders::PromoCodeId).integer())
+                .col(
+                    ColumnDef::new(TicketOrders::Subtotal)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(TicketOrders::Discount)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(TicketOrders::Total).big_integer().not_null())
+                .col(
+                    ColumnDef::new(TicketOrders::Currency)
+                        .string_len(3)
+                        .not_null(),
+                )
+                .col(
+                    enum_column(TicketOrders::Status, "order_status", ORDER_STATUS)
+                        .default("Pending")
+                        .to_owned(),
+                )
+                .col(
+                    ColumnDef::new(TicketOrders::ExpiresAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(TicketOrders::PaymentReference).string())
+                .col(ColumnDef::new(TicketOrders::CheckoutUrl).text())
+                .col(ColumnDef::new(TicketOrders::AttendanceId).integer())
+                .col(ColumnDef::new(TicketOrders::PaidAt).timestamp_with_time_zone())
+                .col(timestamp_now(TicketOrders::CreatedAt))
+                .col(timestamp_now(TicketOrders::UpdatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(TicketOrders::Table, TicketOrders::EventId)
+                        .to(Events::Table, Events::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(TicketOrders::Table, TicketOrders::TierId)
+                        .to(TicketTiers::Table, TicketTiers::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(TicketOrders::Table, TicketOrders::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(TicketOrders::Table, TicketOrders::PromoCodeId)
+                        .to(PromoCodes::Table, PromoCodes::Id)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(TicketOrders::Table, TicketOrders::AttendanceId)
+                        .to(Attendance::Table, Attendance::Id)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .to_owned(),
+        )
+        .await?;
+        for (name, column) in [
+            ("idx-ticket_orders-user_id", TicketOrders::UserId),
+            ("idx-ticket_orders-tier_id", TicketOrders::TierId),
+        ] {
+            db.execute(
+                &Index::create()
+                    .name(name)
+                    .table(TicketOrders::Table)
+                    .col(column)
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        drop_table(db, TicketOrders::Table).await?;
+        drop_table(db, PromoCodes::Table).await?;
+        drop_table(db, TicketTiers::Table).await?;
+        drop_enum(db, "order_status").await?;
+        drop_enum(db, "discount_type").await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Attendance {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum TicketTiers {
+    Table,
+    Id,
+    EventId,
+    Name,
+    Description,
+    Price,
+    Currency,
+    Quantity,
+    Reserved,
+    SalesStartAt,
+    SalesEndAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum PromoCodes {
+    Table,
+    Id,
+    EventId,
+    Code,
+    DiscountType,
+    Amount,
+    MaxUses,
+    Uses,
+    ExpiresAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TicketOrders {
+    Table,
+    Id,
+    Reference,
+    EventId,
+    TierId,
+    UserId,
+    Quantity,
+    PromoCodeId,
+    Subtotal,
+    Discount,
+    Total,
+    Currency,
+    Status,
+    ExpiresAt,
+    PaymentReference,
+    CheckoutUrl,
+    AttendanceId,
+    PaidAt,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,237 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{Alias, ColumnDef, ForeignKey, ForeignKeyAction, Index, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+use super::{create_enum, drop_enum, drop_table, enum_column, is_postgres, timestamp_now};
+
+const LEDGER_ENTRY_KIND: &[&str] = &["Charge", "Refund"];
+
+/// Payment provider integration: a `Refunded` order status, which provider
+/// took each order's payment, a ledger of every money movement and the
+/// webhook events already processed.
+pub struct Migration;
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000014_create_payment_ledger"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        if is_postgres(db) {
+            // New values cannot be used in the transaction that adds them,
+            // which is fine as nothing here does
+            let stmt = sea_orm::sea_query::extension::postgres::Type::alter()
+                .name(Alias::new("order_status"))
+                .add_value(Alias::new("Refunded"))
+                .if_not_exists()
+                .to_owned();
+            db.execute(&stmt).await?;
+        }
+        create_enum(db, "ledger_entry_kind", LEDGER_ENTRY_KIND).await?;
+
+        // SQLite takes one column per ALTER TABLE
+        for column in [
+            ColumnDef::new(TicketOrders::PaymentProvider)
+                .string()
+                .to_owned(),
+            ColumnDef::new(TicketOrders::RefundedAt)
+                .timestamp_with_time_zone()
+                .to_owned(),
+        ] {
+            db.execute(
+                &Table::alter()
+                    .table(TicketOrders::Table)
+                    .add_column(column)
+                    .to_owned(),
+            )
+            .await?;
+        }
+        db.execute(
+            &Index::create()
+                .name("idx-ticket_orders-payment_reference")
+                .table(TicketOrders::Table)
+                .col(TicketOrders::PaymentReference)
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(LedgerEntries::Table)
+                .col(
+                    ColumnDef::new(LedgerEntries::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(LedgerEntries::OrderId).integer())
+                .col(ColumnDef::new(LedgerEntries::EventId).integer())
+                .col(enum_column(
+                    LedgerEntries::Kind,
+                    "ledger_entry_kind",
+                    LEDGER_ENTRY_KIND,
+                ))
+                .col(
+                    ColumnDef::new(LedgerEntries::Amount)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(LedgerEntries::Currency)
+                        .string_len(3)
+                        .not_null(),
+                )
+                .col(ColumnDef::new(LedgerEntries::Provider).string().not_null())
+                .col(
+                    ColumnDef::new(LedgerEntries::ProviderReference)
+                        .string()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(LedgerEntries::Description).text().not_null())
+                .col(timestamp_now(LedgerEntries::CreatedAt))
+                // Entries outlive the orders and events they are about
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(LedgerEntries::Table, LedgerEntries::OrderId)
+                        .to(TicketOrders::Table, TicketOrders::Id)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(LedgerEntries::Table, LedgerEntries::EventId)
+                        .to(Events::Table, Events::Id)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .to_owned(),
+        )
+        .await?;
+        // One entry per charge or refund, however often it is reported
+        db.execute(
+            &Index::create()
+                .name("idx-ledger_entries-provider-kind-reference")
+                .table(LedgerEntries::Table)
+                .col(LedgerEntries::Provider)
+                .col(LedgerEntries::Kind)
+                .col(LedgerEntries::ProviderReference)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-ledger_entries-event_id")
+                .table(LedgerEntries::Table)
+                .col(LedgerEntries::EventId)
+                .to_owned(),
+        )
+        .await?;
+
+        db.execute(
+            &Table::create()
+                .table(PaymentWebhookEvents::Table)
+                .col(
+                    ColumnDef::new(PaymentWebhookEvents::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(PaymentWebhookEvents::Provider)
+                        .string()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(PaymentWebhookEvents::EventId)
+                        .string()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(PaymentWebhookEvents::EventType)
+                        .string()
+                        .not_null(),
+                )
+                .col(timestamp_now(PaymentWebhookEvents::ProcessedAt))
+                .to_owned(),
+        )
+        .await?;
+        db.execute(
+            &Index::create()
+                .name("idx-payment_webhook_events-provider-event_id")
+                .table(PaymentWebhookEvents::Table)
+                .col(PaymentWebhookEvents::Provider)
+                .col(PaymentWebhookEvents::EventId)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        drop_table(db, PaymentWebhookEvents::Table).await?;
+        drop_table(db, LedgerEntries::Table).await?;
+        drop_enum(db, "ledger_entry_kind").await?;
+        db.execute(
+            &Index::drop()
+                .name("idx-ticket_orders-payment_reference")
+                .table(TicketOrders::Table)
+                .to_owned(),
+        )
+        .await?;
+        // Postgres cannot drop an enum value; `Refunded` stays in the type
+        for column in [TicketOrders::RefundedAt, TicketOrders::PaymentProvider] {
+            db.execute(
+                &Table::alter()
+                    .table(TicketOrders::Table)
+                    .drop_column(column)
+                    .to_owned(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum TicketOrders {
+    Table,
+    Id,
+    PaymentReference,
+    PaymentProvider,
+    RefundedAt,
+}
+
+#[derive(DeriveIden)]
+enum LedgerEntries {
+    Table,
+    Id,
+    OrderId,
+    EventId,
+    Kind,
+    Amount,
+    Currency,
+    Provider,
+    ProviderReference,
+    Description,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum PaymentWebhookEvents {
+    Table,
+    Id,
+    Provider,
+    EventId,
+    EventType,
+    ProcessedAt,
+}
@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use sea_orm::sea_query::{ColumnDef, Table};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, DeriveIden};
+
+/// Refund ledger entries written when a refund is claimed, before the
+/// provider has confirmed it.
+pub struct Migration;
+
+#[async_trait]
+impl super::Migration for Migration {
+    fn name(&self) -> &'static str {
+        "m20261019_000015_add_pending_refunds"
+    }
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        db.execute(
+            &Table::alter()
+                .table(LedgerEntries::Table)
+                .add_column(
+                    ColumnDef::new(LedgerEntries::Pending)
+                        .boolean()
+                        .not_null()
+                        .default(false),
+                )
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr> {
+        db.execute(
+            &Table::alter()
+                .table(LedgerEntries::Table)
+                .drop_column(LedgerEntries::Pending)
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum LedgerEntries {
+    Table,
+    Pending,
+}
@@ -0,0 +1,357 @@
+//! Versioned schema migrations.
+//!
+//! Each migration is a module named `mYYYYMMDD_NNNNNN_description` with an
+//! `up` and a `down` step, listed in order in [`migrations`]. Applied
+//! versions are recorded in the `schema_migrations` table. Every step runs in
+//! its own transaction, so a failing migration leaves no partial changes on
+//! backends with transactional DDL (Postgres, SQLite).
+//!
+//! Migrations must never change once released: add a new one instead.
+//!
+//! Databases created before migrations existed, when the server synced the
+//! schema from the entity registry on startup, are adopted with
+//! [`baseline`].
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::{Alias, ColumnDef, Expr, ExprTrait, Iden, Query, Table};
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr, Statement,
+    TransactionTrait,
+};
+use tracing::info;
+
+mod m20261019_000001_create_base_tables;
+mod m20261019_000002_create_auth_tables;
+mod m20261019_000003_add_user_admin_flag;
+mod m20261019_000004_create_user_tokens;
+mod m20261019_000005_add_event_cancellation;
+mod m20261019_000006_create_event_series;
+mod m20261019_000007_create_calendar_feeds;
+mod m20261019_000008_add_timezones;
+mod m20261019_000009_create_event_invitations;
+mod m20261019_000010_create_event_staff;
+mod m20261019_000011_create_event_tags;
+mod m20261019_000012_add_search_indexes;
+mod m20261019_000013_create_ticketing;
+mod m20261019_000014_create_payment_ledger;
+mod m20261019_000015_add_pending_refunds;
+
+const MIGRATIONS_TABLE: &str = "schema_migrations";
+
+#[async_trait]
+pub trait Migration: Send + Sync {
+    /// Unique, sortable version name; the module name by convention.
+    fn name(&self) -> &'static str;
+
+    async fn up(&self, db: &DatabaseTransaction) -> Result<(), DbErr>;
+
+    async fn down(&self, db: &DatabaseTransaction) -> Result<(), DbErr>;
+}
+
+/// All migrations, oldest first.
+pub fn migrations() -> Vec<Box<dyn Migration>> {
+    vec![
+        Box::new(m20261019_000001_create_base_tables::Migration),
+        Box::new(m20261019_000002_create_auth_tables::Migration),
+        Box::new(m20261019_000003_add_user_admin_flag::Migration),
+        Box::new(m20261019_000004_create_user_tokens::Migration),
+        Box::new(m20261019_000005_add_event_cancellation::Migration),
+        Box::new(m20261019_000006_create_event_series::Migration),
+        Box::new(m20261019_000007_create_calendar_feeds::Migration),
+        Box::new(m20261019_000008_add_timezones::Migration),
+        Box::new(m20261019_000009_create_event_invitations::Migration),
+        Box::new(m20261019_000010_create_event_staff::Migration),
+        Box::new(m20261019_000011_create_event_tags::Migration),
+        Box::new(m20261019_000012_add_search_indexes::Migration),
+        Box::new(m20261019_000013_create_ticketing::Migration),
+        Box::new(m20261019_000014_create_payment_ledger::Migration),
+        Box::new(m20261019_000015_add_pending_refunds::Migration),
+    ]
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MigrationStatus {
+    pub name: &'static str,
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+async fn ensure_migrations_table(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let stmt = Table::create()
+        .table(Alias::new(MIGRATIONS_TABLE))
+        .if_not_exists()
+        .col(
+            ColumnDef::new(Alias::new("version"))
+                .string()
+                .not_null()
+                .primary_key(),
+        )
+        .col(
+            ColumnDef::new(Alias::new("applied_at"))
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .to_owned();
+    db.execute(&stmt).await?;
+    Ok(())
+}
+
+async fn applied_versions(db: &DatabaseConnection) -> Result<Vec<(String, DateTime<Utc>)>, DbErr> {
+    let stmt = Query::select()
+        .columns([Alias::new("version"), Alias::new("applied_at")])
+        .from(Alias::new(MIGRATIONS_TABLE))
+        .to_owned();
+    db.query_all(&stmt)
+        .await?
+        .into_iter()
+        .map(|row| Ok((row.try_get("", "version")?, row.try_get("", "applied_at")?)))
+        .collect()
+}
+
+/// Every known migration and when it was applied, oldest first.
+pub async fn status(db: &DatabaseConnection) -> Result<Vec<MigrationStatus>, DbErr> {
+    ensure_migrations_table(db).await?;
+    let applied = applied_versions(db).await?;
+
+    Ok(migrations()
+        .iter()
+        .map(|m| MigrationStatus {
+            name: m.name(),
+            applied_at: applied
+                .iter()
+                .find(|(version, _)| version == m.name())
+                .map(|(_, at)| *at),
+        })
+        .collect())
+}
+
+/// Names of migrations that have not been applied yet.
+pub async fn pending(db: &DatabaseConnection) -> Result<Vec<&'static str>, DbErr> {
+    Ok(status(db)
+        .await?
+        .into_iter()
+        .filter(|s| s.applied_at.is_none())
+        .map(|s| s.name)
+        .collect())
+}
+
+/// Apply pending migrations in order, at most `steps` of them when given.
+/// Returns the names of the migrations that ran.
+pub async fn up(db: &DatabaseConnection, steps: Option<usize>) -> Result<Vec<&'static str>, DbErr> {
+    let pending = pending(db).await?;
+    let mut ran = Vec::new();
+
+    for migration in migrations()
+        .into_iter()
+        .filter(|m| pending.contains(&m.name()))
+        .take(steps.unwrap_or(usize::MAX))
+    {
+        info!("Applying migration {}", migration.name());
+        let txn = db.begin().await?;
+        migration.up(&txn).await?;
+        let record = Query::insert()
+            .into_table(Alias::new(MIGRATIONS_TABLE))
+            .columns([Alias::new("version"), Alias::new("applied_at")])
+            .values_panic([migration.name().into(), Utc::now().into()])
+            .to_owned();
+        txn.execute(&record).await?;
+        txn.commit().await?;
+        ran.push(migration.name());
+    }
+
+    Ok(ran)
+}
+
+/// Roll back the `steps` most recently applied migrations, newest first.
+/// Returns the names of the migrations that were reverted.
+pub async fn down(db: &DatabaseConnection, steps: usize) -> Result<Vec<&'static str>, DbErr> {
+    let applied: Vec<&'static str> = status(db)
+        .await?
+        .into_iter()
+        .filter(|s| s.applied_at.is_some())
+        .map(|s| s.name)
+        .collect();
+    let mut reverted = Vec::new();
+
+    for migration in migrations()
+        .into_iter()
+        .rev()
+        .filter(|m| applied.contains(&m.name()))
+        .take(steps)
+    {
+        info!("Reverting migration {}", migration.name());
+        let txn = db.begin().await?;
+        migration.down(&txn).await?;
+        let record = Query::delete()
+            .from_table(Alias::new(MIGRATIONS_TABLE))
+            .and_where(Expr::col(Alias::new("version")).eq(migration.name()))
+            .to_owned();
+        txn.execute(&record).await?;
+        txn.commit().await?;
+        reverted.push(migration.name());
+    }
+
+    Ok(reverted)
+}
+
+/// The migrations that reproduce the schema the server used to sync from
+/// the entity registry on startup, with the tables each creates.
+const BASELINE: &[(&str, &[&str])] = &[
+    (
+        "m20261019_000001_create_base_tables",
+        &[
+            "users",
+            "hosts",
+            "attendees",
+            "events",
+            "attendance",
+            "locations",
+            "skills",
+            "motivations",
+            "event_categories",
+            "user_motivations",
+            "attendee_motivations",
+            "categories_join",
+        ],
+    ),
+    (
+        "m20261019_000002_create_auth_tables",
+        &["sessions", "api_keys"],
+    ),
+];
+
+async fn table_exists(db: &DatabaseConnection, table: &str) -> Result<bool, DbErr> {
+    let backend = db.get_database_backend();
+    let sql = match backend {
+        DbBackend::Sqlite => "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?",
+        _ => {
+            "SELECT 1 FROM information_schema.tables \
+             WHERE table_schema = current_schema() AND table_name = $1"
+        }
+    };
+    Ok(db
+        .query_one_raw(Statement::from_sql_and_values(backend, sql, [table.into()]))
+        .await?
+        .is_some())
+}
+
+/// Adopt a database whose schema was synced from the entity registry by an
+/// older server: record the [`BASELINE`] migrations whose tables already
+/// exist as applied, without running them, so `up` carries on from there.
+/// Returns the names recorded; empty when the database is new or already
+/// tracks migrations.
+pub async fn baseline(db: &DatabaseConnection) -> Result<Vec<&'static str>, DbErr> {
+    if status(db).await?.iter().any(|s| s.applied_at.is_some()) {
+        return Ok(Vec::new());
+    }
+    let mut adopted = Vec::new();
+    for (name, tables) in BASELINE {
+        let mut missing = Vec::new();
+        for table in *tables {
+            if !table_exists(db, table).await? {
+                missing.push(*table);
+            }
+        }
+        if missing.len() == tables.len() {
+            break;
+        }
+        if !missing.is_empty() {
+            return Err(DbErr::Custom(format!(
+                "Cannot baseline {}: tables {} are missing",
+                name,
+                missing.join(", ")
+            )));
+        }
+        adopted.push(*name);
+    }
+
+    let txn = db.begin().await?;
+    for name in &adopted {
+        info!("Recording existing schema as migration {}", name);
+        let record = Query::insert()
+            .into_table(Alias::new(MIGRATIONS_TABLE))
+            .columns([Alias::new("version"), Alias::new("applied_at")])
+            .values_panic([(*name).into(), Utc::now().into()])
+            .to_owned();
+        txn.execute(&record).await?;
+    }
+    txn.commit().await?;
+    Ok(adopted)
+}
+
+/// Fail when the database is missing migrations this build expects.
+pub async fn ensure_up_to_date(db: &DatabaseConnection) -> Result<(), String> {
+    let pending = pending(db)
+        .await
+        .map_err(|e| format!("Failed to read migration status: {}", e))?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "Database has {} pending migration(s): {}. Run `here-migrate up` first, \
+         after `here-migrate baseline` if its schema predates migrations.",
+        pending.len(),
+        pending.join(", ")
+    ))
+}
+
+// --- Helpers shared by migrations ---
+
+pub(crate) fn is_postgres(db: &DatabaseTransaction) -> bool {
+    db.get_database_backend() == DbBackend::Postgres
+}
+
+/// Create a Postgres enum type. SQLite stores enums as text, so this is a
+/// no-op there.
+pub(crate) async fn create_enum(
+    db: &DatabaseTransaction,
+    name: &str,
+    values: &[&str],
+) -> Result<(), DbErr> {
+    if !is_postgres(db) {
+        return Ok(());
+    }
+    let stmt = sea_orm::sea_query::extension::postgres::Type::create()
+        .as_enum(Alias::new(name))
+        .values(values.iter().map(|v| Alias::new(*v)))
+        .to_owned();
+    db.execute(&stmt).await?;
+    Ok(())
+}
+
+pub(crate) async fn drop_enum(db: &DatabaseTransaction, name: &str) -> Result<(), DbErr> {
+    if !is_postgres(db) {
+        return Ok(());
+    }
+    let stmt = sea_orm::sea_query::extension::postgres::Type::drop()
+        .if_exists()
+        .name(Alias::new(name))
+        .to_owned();
+    db.execute(&stmt).await?;
+    Ok(())
+}
+
+/// Column of the given enum type; `values` must match [`create_enum`].
+pub(crate) fn enum_column<T: Iden + 'static>(column: T, name: &str, values: &[&str]) -> ColumnDef {
+    ColumnDef::new(column)
+        .enumeration(Alias::new(name), values.iter().map(|v| Alias::new(*v)))
+        .not_null()
+        .to_owned()
+}
+
+/// `created_at`/`updated_at` style column defaulting to the current time.
+pub(crate) fn timestamp_now<T: Iden + 'static>(column: T) -> ColumnDef {
+    ColumnDef::new(column)
+        .timestamp_with_time_zone()
+        .not_null()
+        .default(Expr::current_timestamp())
+        .to_owned()
+}
+
+pub(crate) async fn drop_table<T: Iden + 'static>(
+    db: &DatabaseTransaction,
+    table: T,
+) -> Result<(), DbErr> {
+    db.execute(&Table::drop().table(table).if_exists().to_owned())
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use actix_web::web;
+
+/// Configure administrator routes.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::jobs::{delete_job, list_jobs, retry_job};
+
+    cfg.service(
+        web::scope("/admin")
+            .service(list_jobs)
+            .service(retry_job)
+            .service(delete_job),
+    );
+}
@@ -2,7 +2,20 @@ use actix_web::web;
 
 /// Configure auth-related routes.
 pub fn init(cfg: &mut web::ServiceConfig) {
-    use crate::handlers::auth::login;
+    use crate::handlers::auth::{
+        delete_session, jwks, list_sessions, login, request_email_verification,
+        request_password_reset, reset_password, verify_email,
+    };
 
-    cfg.service(web::scope("/auth").service(login));
+    cfg.service(
+        web::scope("/auth")
+            .service(login)
+            .service(list_sessions)
+            .service(delete_session)
+            .service(request_email_verification)
+            .service(verify_email)
+            .service(request_password_reset)
+            .service(reset_password),
+    )
+    .service(jwks);
 }
@@ -0,0 +1,8 @@
+use actix_web::web;
+
+/// Configure subscribable calendar feed routes.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::calendar::calendar_feed;
+
+    cfg.service(web::scope("/calendar").service(calendar_feed));
+}
@@ -0,0 +1,13 @@
+use actix_web::web;
+
+/// Configure routes for answering requests to help run events.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::event_staff::{accept_role, decline_role, list_my_roles};
+
+    cfg.service(
+        web::scope("/event-roles")
+            .service(list_my_roles)
+            .service(accept_role)
+            .service(decline_role),
+    );
+}
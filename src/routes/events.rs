@@ -0,0 +1,59 @@
+use actix_web::web;
+
+/// Configure event routes.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::calendar::event_ics;
+    use crate::handlers::event_staff::{
+        add_event_staff, check_in_attendee, delete_event_staff, list_event_attendees,
+        list_event_staff, list_managed_events,
+    };
+    use crate::handlers::events::{
+        cancel, create_event_series, create_rsvp, edit_event, get_event, get_event_series,
+        list_events, update_event_status,
+    };
+    use crate::handlers::invitations::{
+        create_link, delete_invitation, delete_link, invite, list_event_invitations, list_links,
+    };
+    use crate::handlers::tickets::{
+        checkout_tickets, create_event_promo_code, create_ticket_tier, delete_event_promo_code,
+        delete_ticket_tier, event_ledger, list_event_promo_codes, list_ticket_orders,
+        list_ticket_tiers, refund_ticket_order, update_ticket_tier,
+    };
+
+    cfg.service(
+        web::scope("/events")
+            .service(list_events)
+            .service(create_event_series)
+            .service(get_event_series)
+            // Before `get_event`, whose `{id}` would also match `managed` and `5.ics`
+            .service(list_managed_events)
+            .service(event_ics)
+            .service(get_event)
+            .service(edit_event)
+            .service(update_event_status)
+            .service(cancel)
+            .service(create_rsvp)
+            .service(invite)
+            .service(list_event_invitations)
+            .service(delete_invitation)
+            .service(create_link)
+            .service(list_links)
+            .service(delete_link)
+            .service(add_event_staff)
+            .service(list_event_staff)
+            .service(delete_event_staff)
+            .service(list_event_attendees)
+            .service(check_in_attendee)
+            .service(list_ticket_tiers)
+            .service(create_ticket_tier)
+            .service(update_ticket_tier)
+            .service(delete_ticket_tier)
+            .service(list_event_promo_codes)
+            .service(create_event_promo_code)
+            .service(delete_event_promo_code)
+            .service(checkout_tickets)
+            .service(list_ticket_orders)
+            .service(refund_ticket_order)
+            .service(event_ledger),
+    );
+}
@@ -0,0 +1,8 @@
+use actix_web::web;
+
+/// Configure liveness and readiness probes.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::health::{live, ready};
+
+    cfg.service(web::scope("/health").service(live).service(ready));
+}
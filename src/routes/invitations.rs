@@ -0,0 +1,14 @@
+use actix_web::web;
+
+/// Configure routes for answering invitations to private events.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::invitations::{accept, decline, list_mine, redeem};
+
+    cfg.service(
+        web::scope("/invitations")
+            .service(list_mine)
+            .service(redeem)
+            .service(accept)
+            .service(decline),
+    );
+}
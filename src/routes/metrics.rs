@@ -0,0 +1,8 @@
+use actix_web::web;
+
+/// Configure the Prometheus scrape endpoint.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::metrics::metrics;
+
+    cfg.service(metrics);
+}
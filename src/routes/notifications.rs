@@ -0,0 +1,12 @@
+use actix_web::web;
+
+/// Configure in-app notification routes.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::notifications::{list, mark_read};
+
+    cfg.service(
+        web::scope("/notifications")
+            .service(list)
+            .service(mark_read),
+    );
+}
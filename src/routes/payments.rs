@@ -0,0 +1,8 @@
+use actix_web::web;
+
+/// Configure the payment provider webhook.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::payments::payment_webhook;
+
+    cfg.service(web::scope("/payments").service(payment_webhook));
+}
@@ -0,0 +1,8 @@
+use actix_web::web;
+
+/// Configure the reference data endpoint.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::reference_data::reference_data;
+
+    cfg.service(reference_data);
+}
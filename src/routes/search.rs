@@ -0,0 +1,8 @@
+use actix_web::web;
+
+/// Configure the search endpoint.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::search::search;
+
+    cfg.service(search);
+}
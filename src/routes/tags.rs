@@ -0,0 +1,8 @@
+use actix_web::web;
+
+/// Configure the tag autocomplete endpoint.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::tags::autocomplete_tags;
+
+    cfg.service(autocomplete_tags);
+}
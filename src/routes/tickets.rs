@@ -0,0 +1,16 @@
+use actix_web::web;
+
+/// Configure routes for the current user's ticket orders.
+pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::tickets::{
+        cancel_my_order, confirm_my_order, get_my_order, list_my_orders,
+    };
+
+    cfg.service(
+        web::scope("/orders")
+            .service(list_my_orders)
+            .service(get_my_order)
+            .service(confirm_my_order)
+            .service(cancel_my_order),
+    );
+}
@@ -2,13 +2,20 @@ use actix_web::web;
 
 /// Configure user-related routes.
 pub fn init(cfg: &mut web::ServiceConfig) {
+    use crate::handlers::api_keys::{create_key, delete_key, list_keys};
     use crate::handlers::auth::get_me;
-    use crate::handlers::users::{health_check, signup};
+    use crate::handlers::calendar::{create_calendar_feed, delete_calendar_feed};
+    use crate::handlers::users::{signup, update_timezone};
 
     cfg.service(
         web::scope("/users")
             .service(signup)
-            .service(health_check)
-            .service(get_me),
+            .service(get_me)
+            .service(update_timezone)
+            .service(create_key)
+            .service(list_keys)
+            .service(delete_key)
+            .service(create_calendar_feed)
+            .service(delete_calendar_feed),
     );
 }
@@ -1,8 +1,14 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use validator::Validate;
 
-#[derive(Debug, Serialize, Validate, Deserialize, ToSchema)]
+use crate::entity::prelude::UserModel;
+use crate::utils::redact::REDACTED;
+
+#[derive(Serialize, Validate, Deserialize, ToSchema)]
 pub struct LoginRequest {
     #[validate(length(min = 1))]
     pub identifier: String, // Can be username or email
@@ -10,6 +16,15 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+impl fmt::Debug for LoginRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoginRequest")
+            .field("identifier", &self.identifier)
+            .field("password", &REDACTED)
+            .finish()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct LoginResponse {
     pub id: i32,
@@ -26,4 +41,70 @@ pub struct UserMeResponse {
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub avatar_url: Option<String>,
+    /// Preferred IANA time zone; event times are also given in it
+    pub timezone: Option<String>,
+}
+
+impl From<UserModel> for UserMeResponse {
+    fn from(user: UserModel) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            avatar_url: user.avatar_url,
+            timezone: user.timezone,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SessionResponse {
+    pub id: i32,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    /// True for the session the request was authenticated with
+    pub current: bool,
+}
+
+#[derive(Serialize, Validate, Deserialize, ToSchema)]
+pub struct VerifyEmailRequest {
+    /// Token from the verification email
+    #[validate(length(min = 1))]
+    pub token: String,
+}
+
+impl fmt::Debug for VerifyEmailRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifyEmailRequest")
+            .field("token", &REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize, Validate, Deserialize, ToSchema)]
+pub struct PasswordResetRequest {
+    #[validate(email)]
+    pub email: String,
+}
+
+#[derive(Serialize, Validate, Deserialize, ToSchema)]
+pub struct PasswordResetConfirm {
+    /// Token from the password reset email
+    #[validate(length(min = 1))]
+    pub token: String,
+    #[validate(length(min = 8))]
+    pub password: String,
+}
+
+impl fmt::Debug for PasswordResetConfirm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PasswordResetConfirm")
+            .field("token", &REDACTED)
+            .field("password", &REDACTED)
+            .finish()
+    }
 }
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CalendarFeedResponse {
+    /// Feed URL to subscribe to. It contains a secret token and is only
+    /// returned once; creating a new feed replaces it.
+    pub url: String,
+    /// The same feed with the `webcal://` scheme, which opens calendar apps
+    pub webcal_url: String,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Body of requests rejected before they reach a handler (malformed JSON,
+/// oversized bodies, bad query strings or path parameters).
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+    /// Stable, machine-readable error code, e.g. `payload_too_large`
+    pub error: String,
+    /// Human-readable explanation
+    pub message: String,
+}
+
+impl ErrorResponse {
+    pub fn new(error: &str, message: impl Into<String>) -> Self {
+        ErrorResponse {
+            error: error.to_string(),
+            message: message.into(),
+        }
+    }
+}
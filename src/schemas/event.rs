@@ -0,0 +1,386 @@
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use sea_orm::ActiveEnum;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::entity::prelude::{AttendanceModel, EventModel, EventSeriesModel};
+use crate::entity::{
+    AttendanceStatus, EventCategory, EventStatus, EventType, EventVisibility, Motivation,
+};
+use crate::utils::recurrence::parse_exdates;
+use crate::utils::tags::normalize_tags;
+use crate::utils::timezone::{local_time, stored_timezone};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EventResponse {
+    pub id: i32,
+    pub title: String,
+    pub description: String,
+    pub location: String,
+    pub event_type: EventType,
+    /// Primary category
+    pub category: EventCategory,
+    /// Every category, the primary one first
+    pub categories: Vec<EventCategory>,
+    /// Normalized free-form tags, alphabetically
+    pub tags: Vec<String>,
+    pub status: EventStatus,
+    pub visibility: EventVisibility,
+    pub host_id: i32,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    /// IANA time zone the event takes place in
+    pub timezone: String,
+    /// Wall-clock start in the event's time zone
+    #[schema(value_type = String, example = "2026-11-03T19:00:00")]
+    pub local_start_time: NaiveDateTime,
+    /// Wall-clock end in the event's time zone
+    #[schema(value_type = String, example = "2026-11-03T21:00:00")]
+    pub local_end_time: NaiveDateTime,
+    /// The signed-in user's preferred time zone, when they have set one
+    pub viewer_timezone: Option<String>,
+    /// Wall-clock start in `viewer_timezone`
+    #[schema(value_type = Option<String>)]
+    pub viewer_start_time: Option<NaiveDateTime>,
+    /// Wall-clock end in `viewer_timezone`
+    #[schema(value_type = Option<String>)]
+    pub viewer_end_time: Option<NaiveDateTime>,
+    /// Set for cancelled events
+    pub cancelled_at: Option<DateTime<Utc>>,
+    /// User who cancelled the event
+    pub cancelled_by: Option<i32>,
+    pub cancellation_reason: Option<String>,
+    /// Recurring series this event is an occurrence of
+    pub series_id: Option<i32>,
+    /// Start time the series rule gave this occurrence
+    pub recurrence_id: Option<DateTime<Utc>>,
+    /// Edited on its own; series-wide edits leave it alone
+    pub is_exception: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl EventResponse {
+    /// The event with its times also given in `viewer`'s time zone.
+    pub fn localized(event: EventModel, viewer: Option<Tz>) -> Self {
+        let tz = stored_timezone(&event.timezone);
+        Self {
+            local_start_time: local_time(event.start_time, tz),
+            local_end_time: local_time(event.end_time, tz),
+            viewer_timezone: viewer.map(|viewer| viewer.name().to_string()),
+            viewer_start_time: viewer.map(|viewer| local_time(event.start_time, viewer)),
+            viewer_end_time: viewer.map(|viewer| local_time(event.end_time, viewer)),
+            id: event.id,
+            title: event.title,
+            description: event.description,
+            location: event.location,
+            event_type: event.event_type,
+            category: event.category,
+            // Only the primary category until `with_labels` fills in the rest
+            categories: vec![event.category],
+            tags: Vec::new(),
+            status: event.status,
+            visibility: event.visibility,
+            host_id: event.host_id,
+            start_time: event.start_time,
+            end_time: event.end_time,
+            timezone: event.timezone,
+            cancelled_at: event.cancelled_at,
+            cancelled_by: event.cancelled_by,
+            cancellation_reason: event.cancellation_reason,
+            series_id: event.series_id,
+            recurrence_id: event.recurrence_id,
+            is_exception: event.is_exception,
+            created_at: event.created_at,
+            updated_at: event.updated_at,
+        }
+    }
+
+    /// The response with the event's categories and tags, which are not
+    /// part of the event row.
+    pub fn with_labels(mut self, labels: EventLabels) -> Self {
+        self.categories = labels.categories;
+        self.tags = labels.tags;
+        self
+    }
+}
+
+impl From<EventModel> for EventResponse {
+    fn from(event: EventModel) -> Self {
+        Self::localized(event, None)
+    }
+}
+
+/// An event's categories, the primary one first, and its tags.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventLabels {
+    pub categories: Vec<EventCategory>,
+    pub tags: Vec<String>,
+}
+
+/// How the categories and tags in a filter combine.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelMatch {
+    /// Events with at least one of them
+    #[default]
+    Any,
+    /// Events with every one of them
+    All,
+}
+
+/// Categories and tags to filter events by. Empty matches every event.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LabelFilter {
+    pub categories: Vec<EventCategory>,
+    /// Normalized
+    pub tags: Vec<String>,
+    pub matching: LabelMatch,
+}
+
+impl LabelFilter {
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty() && self.tags.is_empty()
+    }
+}
+
+/// Named date ranges for listing events. Each is worked out in the event's
+/// own time zone, so "today" for a Lagos meetup is the day in Lagos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventWindow {
+    Today,
+    Tomorrow,
+    /// The coming Saturday and Sunday, or the rest of the weekend when it
+    /// has already started
+    ThisWeekend,
+    /// Today until Sunday
+    ThisWeek,
+}
+
+impl EventWindow {
+    /// First and last day of the window, given the local date today.
+    pub fn dates(self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let days_to_sunday = u64::from(6 - today.weekday().num_days_from_monday());
+        let sunday = today + Days::new(days_to_sunday);
+        match self {
+            Self::Today => (today, today),
+            Self::Tomorrow => {
+                let tomorrow = today + Days::new(1);
+                (tomorrow, tomorrow)
+            }
+            Self::ThisWeekend => ((sunday - Days::new(1)).max(today), sunday),
+            Self::ThisWeek => (today, sunday),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EventListQuery {
+    /// Page size (1-100, default 20)
+    #[validate(range(min = 1, max = 100))]
+    pub limit: Option<u64>,
+    /// Number of events to skip
+    pub offset: Option<u64>,
+    /// Only events starting in this range, in each event's time zone
+    pub when: Option<EventWindow>,
+    /// Comma-separated categories, e.g. `Meetup,Workshop`
+    #[validate(length(max = 200))]
+    pub categories: Option<String>,
+    /// Comma-separated tags, e.g. `rust,open-source`
+    #[validate(length(max = 500))]
+    pub tags: Option<String>,
+    /// Whether events need `any` (the default) or `all` of the categories
+    /// and tags
+    #[serde(rename = "match")]
+    pub matching: Option<LabelMatch>,
+}
+
+impl EventListQuery {
+    /// The category and tag filter, with tags normalized.
+    pub fn label_filter(&self) -> Result<LabelFilter, String> {
+        let split = |list: &Option<String>| -> Vec<String> {
+            list.iter()
+                .flat_map(|list| list.split(','))
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        };
+        let mut categories = Vec::new();
+        for name in split(&self.categories) {
+            let category = EventCategory::try_from_value(&name)
+                .map_err(|_| format!("Unknown category '{}'", name))?;
+            if !categories.contains(&category) {
+                categories.push(category);
+            }
+        }
+        Ok(LabelFilter {
+            categories,
+            tags: normalize_tags(&split(&self.tags))?,
+            matching: self.matching.unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EventStatusUpdate {
+    /// New status; events move Scheduled -> Ongoing -> Completed and can be
+    /// cancelled until they complete
+    pub status: EventStatus,
+}
+
+/// Which occurrences of a recurring series an edit applies to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EditScope {
+    /// Only this occurrence, which then keeps its own details
+    #[default]
+    This,
+    /// This occurrence and every later one in the series
+    Following,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct EventUpdate {
+    #[validate(length(min = 1, max = 200))]
+    pub title: Option<String>,
+    #[validate(length(min = 1, max = 5000))]
+    pub description: Option<String>,
+    #[validate(length(min = 1, max = 500))]
+    pub location: Option<String>,
+    /// New start; the end moves with it unless `end_time` is also given
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    /// IANA time zone, e.g. `Africa/Lagos`. Without a new `start_time` the
+    /// event keeps its wall-clock times in the new zone. Not accepted for
+    /// occurrences of a recurring series
+    #[validate(length(min = 1, max = 64))]
+    pub timezone: Option<String>,
+    /// Replaces the event's categories; the first becomes its primary
+    /// `category`
+    #[validate(length(min = 1, max = 5))]
+    pub categories: Option<Vec<EventCategory>>,
+    /// Replaces the event's tags, e.g. `#Rust` or `open source`; they are
+    /// stored normalized
+    #[validate(length(max = 10))]
+    pub tags: Option<Vec<String>>,
+    /// For occurrences of a recurring series; defaults to `this`
+    #[serde(default)]
+    pub scope: EditScope,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateEventSeriesRequest {
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    #[validate(length(min = 1, max = 5000))]
+    pub description: String,
+    #[validate(length(min = 1, max = 500))]
+    pub location: String,
+    pub event_type: EventType,
+    pub category: EventCategory,
+    pub visibility: EventVisibility,
+    /// RFC 5545 recurrence rule, e.g. `FREQ=WEEKLY;BYDAY=TU;COUNT=10`.
+    /// `FREQ` may be `DAILY`, `WEEKLY` or `MONTHLY`
+    #[validate(length(min = 1, max = 500))]
+    pub rrule: String,
+    /// IANA time zone the rule repeats in, e.g. `Europe/London`
+    #[validate(length(min = 1, max = 64))]
+    pub timezone: String,
+    /// Wall-clock start of the first occurrence in `timezone`
+    #[schema(value_type = String, example = "2026-11-03T19:00:00")]
+    pub start_time: NaiveDateTime,
+    /// Length of each occurrence (at most a week)
+    #[validate(range(min = 1, max = 10080))]
+    pub duration_minutes: i32,
+    /// Wall-clock start times to leave out (EXDATE)
+    #[serde(default)]
+    #[schema(value_type = Vec<String>)]
+    #[validate(length(max = 500))]
+    pub exdates: Vec<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EventSeriesResponse {
+    pub id: i32,
+    pub host_id: i32,
+    pub title: String,
+    pub description: String,
+    pub location: String,
+    pub event_type: EventType,
+    pub category: EventCategory,
+    pub visibility: EventVisibility,
+    pub rrule: String,
+    pub timezone: String,
+    #[schema(value_type = String)]
+    pub start_time: NaiveDateTime,
+    pub duration_minutes: i32,
+    #[schema(value_type = Vec<String>)]
+    pub exdates: Vec<NaiveDateTime>,
+    /// Occurrences created so far, earliest first; later ones are added as
+    /// time passes
+    pub occurrences: Vec<EventResponse>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl EventSeriesResponse {
+    pub fn new(series: EventSeriesModel, occurrences: Vec<EventModel>) -> Self {
+        Self {
+            exdates: parse_exdates(&series.exdates).unwrap_or_default(),
+            id: series.id,
+            host_id: series.host_id,
+            title: series.title,
+            description: series.description,
+            location: series.location,
+            event_type: series.event_type,
+            category: series.category,
+            visibility: series.visibility,
+            rrule: series.rrule,
+            timezone: series.timezone,
+            start_time: series.start_local,
+            duration_minutes: series.duration_minutes,
+            occurrences: occurrences.into_iter().map(Into::into).collect(),
+            created_at: series.created_at,
+            updated_at: series.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CancelEventRequest {
+    /// Shown to attendees and on the event
+    #[validate(length(min = 1, max = 500))]
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AttendanceResponse {
+    pub id: i32,
+    pub event_id: i32,
+    pub attendee_id: i32,
+    pub status: AttendanceStatus,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AttendanceModel> for AttendanceResponse {
+    fn from(attendance: AttendanceModel) -> Self {
+        Self {
+            id: attendance.id,
+            event_id: attendance.event_id,
+            attendee_id: attendance.attendee_id,
+            status: attendance.status,
+            created_at: attendance.created_at,
+        }
+    }
+}
+
+/// Values accepted for event categories and attendee motivations.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ReferenceDataResponse {
+    pub event_categories: Vec<EventCategory>,
+    pub motivations: Vec<Motivation>,
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::entity::prelude::EventStaffModel;
+use crate::entity::{EventRole, InvitationStatus, StaffRole};
+use crate::schemas::event::EventResponse;
+
+#[derive(Debug, Serialize, Validate, Deserialize, ToSchema)]
+pub struct AddStaffRequest {
+    /// Username or email address of the user to ask
+    #[validate(length(min = 1, max = 255))]
+    pub user: String,
+    pub role: StaffRole,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StaffResponse {
+    pub id: i32,
+    pub event_id: i32,
+    pub user_id: i32,
+    pub role: StaffRole,
+    /// The role only takes effect once accepted
+    pub status: InvitationStatus,
+    pub invited_by: Option<i32>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<EventStaffModel> for StaffResponse {
+    fn from(staff: EventStaffModel) -> Self {
+        Self {
+            id: staff.id,
+            event_id: staff.event_id,
+            user_id: staff.user_id,
+            role: staff.role,
+            status: staff.status,
+            invited_by: staff.invited_by,
+            responded_at: staff.responded_at,
+            created_at: staff.created_at,
+            updated_at: staff.updated_at,
+        }
+    }
+}
+
+/// A role on an event together with the event.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserStaffRoleResponse {
+    #[serde(flatten)]
+    pub staff: StaffResponse,
+    pub event: EventResponse,
+}
+
+/// An event on the current user's dashboard.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ManagedEventResponse {
+    #[serde(flatten)]
+    pub event: EventResponse,
+    pub role: EventRole,
+}
@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Up,
+    Down,
+}
+
+/// Result of pinging one dependency.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DependencyCheck {
+    pub status: CheckStatus,
+    /// Whether the service is unready while this dependency is down
+    pub critical: bool,
+    pub latency_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LivenessResponse {
+    pub status: String,
+    pub version: String,
+    pub started_at: DateTime<Utc>,
+    pub uptime_seconds: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadinessResponse {
+    /// `ok`, `degraded` (an optional dependency is down) or `unavailable`
+    pub status: String,
+    pub version: String,
+    pub started_at: DateTime<Utc>,
+    pub uptime_seconds: i64,
+    pub checks: BTreeMap<String, DependencyCheck>,
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::entity::InvitationStatus;
+use crate::entity::prelude::{EventInvitationModel, EventInviteLinkModel};
+use crate::schemas::event::EventResponse;
+
+#[derive(Debug, Serialize, Validate, Deserialize, ToSchema)]
+pub struct InviteUsersRequest {
+    /// Usernames or email addresses of the users to invite
+    #[validate(length(min = 1, max = 100))]
+    pub invitees: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InvitationResponse {
+    pub id: i32,
+    pub event_id: i32,
+    pub user_id: i32,
+    /// Host who sent the invitation; unset for invite links
+    pub invited_by: Option<i32>,
+    /// Invite link the user redeemed, if any
+    pub invite_link_id: Option<i32>,
+    pub status: InvitationStatus,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<EventInvitationModel> for InvitationResponse {
+    fn from(invitation: EventInvitationModel) -> Self {
+        Self {
+            id: invitation.id,
+            event_id: invitation.event_id,
+            user_id: invitation.user_id,
+            invited_by: invitation.invited_by,
+            invite_link_id: invitation.invite_link_id,
+            status: invitation.status,
+            responded_at: invitation.responded_at,
+            created_at: invitation.created_at,
+            updated_at: invitation.updated_at,
+        }
+    }
+}
+
+/// An invitation together with the event it is for.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserInvitationResponse {
+    #[serde(flatten)]
+    pub invitation: InvitationResponse,
+    pub event: EventResponse,
+}
+
+#[derive(Debug, Serialize, Validate, Deserialize, ToSchema)]
+pub struct CreateInviteLinkRequest {
+    /// Number of people who can join through the link; unlimited if unset
+    #[validate(range(min = 1, max = 10000))]
+    pub max_uses: Option<i32>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InviteLinkResponse {
+    pub id: i32,
+    pub event_id: i32,
+    pub max_uses: Option<i32>,
+    pub use_count: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// Whether the link can still be redeemed
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<EventInviteLinkModel> for InviteLinkResponse {
+    fn from(link: EventInviteLinkModel) -> Self {
+        Self {
+            active: link.is_usable(Utc::now()),
+            id: link.id,
+            event_id: link.event_id,
+            max_uses: link.max_uses,
+            use_count: link.use_count,
+            expires_at: link.expires_at,
+            revoked_at: link.revoked_at,
+            created_at: link.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreatedInviteLinkResponse {
+    #[serde(flatten)]
+    pub link: InviteLinkResponse,
+    /// Page in the web app that redeems the link. It is only returned once
+    /// and cannot be retrieved later.
+    pub url: String,
+    /// The token in `url`, for `POST /invitations/redeem`
+    pub token: String,
+}
+
+#[derive(Serialize, Validate, Deserialize, ToSchema)]
+pub struct RedeemInviteLinkRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub token: String,
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::jobs::queue::{JobEnvelope, QueueStats};
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JobListQuery {
+    /// Number of dead jobs to return (1-100, default 50)
+    #[validate(range(min = 1, max = 100))]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct JobResponse {
+    pub id: String,
+    /// Job type, e.g. `send_email`
+    pub name: String,
+    /// The job's arguments
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+    pub attempts: u32,
+    pub max_attempts: u32,
+    pub enqueued_at: DateTime<Utc>,
+    pub run_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+}
+
+impl From<JobEnvelope> for JobResponse {
+    fn from(envelope: JobEnvelope) -> Self {
+        JobResponse {
+            name: envelope.job.name().to_string(),
+            payload: serde_json::to_value(&envelope.job).unwrap_or_default(),
+            id: envelope.id,
+            attempts: envelope.attempts,
+            max_attempts: envelope.max_attempts,
+            enqueued_at: envelope.enqueued_at,
+            run_at: envelope.run_at,
+            last_error: envelope.last_error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct QueueStatsResponse {
+    /// Waiting for a worker
+    pub ready: u64,
+    /// Scheduled for later, including retries
+    pub delayed: u64,
+    /// Currently running
+    pub processing: u64,
+    /// Failed for good
+    pub dead: u64,
+}
+
+impl From<QueueStats> for QueueStatsResponse {
+    fn from(stats: QueueStats) -> Self {
+        QueueStatsResponse {
+            ready: stats.ready,
+            delayed: stats.delayed,
+            processing: stats.processing,
+            dead: stats.dead,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct JobsOverviewResponse {
+    pub stats: QueueStatsResponse,
+    /// Most recently dead-lettered jobs, newest first
+    pub dead: Vec<JobResponse>,
+}
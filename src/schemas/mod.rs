@@ -1,2 +1,14 @@
+pub mod api_keys;
 pub mod auth;
+pub mod calendar;
+pub mod error;
+pub mod event;
+pub mod event_staff;
+pub mod health;
+pub mod invitation;
+pub mod jobs;
+pub mod notification;
+pub mod search;
+pub mod tag;
+pub mod ticket;
 pub mod user;
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::entity::prelude::NotificationModel;
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NotificationListQuery {
+    /// Page size (1-100, default 50)
+    #[validate(range(min = 1, max = 100))]
+    pub limit: Option<u64>,
+    /// Only return notifications that have not been read
+    pub unread: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NotificationResponse {
+    pub id: i32,
+    /// What happened, e.g. `event_cancelled`
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    pub event_id: Option<i32>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<NotificationModel> for NotificationResponse {
+    fn from(notification: NotificationModel) -> Self {
+        Self {
+            id: notification.id,
+            kind: notification.kind,
+            title: notification.title,
+            body: notification.body,
+            event_id: notification.event_id,
+            read_at: notification.read_at,
+            created_at: notification.created_at,
+        }
+    }
+}
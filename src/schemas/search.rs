@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::entity::prelude::UserModel;
+use crate::schemas::event::EventResponse;
+use crate::services::search::HostMatch;
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    /// Words to look for; each must match the start of a word, or nearly
+    /// match a word of a title or name
+    #[validate(length(min = 1, max = 200))]
+    pub q: String,
+    /// Results of each kind (1-50, default 10)
+    #[validate(range(min = 1, max = 50))]
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HostSearchResult {
+    pub user_id: i32,
+    pub username: String,
+    pub organization_name: Option<String>,
+}
+
+impl From<HostMatch> for HostSearchResult {
+    fn from(found: HostMatch) -> Self {
+        Self {
+            user_id: found.host.user_id,
+            username: found.username,
+            organization_name: found.host.organization_name,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserSearchResult {
+    pub id: i32,
+    pub username: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+impl From<UserModel> for UserSearchResult {
+    fn from(user: UserModel) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            avatar_url: user.avatar_url,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SearchResponse {
+    /// Public events, best match first
+    pub events: Vec<EventResponse>,
+    pub hosts: Vec<HostSearchResult>,
+    /// Only searched for signed-in callers
+    pub users: Vec<UserSearchResult>,
+}
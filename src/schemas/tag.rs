@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::services::tags::TagUsage;
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TagQuery {
+    /// Start of the tag, e.g. `ru` or `#Ru`; normalized like tags are
+    #[validate(length(min = 1, max = 50))]
+    pub prefix: String,
+    /// Number of suggestions (1-50, default 10)
+    #[validate(range(min = 1, max = 50))]
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TagResponse {
+    pub name: String,
+    /// Public events with the tag
+    pub event_count: i64,
+}
+
+impl From<TagUsage> for TagResponse {
+    fn from(usage: TagUsage) -> Self {
+        Self {
+            name: usage.name,
+            event_count: usage.event_count,
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::entity::prelude::{LedgerEntryModel, PromoCodeModel, TicketOrderModel, TicketTierModel};
+use crate::entity::{DiscountType, LedgerEntryKind, OrderStatus};
+use crate::services::tickets::{available, on_sale};
+
+#[derive(Debug, Serialize, Validate, Deserialize, ToSchema)]
+pub struct CreateTicketTierRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    /// In the currency's minor unit, e.g. kobo; 0 for free tickets
+    #[validate(range(min = 0, max = 10_000_000_000i64))]
+    pub price: i64,
+    /// ISO 4217 code, e.g. `NGN`
+    pub currency: String,
+    /// Tickets for sale
+    #[validate(range(min = 1, max = 100000))]
+    pub quantity: i32,
+    /// On sale from this time; straight away if unset
+    pub sales_start_at: Option<DateTime<Utc>>,
+    /// On sale until this time; until the event completes if unset
+    pub sales_end_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Validate, Deserialize, ToSchema)]
+pub struct UpdateTicketTierRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    /// Applies to orders placed from now on
+    #[validate(range(min = 0, max = 10_000_000_000i64))]
+    pub price: Option<i64>,
+    /// Cannot go below the tickets already sold or held
+    #[validate(range(min = 1, max = 100000))]
+    pub quantity: Option<i32>,
+    pub sales_start_at: Option<DateTime<Utc>>,
+    pub sales_end_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TicketTierResponse {
+    pub id: i32,
+    pub event_id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: i64,
+    pub currency: String,
+    pub quantity: i32,
+    /// Tickets neither sold nor held for a checkout in progress
+    pub available: i32,
+    pub sales_start_at: Option<DateTime<Utc>>,
+    pub sales_end_at: Option<DateTime<Utc>>,
+    /// Whether the sale window is open now
+    pub on_sale: bool,
+}
+
+impl From<TicketTierModel> for TicketTierResponse {
+    fn from(tier: TicketTierModel) -> Self {
+        Self {
+            available: available(&tier),
+            on_sale: on_sale(&tier, Utc::now()),
+            id: tier.id,
+            event_id: tier.event_id,
+            name: tier.name,
+            description: tier.description,
+            price: tier.price,
+            currency: tier.currency,
+            quantity: tier.quantity,
+            sales_start_at: tier.sales_start_at,
+            sales_end_at: tier.sales_end_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Validate, Deserialize, ToSchema)]
+pub struct CreatePromoCodeRequest {
+    /// Letters, digits, `-` and `_`; not case sensitive
+    pub code: String,
+    pub discount_type: DiscountType,
+    /// Percent off (1-100), or minor units off each order
+    #[validate(range(min = 1))]
+    pub amount: i64,
+    /// Orders that can use the code; unlimited if unset
+    #[validate(range(min = 1, max = 100000))]
+    pub max_uses: Option<i32>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PromoCodeResponse {
+    pub id: i32,
+    pub event_id: i32,
+    pub code: String,
+    pub discount_type: DiscountType,
+    pub amount: i64,
+    pub max_uses: Option<i32>,
+    /// Orders paid or in checkout with the code
+    pub uses: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<PromoCodeModel> for PromoCodeResponse {
+    fn from(promo: PromoCodeModel) -> Self {
+        Self {
+            id: promo.id,
+            event_id: promo.event_id,
+            code: promo.code,
+            discount_type: promo.discount_type,
+            amount: promo.amount,
+            max_uses: promo.max_uses,
+            uses: promo.uses,
+            expires_at: promo.expires_at,
+            created_at: promo.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Validate, Deserialize, ToSchema)]
+pub struct CheckoutRequest {
+    pub tier_id: i32,
+    /// Tickets to buy (1-10)
+    #[validate(range(min = 1, max = 10))]
+    pub quantity: i32,
+    pub promo_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrderResponse {
+    pub id: i32,
+    /// Shown to the payment provider and on receipts
+    pub reference: String,
+    pub event_id: i32,
+    pub tier_id: i32,
+    pub quantity: i32,
+    /// Amounts in the currency's minor unit
+    pub subtotal: i64,
+    pub discount: i64,
+    pub total: i64,
+    pub currency: String,
+    pub status: OrderStatus,
+    /// The tickets are held until then; pay before it
+    pub expires_at: DateTime<Utc>,
+    /// Where to pay, for orders that are not free
+    pub checkout_url: Option<String>,
+    /// The buyer's registration, once paid
+    pub attendance_id: Option<i32>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub refunded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<TicketOrderModel> for OrderResponse {
+    fn from(order: TicketOrderModel) -> Self {
+        Self {
+            id: order.id,
+            reference: order.reference,
+            event_id: order.event_id,
+            tier_id: order.tier_id,
+            quantity: order.quantity,
+            subtotal: order.subtotal,
+            discount: order.discount,
+            total: order.total,
+            currency: order.currency,
+            status: order.status,
+            expires_at: order.expires_at,
+            checkout_url: order.checkout_url,
+            attendance_id: order.attendance_id,
+            paid_at: order.paid_at,
+            refunded_at: order.refunded_at,
+            created_at: order.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LedgerEntryResponse {
+    pub id: i32,
+    pub order_id: Option<i32>,
+    pub kind: LedgerEntryKind,
+    /// In the currency's minor unit: positive for charges, negative for refunds
+    pub amount: i64,
+    pub currency: String,
+    /// The payment provider that moved the money
+    pub provider: String,
+    /// The provider's id for the payment or refund
+    pub provider_reference: String,
+    pub description: String,
+    /// A refund not yet confirmed by the provider
+    pub pending: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<LedgerEntryModel> for LedgerEntryResponse {
+    fn from(entry: LedgerEntryModel) -> Self {
+        Self {
+            id: entry.id,
+            order_id: entry.order_id,
+            kind: entry.kind,
+            amount: entry.amount,
+            currency: entry.currency,
+            provider: entry.provider,
+            provider_reference: entry.provider_reference,
+            description: entry.description,
+            pending: entry.pending,
+            created_at: entry.created_at,
+        }
+    }
+}
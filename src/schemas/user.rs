@@ -1,8 +1,12 @@
+use std::fmt;
+
 use serde::{self, Deserialize, Serialize};
 use utoipa::ToSchema;
 use validator::Validate;
 
-#[derive(Debug, Serialize, Validate, Deserialize, ToSchema)]
+use crate::utils::redact::REDACTED;
+
+#[derive(Serialize, Validate, Deserialize, ToSchema)]
 pub struct SignUp {
     pub username: String,
     pub first_name: Option<String>,
@@ -16,6 +20,19 @@ pub struct SignUp {
     pub password: String,
 }
 
+impl fmt::Debug for SignUp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignUp")
+            .field("username", &self.username)
+            .field("first_name", &self.first_name)
+            .field("last_name", &self.last_name)
+            .field("email", &self.email)
+            .field("avatar_url", &self.avatar_url)
+            .field("password", &REDACTED)
+            .finish()
+    }
+}
+
 #[derive(Debug, Serialize, Validate, Deserialize, ToSchema)]
 pub struct SignShow {
     pub id: i32,
@@ -27,3 +44,10 @@ pub struct SignShow {
     #[validate(url)]
     pub avatar_url: Option<String>,
 }
+
+#[derive(Debug, Serialize, Validate, Deserialize, ToSchema)]
+pub struct TimezoneUpdate {
+    /// IANA time zone such as `Europe/London`, or `null` to clear it
+    #[validate(length(min = 1, max = 64))]
+    pub timezone: Option<String>,
+}
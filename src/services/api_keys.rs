@@ -0,0 +1,116 @@
+use std::error::Error;
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, QueryOrder,
+};
+use subtle::ConstantTimeEq;
+
+use crate::entity::api_key::ApiScope;
+use crate::entity::prelude::*;
+use crate::utils::utils::{api_key_prefix, generate_api_key, hash_api_key};
+
+/// How stale `last_used_at` may get before an authenticated request refreshes it.
+const LAST_USED_REFRESH_SECONDS: i64 = 60;
+
+/// Create a key for the user, returning the stored row and the plaintext key.
+/// The plaintext is not recoverable afterwards.
+pub async fn create_api_key(
+    db: &DatabaseConnection,
+    user_id: i32,
+    name: String,
+    scopes: &[ApiScope],
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<(ApiKeyModel, String), Box<dyn Error>> {
+    let (prefix, key) = generate_api_key();
+    let scopes = scopes
+        .iter()
+        .map(ApiScope::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let api_key = ApiKeyActiveModel {
+        user_id: Set(user_id),
+        name: Set(name),
+        prefix: Set(prefix),
+        key_hash: Set(hash_api_key(&key)),
+        scopes: Set(scopes),
+        expires_at: Set(expires_at),
+        last_used_at: Set(None),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok((api_key, key))
+}
+
+pub async fn list_api_keys(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<ApiKeyModel>, Box<dyn Error>> {
+    let keys = ApiKey::find()
+        .filter(ApiKeyColumn::UserId.eq(user_id))
+        .order_by_desc(ApiKeyColumn::CreatedAt)
+        .all(db)
+        .await?;
+
+    Ok(keys)
+}
+
+/// Delete one of the user's keys. Returns `false` when no such key exists.
+pub async fn delete_api_key(
+    db: &DatabaseConnection,
+    user_id: i32,
+    key_id: i32,
+) -> Result<bool, Box<dyn Error>> {
+    let res = ApiKey::delete_many()
+        .filter(ApiKeyColumn::Id.eq(key_id))
+        .filter(ApiKeyColumn::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+
+    Ok(res.rows_affected > 0)
+}
+
+/// Resolve a plaintext key to its row, rejecting unknown, mismatched or
+/// expired keys. Bumps `last_used_at` at most once per
+/// [`LAST_USED_REFRESH_SECONDS`].
+pub async fn authenticate_api_key(
+    db: &DatabaseConnection,
+    key: &str,
+) -> Result<ApiKeyModel, Box<dyn Error>> {
+    let prefix = api_key_prefix(key).ok_or("Malformed API key")?;
+
+    let api_key = ApiKey::find()
+        .filter(ApiKeyColumn::Prefix.eq(prefix))
+        .one(db)
+        .await?
+        .ok_or("API key not found")?;
+
+    let hash = hash_api_key(key);
+    if !bool::from(hash.as_bytes().ct_eq(api_key.key_hash.as_bytes())) {
+        return Err("API key mismatch".into());
+    }
+
+    let now = Utc::now();
+    if api_key
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= now)
+    {
+        return Err("API key expired".into());
+    }
+
+    let fresh = api_key
+        .last_used_at
+        .is_some_and(|last_used| now - last_used < Duration::seconds(LAST_USED_REFRESH_SECONDS));
+    if fresh {
+        return Ok(api_key);
+    }
+
+    let mut active = api_key.into_active_model();
+    active.last_used_at = Set(Some(now));
+    Ok(active.update(db).await?)
+}
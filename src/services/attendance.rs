@@ -0,0 +1,104 @@
+use std::error::Error;
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, ModelTrait, QueryFilter,
+};
+
+use crate::entity::prelude::*;
+use crate::entity::{AttendanceStatus, EventStatus, EventType};
+use crate::services::invitations::{accept_invitation, can_view};
+use crate::services::tickets::has_tiers;
+
+/// Result of [`rsvp`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RsvpOutcome {
+    Registered(AttendanceModel),
+    /// The user already had a place; nothing changed
+    AlreadyRegistered(AttendanceModel),
+    NotFound,
+    /// The event no longer takes RSVPs
+    Closed(EventStatus),
+    /// The event sells tickets; buying one registers the user
+    TicketRequired,
+}
+
+/// Register `user` for a free event they can see that has not completed or
+/// been cancelled. Users without an attendee profile get one, and an
+/// invitation to the event counts as accepted.
+pub async fn rsvp(
+    db: &DatabaseConnection,
+    event_id: i32,
+    user: &UserModel,
+) -> Result<RsvpOutcome, Box<dyn Error>> {
+    let user_id = user.id;
+    let Some(event) = Event::find_by_id(event_id).one(db).await? else {
+        return Ok(RsvpOutcome::NotFound);
+    };
+    if !can_view(db, &event, Some(user)).await? {
+        return Ok(RsvpOutcome::NotFound);
+    }
+    if event.status.is_final() {
+        return Ok(RsvpOutcome::Closed(event.status));
+    }
+
+    if let Some(existing) = Attendance::find()
+        .filter(AttendanceColumn::EventId.eq(event_id))
+        .filter(AttendanceColumn::AttendeeId.eq(user_id))
+        .one(db)
+        .await?
+    {
+        accept_invitation(db, event_id, user_id).await?;
+        return Ok(RsvpOutcome::AlreadyRegistered(existing));
+    }
+    if has_tiers(db, event_id).await? {
+        return Ok(RsvpOutcome::TicketRequired);
+    }
+
+    ensure_attendee(db, user_id, event.event_type).await?;
+
+    let now = Utc::now();
+    let attendance = AttendanceActiveModel {
+        event_id: Set(event_id),
+        attendee_id: Set(user_id),
+        status: Set(AttendanceStatus::Registered),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    // A cancellation that committed before this insert did not see it and
+    // so notified nobody about it; back out rather than leave a silent RSVP.
+    // One that commits later finds the row and notifies this attendee too.
+    let status = Event::find_by_id(event_id)
+        .one(db)
+        .await?
+        .map(|event| event.status);
+    if let Some(status) = status.filter(|status| status.is_final()) {
+        attendance.delete(db).await?;
+        return Ok(RsvpOutcome::Closed(status));
+    }
+
+    accept_invitation(db, event_id, user_id).await?;
+    Ok(RsvpOutcome::Registered(attendance))
+}
+
+/// Give `user_id` an attendee profile if they have none yet.
+pub(crate) async fn ensure_attendee<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    preferred_event_type: EventType,
+) -> Result<(), Box<dyn Error>> {
+    if Attendee::find_by_id(user_id).one(db).await?.is_none() {
+        AttendeeActiveModel {
+            user_id: Set(user_id),
+            preferred_event_type: Set(preferred_event_type),
+        }
+        .insert(db)
+        .await?;
+    }
+    Ok(())
+}
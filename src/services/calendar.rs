@@ -0,0 +1,267 @@
+//! iCalendar (RFC 5545) export.
+//!
+//! Single events can be downloaded as `.ics` files, and every user can
+//! create a secret feed URL listing the events they registered for.
+//! Calendar apps poll the feed, so changes and cancellations reach them
+//! without any further action.
+use std::collections::HashMap;
+use std::error::Error;
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, QueryOrder, TransactionTrait,
+};
+
+use crate::entity::prelude::*;
+use crate::entity::{AttendanceStatus, EventStatus};
+use crate::utils::utils::{generate_token, hash_api_key};
+
+/// Past events stay in feeds this long after they end.
+const FEED_HISTORY: Duration = Duration::days(90);
+
+/// How often calendar apps are asked to refresh a feed.
+const FEED_REFRESH_INTERVAL: &str = "PT1H";
+
+/// Lines longer than this many octets are folded (RFC 5545 section 3.1).
+const MAX_LINE_OCTETS: usize = 75;
+
+/// An event together with the user hosting it.
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    pub event: EventModel,
+    pub organizer: Option<UserModel>,
+}
+
+/// Render a VCALENDAR containing `events`. `url_base` is the API's base URL,
+/// used to link back to each event.
+pub fn render_calendar(name: &str, events: &[CalendarEvent], url_base: &str) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Here//Events//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(name)),
+        format!("REFRESH-INTERVAL;VALUE=DURATION:{}", FEED_REFRESH_INTERVAL),
+        format!("X-PUBLISHED-TTL:{}", FEED_REFRESH_INTERVAL),
+    ];
+    for entry in events {
+        lines.extend(vevent(entry, url_base));
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut calendar = String::new();
+    for line in lines {
+        calendar.push_str(&fold_line(&line));
+    }
+    calendar
+}
+
+fn vevent(entry: &CalendarEvent, url_base: &str) -> Vec<String> {
+    let event = &entry.event;
+    let mut description = event.description.clone();
+    if let Some(reason) = &event.cancellation_reason {
+        description = format!("Cancelled: {}\n\n{}", reason, description);
+    }
+
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:event-{}@here", event.id),
+        format!("DTSTAMP:{}", format_utc(event.updated_at)),
+        format!("DTSTART:{}", format_utc(event.start_time)),
+        format!("DTEND:{}", format_utc(event.end_time)),
+        format!("SUMMARY:{}", escape_text(&event.title)),
+        format!("DESCRIPTION:{}", escape_text(&description)),
+        format!("LOCATION:{}", escape_text(&event.location)),
+        format!("STATUS:{}", ical_status(event.status)),
+        // Calendar apps take the copy with the highest SEQUENCE; every
+        // change bumps `updated_at`, so seconds since creation only grow
+        format!(
+            "SEQUENCE:{}",
+            (event.updated_at - event.created_at).num_seconds().max(0)
+        ),
+        format!("CREATED:{}", format_utc(event.created_at)),
+        format!("LAST-MODIFIED:{}", format_utc(event.updated_at)),
+        format!("URL:{}/events/{}", url_base.trim_end_matches('/'), event.id),
+    ];
+    if let Some(organizer) = &entry.organizer {
+        let name = match (&organizer.first_name, &organizer.last_name) {
+            (Some(first), Some(last)) => format!("{} {}", first, last),
+            (Some(name), None) | (None, Some(name)) => name.clone(),
+            (None, None) => organizer.username.clone(),
+        };
+        lines.push(format!(
+            "ORGANIZER;CN={}:mailto:{}",
+            quote_param(&name),
+            organizer.email
+        ));
+    }
+    lines.push("END:VEVENT".to_string());
+    lines
+}
+
+fn ical_status(status: EventStatus) -> &'static str {
+    match status {
+        EventStatus::Scheduled | EventStatus::Ongoing | EventStatus::Completed => "CONFIRMED",
+        EventStatus::Cancelled => "CANCELLED",
+    }
+}
+
+fn format_utc(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value (RFC 5545 section 3.3.11).
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Quote a parameter value; DQUOTE cannot appear inside, so it is dropped.
+fn quote_param(value: &str) -> String {
+    let value: String = value
+        .chars()
+        .filter(|c| *c != '"' && !c.is_control())
+        .collect();
+    format!("\"{}\"", value)
+}
+
+/// Split `line` into CRLF-terminated lines of at most [`MAX_LINE_OCTETS`]
+/// octets, continuation lines starting with a space. Never splits a
+/// multi-byte character.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// Attach each event's host as its organizer.
+pub async fn with_organizers(
+    db: &DatabaseConnection,
+    events: Vec<EventModel>,
+) -> Result<Vec<CalendarEvent>, Box<dyn Error>> {
+    let mut host_ids: Vec<i32> = events.iter().map(|event| event.host_id).collect();
+    host_ids.sort_unstable();
+    host_ids.dedup();
+    let hosts: HashMap<i32, UserModel> = User::find()
+        .filter(UserColumn::Id.is_in(host_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user))
+        .collect();
+
+    Ok(events
+        .into_iter()
+        .map(|event| CalendarEvent {
+            organizer: hosts.get(&event.host_id).cloned(),
+            event,
+        })
+        .collect())
+}
+
+/// Give the user a new feed token, replacing any previous one so old feed
+/// URLs stop working. Returns the plaintext token, which is not stored.
+pub async fn rotate_feed_token(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<String, Box<dyn Error>> {
+    let token = generate_token();
+    let txn = db.begin().await?;
+    CalendarFeed::delete_many()
+        .filter(CalendarFeedColumn::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+    CalendarFeedActiveModel {
+        user_id: Set(user_id),
+        token_hash: Set(hash_api_key(&token)),
+        last_accessed_at: Set(None),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    txn.commit().await?;
+
+    Ok(token)
+}
+
+/// Turn off the user's feed. Returns `false` if they had none.
+pub async fn delete_feed(db: &DatabaseConnection, user_id: i32) -> Result<bool, Box<dyn Error>> {
+    let res = CalendarFeed::delete_many()
+        .filter(CalendarFeedColumn::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+    Ok(res.rows_affected > 0)
+}
+
+/// The active user a feed token belongs to, if any.
+pub async fn feed_owner(
+    db: &DatabaseConnection,
+    token: &str,
+) -> Result<Option<UserModel>, Box<dyn Error>> {
+    let Some(feed) = CalendarFeed::find()
+        .filter(CalendarFeedColumn::TokenHash.eq(hash_api_key(token)))
+        .one(db)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let user_id = feed.user_id;
+    let mut feed = feed.into_active_model();
+    feed.last_accessed_at = Set(Some(Utc::now()));
+    feed.update(db).await?;
+
+    Ok(User::find_by_id(user_id)
+        .one(db)
+        .await?
+        .filter(|user| user.is_active))
+}
+
+/// Events the user registered for that have not ended more than
+/// [`FEED_HISTORY`] ago, cancelled ones included so calendars drop them.
+pub async fn registered_events(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<EventModel>, Box<dyn Error>> {
+    let event_ids: Vec<i32> = Attendance::find()
+        .filter(AttendanceColumn::AttendeeId.eq(user_id))
+        .filter(
+            AttendanceColumn::Status
+                .is_in([AttendanceStatus::Registered, AttendanceStatus::CheckedIn]),
+        )
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|attendance| attendance.event_id)
+        .collect();
+
+    let events = Event::find()
+        .filter(EventColumn::Id.is_in(event_ids))
+        .filter(EventColumn::EndTime.gt(Utc::now() - FEED_HISTORY))
+        .order_by_asc(EventColumn::StartTime)
+        .all(db)
+        .await?;
+    Ok(events)
+}
@@ -0,0 +1,167 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+
+use crate::core::configs::AppConfig;
+use crate::entity::prelude::*;
+
+/// A plain-text email.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Delivers email. Jobs send through this so tests can swap in [`RecordingMailer`].
+#[async_trait(?Send)]
+pub trait Mailer: Send + Sync + fmt::Debug {
+    async fn send(&self, message: &EmailMessage) -> Result<(), Box<dyn Error>>;
+}
+
+/// Sends through the SMTP server from `SMTP_*` settings.
+#[derive(Debug)]
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    /// Build the transport; no connection is made until the first send.
+    pub fn from_config(config: &AppConfig) -> Result<Self, String> {
+        let builder = match config.smtp_tls.as_str() {
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host),
+            "none" => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                &config.smtp_host,
+            )),
+            _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host),
+        }
+        .map_err(|e| format!("Invalid SMTP settings: {}", e))?;
+
+        let transport = builder
+            .port(config.smtp_port)
+            .credentials(Credentials::new(
+                config.smtp_username.clone(),
+                config.smtp_password.clone(),
+            ))
+            .build();
+        let from = config
+            .smtp_from_email
+            .parse()
+            .map_err(|e| format!("Invalid SMTP_FROM_EMAIL: {}", e))?;
+
+        Ok(SmtpMailer { transport, from })
+    }
+}
+
+#[async_trait(?Send)]
+impl Mailer for SmtpMailer {
+    async fn send(&self, message: &EmailMessage) -> Result<(), Box<dyn Error>> {
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(message.to.parse()?)
+            .subject(message.subject.clone())
+            .body(message.body.clone())?;
+        self.transport.send(email).await?;
+        Ok(())
+    }
+}
+
+/// Keeps messages in memory instead of sending them.
+#[derive(Debug, Default)]
+pub struct RecordingMailer {
+    sent: Mutex<Vec<EmailMessage>>,
+}
+
+impl RecordingMailer {
+    pub fn sent(&self) -> Vec<EmailMessage> {
+        self.sent
+            .lock()
+            .map(|sent| sent.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait(?Send)]
+impl Mailer for RecordingMailer {
+    async fn send(&self, message: &EmailMessage) -> Result<(), Box<dyn Error>> {
+        self.sent
+            .lock()
+            .map_err(|_| "Mailer lock poisoned")?
+            .push(message.clone());
+        Ok(())
+    }
+}
+
+fn greeting(user: &UserModel) -> String {
+    format!(
+        "Hi {},",
+        user.first_name.as_deref().unwrap_or(&user.username)
+    )
+}
+
+pub fn verification_email(user: &UserModel, link: &str) -> EmailMessage {
+    EmailMessage {
+        to: user.email.clone(),
+        subject: "Confirm your email address".to_string(),
+        body: format!(
+            "{}\n\nPlease confirm your email address by opening this link:\n\n{}\n\n\
+             The link is valid for 48 hours. If you did not sign up, ignore this email.\n",
+            greeting(user),
+            link
+        ),
+    }
+}
+
+pub fn password_reset_email(user: &UserModel, link: &str) -> EmailMessage {
+    EmailMessage {
+        to: user.email.clone(),
+        subject: "Reset your password".to_string(),
+        body: format!(
+            "{}\n\nSomeone asked to reset the password for your account. To choose a new \
+             password, open this link:\n\n{}\n\nThe link is valid for one hour. If this \
+             wasn't you, ignore this email; your password stays the same.\n",
+            greeting(user),
+            link
+        ),
+    }
+}
+
+pub fn event_reminder_email(user: &UserModel, event: &EventModel) -> EmailMessage {
+    EmailMessage {
+        to: user.email.clone(),
+        subject: format!("Reminder: {} starts soon", event.title),
+        body: format!(
+            "{}\n\n{} starts at {} in {}.\n\nSee you there!\n",
+            greeting(user),
+            event.title,
+            format_time(event.start_time),
+            event.location
+        ),
+    }
+}
+
+pub fn event_cancelled_email(user: &UserModel, event: &EventModel) -> EmailMessage {
+    EmailMessage {
+        to: user.email.clone(),
+        subject: format!("Cancelled: {}", event.title),
+        body: format!(
+            "{}\n\nUnfortunately {} on {} has been cancelled by the host.\n\nReason: {}\n",
+            greeting(user),
+            event.title,
+            format_time(event.start_time),
+            event.cancellation_reason.as_deref().unwrap_or("none given")
+        ),
+    }
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%A %-d %B %Y, %H:%M UTC").to_string()
+}
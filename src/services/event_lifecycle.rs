@@ -0,0 +1,305 @@
+//! Event status lifecycle.
+//!
+//! Events move Scheduled -> Ongoing -> Completed on their own as their start
+//! and end times pass, and hosts may move them along early or cancel them.
+//! Every change goes through [`transition_event_status`] or
+//! [`cancel_event`], which enforce [`EventStatus::can_transition_to`] and
+//! publish domain events.
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection,
+    DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+
+use crate::core::cache::Cache;
+use crate::core::domain_events::{DomainEvent, DomainEvents};
+use crate::entity::notification::NotificationKind;
+use crate::entity::prelude::*;
+use crate::entity::{AttendanceStatus, EventStatus};
+use crate::services::events::invalidate_event;
+use crate::services::notifications::{NewNotification, notify_users};
+
+/// Events advanced per sweep; the rest are picked up by the next one.
+const SWEEP_BATCH_SIZE: u64 = 500;
+
+/// Result of [`transition_event_status`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatusChange {
+    Changed(Box<EventModel>),
+    NotFound,
+    /// The state machine does not allow moving from `from`
+    Rejected {
+        from: EventStatus,
+    },
+}
+
+/// The status an event should move to at `now` according to its times, if any.
+pub fn due_status(event: &EventModel, now: DateTime<Utc>) -> Option<EventStatus> {
+    match event.status {
+        EventStatus::Scheduled if event.start_time <= now => Some(EventStatus::Ongoing),
+        EventStatus::Ongoing if event.end_time <= now => Some(EventStatus::Completed),
+        _ => None,
+    }
+}
+
+/// Move an event to `to`. `changed_by` is the acting user, `None` for the
+/// scheduler. On completion, registrations nobody checked in for become
+/// `NoShow` in the same transaction. Cancelling should go through
+/// [`cancel_event`], which also records why and tells attendees.
+pub async fn transition_event_status(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    domain_events: &DomainEvents,
+    event_id: i32,
+    to: EventStatus,
+    changed_by: Option<i32>,
+) -> Result<StatusChange, Box<dyn Error>> {
+    let Some(event) = Event::find_by_id(event_id).one(db).await? else {
+        return Ok(StatusChange::NotFound);
+    };
+    if !event.status.can_transition_to(to) {
+        return Ok(StatusChange::Rejected { from: event.status });
+    }
+
+    let now = Utc::now();
+    let txn = db.begin().await?;
+    let Some(no_shows) = apply_transition(
+        &txn,
+        &event,
+        to,
+        now,
+        EventActiveModel {
+            ..Default::default()
+        },
+    )
+    .await?
+    else {
+        return Ok(StatusChange::Rejected { from: event.status });
+    };
+    txn.commit().await?;
+
+    let event = finish_transition(cache, domain_events, event, to, changed_by, now, no_shows).await;
+    Ok(StatusChange::Changed(Box::new(event)))
+}
+
+/// Cancel an event on behalf of `cancelled_by`, recording the reason, and
+/// give every registered and waitlisted attendee an in-app notification in
+/// the same transaction. Emailing them is left to the caller, see
+/// [`Job::EventCancelledEmails`](crate::jobs::Job::EventCancelledEmails).
+pub async fn cancel_event(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    domain_events: &DomainEvents,
+    event_id: i32,
+    cancelled_by: i32,
+    reason: &str,
+) -> Result<StatusChange, Box<dyn Error>> {
+    let Some(event) = Event::find_by_id(event_id).one(db).await? else {
+        return Ok(StatusChange::NotFound);
+    };
+    if !event.status.can_transition_to(EventStatus::Cancelled) {
+        return Ok(StatusChange::Rejected { from: event.status });
+    }
+
+    let now = Utc::now();
+    let txn = db.begin().await?;
+    let changes = EventActiveModel {
+        cancelled_at: Set(Some(now)),
+        cancelled_by: Set(Some(cancelled_by)),
+        cancellation_reason: Set(Some(reason.to_string())),
+        ..Default::default()
+    };
+    if apply_transition(&txn, &event, EventStatus::Cancelled, now, changes)
+        .await?
+        .is_none()
+    {
+        return Ok(StatusChange::Rejected { from: event.status });
+    }
+
+    let attendees = affected_attendees(&txn, event_id).await?;
+    let notified = notify_users(
+        &txn,
+        &attendees,
+        &NewNotification {
+            kind: NotificationKind::EventCancelled,
+            title: format!("{} has been cancelled", event.title),
+            body: format!("The host cancelled this event: {}", reason),
+            event_id: Some(event_id),
+        },
+    )
+    .await?;
+    txn.commit().await?;
+
+    let mut event = finish_transition(
+        cache,
+        domain_events,
+        event,
+        EventStatus::Cancelled,
+        Some(cancelled_by),
+        now,
+        0,
+    )
+    .await;
+    event.cancelled_at = Some(now);
+    event.cancelled_by = Some(cancelled_by);
+    event.cancellation_reason = Some(reason.to_string());
+    domain_events.publish(DomainEvent::EventCancelled {
+        event_id,
+        cancelled_by,
+        reason: reason.to_string(),
+        notified,
+    });
+
+    Ok(StatusChange::Changed(Box::new(event)))
+}
+
+/// Users with a registered or waitlisted place at the event.
+pub async fn affected_attendees<C: ConnectionTrait>(
+    db: &C,
+    event_id: i32,
+) -> Result<Vec<i32>, Box<dyn Error>> {
+    let rows = Attendance::find()
+        .filter(AttendanceColumn::EventId.eq(event_id))
+        .filter(
+            AttendanceColumn::Status
+                .is_in([AttendanceStatus::Registered, AttendanceStatus::Waitlisted]),
+        )
+        .order_by_asc(AttendanceColumn::Id)
+        .all(db)
+        .await?;
+
+    Ok(rows.into_iter().map(|row| row.attendee_id).collect())
+}
+
+/// Write the status change guarded on the status it was checked against,
+/// so of two racing changes only one wins. Returns `None` when the guard
+/// failed, otherwise the number of attendees marked `NoShow`.
+async fn apply_transition(
+    txn: &DatabaseTransaction,
+    event: &EventModel,
+    to: EventStatus,
+    now: DateTime<Utc>,
+    mut changes: EventActiveModel,
+) -> Result<Option<u64>, Box<dyn Error>> {
+    changes.status = Set(to);
+    changes.updated_at = Set(now);
+    let updated = Event::update_many()
+        .set(changes)
+        .filter(EventColumn::Id.eq(event.id))
+        .filter(EventColumn::Status.eq(event.status))
+        .exec(txn)
+        .await?;
+    if updated.rows_affected == 0 {
+        return Ok(None);
+    }
+
+    if to != EventStatus::Completed {
+        return Ok(Some(0));
+    }
+    let no_shows = Attendance::update_many()
+        .set(AttendanceActiveModel {
+            status: Set(AttendanceStatus::NoShow),
+            updated_at: Set(now),
+            ..Default::default()
+        })
+        .filter(AttendanceColumn::EventId.eq(event.id))
+        .filter(AttendanceColumn::Status.eq(AttendanceStatus::Registered))
+        .exec(txn)
+        .await?
+        .rows_affected;
+    Ok(Some(no_shows))
+}
+
+/// Invalidate caches and publish domain events after a committed change.
+async fn finish_transition(
+    cache: &Cache,
+    domain_events: &DomainEvents,
+    mut event: EventModel,
+    to: EventStatus,
+    changed_by: Option<i32>,
+    now: DateTime<Utc>,
+    no_shows: u64,
+) -> EventModel {
+    invalidate_event(cache, event.id).await;
+
+    domain_events.publish(DomainEvent::EventStatusChanged {
+        event_id: event.id,
+        from: event.status,
+        to,
+        changed_by,
+        at: now,
+    });
+    if no_shows > 0 {
+        domain_events.publish(DomainEvent::AttendeesMarkedNoShow {
+            event_id: event.id,
+            count: no_shows,
+        });
+    }
+
+    event.status = to;
+    event.updated_at = now;
+    event
+}
+
+/// Apply every transition an event's times call for, e.g. Scheduled
+/// straight through Ongoing to Completed for an event that ended while
+/// nothing was running. Returns the number of transitions made.
+pub async fn advance_event_status(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    domain_events: &DomainEvents,
+    event_id: i32,
+) -> Result<u32, Box<dyn Error>> {
+    let Some(mut event) = Event::find_by_id(event_id).one(db).await? else {
+        return Ok(0);
+    };
+
+    let mut transitions = 0;
+    while let Some(next) = due_status(&event, Utc::now()) {
+        match transition_event_status(db, cache, domain_events, event_id, next, None).await? {
+            StatusChange::Changed(changed) => {
+                event = *changed;
+                transitions += 1;
+            }
+            // Someone else moved it first
+            StatusChange::NotFound | StatusChange::Rejected { .. } => break,
+        }
+    }
+    Ok(transitions)
+}
+
+/// Advance every event whose start or end time has passed. Safe to run
+/// from several workers at once. Returns the number of transitions made.
+pub async fn advance_due_events(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    domain_events: &DomainEvents,
+) -> Result<u32, Box<dyn Error>> {
+    let now = Utc::now();
+    let due = Event::find()
+        .filter(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(EventColumn::Status.eq(EventStatus::Scheduled))
+                        .add(EventColumn::StartTime.lte(now)),
+                )
+                .add(
+                    Condition::all()
+                        .add(EventColumn::Status.eq(EventStatus::Ongoing))
+                        .add(EventColumn::EndTime.lte(now)),
+                ),
+        )
+        .order_by_asc(EventColumn::StartTime)
+        .limit(SWEEP_BATCH_SIZE)
+        .all(db)
+        .await?;
+
+    let mut transitions = 0;
+    for event in due {
+        transitions += advance_event_status(db, cache, domain_events, event.id).await?;
+    }
+    Ok(transitions)
+}
@@ -0,0 +1,464 @@
+//! Recurring event series.
+//!
+//! A series keeps an RRULE anchored at a wall-clock start time in an IANA
+//! time zone. Its occurrences are created ahead of time as ordinary
+//! `events` rows, up to [`MATERIALIZE_HORIZON`] ahead, so RSVPs, status
+//! changes and cancellation work on one occurrence like on any other event.
+//! The job workers extend every series as time passes.
+use std::error::Error;
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait,
+    DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, TransactionTrait,
+};
+use tracing::error;
+
+use crate::core::cache::{Cache, EVENT_LISTS};
+use crate::entity::prelude::*;
+use crate::entity::{EventCategory, EventStatus, EventType, EventVisibility};
+use crate::schemas::event::{EditScope, EventUpdate};
+use crate::services::events::invalidate_event;
+use crate::utils::recurrence::{Recurrence, format_exdates, parse_exdates, resolve_local};
+use crate::utils::timezone::{local_time, parse_timezone, stored_timezone};
+
+/// How far ahead occurrences are created.
+pub const MATERIALIZE_HORIZON: Duration = Duration::days(180);
+
+/// Occurrences created per series in one go; the rest follow on the next
+/// sweep.
+const MAX_OCCURRENCES_PER_RUN: usize = 400;
+
+/// Details of a new series. The rule, time zone and exceptions are already
+/// validated as part of `recurrence`.
+#[derive(Debug, Clone)]
+pub struct NewSeries {
+    pub title: String,
+    pub description: String,
+    pub location: String,
+    pub event_type: EventType,
+    pub category: EventCategory,
+    pub visibility: EventVisibility,
+    pub recurrence: Recurrence,
+    pub duration: Duration,
+}
+
+/// Result of [`create_series`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeriesCreation {
+    Created {
+        series: Box<EventSeriesModel>,
+        occurrences: Vec<EventModel>,
+    },
+    /// Only hosts can create events
+    NotAHost,
+    /// The rule leaves no occurrence that has not already ended
+    NoOccurrences,
+}
+
+/// Result of [`update_event`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventEdit {
+    /// Every event that changed, the edited one first
+    Updated(Vec<EventModel>),
+    /// Completed and cancelled events cannot be edited
+    Closed(EventStatus),
+    Invalid(String),
+}
+
+/// The rule of a stored series.
+pub fn series_recurrence(series: &EventSeriesModel) -> Result<Recurrence, Box<dyn Error>> {
+    Ok(Recurrence {
+        rule: series.rrule.parse()?,
+        start: series.start_local,
+        timezone: parse_timezone(&series.timezone)?,
+        exdates: parse_exdates(&series.exdates)?,
+    })
+}
+
+/// Create a series for `host_id` and its occurrences up to the horizon.
+pub async fn create_series(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    host_id: i32,
+    new: NewSeries,
+) -> Result<SeriesCreation, Box<dyn Error>> {
+    if Host::find_by_id(host_id).one(db).await?.is_none() {
+        return Ok(SeriesCreation::NotAHost);
+    }
+    let now = Utc::now();
+    let has_occurrences = new
+        .recurrence
+        .occurrences()
+        .take_while(|occurrence| occurrence.start <= now + MATERIALIZE_HORIZON)
+        .any(|occurrence| occurrence.start + new.duration > now);
+    if !has_occurrences {
+        return Ok(SeriesCreation::NoOccurrences);
+    }
+
+    let txn = db.begin().await?;
+    let series = EventSeriesActiveModel {
+        host_id: Set(host_id),
+        title: Set(new.title),
+        description: Set(new.description),
+        location: Set(new.location),
+        event_type: Set(new.event_type),
+        category: Set(new.category),
+        visibility: Set(new.visibility),
+        rrule: Set(new.recurrence.rule.to_string()),
+        timezone: Set(new.recurrence.timezone.name().to_string()),
+        start_local: Set(new.recurrence.start),
+        duration_minutes: Set(new.duration.num_minutes() as i32),
+        exdates: Set(format_exdates(&new.recurrence.exdates)),
+        materialized_until: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    let (series, occurrences) = materialize(&txn, series, &new.recurrence, now).await?;
+    txn.commit().await?;
+
+    cache.invalidate_namespace(EVENT_LISTS).await;
+    Ok(SeriesCreation::Created {
+        series: Box::new(series),
+        occurrences,
+    })
+}
+
+/// Create the occurrences of `series` that start after the ones already
+/// created and before the horizon, skipping any that have already ended.
+async fn materialize<C: ConnectionTrait>(
+    db: &C,
+    series: EventSeriesModel,
+    recurrence: &Recurrence,
+    now: DateTime<Utc>,
+) -> Result<(EventSeriesModel, Vec<EventModel>), Box<dyn Error>> {
+    let duration = Duration::minutes(series.duration_minutes.into());
+    let horizon = now + MATERIALIZE_HORIZON;
+    let pending: Vec<_> = recurrence
+        .occurrences()
+        .take_while(|occurrence| occurrence.start <= horizon)
+        .filter(|occurrence| {
+            series
+                .materialized_until
+                .is_none_or(|until| occurrence.start > until)
+        })
+        .filter(|occurrence| occurrence.start + duration > now)
+        .take(MAX_OCCURRENCES_PER_RUN)
+        .collect();
+    let Some(last) = pending.last().map(|occurrence| occurrence.start) else {
+        return Ok((series, Vec::new()));
+    };
+
+    let mut occurrences = Vec::with_capacity(pending.len());
+    for occurrence in pending {
+        let event = EventActiveModel {
+            title: Set(series.title.clone()),
+            description: Set(series.description.clone()),
+            location: Set(series.location.clone()),
+            event_type: Set(series.event_type),
+            category: Set(series.category),
+            status: Set(EventStatus::Scheduled),
+            visibility: Set(series.visibility),
+            host_id: Set(series.host_id),
+            start_time: Set(occurrence.start),
+            end_time: Set(occurrence.start + duration),
+            timezone: Set(series.timezone.clone()),
+            series_id: Set(Some(series.id)),
+            recurrence_id: Set(Some(occurrence.start)),
+            is_exception: Set(false),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        occurrences.push(event);
+    }
+
+    let mut series = series.into_active_model();
+    series.materialized_until = Set(Some(last));
+    let series = series.update(db).await?;
+    Ok((series, occurrences))
+}
+
+/// Create the next occurrences of every series that is not materialized up
+/// to the horizon yet. Returns the new occurrences.
+pub async fn extend_all_series(
+    db: &DatabaseConnection,
+    cache: &Cache,
+) -> Result<Vec<EventModel>, Box<dyn Error>> {
+    let now = Utc::now();
+    let due = EventSeries::find()
+        .filter(
+            Condition::any()
+                .add(EventSeriesColumn::MaterializedUntil.is_null())
+                .add(EventSeriesColumn::MaterializedUntil.lt(now + MATERIALIZE_HORIZON)),
+        )
+        .all(db)
+        .await?;
+
+    let mut created = Vec::new();
+    for series in due {
+        let recurrence = match series_recurrence(&series) {
+            Ok(recurrence) => recurrence,
+            Err(e) => {
+                error!("Event series {} has an invalid rule: {}", series.id, e);
+                continue;
+            }
+        };
+        let txn = db.begin().await?;
+        let (_, occurrences) = materialize(&txn, series, &recurrence, now).await?;
+        txn.commit().await?;
+        created.extend(occurrences);
+    }
+
+    if !created.is_empty() {
+        cache.invalidate_namespace(EVENT_LISTS).await;
+    }
+    Ok(created)
+}
+
+pub async fn get_series(
+    db: &DatabaseConnection,
+    series_id: i32,
+) -> Result<Option<EventSeriesModel>, Box<dyn Error>> {
+    Ok(EventSeries::find_by_id(series_id).one(db).await?)
+}
+
+/// Occurrences created so far, earliest first.
+pub async fn series_occurrences(
+    db: &DatabaseConnection,
+    series_id: i32,
+) -> Result<Vec<EventModel>, Box<dyn Error>> {
+    let events = Event::find()
+        .filter(EventColumn::SeriesId.eq(series_id))
+        .order_by_asc(EventColumn::StartTime)
+        .order_by_asc(EventColumn::Id)
+        .all(db)
+        .await?;
+    Ok(events)
+}
+
+/// Edit an event. For an occurrence of a series, `scope` picks between this
+/// occurrence alone, which becomes an exception the series leaves alone
+/// from then on, and this and every later occurrence. The latter splits the
+/// series at this occurrence unless it is the first.
+pub async fn update_event(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    event: EventModel,
+    update: &EventUpdate,
+) -> Result<EventEdit, Box<dyn Error>> {
+    if event.status.is_final() {
+        return Ok(EventEdit::Closed(event.status));
+    }
+    let timezone = match &update.timezone {
+        Some(_) if event.series_id.is_some() => {
+            return Ok(EventEdit::Invalid(
+                "Occurrences of a recurring series keep the series' time zone".to_string(),
+            ));
+        }
+        Some(name) => match parse_timezone(name) {
+            Ok(tz) => Some(tz),
+            Err(e) => return Ok(EventEdit::Invalid(e)),
+        },
+        None => None,
+    };
+    // A new zone keeps the wall-clock time unless the start moves too
+    let current_start = match timezone {
+        Some(tz) if update.start_time.is_none() => resolve_local(
+            tz,
+            local_time(event.start_time, stored_timezone(&event.timezone)),
+        ),
+        _ => event.start_time,
+    };
+    let start = update.start_time.unwrap_or(current_start);
+    let end = update
+        .end_time
+        .unwrap_or(start + (event.end_time - event.start_time));
+    if end <= start {
+        return Ok(EventEdit::Invalid(
+            "The event must end after it starts".to_string(),
+        ));
+    }
+
+    let series = match (update.scope, event.series_id) {
+        (EditScope::Following, Some(series_id)) => get_series(db, series_id).await?,
+        _ => None,
+    };
+    let Some(series) = series else {
+        let event_id = event.id;
+        let is_occurrence = event.series_id.is_some();
+        let mut event = event.into_active_model();
+        apply_details(&mut event, update);
+        if let Some(tz) = timezone {
+            event.timezone = Set(tz.name().to_string());
+        }
+        event.start_time = Set(start);
+        event.end_time = Set(end);
+        event.is_exception = Set(is_occurrence);
+        event.updated_at = Set(Utc::now());
+        let event = event.update(db).await?;
+        invalidate_event(cache, event_id).await;
+        return Ok(EventEdit::Updated(vec![event]));
+    };
+
+    update_following(db, cache, event, series, update, start, end).await
+}
+
+async fn update_following(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    event: EventModel,
+    series: EventSeriesModel,
+    update: &EventUpdate,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<EventEdit, Box<dyn Error>> {
+    let recurrence = series_recurrence(&series)?;
+    let tz = recurrence.timezone;
+    let local = |time: DateTime<Utc>| time.with_timezone(&tz).naive_local();
+
+    // Shifting by a whole day or more would move occurrences off the days
+    // the rule picks; those moves are made one occurrence at a time
+    let slot = event.recurrence_id.unwrap_or(event.start_time);
+    let slot_local = local(slot);
+    if local(start).date() != slot_local.date() {
+        return Ok(EventEdit::Invalid(
+            "Later occurrences can only move within their day; \
+             edit occurrences one at a time to move them to another day"
+                .to_string(),
+        ));
+    }
+    let shift = local(start) - slot_local;
+    let duration = end - start;
+    let shifted = |time: NaiveDateTime| time + shift;
+    let now = Utc::now();
+
+    let txn = db.begin().await?;
+    let following = Event::find()
+        .filter(EventColumn::SeriesId.eq(series.id))
+        .filter(EventColumn::RecurrenceId.gte(slot))
+        .order_by_asc(EventColumn::RecurrenceId)
+        .all(&txn)
+        .await?;
+
+    let (earlier_exdates, later_exdates): (Vec<_>, Vec<_>) = recurrence
+        .exdates
+        .iter()
+        .partition(|exdate| **exdate < slot_local);
+    let later_exdates: Vec<NaiveDateTime> = later_exdates.into_iter().map(shifted).collect();
+    let materialized_until = series
+        .materialized_until
+        .map(|until| resolve_local(tz, shifted(local(until))));
+
+    let target = if slot_local <= recurrence.start {
+        // From the first occurrence on is the whole series
+        let mut target = series.into_active_model();
+        apply_series_details(&mut target, update);
+        target.start_local = Set(shifted(recurrence.start));
+        target.duration_minutes = Set(duration.num_minutes() as i32);
+        target.exdates = Set(format_exdates(&later_exdates));
+        target.materialized_until = Set(materialized_until);
+        target.updated_at = Set(now);
+        target.update(&txn).await?
+    } else {
+        let rule = match recurrence.rule.count {
+            Some(count) => recurrence.rule.with_count(
+                count
+                    .saturating_sub(recurrence.count_before(slot_local))
+                    .max(1),
+            ),
+            None => recurrence.rule.clone(),
+        };
+        let mut target = EventSeriesActiveModel {
+            rrule: Set(rule.to_string()),
+            start_local: Set(shifted(slot_local)),
+            duration_minutes: Set(duration.num_minutes() as i32),
+            exdates: Set(format_exdates(&later_exdates)),
+            materialized_until: Set(materialized_until),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..series.clone().into_active_model()
+        };
+        target.id = Default::default();
+        apply_series_details(&mut target, update);
+        let target = target.insert(&txn).await?;
+
+        let mut original = series.into_active_model();
+        original.rrule = Set(recurrence
+            .rule
+            .ending_at(slot - Duration::seconds(1))
+            .to_string());
+        original.exdates = Set(format_exdates(&earlier_exdates));
+        original.materialized_until = Set(Some(slot - Duration::seconds(1)));
+        original.updated_at = Set(now);
+        original.update(&txn).await?;
+        target
+    };
+
+    let mut changed = Vec::with_capacity(following.len());
+    for occurrence in following {
+        let is_edited = occurrence.id == event.id;
+        // Exceptions and past or cancelled occurrences keep their details
+        let follows_series =
+            is_edited || !(occurrence.is_exception || occurrence.status.is_final());
+        let new_slot = occurrence
+            .recurrence_id
+            .map(|slot| resolve_local(tz, shifted(local(slot))));
+        let mut model = occurrence.into_active_model();
+        model.series_id = Set(Some(target.id));
+        model.recurrence_id = Set(new_slot);
+        if follows_series {
+            apply_details(&mut model, update);
+            let occurrence_start = if is_edited {
+                start
+            } else {
+                new_slot.unwrap_or(start)
+            };
+            model.start_time = Set(occurrence_start);
+            model.end_time = Set(occurrence_start + duration);
+            model.is_exception = Set(false);
+        }
+        model.updated_at = Set(now);
+        let occurrence = model.update(&txn).await?;
+        if is_edited {
+            changed.insert(0, occurrence);
+        } else {
+            changed.push(occurrence);
+        }
+    }
+    txn.commit().await?;
+
+    for occurrence in &changed {
+        invalidate_event(cache, occurrence.id).await;
+    }
+    Ok(EventEdit::Updated(changed))
+}
+
+fn apply_details(event: &mut EventActiveModel, update: &EventUpdate) {
+    if let Some(title) = &update.title {
+        event.title = Set(title.clone());
+    }
+    if let Some(description) = &update.description {
+        event.description = Set(description.clone());
+    }
+    if let Some(location) = &update.location {
+        event.location = Set(location.clone());
+    }
+}
+
+fn apply_series_details(series: &mut EventSeriesActiveModel, update: &EventUpdate) {
+    if let Some(title) = &update.title {
+        series.title = Set(title.clone());
+    }
+    if let Some(description) = &update.description {
+        series.description = Set(description.clone());
+    }
+    if let Some(location) = &update.location {
+        series.location = Set(location.clone());
+    }
+}
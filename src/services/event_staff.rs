@@ -0,0 +1,310 @@
+//! Co-hosts and staff.
+//!
+//! Besides its host, an event can have co-hosts, who edit it and manage its
+//! invitations, and staff, who check attendees in. Both are invited by the
+//! host and only get their role once they accept. See [`EventRole`] for how
+//! the roles rank.
+use std::error::Error;
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, QueryOrder, TransactionTrait,
+};
+
+use crate::entity::notification::NotificationKind;
+use crate::entity::prelude::*;
+use crate::entity::{AttendanceStatus, EventRole, EventStatus, InvitationStatus, StaffRole};
+use crate::services::events::get_event_by_id;
+use crate::services::notifications::{NewNotification, notify_users};
+
+/// Result of [`add_staff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum StaffInvite {
+    /// Invited for the first time, or again after declining
+    Invited(EventStaffModel),
+    /// Already on the team, or already asked; the role is updated
+    Updated(EventStaffModel),
+    UnknownUser,
+    /// The user hosts the event already
+    IsHost,
+}
+
+/// Result of [`respond_to_staff_invite`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum StaffReply {
+    Responded(EventStaffModel),
+    NotFound,
+    /// The event has been cancelled or has completed
+    Closed(EventStatus),
+}
+
+/// Result of [`check_in`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckIn {
+    CheckedIn(AttendanceModel),
+    AlreadyCheckedIn(AttendanceModel),
+    /// The user has no place at the event; waitlisted users included
+    NotRegistered,
+    Closed(EventStatus),
+}
+
+/// What `user` may do with `event`, if anything. Administrators count as
+/// hosts; co-hosts and staff only once they have accepted.
+pub async fn event_role(
+    db: &DatabaseConnection,
+    event: &EventModel,
+    user: &UserModel,
+) -> Result<Option<EventRole>, Box<dyn Error>> {
+    if user.is_admin || user.id == event.host_id {
+        return Ok(Some(EventRole::Host));
+    }
+    let staff = EventStaff::find()
+        .filter(EventStaffColumn::EventId.eq(event.id))
+        .filter(EventStaffColumn::UserId.eq(user.id))
+        .filter(EventStaffColumn::Status.eq(InvitationStatus::Accepted))
+        .one(db)
+        .await?;
+    Ok(staff.map(|staff| staff.role.into()))
+}
+
+/// Ask an active user, named by username or email, to help run `event`.
+/// Asking someone already on the team changes their role without asking
+/// again; someone who declined is asked again and notified.
+pub async fn add_staff(
+    db: &DatabaseConnection,
+    event: &EventModel,
+    invited_by: i32,
+    identifier: &str,
+    role: StaffRole,
+) -> Result<StaffInvite, Box<dyn Error>> {
+    let Some(user) = User::find()
+        .filter(UserColumn::IsActive.eq(true))
+        .filter(
+            Condition::any()
+                .add(UserColumn::Username.eq(identifier))
+                .add(UserColumn::Email.eq(identifier)),
+        )
+        .one(db)
+        .await?
+    else {
+        return Ok(StaffInvite::UnknownUser);
+    };
+    if user.id == event.host_id {
+        return Ok(StaffInvite::IsHost);
+    }
+
+    let txn = db.begin().await?;
+    let existing = EventStaff::find()
+        .filter(EventStaffColumn::EventId.eq(event.id))
+        .filter(EventStaffColumn::UserId.eq(user.id))
+        .one(&txn)
+        .await?;
+    let now = Utc::now();
+    let outcome = match existing {
+        Some(staff) if staff.status != InvitationStatus::Declined => {
+            let mut staff = staff.into_active_model();
+            staff.role = Set(role);
+            staff.updated_at = Set(now);
+            StaffInvite::Updated(staff.update(&txn).await?)
+        }
+        Some(staff) => {
+            let mut staff = staff.into_active_model();
+            staff.role = Set(role);
+            staff.status = Set(InvitationStatus::Pending);
+            staff.invited_by = Set(Some(invited_by));
+            staff.responded_at = Set(None);
+            staff.updated_at = Set(now);
+            StaffInvite::Invited(staff.update(&txn).await?)
+        }
+        None => StaffInvite::Invited(
+            EventStaffActiveModel {
+                event_id: Set(event.id),
+                user_id: Set(user.id),
+                role: Set(role),
+                status: Set(InvitationStatus::Pending),
+                invited_by: Set(Some(invited_by)),
+                responded_at: Set(None),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?,
+        ),
+    };
+
+    if let StaffInvite::Invited(_) = outcome {
+        let role_name = match role {
+            StaffRole::CoHost => "co-host",
+            StaffRole::Staff => "staff",
+        };
+        notify_users(
+            &txn,
+            &[user.id],
+            &NewNotification {
+                kind: NotificationKind::StaffInvitation,
+                title: format!("Help run {}", event.title),
+                body: format!(
+                    "You have been asked to join {} as {}.",
+                    event.title, role_name
+                ),
+                event_id: Some(event.id),
+            },
+        )
+        .await?;
+    }
+    txn.commit().await?;
+
+    Ok(outcome)
+}
+
+/// The event's co-hosts and staff, answered or not, oldest first.
+pub async fn list_staff(
+    db: &DatabaseConnection,
+    event_id: i32,
+) -> Result<Vec<EventStaffModel>, Box<dyn Error>> {
+    Ok(EventStaff::find()
+        .filter(EventStaffColumn::EventId.eq(event_id))
+        .order_by_asc(EventStaffColumn::Id)
+        .all(db)
+        .await?)
+}
+
+/// Take someone off the team. Returns `false` if there was no such member.
+pub async fn remove_staff(
+    db: &DatabaseConnection,
+    event_id: i32,
+    staff_id: i32,
+) -> Result<bool, Box<dyn Error>> {
+    let result = EventStaff::delete_many()
+        .filter(EventStaffColumn::Id.eq(staff_id))
+        .filter(EventStaffColumn::EventId.eq(event_id))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+/// The user's roles, asked or accepted, on events that have not ended,
+/// soonest first.
+pub async fn user_staff_roles(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<(EventStaffModel, EventModel)>, Box<dyn Error>> {
+    let roles = EventStaff::find()
+        .find_also_related(Event)
+        .filter(EventStaffColumn::UserId.eq(user_id))
+        .filter(EventStaffColumn::Status.ne(InvitationStatus::Declined))
+        .filter(EventColumn::EndTime.gt(Utc::now()))
+        .order_by_asc(EventColumn::StartTime)
+        .all(db)
+        .await?;
+
+    Ok(roles
+        .into_iter()
+        .filter_map(|(staff, event)| Some((staff, event?)))
+        .collect())
+}
+
+/// Accept or decline a role. Declining an accepted role steps down.
+pub async fn respond_to_staff_invite(
+    db: &DatabaseConnection,
+    staff_id: i32,
+    user_id: i32,
+    accept: bool,
+) -> Result<StaffReply, Box<dyn Error>> {
+    let Some(staff) = EventStaff::find_by_id(staff_id)
+        .filter(EventStaffColumn::UserId.eq(user_id))
+        .one(db)
+        .await?
+    else {
+        return Ok(StaffReply::NotFound);
+    };
+    let Some(event) = get_event_by_id(db, staff.event_id).await? else {
+        return Ok(StaffReply::NotFound);
+    };
+    if event.status.is_final() {
+        return Ok(StaffReply::Closed(event.status));
+    }
+
+    let mut staff = staff.into_active_model();
+    staff.status = Set(if accept {
+        InvitationStatus::Accepted
+    } else {
+        InvitationStatus::Declined
+    });
+    staff.responded_at = Set(Some(Utc::now()));
+    staff.updated_at = Set(Utc::now());
+    Ok(StaffReply::Responded(staff.update(db).await?))
+}
+
+/// Every event the user hosts or has accepted a role on, soonest first,
+/// with their role. Administrators only see their own events here.
+pub async fn managed_events(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<(EventModel, EventRole)>, Box<dyn Error>> {
+    let mut events: Vec<(EventModel, EventRole)> = Event::find()
+        .filter(EventColumn::HostId.eq(user_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|event| (event, EventRole::Host))
+        .collect();
+    let staffed = EventStaff::find()
+        .find_also_related(Event)
+        .filter(EventStaffColumn::UserId.eq(user_id))
+        .filter(EventStaffColumn::Status.eq(InvitationStatus::Accepted))
+        .all(db)
+        .await?;
+    events.extend(
+        staffed
+            .into_iter()
+            .filter_map(|(staff, event)| Some((event?, staff.role.into()))),
+    );
+    events.sort_by_key(|(event, _)| (event.start_time, event.id));
+    Ok(events)
+}
+
+/// Everyone with a place at the event or on its waitlist, in the order they
+/// signed up.
+pub async fn list_attendees(
+    db: &DatabaseConnection,
+    event_id: i32,
+) -> Result<Vec<AttendanceModel>, Box<dyn Error>> {
+    Ok(Attendance::find()
+        .filter(AttendanceColumn::EventId.eq(event_id))
+        .order_by_asc(AttendanceColumn::Id)
+        .all(db)
+        .await?)
+}
+
+/// Mark a registered attendee as arrived.
+pub async fn check_in(
+    db: &DatabaseConnection,
+    event: &EventModel,
+    attendee_id: i32,
+) -> Result<CheckIn, Box<dyn Error>> {
+    if event.status.is_final() {
+        return Ok(CheckIn::Closed(event.status));
+    }
+    let Some(attendance) = Attendance::find()
+        .filter(AttendanceColumn::EventId.eq(event.id))
+        .filter(AttendanceColumn::AttendeeId.eq(attendee_id))
+        .one(db)
+        .await?
+    else {
+        return Ok(CheckIn::NotRegistered);
+    };
+
+    match attendance.status {
+        AttendanceStatus::CheckedIn => Ok(CheckIn::AlreadyCheckedIn(attendance)),
+        AttendanceStatus::Registered | AttendanceStatus::NoShow => {
+            let mut attendance = attendance.into_active_model();
+            attendance.status = Set(AttendanceStatus::CheckedIn);
+            attendance.updated_at = Set(Utc::now());
+            Ok(CheckIn::CheckedIn(attendance.update(db).await?))
+        }
+        AttendanceStatus::Waitlisted => Ok(CheckIn::NotRegistered),
+    }
+}
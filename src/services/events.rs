@@ -0,0 +1,166 @@
+use std::error::Error;
+
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+
+use crate::core::cache::{Cache, EVENT_LISTS, EVENTS};
+use crate::entity::prelude::*;
+use crate::entity::{EventStatus, EventVisibility};
+use crate::schemas::event::{EventWindow, LabelFilter};
+use crate::services::tags::label_condition;
+use crate::utils::timezone::{local_time, stored_timezone};
+
+/// Events starting after now, soonest first.
+pub async fn list_upcoming_events(
+    db: &DatabaseConnection,
+    limit: u64,
+) -> Result<Vec<EventModel>, Box<dyn Error>> {
+    let events = Event::find()
+        .filter(EventColumn::StartTime.gt(Utc::now()))
+        .order_by_asc(EventColumn::StartTime)
+        .limit(limit)
+        .all(db)
+        .await?;
+
+    Ok(events)
+}
+
+/// Number of scheduled events that have not started yet.
+pub async fn count_upcoming_events(db: &DatabaseConnection) -> Result<u64, Box<dyn Error>> {
+    let count = Event::find()
+        .filter(EventColumn::Status.eq(EventStatus::Scheduled))
+        .filter(EventColumn::StartTime.gt(Utc::now()))
+        .count(db)
+        .await?;
+
+    Ok(count)
+}
+
+/// Any event by id, private or not, straight from the database.
+pub async fn get_event_by_id(
+    db: &DatabaseConnection,
+    event_id: i32,
+) -> Result<Option<EventModel>, Box<dyn Error>> {
+    Ok(Event::find_by_id(event_id).one(db).await?)
+}
+
+/// A public event by id, through the cache. Private events are not returned.
+pub async fn get_public_event(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    event_id: i32,
+) -> Result<Option<EventModel>, Box<dyn Error>> {
+    cache
+        .get_or_load(EVENTS, &event_id.to_string(), || async move {
+            let event = Event::find_by_id(event_id)
+                .filter(EventColumn::Visibility.eq(EventVisibility::Public))
+                .one(db)
+                .await?;
+            Ok(event)
+        })
+        .await
+}
+
+/// Public events that have not ended and are not cancelled, soonest first,
+/// through the cache.
+pub async fn list_public_events(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    limit: u64,
+    offset: u64,
+) -> Result<Vec<EventModel>, Box<dyn Error>> {
+    cache
+        .get_or_load(
+            EVENT_LISTS,
+            &format!("{}:{}", limit, offset),
+            || async move {
+                let events = Event::find()
+                    .filter(EventColumn::Visibility.eq(EventVisibility::Public))
+                    .filter(EventColumn::Status.ne(EventStatus::Cancelled))
+                    .filter(EventColumn::EndTime.gt(Utc::now()))
+                    .order_by_asc(EventColumn::StartTime)
+                    .order_by_asc(EventColumn::Id)
+                    .limit(limit)
+                    .offset(offset)
+                    .all(db)
+                    .await?;
+                Ok(events)
+            },
+        )
+        .await
+}
+
+/// Public events that have not ended and are not cancelled and that match
+/// `filter`, soonest first. Not cached, as filters are too varied to be
+/// worth it.
+pub async fn list_public_events_matching(
+    db: &DatabaseConnection,
+    filter: &LabelFilter,
+    limit: u64,
+    offset: u64,
+) -> Result<Vec<EventModel>, Box<dyn Error>> {
+    let events = Event::find()
+        .filter(EventColumn::Visibility.eq(EventVisibility::Public))
+        .filter(EventColumn::Status.ne(EventStatus::Cancelled))
+        .filter(EventColumn::EndTime.gt(Utc::now()))
+        .filter(label_condition(db, filter).await?)
+        .order_by_asc(EventColumn::StartTime)
+        .order_by_asc(EventColumn::Id)
+        .limit(limit)
+        .offset(offset)
+        .all(db)
+        .await?;
+
+    Ok(events)
+}
+
+/// Public events that have not ended and are not cancelled, that start
+/// within `window` in their own time zone and that match `filter`, soonest
+/// first.
+///
+/// Not cached: the windows move at local midnight, which comes at a
+/// different time in every zone.
+pub async fn list_public_events_in(
+    db: &DatabaseConnection,
+    window: EventWindow,
+    filter: &LabelFilter,
+    limit: u64,
+    offset: u64,
+) -> Result<Vec<EventModel>, Box<dyn Error>> {
+    let now = Utc::now();
+    // Every window lies within a week of today, and local dates are never
+    // more than a day away from the UTC date
+    let candidates = Event::find()
+        .filter(label_condition(db, filter).await?)
+        .filter(EventColumn::Visibility.eq(EventVisibility::Public))
+        .filter(EventColumn::Status.ne(EventStatus::Cancelled))
+        .filter(EventColumn::EndTime.gt(now))
+        .filter(EventColumn::StartTime.gte(now - Duration::days(2)))
+        .filter(EventColumn::StartTime.lt(now + Duration::days(9)))
+        .order_by_asc(EventColumn::StartTime)
+        .order_by_asc(EventColumn::Id)
+        .all(db)
+        .await?;
+
+    Ok(candidates
+        .into_iter()
+        .filter(|event| {
+            let tz = stored_timezone(&event.timezone);
+            let (first, last) = window.dates(local_time(now, tz).date());
+            let day = local_time(event.start_time, tz).date();
+            first <= day && day <= last
+        })
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect())
+}
+
+/// Drop cached copies of an event and every cached listing. Call after any
+/// change to an `events` row.
+pub async fn invalidate_event(cache: &Cache, event_id: i32) {
+    cache.invalidate(EVENTS, &event_id.to_string()).await;
+    cache.invalidate_namespace(EVENT_LISTS).await;
+}
@@ -0,0 +1,243 @@
+//! Whole-database export and import used by `here-admin`.
+//!
+//! The export is a single JSON document holding every row of the domain
+//! tables, password hashes included, so treat it as a secret. Login sessions,
+//! API keys, calendar feed tokens, event invite links and emailed one-time
+//! tokens (email verification, password reset) are deliberately left out: a
+//! restored database starts with everyone signed out, and feeds and links
+//! have to be issued again.
+use std::error::Error;
+
+use sea_orm::{
+    ActiveModelTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr,
+    EntityTrait, IntoActiveModel, PaginatorTrait, Statement, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::core::cache::Cache;
+use crate::entity::prelude::*;
+
+/// Rows per `INSERT` statement, well under SQLite's bound-parameter limit.
+const IMPORT_CHUNK_SIZE: usize = 200;
+
+/// Tables with an auto-increment `id` whose Postgres sequence must be moved
+/// past the imported ids.
+const SERIAL_TABLES: [&str; 16] = [
+    "users",
+    "skills",
+    "motivations",
+    "event_categories",
+    "event_series",
+    "events",
+    "attendance",
+    "notifications",
+    "tags",
+    "event_invitations",
+    "event_staff",
+    "ticket_tiers",
+    "promo_codes",
+    "ticket_orders",
+    "ledger_entries",
+    "payment_webhook_events",
+];
+
+/// Tables added after an export was written are missing from it and
+/// import empty.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DataExport {
+    pub users: Vec<UserModel>,
+    pub hosts: Vec<HostModel>,
+    pub attendees: Vec<AttendeeModel>,
+    pub skills: Vec<SkillsModel>,
+    pub motivations: Vec<MotivationModel>,
+    pub event_categories: Vec<EventCategoriesModel>,
+    pub user_motivations: Vec<UserMotivationsModel>,
+    pub attendee_motivations: Vec<AttendeeMotivationsModel>,
+    pub categories_join: Vec<CategoriesJoinModel>,
+    pub locations: Vec<LocationModel>,
+    pub event_series: Vec<EventSeriesModel>,
+    pub events: Vec<EventModel>,
+    pub attendance: Vec<AttendanceModel>,
+    pub notifications: Vec<NotificationModel>,
+    pub event_category_links: Vec<EventCategoryLinkModel>,
+    pub tags: Vec<TagModel>,
+    pub event_tags: Vec<EventTagModel>,
+    pub event_invitations: Vec<EventInvitationModel>,
+    pub event_staff: Vec<EventStaffModel>,
+    pub ticket_tiers: Vec<TicketTierModel>,
+    pub promo_codes: Vec<PromoCodeModel>,
+    pub ticket_orders: Vec<TicketOrderModel>,
+    pub ledger_entries: Vec<LedgerEntryModel>,
+    pub payment_webhook_events: Vec<PaymentWebhookEventModel>,
+}
+
+/// Number of rows per table, as written by [`import_data`].
+#[derive(Debug, Default, Serialize)]
+pub struct ImportReport {
+    pub users: u64,
+    pub hosts: u64,
+    pub attendees: u64,
+    pub skills: u64,
+    pub motivations: u64,
+    pub event_categories: u64,
+    pub user_motivations: u64,
+    pub attendee_motivations: u64,
+    pub categories_join: u64,
+    pub locations: u64,
+    pub event_series: u64,
+    pub events: u64,
+    pub attendance: u64,
+    pub notifications: u64,
+    pub event_category_links: u64,
+    pub tags: u64,
+    pub event_tags: u64,
+    pub event_invitations: u64,
+    pub event_staff: u64,
+    pub ticket_tiers: u64,
+    pub promo_codes: u64,
+    pub ticket_orders: u64,
+    pub ledger_entries: u64,
+    pub payment_webhook_events: u64,
+}
+
+pub async fn export_data(db: &DatabaseConnection) -> Result<DataExport, Box<dyn Error>> {
+    Ok(DataExport {
+        users: User::find().all(db).await?,
+        hosts: Host::find().all(db).await?,
+        attendees: Attendee::find().all(db).await?,
+        skills: Skills::find().all(db).await?,
+        motivations: Motivation::find().all(db).await?,
+        event_categories: EventCategories::find().all(db).await?,
+        user_motivations: UserMotivations::find().all(db).await?,
+        attendee_motivations: AttendeeMotivations::find().all(db).await?,
+        categories_join: CategoriesJoin::find().all(db).await?,
+        locations: Location::find().all(db).await?,
+        event_series: EventSeries::find().all(db).await?,
+        events: Event::find().all(db).await?,
+        attendance: Attendance::find().all(db).await?,
+        notifications: Notification::find().all(db).await?,
+        event_category_links: EventCategoryLink::find().all(db).await?,
+        tags: Tag::find().all(db).await?,
+        event_tags: EventTag::find().all(db).await?,
+        // Invite links are not exported, so forget which one was redeemed
+        event_invitations: EventInvitation::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|invitation| EventInvitationModel {
+                invite_link_id: None,
+                ..invitation
+            })
+            .collect(),
+        event_staff: EventStaff::find().all(db).await?,
+        ticket_tiers: TicketTier::find().all(db).await?,
+        promo_codes: PromoCode::find().all(db).await?,
+        ticket_orders: TicketOrder::find().all(db).await?,
+        ledger_entries: LedgerEntry::find().all(db).await?,
+        payment_webhook_events: PaymentWebhookEvent::find().all(db).await?,
+    })
+}
+
+/// Load an export into an empty database, keeping the original ids.
+///
+/// Everything runs in one transaction; the import is refused when the
+/// database already has users, so it cannot clash with existing rows.
+pub async fn import_data(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    data: DataExport,
+) -> Result<ImportReport, Box<dyn Error>> {
+    if User::find().count(db).await? > 0 {
+        return Err("Database already contains users; import requires an empty database".into());
+    }
+
+    let txn = db.begin().await?;
+    // Parents before children so foreign keys are satisfied.
+    let report = ImportReport {
+        users: insert_rows::<UserActiveModel, _>(&txn, data.users).await?,
+        hosts: insert_rows::<HostActiveModel, _>(&txn, data.hosts).await?,
+        attendees: insert_rows::<AttendeeActiveModel, _>(&txn, data.attendees).await?,
+        skills: insert_rows::<SkillsActiveModel, _>(&txn, data.skills).await?,
+        motivations: insert_rows::<MotivationActiveModel, _>(&txn, data.motivations).await?,
+        event_categories: insert_rows::<EventCategoriesActiveModel, _>(&txn, data.event_categories)
+            .await?,
+        user_motivations: insert_rows::<UserMotivationsActiveModel, _>(&txn, data.user_motivations)
+            .await?,
+        attendee_motivations: insert_rows::<AttendeeMotivationsActiveModel, _>(
+            &txn,
+            data.attendee_motivations,
+        )
+        .await?,
+        categories_join: insert_rows::<CategoriesJoinActiveModel, _>(&txn, data.categories_join)
+            .await?,
+        locations: insert_rows::<LocationActiveModel, _>(&txn, data.locations).await?,
+        event_series: insert_rows::<EventSeriesActiveModel, _>(&txn, data.event_series).await?,
+        events: insert_rows::<EventActiveModel, _>(&txn, data.events).await?,
+        attendance: insert_rows::<AttendanceActiveModel, _>(&txn, data.attendance).await?,
+        notifications: insert_rows::<NotificationActiveModel, _>(&txn, data.notifications).await?,
+        event_category_links: insert_rows::<EventCategoryLinkActiveModel, _>(
+            &txn,
+            data.event_category_links,
+        )
+        .await?,
+        tags: insert_rows::<TagActiveModel, _>(&txn, data.tags).await?,
+        event_tags: insert_rows::<EventTagActiveModel, _>(&txn, data.event_tags).await?,
+        event_invitations: insert_rows::<EventInvitationActiveModel, _>(
+            &txn,
+            data.event_invitations,
+        )
+        .await?,
+        event_staff: insert_rows::<EventStaffActiveModel, _>(&txn, data.event_staff).await?,
+        ticket_tiers: insert_rows::<TicketTierActiveModel, _>(&txn, data.ticket_tiers).await?,
+        promo_codes: insert_rows::<PromoCodeActiveModel, _>(&txn, data.promo_codes).await?,
+        ticket_orders: insert_rows::<TicketOrderActiveModel, _>(&txn, data.ticket_orders).await?,
+        ledger_entries: insert_rows::<LedgerEntryActiveModel, _>(&txn, data.ledger_entries).await?,
+        payment_webhook_events: insert_rows::<PaymentWebhookEventActiveModel, _>(
+            &txn,
+            data.payment_webhook_events,
+        )
+        .await?,
+    };
+
+    if txn.get_database_backend() == DbBackend::Postgres {
+        for table in SERIAL_TABLES {
+            txn.execute_raw(Statement::from_string(
+                DbBackend::Postgres,
+                format!(
+                    "SELECT setval(pg_get_serial_sequence('{table}', 'id'), \
+                     COALESCE((SELECT MAX(id) FROM \"{table}\"), 0) + 1, false)"
+                ),
+            ))
+            .await?;
+        }
+    }
+
+    txn.commit().await?;
+    // Entries cached for a previous database must not shadow imported rows
+    cache.invalidate_all().await;
+    Ok(report)
+}
+
+async fn insert_rows<A, M>(txn: &DatabaseTransaction, rows: Vec<M>) -> Result<u64, DbErr>
+where
+    A: ActiveModelTrait + Send,
+    M: IntoActiveModel<A>,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+{
+    let mut rows: Vec<A> = rows
+        .into_iter()
+        .map(|row| row.into_active_model().reset_all())
+        .collect();
+    let mut inserted = 0;
+
+    while !rows.is_empty() {
+        let rest = rows.split_off(rows.len().min(IMPORT_CHUNK_SIZE));
+        inserted += A::Entity::insert_many(rows)
+            .exec_without_returning(txn)
+            .await?;
+        rows = rest;
+    }
+
+    Ok(inserted)
+}
@@ -0,0 +1,74 @@
+use std::fmt::Display;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use deadpool_redis::Pool as RedisPool;
+use sea_orm::DatabaseConnection;
+use tokio::net::TcpStream;
+use tracing::warn;
+
+use crate::schemas::health::{CheckStatus, DependencyCheck};
+
+/// Upper bound for a single dependency check so `/health/ready` stays fast
+/// even when a dependency hangs.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Run `check` with [`CHECK_TIMEOUT`], recording how long it took.
+async fn timed<F, E>(name: &str, critical: bool, check: F) -> DependencyCheck
+where
+    F: Future<Output = Result<(), E>>,
+    E: Display,
+{
+    let started = Instant::now();
+    let outcome = tokio::time::timeout(CHECK_TIMEOUT, check).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    // Details stay in the logs; the endpoint is unauthenticated.
+    let error = match outcome {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => {
+            warn!("Health check for {} failed: {}", name, e);
+            Some("unreachable".to_string())
+        }
+        Err(_) => {
+            warn!("Health check for {} timed out", name);
+            Some("timed out".to_string())
+        }
+    };
+
+    DependencyCheck {
+        status: if error.is_none() {
+            CheckStatus::Up
+        } else {
+            CheckStatus::Down
+        },
+        critical,
+        latency_ms,
+        error,
+    }
+}
+
+pub async fn check_database(db: &DatabaseConnection) -> DependencyCheck {
+    timed("database", true, db.ping()).await
+}
+
+pub async fn check_redis(pool: &RedisPool) -> DependencyCheck {
+    timed("redis", true, async {
+        let mut conn = pool.get().await.map_err(|e| e.to_string())?;
+        redis::cmd("PING")
+            .query_async::<String>(&mut conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok::<(), String>(())
+    })
+    .await
+}
+
+/// Only checks that the SMTP port accepts connections; mail is not critical
+/// for serving requests.
+pub async fn check_smtp(host: &str, port: u16) -> DependencyCheck {
+    timed("smtp", false, async {
+        TcpStream::connect((host, port)).await.map(|_| ())
+    })
+    .await
+}
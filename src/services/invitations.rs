@@ -0,0 +1,558 @@
+//! Invitations to private events.
+//!
+//! A private event is visible only to its host, administrators and users
+//! with an invitation or an RSVP. Hosts invite users by username or email,
+//! or share invite links; redeeming a link gives the user an invitation of
+//! their own. Only a hash of each link's token is stored.
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait,
+    DatabaseConnection, EntityTrait, ExprTrait, IntoActiveModel, PaginatorTrait, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+
+use crate::core::cache::Cache;
+use crate::entity::notification::NotificationKind;
+use crate::entity::prelude::*;
+use crate::entity::{AttendanceStatus, EventStatus, EventVisibility, InvitationStatus};
+use crate::services::attendance::{RsvpOutcome, rsvp};
+use crate::services::events::{get_event_by_id, get_public_event};
+use crate::services::notifications::{NewNotification, notify_users};
+use crate::utils::timezone::{local_time, stored_timezone};
+use crate::utils::utils::{generate_token, hash_api_key};
+
+/// Result of [`invite_users`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum InviteOutcome {
+    /// An invitation for every user named, existing ones included
+    Invited(Vec<EventInvitationModel>),
+    /// Nobody was invited because these names matched no active user
+    UnknownUsers(Vec<String>),
+}
+
+/// Result of [`respond_to_invitation`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvitationReply {
+    Responded(EventInvitationModel),
+    NotFound,
+    /// The event has been cancelled or has completed
+    Closed(EventStatus),
+}
+
+/// Result of [`redeem_invite_link`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkRedemption {
+    /// The caller's invitation, new or one they already had
+    Redeemed(EventInvitationModel),
+    NotFound,
+    /// Revoked, expired or used up
+    Unusable,
+    Closed(EventStatus),
+}
+
+/// Limits for a new invite link.
+#[derive(Debug, Clone, Default)]
+pub struct NewInviteLink {
+    pub max_uses: Option<i32>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Whether `user` may see `event`. Anyone may see public events; private
+/// ones only their host, administrators, co-hosts and staff, invitees and
+/// attendees.
+pub async fn can_view(
+    db: &DatabaseConnection,
+    event: &EventModel,
+    user: Option<&UserModel>,
+) -> Result<bool, Box<dyn Error>> {
+    if event.visibility == EventVisibility::Public {
+        return Ok(true);
+    }
+    let Some(user) = user else {
+        return Ok(false);
+    };
+    if user.is_admin || user.id == event.host_id {
+        return Ok(true);
+    }
+    involved_in(db, &[event.id], user.id).await
+}
+
+/// Whether `user` may see `series`, by the same rule as [`can_view`]:
+/// being involved in any of its `occurrences` is enough.
+pub async fn can_view_series(
+    db: &DatabaseConnection,
+    series: &EventSeriesModel,
+    occurrences: &[EventModel],
+    user: Option<&UserModel>,
+) -> Result<bool, Box<dyn Error>> {
+    if series.visibility == EventVisibility::Public {
+        return Ok(true);
+    }
+    let Some(user) = user else {
+        return Ok(false);
+    };
+    if user.is_admin || user.id == series.host_id {
+        return Ok(true);
+    }
+    let event_ids: Vec<i32> = occurrences.iter().map(|event| event.id).collect();
+    involved_in(db, &event_ids, user.id).await
+}
+
+/// Whether `user_id` is a co-host or staff member of, invited to or
+/// attending any of `event_ids`.
+async fn involved_in(
+    db: &DatabaseConnection,
+    event_ids: &[i32],
+    user_id: i32,
+) -> Result<bool, Box<dyn Error>> {
+    if event_ids.is_empty() {
+        return Ok(false);
+    }
+    let staffed = EventStaff::find()
+        .filter(EventStaffColumn::EventId.is_in(event_ids.iter().copied()))
+        .filter(EventStaffColumn::UserId.eq(user_id))
+        .filter(EventStaffColumn::Status.eq(InvitationStatus::Accepted))
+        .count(db)
+        .await?
+        > 0;
+    if staffed {
+        return Ok(true);
+    }
+    let invited = EventInvitation::find()
+        .filter(EventInvitationColumn::EventId.is_in(event_ids.iter().copied()))
+        .filter(EventInvitationColumn::UserId.eq(user_id))
+        .count(db)
+        .await?
+        > 0;
+    if invited {
+        return Ok(true);
+    }
+    let attending = Attendance::find()
+        .filter(AttendanceColumn::EventId.is_in(event_ids.iter().copied()))
+        .filter(AttendanceColumn::AttendeeId.eq(user_id))
+        .count(db)
+        .await?
+        > 0;
+    Ok(attending)
+}
+
+/// An event by id if `user` may see it. Public events come through the
+/// cache; private ones are read from the database and checked every time.
+pub async fn get_visible_event(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    event_id: i32,
+    user: Option<&UserModel>,
+) -> Result<Option<EventModel>, Box<dyn Error>> {
+    if let Some(event) = get_public_event(db, cache, event_id).await? {
+        return Ok(Some(event));
+    }
+    if user.is_none() {
+        return Ok(None);
+    }
+    let Some(event) = get_event_by_id(db, event_id).await? else {
+        return Ok(None);
+    };
+    Ok(can_view(db, &event, user).await?.then_some(event))
+}
+
+/// Invite active users, named by username or email, to `event`. Users who
+/// already have an invitation keep it as it is; new invitees get an in-app
+/// notification. The host is never invited to their own event.
+pub async fn invite_users(
+    db: &DatabaseConnection,
+    event: &EventModel,
+    invited_by: i32,
+    identifiers: &[String],
+) -> Result<InviteOutcome, Box<dyn Error>> {
+    let users = User::find()
+        .filter(UserColumn::IsActive.eq(true))
+        .filter(
+            Condition::any()
+                .add(UserColumn::Username.is_in(identifiers))
+                .add(UserColumn::Email.is_in(identifiers)),
+        )
+        .all(db)
+        .await?;
+    let unknown: Vec<String> = identifiers
+        .iter()
+        .filter(|identifier| {
+            !users
+                .iter()
+                .any(|user| &user.username == *identifier || &user.email == *identifier)
+        })
+        .cloned()
+        .collect();
+    if !unknown.is_empty() {
+        return Ok(InviteOutcome::UnknownUsers(unknown));
+    }
+
+    let user_ids: Vec<i32> = users
+        .iter()
+        .map(|user| user.id)
+        .filter(|user_id| *user_id != event.host_id)
+        .collect();
+    let txn = db.begin().await?;
+    let mut invitations = EventInvitation::find()
+        .filter(EventInvitationColumn::EventId.eq(event.id))
+        .filter(EventInvitationColumn::UserId.is_in(user_ids.clone()))
+        .all(&txn)
+        .await?;
+    let mut invited = Vec::new();
+    let now = Utc::now();
+    for user_id in user_ids {
+        if invitations
+            .iter()
+            .any(|invitation| invitation.user_id == user_id)
+        {
+            continue;
+        }
+        let invitation = EventInvitationActiveModel {
+            event_id: Set(event.id),
+            user_id: Set(user_id),
+            invited_by: Set(Some(invited_by)),
+            invite_link_id: Set(None),
+            status: Set(InvitationStatus::Pending),
+            responded_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        invited.push(invitation.user_id);
+        invitations.push(invitation);
+    }
+
+    let start = local_time(event.start_time, stored_timezone(&event.timezone));
+    notify_users(
+        &txn,
+        &invited,
+        &NewNotification {
+            kind: NotificationKind::EventInvitation,
+            title: format!("You're invited to {}", event.title),
+            body: format!(
+                "You have been invited to {} on {} ({}).",
+                event.title,
+                start.format("%A %-d %B at %H:%M"),
+                event.timezone
+            ),
+            event_id: Some(event.id),
+        },
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(InviteOutcome::Invited(invitations))
+}
+
+/// Every invitation to an event, oldest first.
+pub async fn list_invitations(
+    db: &DatabaseConnection,
+    event_id: i32,
+) -> Result<Vec<EventInvitationModel>, Box<dyn Error>> {
+    Ok(EventInvitation::find()
+        .filter(EventInvitationColumn::EventId.eq(event_id))
+        .order_by_asc(EventInvitationColumn::Id)
+        .all(db)
+        .await?)
+}
+
+/// Withdraw an invitation. The invitee's place, if they had RSVPed, goes
+/// with it, so they lose access to the event. Returns `false` if there was
+/// no such invitation.
+pub async fn revoke_invitation(
+    db: &DatabaseConnection,
+    event_id: i32,
+    invitation_id: i32,
+) -> Result<bool, Box<dyn Error>> {
+    let txn = db.begin().await?;
+    let Some(invitation) = EventInvitation::find_by_id(invitation_id)
+        .filter(EventInvitationColumn::EventId.eq(event_id))
+        .one(&txn)
+        .await?
+    else {
+        return Ok(false);
+    };
+    EventInvitation::delete_by_id(invitation.id)
+        .exec(&txn)
+        .await?;
+    Attendance::delete_many()
+        .filter(AttendanceColumn::EventId.eq(event_id))
+        .filter(AttendanceColumn::AttendeeId.eq(invitation.user_id))
+        .exec(&txn)
+        .await?;
+    txn.commit().await?;
+    Ok(true)
+}
+
+/// The user's invitations to events that have not ended, soonest first.
+pub async fn user_invitations(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<(EventInvitationModel, EventModel)>, Box<dyn Error>> {
+    let invitations = EventInvitation::find()
+        .find_also_related(Event)
+        .filter(EventInvitationColumn::UserId.eq(user_id))
+        .filter(EventColumn::EndTime.gt(Utc::now()))
+        .order_by_asc(EventColumn::StartTime)
+        .all(db)
+        .await?;
+
+    Ok(invitations
+        .into_iter()
+        .filter_map(|(invitation, event)| Some((invitation, event?)))
+        .collect())
+}
+
+/// Accept or decline an invitation. Accepting RSVPs the user; declining
+/// gives up any place they had.
+pub async fn respond_to_invitation(
+    db: &DatabaseConnection,
+    invitation_id: i32,
+    user: &UserModel,
+    accept: bool,
+) -> Result<InvitationReply, Box<dyn Error>> {
+    let Some(invitation) = EventInvitation::find_by_id(invitation_id)
+        .filter(EventInvitationColumn::UserId.eq(user.id))
+        .one(db)
+        .await?
+    else {
+        return Ok(InvitationReply::NotFound);
+    };
+
+    if accept {
+        // The RSVP marks the invitation accepted, as does buying a ticket
+        match rsvp(db, invitation.event_id, user).await? {
+            RsvpOutcome::Registered(_) | RsvpOutcome::AlreadyRegistered(_) => {}
+            RsvpOutcome::NotFound => return Ok(InvitationReply::NotFound),
+            RsvpOutcome::Closed(status) => return Ok(InvitationReply::Closed(status)),
+            // Buying a ticket registers them later
+            RsvpOutcome::TicketRequired => {
+                accept_invitation(db, invitation.event_id, user.id).await?
+            }
+        }
+        return Ok(EventInvitation::find_by_id(invitation.id)
+            .one(db)
+            .await?
+            .map_or(InvitationReply::NotFound, InvitationReply::Responded));
+    }
+
+    let Some(event) = get_event_by_id(db, invitation.event_id).await? else {
+        return Ok(InvitationReply::NotFound);
+    };
+    if event.status.is_final() {
+        return Ok(InvitationReply::Closed(event.status));
+    }
+    let txn = db.begin().await?;
+    Attendance::delete_many()
+        .filter(AttendanceColumn::EventId.eq(invitation.event_id))
+        .filter(AttendanceColumn::AttendeeId.eq(user.id))
+        .filter(
+            AttendanceColumn::Status
+                .is_in([AttendanceStatus::Registered, AttendanceStatus::Waitlisted]),
+        )
+        .exec(&txn)
+        .await?;
+    let now = Utc::now();
+    let mut invitation = invitation.into_active_model();
+    invitation.status = Set(InvitationStatus::Declined);
+    invitation.responded_at = Set(Some(now));
+    invitation.updated_at = Set(now);
+    let invitation = invitation.update(&txn).await?;
+    txn.commit().await?;
+
+    Ok(InvitationReply::Responded(invitation))
+}
+
+/// Mark the user's invitation to an event accepted, if they have one that
+/// is not already. Called when they RSVP.
+pub async fn accept_invitation<C: sea_orm::ConnectionTrait>(
+    db: &C,
+    event_id: i32,
+    user_id: i32,
+) -> Result<(), Box<dyn Error>> {
+    let now = Utc::now();
+    EventInvitation::update_many()
+        .col_expr(
+            EventInvitationColumn::Status,
+            Expr::value(InvitationStatus::Accepted),
+        )
+        .col_expr(EventInvitationColumn::RespondedAt, Expr::value(now))
+        .col_expr(EventInvitationColumn::UpdatedAt, Expr::value(now))
+        .filter(EventInvitationColumn::EventId.eq(event_id))
+        .filter(EventInvitationColumn::UserId.eq(user_id))
+        .filter(EventInvitationColumn::Status.ne(InvitationStatus::Accepted))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Create an invite link for an event. Returns the link and its token,
+/// which is not stored and cannot be shown again.
+pub async fn create_invite_link(
+    db: &DatabaseConnection,
+    event_id: i32,
+    created_by: i32,
+    new: NewInviteLink,
+) -> Result<(EventInviteLinkModel, String), Box<dyn Error>> {
+    let token = generate_token();
+    let link = EventInviteLinkActiveModel {
+        event_id: Set(event_id),
+        created_by: Set(created_by),
+        token_hash: Set(hash_api_key(&token)),
+        max_uses: Set(new.max_uses),
+        use_count: Set(0),
+        expires_at: Set(new.expires_at),
+        revoked_at: Set(None),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok((link, token))
+}
+
+/// Every invite link of an event, newest first.
+pub async fn list_invite_links(
+    db: &DatabaseConnection,
+    event_id: i32,
+) -> Result<Vec<EventInviteLinkModel>, Box<dyn Error>> {
+    Ok(EventInviteLink::find()
+        .filter(EventInviteLinkColumn::EventId.eq(event_id))
+        .order_by_desc(EventInviteLinkColumn::Id)
+        .all(db)
+        .await?)
+}
+
+/// Stop a link from being redeemed. Invitations already made through it
+/// stay. Returns `false` if there was no such link.
+pub async fn revoke_invite_link(
+    db: &DatabaseConnection,
+    event_id: i32,
+    link_id: i32,
+) -> Result<bool, Box<dyn Error>> {
+    let Some(link) = EventInviteLink::find_by_id(link_id)
+        .filter(EventInviteLinkColumn::EventId.eq(event_id))
+        .one(db)
+        .await?
+    else {
+        return Ok(false);
+    };
+    if link.revoked_at.is_none() {
+        let mut link = link.into_active_model();
+        link.revoked_at = Set(Some(Utc::now()));
+        link.update(db).await?;
+    }
+    Ok(true)
+}
+
+/// Give `user_id` an invitation through an invite link. A user who already
+/// has one keeps it and uses up nothing.
+pub async fn redeem_invite_link(
+    db: &DatabaseConnection,
+    token: &str,
+    user_id: i32,
+) -> Result<LinkRedemption, Box<dyn Error>> {
+    let Some(link) = EventInviteLink::find()
+        .filter(EventInviteLinkColumn::TokenHash.eq(hash_api_key(token)))
+        .one(db)
+        .await?
+    else {
+        return Ok(LinkRedemption::NotFound);
+    };
+    let Some(event) = get_event_by_id(db, link.event_id).await? else {
+        return Ok(LinkRedemption::NotFound);
+    };
+    if event.status.is_final() {
+        return Ok(LinkRedemption::Closed(event.status));
+    }
+
+    let txn = db.begin().await?;
+    if let Some(invitation) = find_invitation(&txn, event.id, user_id).await? {
+        return Ok(LinkRedemption::Redeemed(invitation));
+    }
+
+    // Counted in the database so concurrent redemptions cannot go over
+    // the limit
+    let now = Utc::now();
+    let claimed = EventInviteLink::update_many()
+        .col_expr(
+            EventInviteLinkColumn::UseCount,
+            Expr::col(EventInviteLinkColumn::UseCount).add(1),
+        )
+        .filter(EventInviteLinkColumn::Id.eq(link.id))
+        .filter(EventInviteLinkColumn::RevokedAt.is_null())
+        .filter(
+            Condition::any()
+                .add(EventInviteLinkColumn::ExpiresAt.is_null())
+                .add(EventInviteLinkColumn::ExpiresAt.gt(now)),
+        )
+        .filter(
+            Condition::any()
+                .add(EventInviteLinkColumn::MaxUses.is_null())
+                .add(
+                    Expr::col(EventInviteLinkColumn::UseCount)
+                        .lt(Expr::col(EventInviteLinkColumn::MaxUses)),
+                ),
+        )
+        .exec(&txn)
+        .await?;
+    if claimed.rows_affected == 0 {
+        return Ok(LinkRedemption::Unusable);
+    }
+
+    let inserted = EventInvitation::insert(EventInvitationActiveModel {
+        event_id: Set(event.id),
+        user_id: Set(user_id),
+        invited_by: Set(None),
+        invite_link_id: Set(Some(link.id)),
+        status: Set(InvitationStatus::Pending),
+        responded_at: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    })
+    .on_conflict(
+        OnConflict::columns([
+            EventInvitationColumn::EventId,
+            EventInvitationColumn::UserId,
+        ])
+        .do_nothing()
+        .to_owned(),
+    )
+    .exec_without_returning(&txn)
+    .await?
+        == 1;
+    let invitation = if inserted {
+        let invitation = find_invitation(&txn, event.id, user_id).await?;
+        txn.commit().await?;
+        invitation
+    } else {
+        // The same user redeemed the link at the same moment; their other
+        // request made the invitation, so this one gives its use back
+        txn.rollback().await?;
+        find_invitation(db, event.id, user_id).await?
+    };
+
+    Ok(LinkRedemption::Redeemed(
+        invitation.ok_or("Invitation disappeared")?,
+    ))
+}
+
+/// The invitation `user_id` has to the event, if any.
+async fn find_invitation<C: ConnectionTrait>(
+    db: &C,
+    event_id: i32,
+    user_id: i32,
+) -> Result<Option<EventInvitationModel>, Box<dyn Error>> {
+    Ok(EventInvitation::find()
+        .filter(EventInvitationColumn::EventId.eq(event_id))
+        .filter(EventInvitationColumn::UserId.eq(user_id))
+        .one(db)
+        .await?)
+}
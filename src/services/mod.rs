@@ -1 +1,21 @@
+pub mod api_keys;
+pub mod attendance;
+pub mod calendar;
+pub mod email;
+pub mod event_lifecycle;
+pub mod event_series;
+pub mod event_staff;
+pub mod events;
+pub mod export;
+pub mod health;
+pub mod invitations;
+pub mod notifications;
+pub mod payments;
+pub mod paystack;
+pub mod reference_data;
+pub mod search;
+pub mod sessions;
+pub mod tags;
+pub mod tickets;
+pub mod user_tokens;
 pub mod users;
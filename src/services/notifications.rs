@@ -0,0 +1,94 @@
+use std::error::Error;
+
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+
+use crate::entity::notification::NotificationKind;
+use crate::entity::prelude::*;
+
+/// Content shared by every recipient of a notification.
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+    pub event_id: Option<i32>,
+}
+
+/// Give each of `user_ids` an in-app notification. Takes any connection so
+/// callers can notify in the transaction that made the change.
+pub async fn notify_users<C: ConnectionTrait>(
+    db: &C,
+    user_ids: &[i32],
+    notification: &NewNotification,
+) -> Result<u64, Box<dyn Error>> {
+    if user_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let now = Utc::now();
+    let rows = user_ids.iter().map(|user_id| NotificationActiveModel {
+        user_id: Set(*user_id),
+        kind: Set(notification.kind.as_str().to_string()),
+        title: Set(notification.title.clone()),
+        body: Set(notification.body.clone()),
+        event_id: Set(notification.event_id),
+        read_at: Set(None),
+        created_at: Set(now),
+        ..Default::default()
+    });
+    Notification::insert_many(rows).exec(db).await?;
+
+    Ok(user_ids.len() as u64)
+}
+
+/// The user's notifications, newest first.
+pub async fn list_notifications(
+    db: &DatabaseConnection,
+    user_id: i32,
+    unread_only: bool,
+    limit: u64,
+) -> Result<Vec<NotificationModel>, Box<dyn Error>> {
+    let mut query = Notification::find().filter(NotificationColumn::UserId.eq(user_id));
+    if unread_only {
+        query = query.filter(NotificationColumn::ReadAt.is_null());
+    }
+    let notifications = query
+        .order_by_desc(NotificationColumn::CreatedAt)
+        .order_by_desc(NotificationColumn::Id)
+        .limit(limit)
+        .all(db)
+        .await?;
+
+    Ok(notifications)
+}
+
+/// Mark one of the user's notifications as read. Returns `false` if the
+/// user has no such notification; marking it again is a no-op.
+pub async fn mark_notification_read(
+    db: &DatabaseConnection,
+    user_id: i32,
+    notification_id: i32,
+) -> Result<bool, Box<dyn Error>> {
+    let exists = Notification::find_by_id(notification_id)
+        .filter(NotificationColumn::UserId.eq(user_id))
+        .one(db)
+        .await?
+        .is_some();
+    if !exists {
+        return Ok(false);
+    }
+
+    Notification::update_many()
+        .col_expr(NotificationColumn::ReadAt, Expr::value(Some(Utc::now())))
+        .filter(NotificationColumn::Id.eq(notification_id))
+        .filter(NotificationColumn::ReadAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(true)
+}
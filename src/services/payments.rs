@@ -0,0 +1,423 @@
+//! Taking payment for tickets.
+//!
+//! Checkout goes through a [`PaymentProvider`], so the ticketing service
+//! never talks to a payment company directly and tests can swap in the
+//! [`FakePaymentProvider`]. Providers report payments and refunds back
+//! through webhooks signed with an HMAC of the body; see [`sign_webhook`].
+use std::error::Error;
+use std::fmt;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use rand::distr::Alphanumeric;
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
+use uuid::Uuid;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// A payment to collect for an order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentRequest {
+    /// The order's reference, so the payment can be traced back to it
+    pub reference: String,
+    /// In the currency's minor unit
+    pub amount: i64,
+    pub currency: String,
+    /// Buyer's email address, for the provider's receipt
+    pub email: String,
+    pub description: String,
+}
+
+/// A payment the buyer has yet to make.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentSession {
+    /// The provider's id for the payment
+    pub payment_reference: String,
+    /// Where to send the buyer to pay
+    pub checkout_url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaymentStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+/// What a webhook reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookKind {
+    PaymentSucceeded,
+    PaymentFailed,
+    /// Money given back, from the provider's dashboard or through
+    /// [`PaymentProvider::refund`]
+    Refunded,
+}
+
+/// A verified webhook the ticketing service acts on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookEvent {
+    /// The provider's id for the event; the same for every redelivery
+    pub id: String,
+    /// The provider's name for the event, for the record
+    pub event_type: String,
+    pub kind: WebhookKind,
+    pub payment_reference: String,
+    /// In the currency's minor unit
+    pub amount: i64,
+    pub currency: String,
+    /// The provider's id for the refund, for [`WebhookKind::Refunded`]
+    pub refund_reference: Option<String>,
+    /// The order's reference when the provider passes it back, which finds
+    /// the order for a payment it did not start (such as a second one)
+    pub order_reference: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebhookError {
+    /// Missing or wrong signature: not sent by the provider
+    InvalidSignature,
+    /// Signed, but not in the shape the provider documents
+    Malformed(String),
+}
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebhookError::InvalidSignature => write!(f, "invalid webhook signature"),
+            WebhookError::Malformed(reason) => write!(f, "malformed webhook: {}", reason),
+        }
+    }
+}
+
+impl Error for WebhookError {}
+
+/// Collects payments. Checkout goes through this so tests can swap in a
+/// [`FakePaymentProvider`].
+#[async_trait(?Send)]
+pub trait PaymentProvider: Send + Sync + fmt::Debug {
+    /// Short stable name, stored with orders and ledger entries.
+    fn name(&self) -> &'static str;
+
+    /// Start collecting a payment.
+    async fn create_payment(
+        &self,
+        request: &PaymentRequest,
+    ) -> Result<PaymentSession, Box<dyn Error>>;
+
+    /// Where a payment started by [`PaymentProvider::create_payment`] is.
+    async fn payment_status(
+        &self,
+        payment_reference: &str,
+    ) -> Result<PaymentStatus, Box<dyn Error>>;
+
+    /// Give `amount` of a payment back to the buyer, returning the
+    /// provider's id for the refund.
+    async fn refund(&self, payment_reference: &str, amount: i64) -> Result<String, Box<dyn Error>>;
+
+    /// The request header carrying the webhook signature.
+    fn signature_header(&self) -> &'static str;
+
+    /// Check a webhook's signature and read it. `Ok(None)` for events
+    /// nothing here acts on, which should still be acknowledged.
+    fn parse_webhook(
+        &self,
+        body: &[u8],
+        signature: Option<&str>,
+    ) -> Result<Option<WebhookEvent>, WebhookError>;
+}
+
+/// Hex HMAC-SHA512 of `body` keyed with `secret`, as Paystack signs its
+/// webhooks.
+pub fn sign_webhook(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha512::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Whether `signature` is [`sign_webhook`] of `body`, compared in constant
+/// time.
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature.trim()) else {
+        return false;
+    };
+    let mut mac =
+        HmacSha512::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Takes no money: payments stay pending until [`FakePaymentProvider::succeed`]
+/// or [`FakePaymentProvider::fail`] settles them. Doubles as a local webhook
+/// sender through [`FakePaymentProvider::webhook`].
+#[derive(Debug)]
+pub struct FakePaymentProvider {
+    /// Signs webhooks; random, so nothing outside the process can forge one
+    webhook_secret: String,
+    /// The provider's id, request and status of each payment, oldest first
+    payments: Mutex<Vec<(String, PaymentRequest, PaymentStatus)>>,
+    /// The payment and amount of each refund, oldest first
+    refunds: Mutex<Vec<(String, i64)>>,
+}
+
+impl Default for FakePaymentProvider {
+    fn default() -> Self {
+        Self {
+            webhook_secret: rand::rng()
+                .sample_iter(&Alphanumeric)
+                .take(32)
+                .map(char::from)
+                .collect(),
+            payments: Mutex::default(),
+            refunds: Mutex::default(),
+        }
+    }
+}
+
+/// The body of a [`FakePaymentProvider`] webhook.
+#[derive(Debug, Serialize, Deserialize)]
+struct FakeWebhook {
+    id: String,
+    #[serde(rename = "type")]
+    kind: WebhookKind,
+    payment_reference: String,
+    amount: i64,
+    currency: String,
+    refund_reference: Option<String>,
+    #[serde(default)]
+    order_reference: Option<String>,
+}
+
+impl FakePaymentProvider {
+    /// Every payment started, oldest first.
+    pub fn requests(&self) -> Vec<PaymentRequest> {
+        self.payments
+            .lock()
+            .map(|payments| {
+                payments
+                    .iter()
+                    .map(|(_, request, _)| request.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The payment reference and amount of every refund, oldest first.
+    pub fn refunds(&self) -> Vec<(String, i64)> {
+        self.refunds
+            .lock()
+            .map(|refunds| refunds.clone())
+            .unwrap_or_default()
+    }
+
+    /// Mark the payment for the order `reference` as made.
+    pub fn succeed(&self, reference: &str) {
+        self.settle(reference, PaymentStatus::Succeeded);
+    }
+
+    /// Mark the payment for the order `reference` as declined.
+    pub fn fail(&self, reference: &str) {
+        self.settle(reference, PaymentStatus::Failed);
+    }
+
+    /// The webhook this provider would send about the payment for the
+    /// order `reference`: its body and signature header value. Settles
+    /// the payment to match; a refund is recorded as if made from the
+    /// provider's dashboard.
+    pub fn webhook(&self, kind: WebhookKind, reference: &str) -> (String, String) {
+        let payment_reference = fake_payment_reference(reference);
+        self.signed_webhook(kind, reference, payment_reference, None, None)
+    }
+
+    /// The webhook for a second payment of the order `reference`, as when
+    /// the buyer pays twice.
+    pub fn second_payment_webhook(&self, reference: &str) -> (String, String) {
+        let payment_reference = format!("{}_2", fake_payment_reference(reference));
+        self.signed_webhook(
+            WebhookKind::PaymentSucceeded,
+            reference,
+            payment_reference,
+            None,
+            None,
+        )
+    }
+
+    /// Like [`FakePaymentProvider::webhook`] for a refund of only `amount`
+    /// of the payment.
+    pub fn partial_refund_webhook(&self, reference: &str, amount: i64) -> (String, String) {
+        let payment_reference = fake_payment_reference(reference);
+        self.signed_webhook(
+            WebhookKind::Refunded,
+            reference,
+            payment_reference,
+            Some(amount),
+            None,
+        )
+    }
+
+    /// Like [`FakePaymentProvider::partial_refund_webhook`], reported in
+    /// `currency` instead of the payment's.
+    pub fn refund_webhook_in(
+        &self,
+        reference: &str,
+        amount: i64,
+        currency: &str,
+    ) -> (String, String) {
+        let payment_reference = fake_payment_reference(reference);
+        self.signed_webhook(
+            WebhookKind::Refunded,
+            reference,
+            payment_reference,
+            Some(amount),
+            Some(currency),
+        )
+    }
+
+    fn signed_webhook(
+        &self,
+        kind: WebhookKind,
+        reference: &str,
+        payment_reference: String,
+        amount: Option<i64>,
+        currency: Option<&str>,
+    ) -> (String, String) {
+        let (paid, currency_paid) = self
+            .requests()
+            .into_iter()
+            .find(|request| request.reference == reference)
+            .map_or((0, "NGN".to_string()), |request| {
+                (request.amount, request.currency)
+            });
+        let amount = amount.unwrap_or(paid);
+        let currency = currency.map_or(currency_paid, str::to_string);
+        let refund_reference = match kind {
+            WebhookKind::PaymentSucceeded => {
+                self.succeed(reference);
+                None
+            }
+            WebhookKind::PaymentFailed => {
+                self.fail(reference);
+                None
+            }
+            WebhookKind::Refunded => {
+                if let Ok(mut refunds) = self.refunds.lock() {
+                    refunds.push((payment_reference.clone(), amount));
+                }
+                Some(format!("fake_refund_{}", Uuid::new_v4().simple()))
+            }
+        };
+        let body = serde_json::to_string(&FakeWebhook {
+            id: format!("evt_{}", Uuid::new_v4().simple()),
+            kind,
+            payment_reference,
+            amount,
+            currency,
+            refund_reference,
+            order_reference: Some(reference.to_string()),
+        })
+        .expect("webhook serializes");
+        let signature = sign_webhook(&self.webhook_secret, body.as_bytes());
+        (body, signature)
+    }
+
+    fn settle(&self, reference: &str, status: PaymentStatus) {
+        let payment_reference = fake_payment_reference(reference);
+        if let Ok(mut payments) = self.payments.lock()
+            && let Some(payment) = payments
+                .iter_mut()
+                .find(|(id, _, _)| *id == payment_reference)
+        {
+            payment.2 = status;
+        }
+    }
+}
+
+/// The fake provider's id for the payment of the order `reference`.
+fn fake_payment_reference(reference: &str) -> String {
+    format!("fake_{}", reference)
+}
+
+#[async_trait(?Send)]
+impl PaymentProvider for FakePaymentProvider {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn create_payment(
+        &self,
+        request: &PaymentRequest,
+    ) -> Result<PaymentSession, Box<dyn Error>> {
+        let payment_reference = fake_payment_reference(&request.reference);
+        self.payments
+            .lock()
+            .map_err(|_| "Payment provider lock poisoned")?
+            .push((
+                payment_reference.clone(),
+                request.clone(),
+                PaymentStatus::Pending,
+            ));
+        Ok(PaymentSession {
+            checkout_url: format!("https://payments.invalid/checkout/{}", payment_reference),
+            payment_reference,
+        })
+    }
+
+    async fn payment_status(
+        &self,
+        payment_reference: &str,
+    ) -> Result<PaymentStatus, Box<dyn Error>> {
+        self.payments
+            .lock()
+            .map_err(|_| "Payment provider lock poisoned")?
+            .iter()
+            .find(|(id, _, _)| id == payment_reference)
+            .map(|(_, _, status)| *status)
+            .ok_or_else(|| format!("Unknown payment: {}", payment_reference).into())
+    }
+
+    async fn refund(&self, payment_reference: &str, amount: i64) -> Result<String, Box<dyn Error>> {
+        self.refunds
+            .lock()
+            .map_err(|_| "Payment provider lock poisoned")?
+            .push((payment_reference.to_string(), amount));
+        Ok(format!("fake_refund_{}", Uuid::new_v4().simple()))
+    }
+
+    fn signature_header(&self) -> &'static str {
+        "x-fake-signature"
+    }
+
+    fn parse_webhook(
+        &self,
+        body: &[u8],
+        signature: Option<&str>,
+    ) -> Result<Option<WebhookEvent>, WebhookError> {
+        if !signature
+            .is_some_and(|signature| verify_signature(&self.webhook_secret, body, signature))
+        {
+            return Err(WebhookError::InvalidSignature);
+        }
+        let webhook: FakeWebhook =
+            serde_json::from_slice(body).map_err(|e| WebhookError::Malformed(e.to_string()))?;
+        let event_type = match webhook.kind {
+            WebhookKind::PaymentSucceeded => "payment_succeeded",
+            WebhookKind::PaymentFailed => "payment_failed",
+            WebhookKind::Refunded => "refunded",
+        };
+        Ok(Some(WebhookEvent {
+            id: webhook.id,
+            event_type: event_type.to_string(),
+            kind: webhook.kind,
+            payment_reference: webhook.payment_reference,
+            amount: webhook.amount,
+            currency: webhook.currency,
+            refund_reference: webhook.refund_reference,
+            order_reference: webhook.order_reference,
+        }))
+    }
+}
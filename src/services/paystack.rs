@@ -0,0 +1,234 @@
+//! [`PaymentProvider`] backed by the Paystack API.
+//!
+//! Paystack reports successful payments and processed refunds through
+//! webhooks signed with the secret key. It sends no webhook for declined
+//! payments; those are picked up when the buyer confirms the order.
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::core::configs::AppConfig;
+use crate::services::payments::{
+    PaymentProvider, PaymentRequest, PaymentSession, PaymentStatus, WebhookError, WebhookEvent,
+    WebhookKind, verify_signature,
+};
+
+/// Upper bound for one Paystack API call, so checkout and refunds never
+/// hang on a slow API.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct PaystackProvider {
+    client: reqwest::Client,
+    base_url: String,
+    secret_key: String,
+    /// Where Paystack sends the buyer after paying
+    callback_url: String,
+}
+
+impl fmt::Debug for PaystackProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PaystackProvider")
+            .field("base_url", &self.base_url)
+            .field("callback_url", &self.callback_url)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Every Paystack response comes wrapped in one of these.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    status: bool,
+    message: String,
+    data: Option<T>,
+}
+
+#[derive(Deserialize)]
+struct Initialized {
+    authorization_url: String,
+    reference: String,
+}
+
+#[derive(Deserialize)]
+struct Verified {
+    status: String,
+}
+
+#[derive(Deserialize)]
+struct Webhook {
+    event: String,
+    data: Value,
+}
+
+impl PaystackProvider {
+    pub fn new(base_url: &str, secret_key: &str, callback_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("HTTP client builds"),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            secret_key: secret_key.to_string(),
+            callback_url: callback_url.to_string(),
+        }
+    }
+
+    /// Build from the `PAYSTACK_*` settings. Buyers come back to the web
+    /// app's orders page after paying.
+    pub fn from_config(config: &AppConfig) -> Result<Self, String> {
+        let secret_key = config
+            .paystack_secret_key
+            .as_deref()
+            .ok_or("PAYSTACK_SECRET_KEY is not set")?;
+        Ok(Self::new(
+            &config.paystack_base_url,
+            secret_key,
+            &format!("{}/orders", config.public_url.trim_end_matches('/')),
+        ))
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, Box<dyn Error>> {
+        let envelope: Envelope<T> = request
+            .bearer_auth(&self.secret_key)
+            .send()
+            .await?
+            .json()
+            .await?;
+        if !envelope.status {
+            return Err(format!("Paystack refused the request: {}", envelope.message).into());
+        }
+        envelope.data.ok_or_else(|| "Paystack sent no data".into())
+    }
+}
+
+/// A field that Paystack sends as either a string or a number.
+fn text(data: &Value, field: &str) -> Option<String> {
+    match data.get(field)? {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+fn amount(data: &Value) -> Result<i64, WebhookError> {
+    text(data, "amount")
+        .and_then(|amount| amount.parse().ok())
+        .ok_or_else(|| WebhookError::Malformed("missing amount".to_string()))
+}
+
+#[async_trait(?Send)]
+impl PaymentProvider for PaystackProvider {
+    fn name(&self) -> &'static str {
+        "paystack"
+    }
+
+    async fn create_payment(
+        &self,
+        request: &PaymentRequest,
+    ) -> Result<PaymentSession, Box<dyn Error>> {
+        let initialized: Initialized = self
+            .call(
+                self.client
+                    .post(format!("{}/transaction/initialize", self.base_url))
+                    .json(&json!({
+                        "email": request.email,
+                        "amount": request.amount,
+                        "currency": request.currency,
+                        "reference": request.reference,
+                        "callback_url": self.callback_url,
+                        "metadata": {
+                            "description": request.description,
+                            "order_reference": request.reference,
+                        },
+                    })),
+            )
+            .await?;
+        Ok(PaymentSession {
+            payment_reference: initialized.reference,
+            checkout_url: initialized.authorization_url,
+        })
+    }
+
+    async fn payment_status(
+        &self,
+        payment_reference: &str,
+    ) -> Result<PaymentStatus, Box<dyn Error>> {
+        let verified: Verified = self
+            .call(self.client.get(format!(
+                "{}/transaction/verify/{}",
+                self.base_url, payment_reference
+            )))
+            .await?;
+        Ok(match verified.status.as_str() {
+            "success" => PaymentStatus::Succeeded,
+            "failed" | "reversed" => PaymentStatus::Failed,
+            // `abandoned` only means the buyer has not finished paying yet
+            _ => PaymentStatus::Pending,
+        })
+    }
+
+    async fn refund(&self, payment_reference: &str, amount: i64) -> Result<String, Box<dyn Error>> {
+        let refund: Value = self
+            .call(
+                self.client
+                    .post(format!("{}/refund", self.base_url))
+                    .json(&json!({ "transaction": payment_reference, "amount": amount })),
+            )
+            .await?;
+        text(&refund, "id").ok_or_else(|| "Paystack sent no refund id".into())
+    }
+
+    fn signature_header(&self) -> &'static str {
+        "x-paystack-signature"
+    }
+
+    fn parse_webhook(
+        &self,
+        body: &[u8],
+        signature: Option<&str>,
+    ) -> Result<Option<WebhookEvent>, WebhookError> {
+        if !signature.is_some_and(|signature| verify_signature(&self.secret_key, body, signature)) {
+            return Err(WebhookError::InvalidSignature);
+        }
+        let webhook: Webhook =
+            serde_json::from_slice(body).map_err(|e| WebhookError::Malformed(e.to_string()))?;
+        let data = &webhook.data;
+        let (kind, payment_reference, refund_reference) = match webhook.event.as_str() {
+            "charge.success" => (WebhookKind::PaymentSucceeded, text(data, "reference"), None),
+            "refund.processed" => (
+                WebhookKind::Refunded,
+                text(data, "transaction_reference"),
+                text(data, "id").or_else(|| text(data, "refund_reference")),
+            ),
+            _ => return Ok(None),
+        };
+        let payment_reference = payment_reference
+            .ok_or_else(|| WebhookError::Malformed("missing payment reference".to_string()))?;
+        // Paystack events have no id of their own; the event name and the
+        // transaction or refund it is about identify redeliveries
+        let subject = refund_reference
+            .clone()
+            .or_else(|| text(data, "id"))
+            .unwrap_or_else(|| payment_reference.clone());
+        Ok(Some(WebhookEvent {
+            id: format!("{}:{}", webhook.event, subject),
+            event_type: webhook.event.clone(),
+            kind,
+            amount: amount(data)?,
+            currency: text(data, "currency").unwrap_or_default(),
+            payment_reference,
+            refund_reference,
+            order_reference: data
+                .get("metadata")
+                .and_then(|metadata| text(metadata, "order_reference")),
+        }))
+    }
+}
@@ -0,0 +1,90 @@
+use std::error::Error;
+
+use sea_orm::{ActiveValue::Set, DatabaseConnection, EntityTrait, Iterable, QueryOrder};
+use serde::Serialize;
+
+use crate::core::cache::{Cache, REFERENCE_DATA};
+use crate::entity::prelude::*;
+use crate::entity::{EventCategory, Motivation as MotivationKind};
+use crate::schemas::event::ReferenceDataResponse;
+
+/// Rows inserted by [`seed_reference_data`].
+#[derive(Debug, Default, Serialize)]
+pub struct SeedReport {
+    pub event_categories: Vec<EventCategory>,
+    pub motivations: Vec<MotivationKind>,
+}
+
+/// Insert an `event_categories` row for every [`EventCategory`] and a
+/// `motivations` row for every motivation that is not in the table yet.
+/// Safe to run repeatedly.
+pub async fn seed_reference_data(
+    db: &DatabaseConnection,
+    cache: &Cache,
+) -> Result<SeedReport, Box<dyn Error>> {
+    let mut report = SeedReport::default();
+
+    let existing: Vec<EventCategory> = EventCategories::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|row| row.name)
+        .collect();
+    for category in EventCategory::iter().filter(|c| !existing.contains(c)) {
+        EventCategories::insert(EventCategoriesActiveModel {
+            name: Set(category),
+            ..Default::default()
+        })
+        .exec(db)
+        .await?;
+        report.event_categories.push(category);
+    }
+
+    let existing: Vec<MotivationKind> = Motivation::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|row| row.motivation)
+        .collect();
+    for motivation in MotivationKind::iter().filter(|m| !existing.contains(m)) {
+        Motivation::insert(MotivationActiveModel {
+            motivation: Set(motivation),
+            ..Default::default()
+        })
+        .exec(db)
+        .await?;
+        report.motivations.push(motivation);
+    }
+
+    cache.invalidate_namespace(REFERENCE_DATA).await;
+    Ok(report)
+}
+
+/// The seeded event categories and motivations, through the cache.
+pub async fn list_reference_data(
+    db: &DatabaseConnection,
+    cache: &Cache,
+) -> Result<ReferenceDataResponse, Box<dyn Error>> {
+    cache
+        .get_or_load(REFERENCE_DATA, "all", || async move {
+            let event_categories = EventCategories::find()
+                .order_by_asc(EventCategoriesColumn::Id)
+                .all(db)
+                .await?
+                .into_iter()
+                .map(|row| row.name)
+                .collect();
+            let motivations = Motivation::find()
+                .order_by_asc(MotivationColumn::Id)
+                .all(db)
+                .await?
+                .into_iter()
+                .map(|row| row.motivation)
+                .collect();
+            Ok(ReferenceDataResponse {
+                event_categories,
+                motivations,
+            })
+        })
+        .await
+}
@@ -0,0 +1,357 @@
+//! Full-text search over events, hosts and users.
+//!
+//! Matching is by word prefix, ranked with the title (or username) counting
+//! most. Postgres uses the weighted `search_vector` columns and `pg_trgm`;
+//! SQLite uses FTS5 with BM25 column weights. Rows the full-text search
+//! misses can still match on a misspelt word, ranked after every full-text
+//! hit; Postgres has `pg_trgm` work this out, and on SQLite the same word
+//! similarity is computed here.
+use std::collections::HashSet;
+use std::error::Error;
+
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait,
+    FromQueryResult, QueryFilter, Statement,
+};
+
+use crate::entity::prelude::*;
+use crate::entity::{EventStatus, EventVisibility};
+
+/// Most words of a query that are searched for.
+pub const MAX_TERMS: usize = 8;
+/// Most visible matches of each kind looked at before paging.
+const MAX_CANDIDATES: usize = 200;
+/// `pg_trgm`'s default `word_similarity_threshold`.
+const WORD_SIMILARITY_THRESHOLD: f64 = 0.6;
+
+/// A searchable table.
+struct SearchIndex {
+    table: &'static str,
+    key: &'static str,
+    /// Postgres text search configuration of its `search_vector`
+    config: &'static str,
+    /// BM25 weights of its FTS5 columns, in column order
+    weights: &'static str,
+    /// Expressions for the fields typos are forgiven in
+    fuzzy: &'static [&'static str],
+    /// Condition for the rows that may be returned, checked while ranking
+    /// so hidden rows do not use up the candidates
+    visible: &'static str,
+}
+
+const EVENTS: SearchIndex = SearchIndex {
+    table: "events",
+    key: "id",
+    config: "english",
+    weights: "4.0, 2.0, 1.0",
+    fuzzy: &["title"],
+    visible: "events.visibility = 'Public' AND events.status <> 'Cancelled'",
+};
+
+const HOSTS: SearchIndex = SearchIndex {
+    table: "hosts",
+    key: "user_id",
+    config: "simple",
+    weights: "1.0",
+    fuzzy: &["organization_name"],
+    visible: "EXISTS (SELECT 1 FROM users WHERE users.id = hosts.user_id AND users.is_active)",
+};
+
+const USERS: SearchIndex = SearchIndex {
+    table: "users",
+    key: "id",
+    config: "simple",
+    weights: "4.0, 2.0, 2.0",
+    fuzzy: &[
+        "username",
+        "coalesce(first_name, '') || ' ' || coalesce(last_name, '')",
+    ],
+    visible: "users.is_active",
+};
+
+#[derive(Debug, FromQueryResult)]
+struct Hit {
+    id: i32,
+    score: f64,
+}
+
+/// A host and the username of their account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostMatch {
+    pub host: HostModel,
+    pub username: String,
+}
+
+/// Matches of each kind, best first.
+#[derive(Debug, Default)]
+pub struct SearchResults {
+    pub events: Vec<EventModel>,
+    pub hosts: Vec<HostMatch>,
+    pub users: Vec<UserModel>,
+}
+
+/// The words of `query` to search for: runs of letters and digits,
+/// lowercased, without repeats. Everything else, including any search
+/// syntax, is ignored.
+pub fn search_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let word = word.to_lowercase();
+        if !terms.contains(&word) {
+            terms.push(word);
+        }
+    }
+    terms.truncate(MAX_TERMS);
+    terms
+}
+
+/// Public, not cancelled events, active hosts and, when `include_users`,
+/// active users matching every one of `terms`; at most `limit` of each.
+pub async fn search(
+    db: &DatabaseConnection,
+    terms: &[String],
+    include_users: bool,
+    limit: u64,
+) -> Result<SearchResults, Box<dyn Error>> {
+    let limit = limit as usize;
+    if terms.is_empty() {
+        return Ok(SearchResults::default());
+    }
+
+    let ids = ranked(db, &EVENTS, terms).await?;
+    let events = Event::find()
+        .filter(EventColumn::Id.is_in(ids.clone()))
+        .filter(EventColumn::Visibility.eq(EventVisibility::Public))
+        .filter(EventColumn::Status.ne(EventStatus::Cancelled))
+        .all(db)
+        .await?;
+    let events = in_order(&ids, events, |event| event.id, limit);
+
+    let ids = ranked(db, &HOSTS, terms).await?;
+    let hosts = Host::find()
+        .find_also_related(User)
+        .filter(HostColumn::UserId.is_in(ids.clone()))
+        .filter(UserColumn::IsActive.eq(true))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|(host, user)| {
+            user.map(|user| HostMatch {
+                host,
+                username: user.username,
+            })
+        })
+        .collect();
+    let hosts = in_order(&ids, hosts, |host| host.host.user_id, limit);
+
+    let users = if include_users {
+        let ids = ranked(db, &USERS, terms).await?;
+        let users = User::find()
+            .filter(UserColumn::Id.is_in(ids.clone()))
+            .filter(UserColumn::IsActive.eq(true))
+            .all(db)
+            .await?;
+        in_order(&ids, users, |user| user.id, limit)
+    } else {
+        Vec::new()
+    };
+
+    Ok(SearchResults {
+        events,
+        hosts,
+        users,
+    })
+}
+
+/// Keys of the rows of `index` matching `terms`, best first: full-text
+/// matches, then near misses.
+async fn ranked(
+    db: &DatabaseConnection,
+    index: &SearchIndex,
+    terms: &[String],
+) -> Result<Vec<i32>, DbErr> {
+    let mut seen = HashSet::new();
+    Ok(full_text(db, index, terms)
+        .await?
+        .into_iter()
+        .chain(similar(db, index, terms).await?)
+        .map(|hit| hit.id)
+        .filter(|id| seen.insert(*id))
+        .take(MAX_CANDIDATES)
+        .collect())
+}
+
+async fn full_text(
+    db: &DatabaseConnection,
+    index: &SearchIndex,
+    terms: &[String],
+) -> Result<Vec<Hit>, DbErr> {
+    let SearchIndex {
+        table,
+        key,
+        config,
+        weights,
+        visible,
+        ..
+    } = index;
+    let backend = db.get_database_backend();
+    // Terms are letters and digits only, so they cannot break out of the
+    // query syntax
+    let statement = if backend == DbBackend::Postgres {
+        let query = terms
+            .iter()
+            .map(|term| format!("{term}:*"))
+            .collect::<Vec<_>>()
+            .join(" & ");
+        Statement::from_sql_and_values(
+            backend,
+            format!(
+                "SELECT {key} AS id, ts_rank(search_vector, query)::float8 AS score \
+                 FROM {table}, to_tsquery('{config}', $1) AS query \
+                 WHERE search_vector @@ query AND ({visible}) \
+                 ORDER BY score DESC, {key} LIMIT {MAX_CANDIDATES}"
+            ),
+            [query.into()],
+        )
+    } else {
+        let query = terms
+            .iter()
+            .map(|term| format!("\"{term}\"*"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Statement::from_sql_and_values(
+            backend,
+            format!(
+                "SELECT {table}_fts.rowid AS id, -bm25({table}_fts, {weights}) AS score \
+                 FROM {table}_fts JOIN {table} ON {table}.{key} = {table}_fts.rowid \
+                 WHERE {table}_fts MATCH ? AND ({visible}) \
+                 ORDER BY score DESC, id LIMIT {MAX_CANDIDATES}"
+            ),
+            [query.into()],
+        )
+    };
+    Hit::find_by_statement(statement).all(db).await
+}
+
+/// Rows with a field close enough to `terms` to be a typo of them.
+async fn similar(
+    db: &DatabaseConnection,
+    index: &SearchIndex,
+    terms: &[String],
+) -> Result<Vec<Hit>, DbErr> {
+    let SearchIndex {
+        table,
+        key,
+        fuzzy,
+        visible,
+        ..
+    } = index;
+    let backend = db.get_database_backend();
+    let query = terms.join(" ");
+
+    if backend == DbBackend::Postgres {
+        let scores = fuzzy
+            .iter()
+            .map(|field| format!("word_similarity($1, {field})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let matches = fuzzy
+            .iter()
+            .map(|field| format!("$1 <% ({field})"))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        let statement = Statement::from_sql_and_values(
+            backend,
+            format!(
+                "SELECT {key} AS id, GREATEST({scores})::float8 AS score FROM {table} \
+                 WHERE ({matches}) AND ({visible}) \
+                 ORDER BY score DESC, {key} LIMIT {MAX_CANDIDATES}"
+            ),
+            [query.into()],
+        );
+        return Hit::find_by_statement(statement).all(db).await;
+    }
+
+    // SQLite has no trigram index, so every row is compared; fine for the
+    // sizes SQLite is used at
+    let fields = fuzzy
+        .iter()
+        .enumerate()
+        .map(|(i, field)| format!("{field} AS field_{i}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let rows = db
+        .query_all_raw(Statement::from_string(
+            backend,
+            format!("SELECT {key} AS id, {fields} FROM {table} WHERE {visible}"),
+        ))
+        .await?;
+    let mut hits = Vec::new();
+    for row in rows {
+        let mut score: f64 = 0.0;
+        for i in 0..fuzzy.len() {
+            let field: Option<String> = row.try_get("", &format!("field_{i}"))?;
+            if let Some(field) = field {
+                score = score.max(word_similarity(&query, &field));
+            }
+        }
+        if score >= WORD_SIMILARITY_THRESHOLD {
+            hits.push(Hit {
+                id: row.try_get("", "id")?,
+                score,
+            });
+        }
+    }
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+    hits.truncate(MAX_CANDIDATES);
+    Ok(hits)
+}
+
+/// How much of `query` appears in `text`, from 0 to 1, by trigrams the way
+/// `pg_trgm` counts them: the share of each query word's trigrams found in
+/// the closest word of `text`.
+pub fn word_similarity(query: &str, text: &str) -> f64 {
+    let found: Vec<HashSet<String>> = words(text).iter().map(|word| trigrams(word)).collect();
+    let mut total = 0;
+    let mut shared = 0;
+    for word in words(query) {
+        let wanted = trigrams(&word);
+        total += wanted.len();
+        shared += found
+            .iter()
+            .map(|candidate| wanted.intersection(candidate).count())
+            .max()
+            .unwrap_or(0);
+    }
+    if total == 0 {
+        return 0.0;
+    }
+    shared as f64 / total as f64
+}
+
+/// Lowercased runs of letters and digits, as `pg_trgm` splits text.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Trigrams of a word padded with two spaces in front and one behind.
+fn trigrams(word: &str) -> HashSet<String> {
+    let padded: Vec<char> = format!("  {word} ").chars().collect();
+    padded
+        .windows(3)
+        .map(|window| window.iter().collect())
+        .collect()
+}
+
+/// `rows` in the order of `ids`, at most `limit` of them.
+fn in_order<T>(ids: &[i32], mut rows: Vec<T>, key: impl Fn(&T) -> i32, limit: usize) -> Vec<T> {
+    rows.sort_by_key(|row| ids.iter().position(|id| *id == key(row)));
+    rows.truncate(limit);
+    rows
+}
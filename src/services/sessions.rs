@@ -0,0 +1,136 @@
+use std::error::Error;
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+
+use crate::entity::prelude::*;
+use crate::utils::utils::JWT_LIFETIME;
+
+/// How stale `last_seen_at` may get before an authenticated request refreshes it.
+/// Keeps the extractor from writing to the database on every request.
+const LAST_SEEN_REFRESH_SECONDS: i64 = 60;
+
+pub async fn create_session(
+    db: &DatabaseConnection,
+    user_id: i32,
+    user_agent: Option<String>,
+    ip_address: Option<String>,
+) -> Result<SessionModel, Box<dyn Error>> {
+    let now = Utc::now();
+    let session = SessionActiveModel {
+        user_id: Set(user_id),
+        user_agent: Set(user_agent),
+        ip_address: Set(ip_address),
+        created_at: Set(now),
+        last_seen_at: Set(now),
+        revoked_at: Set(None),
+        ..Default::default()
+    };
+
+    Ok(session.insert(db).await?)
+}
+
+/// List the user's sessions that have not been revoked and whose token has
+/// not expired, most recently used first.
+pub async fn list_active_sessions(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<SessionModel>, Box<dyn Error>> {
+    let sessions = Session::find()
+        .filter(SessionColumn::UserId.eq(user_id))
+        .filter(SessionColumn::RevokedAt.is_null())
+        .filter(SessionColumn::CreatedAt.gt(Utc::now() - JWT_LIFETIME))
+        .order_by_desc(SessionColumn::LastSeenAt)
+        .all(db)
+        .await?;
+
+    Ok(sessions)
+}
+
+/// Revoke one of the user's sessions.
+///
+/// Returns `false` when the session does not exist, belongs to someone else,
+/// or was already revoked.
+pub async fn revoke_session(
+    db: &DatabaseConnection,
+    user_id: i32,
+    session_id: i32,
+) -> Result<bool, Box<dyn Error>> {
+    let session = Session::find_by_id(session_id)
+        .filter(SessionColumn::UserId.eq(user_id))
+        .filter(SessionColumn::RevokedAt.is_null())
+        .one(db)
+        .await?;
+
+    let Some(session) = session else {
+        return Ok(false);
+    };
+
+    let mut session = session.into_active_model();
+    session.revoked_at = Set(Some(Utc::now()));
+    session.update(db).await?;
+
+    Ok(true)
+}
+
+/// Revoke every active session of the user, returning how many were revoked.
+pub async fn revoke_all_sessions(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<u64, Box<dyn Error>> {
+    let res = Session::update_many()
+        .col_expr(SessionColumn::RevokedAt, Expr::value(Some(Utc::now())))
+        .filter(SessionColumn::UserId.eq(user_id))
+        .filter(SessionColumn::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(res.rows_affected)
+}
+
+/// Number of distinct users with an unrevoked session seen since `since`.
+pub async fn count_active_users(
+    db: &DatabaseConnection,
+    since: DateTime<Utc>,
+) -> Result<u64, Box<dyn Error>> {
+    let count = Session::find()
+        .select_only()
+        .column(SessionColumn::UserId)
+        .distinct()
+        .filter(SessionColumn::RevokedAt.is_null())
+        .filter(SessionColumn::LastSeenAt.gt(since))
+        .count(db)
+        .await?;
+
+    Ok(count)
+}
+
+/// Load an active session for the given user, bumping `last_seen_at` when it
+/// is older than [`LAST_SEEN_REFRESH_SECONDS`].
+pub async fn touch_session(
+    db: &DatabaseConnection,
+    user_id: i32,
+    session_id: i32,
+) -> Result<SessionModel, Box<dyn Error>> {
+    let session = Session::find_by_id(session_id)
+        .filter(SessionColumn::UserId.eq(user_id))
+        .one(db)
+        .await?
+        .ok_or("Session not found")?;
+
+    if session.revoked_at.is_some() {
+        return Err("Session has been revoked".into());
+    }
+
+    let now = Utc::now();
+    if now - session.last_seen_at < Duration::seconds(LAST_SEEN_REFRESH_SECONDS) {
+        return Ok(session);
+    }
+
+    let mut active = session.into_active_model();
+    active.last_seen_at = Set(now);
+    Ok(active.update(db).await?)
+}
@@ -0,0 +1,302 @@
+//! Event categories and free-form tags.
+//!
+//! An event's `category` column is its primary category; further ones are
+//! linked through `event_category_links`, and tags through `event_tags`.
+//! The primary category always counts as one of the event's categories,
+//! linked or not, so events created before the links existed still match.
+use std::collections::HashMap;
+use std::error::Error;
+
+use chrono::Utc;
+use sea_orm::sea_query::{Expr, ExprTrait, Func, LikeExpr, OnConflict, Query};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait,
+    DatabaseConnection, EntityTrait, FromQueryResult, IntoActiveModel, JoinType, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait, TransactionTrait,
+};
+
+use crate::core::cache::Cache;
+use crate::entity::prelude::*;
+use crate::entity::{EventCategory, EventVisibility};
+use crate::schemas::event::{EventLabels, LabelFilter, LabelMatch};
+use crate::services::events::invalidate_event;
+
+/// A tag and how many public events use it.
+#[derive(Debug, Clone, PartialEq, FromQueryResult)]
+pub struct TagUsage {
+    pub name: String,
+    pub event_count: i64,
+}
+
+/// Categories and tags for each of `events`, keyed by event id.
+pub async fn event_labels(
+    db: &DatabaseConnection,
+    events: &[EventModel],
+) -> Result<HashMap<i32, EventLabels>, Box<dyn Error>> {
+    let ids: Vec<i32> = events.iter().map(|event| event.id).collect();
+    let mut labels: HashMap<i32, EventLabels> = events
+        .iter()
+        .map(|event| {
+            (
+                event.id,
+                EventLabels {
+                    categories: vec![event.category],
+                    tags: Vec::new(),
+                },
+            )
+        })
+        .collect();
+    if ids.is_empty() {
+        return Ok(labels);
+    }
+
+    let categories: Vec<(i32, EventCategory)> = EventCategoryLink::find()
+        .select_only()
+        .column(EventCategoryLinkColumn::EventId)
+        .column(EventCategoriesColumn::Name)
+        .join(
+            JoinType::InnerJoin,
+            EventCategoryLinkRelation::EventCategory.def(),
+        )
+        .filter(EventCategoryLinkColumn::EventId.is_in(ids.clone()))
+        .order_by_asc(EventCategoriesColumn::Id)
+        .into_tuple()
+        .all(db)
+        .await?;
+    for (event_id, category) in categories {
+        if let Some(labels) = labels.get_mut(&event_id)
+            && !labels.categories.contains(&category)
+        {
+            labels.categories.push(category);
+        }
+    }
+
+    let tags: Vec<(i32, String)> = EventTag::find()
+        .select_only()
+        .column(EventTagColumn::EventId)
+        .column(TagColumn::Name)
+        .join(JoinType::InnerJoin, EventTagRelation::Tag.def())
+        .filter(EventTagColumn::EventId.is_in(ids))
+        .order_by_asc(TagColumn::Name)
+        .into_tuple()
+        .all(db)
+        .await?;
+    for (event_id, tag) in tags {
+        if let Some(labels) = labels.get_mut(&event_id) {
+            labels.tags.push(tag);
+        }
+    }
+
+    Ok(labels)
+}
+
+/// Replace an event's categories, tags or both. The first category becomes
+/// the primary one; tags must already be normalized.
+pub async fn set_event_labels(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    event: EventModel,
+    categories: Option<&[EventCategory]>,
+    tags: Option<&[String]>,
+) -> Result<EventModel, Box<dyn Error>> {
+    let event_id = event.id;
+    let txn = db.begin().await?;
+    let mut event = event;
+
+    if let Some(categories) = categories.filter(|categories| !categories.is_empty()) {
+        let ids = category_ids(&txn, categories).await?;
+        EventCategoryLink::delete_many()
+            .filter(EventCategoryLinkColumn::EventId.eq(event_id))
+            .exec(&txn)
+            .await?;
+        EventCategoryLink::insert_many(ids.into_iter().map(|category_id| {
+            EventCategoryLinkActiveModel {
+                event_id: Set(event_id),
+                category_id: Set(category_id),
+            }
+        }))
+        .exec(&txn)
+        .await?;
+        if event.category != categories[0] {
+            let mut changed = event.into_active_model();
+            changed.category = Set(categories[0]);
+            changed.updated_at = Set(Utc::now());
+            event = changed.update(&txn).await?;
+        }
+    }
+
+    if let Some(tags) = tags {
+        EventTag::delete_many()
+            .filter(EventTagColumn::EventId.eq(event_id))
+            .exec(&txn)
+            .await?;
+        if !tags.is_empty() {
+            let ids = tag_ids(&txn, tags).await?;
+            EventTag::insert_many(ids.into_iter().map(|tag_id| EventTagActiveModel {
+                event_id: Set(event_id),
+                tag_id: Set(tag_id),
+            }))
+            .exec(&txn)
+            .await?;
+        }
+    }
+
+    txn.commit().await?;
+    invalidate_event(cache, event_id).await;
+    Ok(event)
+}
+
+/// Condition on `events` for `filter`; empty filters match everything.
+pub async fn label_condition<C: ConnectionTrait>(
+    db: &C,
+    filter: &LabelFilter,
+) -> Result<Condition, Box<dyn Error>> {
+    if filter.is_empty() {
+        return Ok(Condition::all());
+    }
+    // Categories nobody has linked to yet may have no row, which is fine:
+    // such a category can then only be the primary one
+    let rows = EventCategories::find()
+        .filter(EventCategoriesColumn::Name.is_in(filter.categories.clone()))
+        .all(db)
+        .await?;
+    let has_category = |category: &EventCategory| {
+        let mut condition = Condition::any().add(EventColumn::Category.eq(*category));
+        if let Some(row) = rows.iter().find(|row| row.name == *category) {
+            condition = condition.add(
+                EventColumn::Id.in_subquery(
+                    Query::select()
+                        .column(EventCategoryLinkColumn::EventId)
+                        .from(EventCategoryLink)
+                        .and_where(EventCategoryLinkColumn::CategoryId.eq(row.id))
+                        .to_owned(),
+                ),
+            );
+        }
+        condition
+    };
+    let has_tag = |tags: &[String]| {
+        EventColumn::Id.in_subquery(
+            Query::select()
+                .column((EventTag, EventTagColumn::EventId))
+                .from(EventTag)
+                .inner_join(
+                    Tag,
+                    Expr::col((Tag, TagColumn::Id)).equals((EventTag, EventTagColumn::TagId)),
+                )
+                .and_where(Expr::col((Tag, TagColumn::Name)).is_in(tags.iter().cloned()))
+                .to_owned(),
+        )
+    };
+
+    Ok(match filter.matching {
+        LabelMatch::Any => {
+            let mut condition = Condition::any();
+            for category in &filter.categories {
+                condition = condition.add(has_category(category));
+            }
+            if !filter.tags.is_empty() {
+                condition = condition.add(has_tag(&filter.tags));
+            }
+            condition
+        }
+        LabelMatch::All => {
+            let mut condition = Condition::all();
+            for category in &filter.categories {
+                condition = condition.add(has_category(category));
+            }
+            for tag in &filter.tags {
+                condition = condition.add(has_tag(std::slice::from_ref(tag)));
+            }
+            condition
+        }
+    })
+}
+
+/// Tags starting with `prefix` (normalized), most used on public events
+/// first. Tags used only on private events are never suggested.
+pub async fn suggest_tags(
+    db: &DatabaseConnection,
+    prefix: &str,
+    limit: u64,
+) -> Result<Vec<TagUsage>, Box<dyn Error>> {
+    let pattern = format!(
+        "{}%",
+        prefix
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    let count: Expr = Func::count(Expr::col((EventTag, EventTagColumn::EventId))).into();
+    Ok(Tag::find()
+        .select_only()
+        .column(TagColumn::Name)
+        .column_as(count.clone(), "event_count")
+        .join(JoinType::InnerJoin, EventTagRelation::Tag.def().rev())
+        .join(JoinType::InnerJoin, EventTagRelation::Event.def())
+        .filter(EventColumn::Visibility.eq(EventVisibility::Public))
+        .filter(Expr::col((Tag, TagColumn::Name)).like(LikeExpr::new(pattern).escape('\\')))
+        .group_by(TagColumn::Id)
+        .group_by(TagColumn::Name)
+        .order_by_desc(count)
+        .order_by_asc(TagColumn::Name)
+        .limit(limit)
+        .into_model::<TagUsage>()
+        .all(db)
+        .await?)
+}
+
+/// Ids of the `event_categories` rows for `categories`, in order, adding
+/// rows the reference data seed has not.
+async fn category_ids<C: ConnectionTrait>(
+    db: &C,
+    categories: &[EventCategory],
+) -> Result<Vec<i32>, Box<dyn Error>> {
+    for category in categories {
+        EventCategories::insert(EventCategoriesActiveModel {
+            name: Set(*category),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::column(EventCategoriesColumn::Name)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    }
+    let rows = EventCategories::find()
+        .filter(EventCategoriesColumn::Name.is_in(categories.to_vec()))
+        .all(db)
+        .await?;
+    let mut ids: Vec<i32> = Vec::new();
+    for category in categories {
+        if let Some(row) = rows.iter().find(|row| row.name == *category)
+            && !ids.contains(&row.id)
+        {
+            ids.push(row.id);
+        }
+    }
+    Ok(ids)
+}
+
+/// Ids of the tags named, creating the ones that are new.
+async fn tag_ids<C: ConnectionTrait>(db: &C, names: &[String]) -> Result<Vec<i32>, Box<dyn Error>> {
+    for name in names {
+        Tag::insert(TagActiveModel {
+            name: Set(name.clone()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        })
+        .on_conflict(OnConflict::column(TagColumn::Name).do_nothing().to_owned())
+        .exec_without_returning(db)
+        .await?;
+    }
+    Ok(Tag::find()
+        .filter(TagColumn::Name.is_in(names.iter().cloned()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|tag| tag.id)
+        .collect())
+}
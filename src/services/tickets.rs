@@ -0,0 +1,1447 @@
+//! Ticket tiers, promo codes and ticket orders.
+//!
+//! Checking out reserves the tickets on their tier, and a use of the promo
+//! code, straight away and holds them for [`HOLD_DURATION`] while the buyer
+//! pays. The counters only change through conditional updates, so two
+//! buyers can never both get the last ticket. A paid order registers the
+//! buyer for the event; one that fails, expires or is cancelled gives its
+//! tickets back.
+//!
+//! Payments and refunds are also reported by the provider's webhooks, so
+//! every step here is safe to repeat, and every movement of money is
+//! written to the ledger in the same transaction as the order change. A
+//! refund made here is recorded as pending before the provider is called,
+//! together with claiming the order (or, for a payment the order cannot
+//! take, with recording that payment), and completed with the provider's
+//! reference once it returns; one left pending is completed by the
+//! provider's refund webhook.
+use std::error::Error;
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::sea_query::{Expr, ExprTrait, OnConflict};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait,
+    DatabaseConnection, EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder,
+    TransactionTrait,
+};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::entity::prelude::*;
+use crate::entity::{AttendanceStatus, DiscountType, EventStatus, LedgerEntryKind, OrderStatus};
+use crate::services::attendance::ensure_attendee;
+use crate::services::invitations::{accept_invitation, can_view};
+use crate::services::payments::{
+    PaymentProvider, PaymentRequest, PaymentStatus, WebhookEvent, WebhookKind,
+};
+
+/// How long tickets are held for a buyer to pay.
+pub const HOLD_DURATION: Duration = Duration::minutes(15);
+
+/// A new ticket tier. `currency` must already be upper case.
+#[derive(Debug, Clone)]
+pub struct NewTicketTier {
+    pub name: String,
+    pub description: Option<String>,
+    pub price: i64,
+    pub currency: String,
+    pub quantity: i32,
+    pub sales_start_at: Option<DateTime<Utc>>,
+    pub sales_end_at: Option<DateTime<Utc>>,
+}
+
+/// Changes to a ticket tier; `None` leaves a field as it is.
+#[derive(Debug, Clone, Default)]
+pub struct TicketTierChanges {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub price: Option<i64>,
+    pub quantity: Option<i32>,
+    pub sales_start_at: Option<DateTime<Utc>>,
+    pub sales_end_at: Option<DateTime<Utc>>,
+}
+
+/// A new promo code. `code` must already be upper case.
+#[derive(Debug, Clone)]
+pub struct NewPromoCode {
+    pub code: String,
+    pub discount_type: DiscountType,
+    pub amount: i64,
+    pub max_uses: Option<i32>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Result of [`create_tier`] and [`update_tier`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TierSave {
+    Saved(TicketTierModel),
+    NotFound,
+    /// The event already has a tier of that name
+    DuplicateName,
+    /// Fewer tickets than are already sold or held
+    BelowReserved(i32),
+    /// Sales would end before they start
+    InvalidSaleWindow,
+}
+
+/// Result of [`delete_tier`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TierRemoval {
+    Removed,
+    NotFound,
+    /// Tickets of the tier have been ordered; change its quantity instead
+    HasOrders,
+}
+
+/// Result of [`create_promo_code`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromoCreation {
+    Created(PromoCodeModel),
+    /// The event already has the code
+    Duplicate,
+}
+
+/// Result of [`checkout`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Checkout {
+    /// Tickets held and payment started, or paid outright when free
+    Started(Box<TicketOrderModel>),
+    /// No such event, or it is private and the buyer is not invited
+    EventNotFound,
+    TierNotFound,
+    Closed(EventStatus),
+    NotOnSale,
+    /// Fewer tickets left than asked for
+    SoldOut {
+        available: i32,
+    },
+    /// Unknown, expired or used up
+    InvalidPromoCode,
+}
+
+/// Result of [`confirm_order`] and [`cancel_order`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderUpdate {
+    Updated(Box<TicketOrderModel>),
+    NotFound,
+    /// The order is already settled
+    Settled(OrderStatus),
+}
+
+/// Result of [`refund_order`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Refund {
+    Refunded(Box<TicketOrderModel>),
+    NotFound,
+    /// Only paid orders can be refunded
+    NotPaid(OrderStatus),
+}
+
+/// Result of [`handle_webhook`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookOutcome {
+    Processed,
+    /// Already processed; the provider sent it again
+    Duplicate,
+    /// No order has the payment; nothing to do
+    UnknownPayment,
+}
+
+/// Tickets of `tier` still to be had.
+pub fn available(tier: &TicketTierModel) -> i32 {
+    (tier.quantity - tier.reserved).max(0)
+}
+
+/// Whether `tier` is on sale at `now`.
+pub fn on_sale(tier: &TicketTierModel, now: DateTime<Utc>) -> bool {
+    tier.sales_start_at.is_none_or(|start| start <= now)
+        && tier.sales_end_at.is_none_or(|end| now < end)
+}
+
+/// Subtotal, discount and total for `quantity` tickets at `price`.
+/// Discounts never take the total below zero.
+pub fn price_order(price: i64, quantity: i32, promo: Option<&PromoCodeModel>) -> (i64, i64, i64) {
+    let subtotal = price.saturating_mul(i64::from(quantity));
+    let discount = match promo {
+        Some(promo) if promo.discount_type == DiscountType::Percentage => {
+            subtotal.saturating_mul(promo.amount.clamp(0, 100)) / 100
+        }
+        Some(promo) => promo.amount.max(0),
+        None => 0,
+    }
+    .min(subtotal);
+    (subtotal, discount, subtotal - discount)
+}
+
+/// The event's ticket tiers, cheapest first.
+pub async fn list_tiers(
+    db: &DatabaseConnection,
+    event_id: i32,
+) -> Result<Vec<TicketTierModel>, Box<dyn Error>> {
+    Ok(TicketTier::find()
+        .filter(TicketTierColumn::EventId.eq(event_id))
+        .order_by_asc(TicketTierColumn::Price)
+        .order_by_asc(TicketTierColumn::Id)
+        .all(db)
+        .await?)
+}
+
+/// Whether attending the event takes a ticket.
+pub async fn has_tiers<C: ConnectionTrait>(db: &C, event_id: i32) -> Result<bool, Box<dyn Error>> {
+    Ok(TicketTier::find()
+        .filter(TicketTierColumn::EventId.eq(event_id))
+        .count(db)
+        .await?
+        > 0)
+}
+
+pub async fn create_tier(
+    db: &DatabaseConnection,
+    event_id: i32,
+    tier: NewTicketTier,
+) -> Result<TierSave, Box<dyn Error>> {
+    if let (Some(start), Some(end)) = (tier.sales_start_at, tier.sales_end_at)
+        && end <= start
+    {
+        return Ok(TierSave::InvalidSaleWindow);
+    }
+    if name_taken(db, event_id, &tier.name, None).await? {
+        return Ok(TierSave::DuplicateName);
+    }
+
+    let now = Utc::now();
+    let tier = TicketTierActiveModel {
+        event_id: Set(event_id),
+        name: Set(tier.name),
+        description: Set(tier.description),
+        price: Set(tier.price),
+        currency: Set(tier.currency),
+        quantity: Set(tier.quantity),
+        reserved: Set(0),
+        sales_start_at: Set(tier.sales_start_at),
+        sales_end_at: Set(tier.sales_end_at),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(TierSave::Saved(tier))
+}
+
+pub async fn update_tier(
+    db: &DatabaseConnection,
+    event_id: i32,
+    tier_id: i32,
+    changes: TicketTierChanges,
+) -> Result<TierSave, Box<dyn Error>> {
+    let Some(tier) = find_tier(db, event_id, tier_id).await? else {
+        return Ok(TierSave::NotFound);
+    };
+    let start = changes.sales_start_at.or(tier.sales_start_at);
+    let end = changes.sales_end_at.or(tier.sales_end_at);
+    if let (Some(start), Some(end)) = (start, end)
+        && end <= start
+    {
+        return Ok(TierSave::InvalidSaleWindow);
+    }
+    if let Some(name) = &changes.name
+        && name_taken(db, event_id, name, Some(tier_id)).await?
+    {
+        return Ok(TierSave::DuplicateName);
+    }
+
+    if let Some(quantity) = changes.quantity {
+        // Conditional, so a ticket sold meanwhile is not oversold
+        let resized = TicketTier::update_many()
+            .col_expr(TicketTierColumn::Quantity, Expr::value(quantity))
+            .filter(TicketTierColumn::Id.eq(tier_id))
+            .filter(TicketTierColumn::Reserved.lte(quantity))
+            .exec(db)
+            .await?
+            .rows_affected
+            == 1;
+        if !resized {
+            let reserved = find_tier(db, event_id, tier_id)
+                .await?
+                .map_or(tier.reserved, |tier| tier.reserved);
+            return Ok(TierSave::BelowReserved(reserved));
+        }
+    }
+
+    let mut tier = find_tier(db, event_id, tier_id)
+        .await?
+        .ok_or("Ticket tier disappeared")?
+        .into_active_model();
+    if let Some(name) = changes.name {
+        tier.name = Set(name);
+    }
+    if let Some(description) = changes.description {
+        tier.description = Set(Some(description));
+    }
+    if let Some(price) = changes.price {
+        tier.price = Set(price);
+    }
+    if changes.sales_start_at.is_some() {
+        tier.sales_start_at = Set(start);
+    }
+    if changes.sales_end_at.is_some() {
+        tier.sales_end_at = Set(end);
+    }
+    tier.updated_at = Set(Utc::now());
+    Ok(TierSave::Saved(tier.update(db).await?))
+}
+
+pub async fn delete_tier(
+    db: &DatabaseConnection,
+    event_id: i32,
+    tier_id: i32,
+) -> Result<TierRemoval, Box<dyn Error>> {
+    let Some(tier) = find_tier(db, event_id, tier_id).await? else {
+        return Ok(TierRemoval::NotFound);
+    };
+    let orders = TicketOrder::find()
+        .filter(TicketOrderColumn::TierId.eq(tier.id))
+        .count(db)
+        .await?;
+    if orders > 0 {
+        return Ok(TierRemoval::HasOrders);
+    }
+    TicketTier::delete_by_id(tier.id).exec(db).await?;
+    Ok(TierRemoval::Removed)
+}
+
+/// The event's promo codes, newest first.
+pub async fn list_promo_codes(
+    db: &DatabaseConnection,
+    event_id: i32,
+) -> Result<Vec<PromoCodeModel>, Box<dyn Error>> {
+    Ok(PromoCode::find()
+        .filter(PromoCodeColumn::EventId.eq(event_id))
+        .order_by_desc(PromoCodeColumn::Id)
+        .all(db)
+        .await?)
+}
+
+pub async fn create_promo_code(
+    db: &DatabaseConnection,
+    event_id: i32,
+    promo: NewPromoCode,
+) -> Result<PromoCreation, Box<dyn Error>> {
+    let exists = PromoCode::find()
+        .filter(PromoCodeColumn::EventId.eq(event_id))
+        .filter(PromoCodeColumn::Code.eq(promo.code.as_str()))
+        .count(db)
+        .await?
+        > 0;
+    if exists {
+        return Ok(PromoCreation::Duplicate);
+    }
+
+    let promo = PromoCodeActiveModel {
+        event_id: Set(event_id),
+        code: Set(promo.code),
+        discount_type: Set(promo.discount_type),
+        amount: Set(promo.amount),
+        max_uses: Set(promo.max_uses),
+        uses: Set(0),
+        expires_at: Set(promo.expires_at),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(PromoCreation::Created(promo))
+}
+
+/// Delete a promo code. Orders that used it keep their discount.
+pub async fn delete_promo_code(
+    db: &DatabaseConnection,
+    event_id: i32,
+    promo_id: i32,
+) -> Result<bool, Box<dyn Error>> {
+    let result = PromoCode::delete_many()
+        .filter(PromoCodeColumn::Id.eq(promo_id))
+        .filter(PromoCodeColumn::EventId.eq(event_id))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+/// Hold `quantity` tickets of a tier for `user` and start paying for them.
+/// Free orders are paid straight away.
+pub async fn checkout(
+    db: &DatabaseConnection,
+    payments: &dyn PaymentProvider,
+    user: &UserModel,
+    event_id: i32,
+    tier_id: i32,
+    quantity: i32,
+    promo_code: Option<&str>,
+) -> Result<Checkout, Box<dyn Error>> {
+    let Some(event) = Event::find_by_id(event_id).one(db).await? else {
+        return Ok(Checkout::EventNotFound);
+    };
+    if !can_view(db, &event, Some(user)).await? {
+        return Ok(Checkout::EventNotFound);
+    }
+    if event.status.is_final() {
+        return Ok(Checkout::Closed(event.status));
+    }
+    let Some(tier) = find_tier(db, event_id, tier_id).await? else {
+        return Ok(Checkout::TierNotFound);
+    };
+    let now = Utc::now();
+    if !on_sale(&tier, now) {
+        return Ok(Checkout::NotOnSale);
+    }
+    release_expired_holds(db, tier.id).await?;
+
+    let promo = match promo_code {
+        Some(code) => {
+            let promo = PromoCode::find()
+                .filter(PromoCodeColumn::EventId.eq(event_id))
+                .filter(PromoCodeColumn::Code.eq(code))
+                .one(db)
+                .await?
+                .filter(|promo| promo.expires_at.is_none_or(|expires_at| now < expires_at));
+            match promo {
+                Some(promo) => Some(promo),
+                None => return Ok(Checkout::InvalidPromoCode),
+            }
+        }
+        None => None,
+    };
+    let (subtotal, discount, total) = price_order(tier.price, quantity, promo.as_ref());
+
+    let txn = db.begin().await?;
+    let held = TicketTier::update_many()
+        .col_expr(
+            TicketTierColumn::Reserved,
+            Expr::col(TicketTierColumn::Reserved).add(quantity),
+        )
+        .filter(TicketTierColumn::Id.eq(tier.id))
+        .filter(
+            Expr::col(TicketTierColumn::Reserved)
+                .add(quantity)
+                .lte(Expr::col(TicketTierColumn::Quantity)),
+        )
+        .exec(&txn)
+        .await?
+        .rows_affected
+        == 1;
+    if !held {
+        txn.rollback().await?;
+        let available = find_tier(db, event_id, tier_id)
+            .await?
+            .map_or(0, |tier| available(&tier));
+        return Ok(Checkout::SoldOut { available });
+    }
+    if let Some(promo) = &promo {
+        let used = PromoCode::update_many()
+            .col_expr(
+                PromoCodeColumn::Uses,
+                Expr::col(PromoCodeColumn::Uses).add(1),
+            )
+            .filter(PromoCodeColumn::Id.eq(promo.id))
+            .filter(
+                Condition::any()
+                    .add(PromoCodeColumn::MaxUses.is_null())
+                    .add(Expr::col(PromoCodeColumn::Uses).lt(Expr::col(PromoCodeColumn::MaxUses))),
+            )
+            .exec(&txn)
+            .await?
+            .rows_affected
+            == 1;
+        if !used {
+            txn.rollback().await?;
+            return Ok(Checkout::InvalidPromoCode);
+        }
+    }
+    let order = TicketOrderActiveModel {
+        reference: Set(format!("ord_{}", Uuid::new_v4().simple())),
+        event_id: Set(event_id),
+        tier_id: Set(tier.id),
+        user_id: Set(user.id),
+        quantity: Set(quantity),
+        promo_code_id: Set(promo.as_ref().map(|promo| promo.id)),
+        subtotal: Set(subtotal),
+        discount: Set(discount),
+        total: Set(total),
+        currency: Set(tier.currency.clone()),
+        status: Set(OrderStatus::Pending),
+        expires_at: Set(now + HOLD_DURATION),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    txn.commit().await?;
+
+    if total == 0 {
+        return Ok(Checkout::Started(Box::new(fulfil(db, order).await?)));
+    }
+
+    let request = PaymentRequest {
+        reference: order.reference.clone(),
+        amount: total,
+        currency: order.currency.clone(),
+        email: user.email.clone(),
+        description: format!("{} x {} for {}", quantity, tier.name, event.title),
+    };
+    let session = match payments.create_payment(&request).await {
+        Ok(session) => session,
+        Err(e) => {
+            // Nobody can pay for it, so give the tickets back
+            release(db, &order, OrderStatus::Cancelled).await?;
+            return Err(e);
+        }
+    };
+    let mut order = order.into_active_model();
+    order.payment_provider = Set(Some(payments.name().to_string()));
+    order.payment_reference = Set(Some(session.payment_reference));
+    order.checkout_url = Set(Some(session.checkout_url));
+    order.updated_at = Set(Utc::now());
+    Ok(Checkout::Started(Box::new(order.update(db).await?)))
+}
+
+/// Check with the payment provider whether `user` has paid for a pending
+/// order, and settle it if the payment went through or failed. Orders
+/// still unpaid when their hold runs out expire.
+pub async fn confirm_order(
+    db: &DatabaseConnection,
+    payments: &dyn PaymentProvider,
+    user: &UserModel,
+    order_id: i32,
+) -> Result<OrderUpdate, Box<dyn Error>> {
+    let Some(order) = get_order(db, user.id, order_id).await? else {
+        return Ok(OrderUpdate::NotFound);
+    };
+    match order.status {
+        OrderStatus::Pending => {}
+        OrderStatus::Paid => return Ok(OrderUpdate::Updated(Box::new(order))),
+        status => return Ok(OrderUpdate::Settled(status)),
+    }
+
+    let status = match &order.payment_reference {
+        Some(payment_reference) => payments.payment_status(payment_reference).await?,
+        None => PaymentStatus::Pending,
+    };
+    // A payment made in time still counts while its hold has not been
+    // released, as the tickets are still there
+    let order = match status {
+        PaymentStatus::Succeeded => {
+            let payment_reference = order.payment_reference.clone().unwrap_or_default();
+            let (amount, currency) = (order.total, order.currency.clone());
+            payment_received(db, payments, order, &payment_reference, amount, &currency).await?
+        }
+        PaymentStatus::Failed => {
+            release(db, &order, OrderStatus::Failed).await?;
+            reload(db, order).await?
+        }
+        PaymentStatus::Pending if order.expires_at <= Utc::now() => {
+            release(db, &order, OrderStatus::Expired).await?;
+            reload(db, order).await?
+        }
+        PaymentStatus::Pending => order,
+    };
+    Ok(OrderUpdate::Updated(Box::new(order)))
+}
+
+/// Give up a pending order and its held tickets.
+pub async fn cancel_order(
+    db: &DatabaseConnection,
+    user: &UserModel,
+    order_id: i32,
+) -> Result<OrderUpdate, Box<dyn Error>> {
+    let Some(order) = get_order(db, user.id, order_id).await? else {
+        return Ok(OrderUpdate::NotFound);
+    };
+    if order.status != OrderStatus::Pending {
+        return Ok(OrderUpdate::Settled(order.status));
+    }
+    release(db, &order, OrderStatus::Cancelled).await?;
+    let order = reload(db, order).await?;
+    match order.status {
+        OrderStatus::Cancelled => Ok(OrderUpdate::Updated(Box::new(order))),
+        status => Ok(OrderUpdate::Settled(status)),
+    }
+}
+
+/// Expire an order whose hold has run out. Safe to call any number of
+/// times; returns whether the order was expired by this call.
+pub async fn expire_order(db: &DatabaseConnection, order_id: i32) -> Result<bool, Box<dyn Error>> {
+    let Some(order) = TicketOrder::find_by_id(order_id).one(db).await? else {
+        return Ok(false);
+    };
+    if order.status != OrderStatus::Pending || order.expires_at > Utc::now() {
+        return Ok(false);
+    }
+    release(db, &order, OrderStatus::Expired).await
+}
+
+/// Give a paid order's money back to the buyer. While the event is still
+/// on, the buyer's registration goes and the tickets can be sold again.
+pub async fn refund_order(
+    db: &DatabaseConnection,
+    payments: &dyn PaymentProvider,
+    event_id: i32,
+    order_id: i32,
+) -> Result<Refund, Box<dyn Error>> {
+    let order = TicketOrder::find_by_id(order_id)
+        .filter(TicketOrderColumn::EventId.eq(event_id))
+        .one(db)
+        .await?;
+    match order {
+        Some(order) => refund_paid_order(db, payments, order).await,
+        None => Ok(Refund::NotFound),
+    }
+}
+
+/// Refund every paid order of a cancelled event and drop its holds.
+/// Safe to call any number of times; returns the orders refunded by this
+/// call. Keeps going past a failed refund and reports the first error at
+/// the end, so a retry only has the failures left to do.
+pub async fn refund_event_orders(
+    db: &DatabaseConnection,
+    payments: &dyn PaymentProvider,
+    event_id: i32,
+) -> Result<usize, Box<dyn Error>> {
+    let orders = TicketOrder::find()
+        .filter(TicketOrderColumn::EventId.eq(event_id))
+        .filter(TicketOrderColumn::Status.is_in([OrderStatus::Pending, OrderStatus::Paid]))
+        .all(db)
+        .await?;
+    let mut refunded = 0;
+    let mut first_error = None;
+    for order in orders {
+        if order.status == OrderStatus::Pending {
+            release(db, &order, OrderStatus::Cancelled).await?;
+            continue;
+        }
+        let order_id = order.id;
+        match refund_paid_order(db, payments, order).await {
+            Ok(Refund::Refunded(_)) => refunded += 1,
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to refund order {}: {}", order_id, e);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(refunded),
+    }
+}
+
+/// Act on a verified webhook. Redeliveries are recognised by the event id
+/// and skipped.
+pub async fn handle_webhook(
+    db: &DatabaseConnection,
+    payments: &dyn PaymentProvider,
+    event: &WebhookEvent,
+) -> Result<WebhookOutcome, Box<dyn Error>> {
+    let provider = payments.name();
+    let seen = PaymentWebhookEvent::find()
+        .filter(PaymentWebhookEventColumn::Provider.eq(provider))
+        .filter(PaymentWebhookEventColumn::EventId.eq(event.id.as_str()))
+        .count(db)
+        .await?
+        > 0;
+    if seen {
+        return Ok(WebhookOutcome::Duplicate);
+    }
+
+    let order = webhook_order(db, provider, event).await?;
+    let outcome = match order {
+        None => WebhookOutcome::UnknownPayment,
+        Some(order) => {
+            match event.kind {
+                WebhookKind::PaymentSucceeded => {
+                    payment_received(
+                        db,
+                        payments,
+                        order,
+                        &event.payment_reference,
+                        event.amount,
+                        &event.currency,
+                    )
+                    .await?;
+                }
+                WebhookKind::PaymentFailed => {
+                    release(db, &order, OrderStatus::Failed).await?;
+                }
+                // An amount in another currency says nothing about how much
+                // of the order was given back
+                WebhookKind::Refunded if !event.currency.eq_ignore_ascii_case(&order.currency) => {
+                    error!(
+                        "Refund of {} {} does not match order {} ({} {})",
+                        event.amount, event.currency, order.reference, order.total, order.currency
+                    );
+                }
+                // A stray payment given back here whose settling was cut short
+                WebhookKind::Refunded
+                    if has_pending_stray_refund(db, &order, &event.payment_reference).await? =>
+                {
+                    let reference = event.refund_reference.as_deref().unwrap_or(&event.id);
+                    settle_stray_refund(db, &order, &event.payment_reference, reference).await?;
+                }
+                // Another payment for the order, recorded when it was given
+                // back; the order's own payment is untouched
+                WebhookKind::Refunded
+                    if order.payment_reference.as_deref()
+                        != Some(event.payment_reference.as_str()) => {}
+                // Refunds made here were settled when they were made; these
+                // catch those made from the provider's dashboard
+                // A refund made here whose settling was cut short is still
+                // pending, and is completed with the reference reported here
+                WebhookKind::Refunded
+                    if event.amount >= order.total - refunded_amount(db, &order).await? =>
+                {
+                    let reference = event.refund_reference.as_deref().unwrap_or(&event.id);
+                    if claim_refund(db, &order, event.amount).await?
+                        || has_pending_refund(db, &order).await?
+                    {
+                        settle_refund(db, &order, Some(reference)).await?;
+                    }
+                }
+                WebhookKind::Refunded => {
+                    // Part of the money back: the buyer keeps the tickets
+                    if order.status == OrderStatus::Paid {
+                        let reference = event.refund_reference.as_deref().unwrap_or(&event.id);
+                        LedgerEntry::insert(ledger_entry(
+                            &order,
+                            LedgerEntryKind::Refund,
+                            -event.amount,
+                            reference,
+                            format!("Partial refund for order {}", order.reference),
+                        ))
+                        .on_conflict(
+                            OnConflict::columns([
+                                LedgerEntryColumn::Provider,
+                                LedgerEntryColumn::Kind,
+                                LedgerEntryColumn::ProviderReference,
+                            ])
+                            .do_nothing()
+                            .to_owned(),
+                        )
+                        .exec_without_returning(db)
+                        .await?;
+                    }
+                }
+            }
+            WebhookOutcome::Processed
+        }
+    };
+
+    // Only recorded once handled, so a failure above gets retried; the
+    // steps are idempotent, so a concurrent redelivery does no harm
+    PaymentWebhookEvent::insert(PaymentWebhookEventActiveModel {
+        provider: Set(provider.to_string()),
+        event_id: Set(event.id.clone()),
+        event_type: Set(event.event_type.clone()),
+        processed_at: Set(Utc::now()),
+        ..Default::default()
+    })
+    .on_conflict(
+        OnConflict::columns([
+            PaymentWebhookEventColumn::Provider,
+            PaymentWebhookEventColumn::EventId,
+        ])
+        .do_nothing()
+        .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+    Ok(outcome)
+}
+
+/// The event's orders, newest first.
+pub async fn list_event_orders(
+    db: &DatabaseConnection,
+    event_id: i32,
+) -> Result<Vec<TicketOrderModel>, Box<dyn Error>> {
+    Ok(TicketOrder::find()
+        .filter(TicketOrderColumn::EventId.eq(event_id))
+        .order_by_desc(TicketOrderColumn::Id)
+        .all(db)
+        .await?)
+}
+
+/// The money taken and given back for the event, oldest first.
+pub async fn list_ledger(
+    db: &DatabaseConnection,
+    event_id: i32,
+) -> Result<Vec<LedgerEntryModel>, Box<dyn Error>> {
+    Ok(LedgerEntry::find()
+        .filter(LedgerEntryColumn::EventId.eq(event_id))
+        .order_by_asc(LedgerEntryColumn::Id)
+        .all(db)
+        .await?)
+}
+
+/// The user's orders, newest first.
+pub async fn list_orders(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<TicketOrderModel>, Box<dyn Error>> {
+    Ok(TicketOrder::find()
+        .filter(TicketOrderColumn::UserId.eq(user_id))
+        .order_by_desc(TicketOrderColumn::Id)
+        .all(db)
+        .await?)
+}
+
+/// One of the user's orders.
+pub async fn get_order(
+    db: &DatabaseConnection,
+    user_id: i32,
+    order_id: i32,
+) -> Result<Option<TicketOrderModel>, Box<dyn Error>> {
+    Ok(TicketOrder::find_by_id(order_id)
+        .filter(TicketOrderColumn::UserId.eq(user_id))
+        .one(db)
+        .await?)
+}
+
+async fn find_tier<C: ConnectionTrait>(
+    db: &C,
+    event_id: i32,
+    tier_id: i32,
+) -> Result<Option<TicketTierModel>, Box<dyn Error>> {
+    Ok(TicketTier::find_by_id(tier_id)
+        .filter(TicketTierColumn::EventId.eq(event_id))
+        .one(db)
+        .await?)
+}
+
+async fn name_taken(
+    db: &DatabaseConnection,
+    event_id: i32,
+    name: &str,
+    except: Option<i32>,
+) -> Result<bool, Box<dyn Error>> {
+    let mut query = TicketTier::find()
+        .filter(TicketTierColumn::EventId.eq(event_id))
+        .filter(TicketTierColumn::Name.eq(name));
+    if let Some(tier_id) = except {
+        query = query.filter(TicketTierColumn::Id.ne(tier_id));
+    }
+    Ok(query.count(db).await? > 0)
+}
+
+async fn reload(
+    db: &DatabaseConnection,
+    order: TicketOrderModel,
+) -> Result<TicketOrderModel, Box<dyn Error>> {
+    Ok(TicketOrder::find_by_id(order.id)
+        .one(db)
+        .await?
+        .unwrap_or(order))
+}
+
+/// Expire the tier's pending orders whose hold has run out, so their
+/// tickets can be sold again without waiting for the expiry jobs.
+async fn release_expired_holds(
+    db: &DatabaseConnection,
+    tier_id: i32,
+) -> Result<(), Box<dyn Error>> {
+    let expired = TicketOrder::find()
+        .filter(TicketOrderColumn::TierId.eq(tier_id))
+        .filter(TicketOrderColumn::Status.eq(OrderStatus::Pending))
+        .filter(TicketOrderColumn::ExpiresAt.lte(Utc::now()))
+        .all(db)
+        .await?;
+    for order in expired {
+        release(db, &order, OrderStatus::Expired).await?;
+    }
+    Ok(())
+}
+
+/// Move a pending order to `status` and give back its tickets and promo
+/// code use. Returns false when the order was no longer pending.
+async fn release(
+    db: &DatabaseConnection,
+    order: &TicketOrderModel,
+    status: OrderStatus,
+) -> Result<bool, Box<dyn Error>> {
+    let txn = db.begin().await?;
+    let released = TicketOrder::update_many()
+        .col_expr(TicketOrderColumn::Status, Expr::value(status))
+        .col_expr(TicketOrderColumn::UpdatedAt, Expr::value(Utc::now()))
+        .filter(TicketOrderColumn::Id.eq(order.id))
+        .filter(TicketOrderColumn::Status.eq(OrderStatus::Pending))
+        .exec(&txn)
+        .await?
+        .rows_affected
+        == 1;
+    if !released {
+        txn.rollback().await?;
+        return Ok(false);
+    }
+    TicketTier::update_many()
+        .col_expr(
+            TicketTierColumn::Reserved,
+            Expr::col(TicketTierColumn::Reserved).sub(order.quantity),
+        )
+        .filter(TicketTierColumn::Id.eq(order.tier_id))
+        .exec(&txn)
+        .await?;
+    if let Some(promo_id) = order.promo_code_id {
+        PromoCode::update_many()
+            .col_expr(
+                PromoCodeColumn::Uses,
+                Expr::col(PromoCodeColumn::Uses).sub(1),
+            )
+            .filter(PromoCodeColumn::Id.eq(promo_id))
+            .exec(&txn)
+            .await?;
+    }
+    txn.commit().await?;
+    Ok(true)
+}
+
+/// Mark a pending order paid and register the buyer for the event.
+async fn fulfil(
+    db: &DatabaseConnection,
+    order: TicketOrderModel,
+) -> Result<TicketOrderModel, Box<dyn Error>> {
+    let txn = db.begin().await?;
+    let now = Utc::now();
+    let paid = TicketOrder::update_many()
+        .col_expr(TicketOrderColumn::Status, Expr::value(OrderStatus::Paid))
+        .col_expr(TicketOrderColumn::PaidAt, Expr::value(now))
+        .col_expr(TicketOrderColumn::UpdatedAt, Expr::value(now))
+        .filter(TicketOrderColumn::Id.eq(order.id))
+        .filter(TicketOrderColumn::Status.eq(OrderStatus::Pending))
+        .exec(&txn)
+        .await?
+        .rows_affected
+        == 1;
+    if !paid {
+        txn.rollback().await?;
+        return reload(db, order).await;
+    }
+
+    let event = Event::find_by_id(order.event_id)
+        .one(&txn)
+        .await?
+        .ok_or("Event of order disappeared")?;
+    ensure_attendee(&txn, order.user_id, event.event_type).await?;
+    let existing = Attendance::find()
+        .filter(AttendanceColumn::EventId.eq(order.event_id))
+        .filter(AttendanceColumn::AttendeeId.eq(order.user_id))
+        .one(&txn)
+        .await?;
+    let attendance = match existing {
+        Some(attendance)
+            if matches!(
+                attendance.status,
+                AttendanceStatus::Registered | AttendanceStatus::CheckedIn
+            ) =>
+        {
+            attendance
+        }
+        Some(attendance) => {
+            let mut attendance = attendance.into_active_model();
+            attendance.status = Set(AttendanceStatus::Registered);
+            attendance.updated_at = Set(now);
+            attendance.update(&txn).await?
+        }
+        None => {
+            AttendanceActiveModel {
+                event_id: Set(order.event_id),
+                attendee_id: Set(order.user_id),
+                status: Set(AttendanceStatus::Registered),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?
+        }
+    };
+    TicketOrder::update_many()
+        .col_expr(TicketOrderColumn::AttendanceId, Expr::value(attendance.id))
+        .filter(TicketOrderColumn::Id.eq(order.id))
+        .exec(&txn)
+        .await?;
+    if let Some(payment_reference) = &order.payment_reference
+        && order.total > 0
+    {
+        ledger_entry(
+            &order,
+            LedgerEntryKind::Charge,
+            order.total,
+            payment_reference,
+            format!("Payment for order {}", order.reference),
+        )
+        .insert(&txn)
+        .await?;
+    }
+    txn.commit().await?;
+
+    accept_invitation(db, order.event_id, order.user_id).await?;
+    reload(db, order).await
+}
+
+/// Act on a payment that went through: pay the order, or give the money
+/// back when the order can no longer take it (released, the event is over,
+/// the amount is wrong or it is not the order's own payment, such as a
+/// second one from a double submit).
+async fn payment_received(
+    db: &DatabaseConnection,
+    payments: &dyn PaymentProvider,
+    order: TicketOrderModel,
+    payment_reference: &str,
+    amount: i64,
+    currency: &str,
+) -> Result<TicketOrderModel, Box<dyn Error>> {
+    if order.payment_reference.as_deref() != Some(payment_reference) {
+        error!(
+            "Payment {} is not the one started for order {}",
+            payment_reference, order.reference
+        );
+        refund_stray_payment(db, payments, &order, payment_reference, amount, currency).await?;
+        return reload(db, order).await;
+    }
+    if matches!(order.status, OrderStatus::Paid | OrderStatus::Refunded) {
+        return Ok(order);
+    }
+    let event = Event::find_by_id(order.event_id)
+        .one(db)
+        .await?
+        .ok_or("Event of order disappeared")?;
+    let matches = amount == order.total && currency.eq_ignore_ascii_case(&order.currency);
+    if order.status == OrderStatus::Pending {
+        if matches && !event.status.is_final() {
+            return fulfil(db, order).await;
+        }
+        let status = if matches {
+            OrderStatus::Cancelled
+        } else {
+            error!(
+                "Payment of {} {} does not match order {} ({} {})",
+                amount, currency, order.reference, order.total, order.currency
+            );
+            OrderStatus::Failed
+        };
+        release(db, &order, status).await?;
+    }
+    refund_stray_payment(db, payments, &order, payment_reference, amount, currency).await?;
+    reload(db, order).await
+}
+
+/// Give back a payment for an order that cannot take it, recording both
+/// the charge and the refund. Like [`refund_paid_order`], the refund is
+/// claimed before the provider is called, so a webhook redelivered while
+/// it runs, or after it was cut short, never gives the money back twice.
+async fn refund_stray_payment(
+    db: &DatabaseConnection,
+    payments: &dyn PaymentProvider,
+    order: &TicketOrderModel,
+    payment_reference: &str,
+    amount: i64,
+    currency: &str,
+) -> Result<(), Box<dyn Error>> {
+    if amount <= 0 || !claim_stray_refund(db, order, payment_reference, amount, currency).await? {
+        return Ok(());
+    }
+
+    match payments.refund(payment_reference, amount).await {
+        Ok(refund_reference) => {
+            settle_stray_refund(db, order, payment_reference, &refund_reference).await
+        }
+        Err(e) => {
+            unclaim_stray_refund(db, order, payment_reference).await?;
+            Err(e)
+        }
+    }
+}
+
+/// Record a stray payment and its refund, as pending, in one
+/// transaction. Returns false when the payment was already recorded.
+async fn claim_stray_refund(
+    db: &DatabaseConnection,
+    order: &TicketOrderModel,
+    payment_reference: &str,
+    amount: i64,
+    currency: &str,
+) -> Result<bool, Box<dyn Error>> {
+    let txn = db.begin().await?;
+    let mut charge = ledger_entry(
+        order,
+        LedgerEntryKind::Charge,
+        amount,
+        payment_reference,
+        format!("Payment not taken by order {}", order.reference),
+    );
+    charge.currency = Set(currency.to_uppercase());
+    let claimed = LedgerEntry::insert(charge)
+        .on_conflict(
+            OnConflict::columns([
+                LedgerEntryColumn::Provider,
+                LedgerEntryColumn::Kind,
+                LedgerEntryColumn::ProviderReference,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(&txn)
+        .await?
+        == 1;
+    if !claimed {
+        txn.rollback().await?;
+        return Ok(false);
+    }
+    let mut refund = ledger_entry(
+        order,
+        LedgerEntryKind::Refund,
+        -amount,
+        &stray_refund_reference(payment_reference),
+        format!("Refund of payment not taken by order {}", order.reference),
+    );
+    refund.currency = Set(currency.to_uppercase());
+    refund.pending = Set(true);
+    refund.insert(&txn).await?;
+    txn.commit().await?;
+    Ok(true)
+}
+
+/// Undo [`claim_stray_refund`] after the provider turned the refund down,
+/// so the payment's next webhook tries again.
+async fn unclaim_stray_refund(
+    db: &DatabaseConnection,
+    order: &TicketOrderModel,
+    payment_reference: &str,
+) -> Result<(), Box<dyn Error>> {
+    LedgerEntry::delete_many()
+        .filter(LedgerEntryColumn::Provider.eq(provider_of(order)))
+        .filter(
+            Condition::any()
+                .add(
+                    LedgerEntryColumn::Kind
+                        .eq(LedgerEntryKind::Charge)
+                        .and(LedgerEntryColumn::ProviderReference.eq(payment_reference)),
+                )
+                .add(
+                    LedgerEntryColumn::ProviderReference
+                        .eq(stray_refund_reference(payment_reference)),
+                ),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Complete a refund claimed by [`claim_stray_refund`] with the provider's
+/// reference. Does nothing when it was already settled.
+async fn settle_stray_refund(
+    db: &DatabaseConnection,
+    order: &TicketOrderModel,
+    payment_reference: &str,
+    refund_reference: &str,
+) -> Result<(), Box<dyn Error>> {
+    LedgerEntry::update_many()
+        .col_expr(
+            LedgerEntryColumn::ProviderReference,
+            Expr::value(refund_reference),
+        )
+        .col_expr(LedgerEntryColumn::Pending, Expr::value(false))
+        .filter(LedgerEntryColumn::Provider.eq(provider_of(order)))
+        .filter(LedgerEntryColumn::ProviderReference.eq(stray_refund_reference(payment_reference)))
+        .filter(LedgerEntryColumn::Pending.eq(true))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Whether the stray payment `payment_reference` has a refund claimed but
+/// not yet settled.
+async fn has_pending_stray_refund(
+    db: &DatabaseConnection,
+    order: &TicketOrderModel,
+    payment_reference: &str,
+) -> Result<bool, Box<dyn Error>> {
+    Ok(LedgerEntry::find()
+        .filter(LedgerEntryColumn::Provider.eq(provider_of(order)))
+        .filter(LedgerEntryColumn::ProviderReference.eq(stray_refund_reference(payment_reference)))
+        .filter(LedgerEntryColumn::Pending.eq(true))
+        .count(db)
+        .await?
+        > 0)
+}
+
+/// Refund a paid order through the provider, unless it was free.
+async fn refund_paid_order(
+    db: &DatabaseConnection,
+    payments: &dyn PaymentProvider,
+    order: TicketOrderModel,
+) -> Result<Refund, Box<dyn Error>> {
+    if order.status != OrderStatus::Paid {
+        return Ok(Refund::NotPaid(order.status));
+    }
+    // Whatever a partial refund from the dashboard left
+    let amount = order.total - refunded_amount(db, &order).await?;
+    // Claimed first, so two refunds of one order never both reach the
+    // provider
+    if !claim_refund(db, &order, amount).await? {
+        let order = reload(db, order).await?;
+        return Ok(Refund::NotPaid(order.status));
+    }
+
+    let refund_reference = match &order.payment_reference {
+        Some(payment_reference) if amount > 0 => {
+            match payments.refund(payment_reference, amount).await {
+                Ok(refund_reference) => Some(refund_reference),
+                Err(e) => {
+                    unclaim_refund(db, &order).await?;
+                    return Err(e);
+                }
+            }
+        }
+        // Free tickets, or already given back in full: nothing to return
+        _ => None,
+    };
+    settle_refund(db, &order, refund_reference.as_deref()).await?;
+    Ok(Refund::Refunded(Box::new(reload(db, order).await?)))
+}
+
+/// Move a paid order to refunded and, when money is to go back, record the
+/// refund of `amount` as pending in the same transaction. Returns false
+/// when the order was not paid.
+async fn claim_refund(
+    db: &DatabaseConnection,
+    order: &TicketOrderModel,
+    amount: i64,
+) -> Result<bool, Box<dyn Error>> {
+    let txn = db.begin().await?;
+    let now = Utc::now();
+    let claimed = TicketOrder::update_many()
+        .col_expr(
+            TicketOrderColumn::Status,
+            Expr::value(OrderStatus::Refunded),
+        )
+        .col_expr(TicketOrderColumn::RefundedAt, Expr::value(now))
+        .col_expr(TicketOrderColumn::UpdatedAt, Expr::value(now))
+        .filter(TicketOrderColumn::Id.eq(order.id))
+        .filter(TicketOrderColumn::Status.eq(OrderStatus::Paid))
+        .exec(&txn)
+        .await?
+        .rows_affected
+        == 1;
+    if !claimed {
+        txn.rollback().await?;
+        return Ok(false);
+    }
+    if order.payment_reference.is_some() && amount > 0 {
+        let mut entry = ledger_entry(
+            order,
+            LedgerEntryKind::Refund,
+            -amount,
+            &pending_refund_reference(order),
+            format!("Refund for order {}", order.reference),
+        );
+        entry.pending = Set(true);
+        entry.insert(&txn).await?;
+    }
+    txn.commit().await?;
+    Ok(true)
+}
+
+/// Undo [`claim_refund`] after the provider turned the refund down.
+async fn unclaim_refund(
+    db: &DatabaseConnection,
+    order: &TicketOrderModel,
+) -> Result<(), Box<dyn Error>> {
+    let txn = db.begin().await?;
+    LedgerEntry::delete_many()
+        .filter(LedgerEntryColumn::OrderId.eq(order.id))
+        .filter(LedgerEntryColumn::ProviderReference.eq(pending_refund_reference(order)))
+        .filter(LedgerEntryColumn::Pending.eq(true))
+        .exec(&txn)
+        .await?;
+    TicketOrder::update_many()
+        .col_expr(TicketOrderColumn::Status, Expr::value(OrderStatus::Paid))
+        .col_expr(
+            TicketOrderColumn::RefundedAt,
+            Expr::value(Option::<DateTime<Utc>>::None),
+        )
+        .filter(TicketOrderColumn::Id.eq(order.id))
+        .filter(TicketOrderColumn::Status.eq(OrderStatus::Refunded))
+        .exec(&txn)
+        .await?;
+    txn.commit().await?;
+    Ok(())
+}
+
+/// Complete a refund claimed by [`claim_refund`], filling in the
+/// provider's reference on its pending ledger entry. While the event is
+/// still on, the buyer's registration goes and the tickets go back on
+/// sale; a cancelled event keeps both, so attendees still hear about it.
+/// Does nothing when the refund was already settled.
+async fn settle_refund(
+    db: &DatabaseConnection,
+    order: &TicketOrderModel,
+    refund_reference: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let txn = db.begin().await?;
+    if let Some(refund_reference) = refund_reference {
+        let settled = LedgerEntry::update_many()
+            .col_expr(
+                LedgerEntryColumn::ProviderReference,
+                Expr::value(refund_reference),
+            )
+            .col_expr(LedgerEntryColumn::Pending, Expr::value(false))
+            .filter(LedgerEntryColumn::OrderId.eq(order.id))
+            .filter(LedgerEntryColumn::ProviderReference.eq(pending_refund_reference(order)))
+            .filter(LedgerEntryColumn::Pending.eq(true))
+            .exec(&txn)
+            .await?
+            .rows_affected
+            == 1;
+        if !settled {
+            txn.rollback().await?;
+            return Ok(());
+        }
+    }
+    let event_on = Event::find_by_id(order.event_id)
+        .one(&txn)
+        .await?
+        .is_some_and(|event| !event.status.is_final());
+    if event_on {
+        if let Some(attendance_id) = order.attendance_id {
+            TicketOrder::update_many()
+                .col_expr(
+                    TicketOrderColumn::AttendanceId,
+                    Expr::value(Option::<i32>::None),
+                )
+                .filter(TicketOrderColumn::Id.eq(order.id))
+                .exec(&txn)
+                .await?;
+            Attendance::delete_by_id(attendance_id).exec(&txn).await?;
+        }
+        TicketTier::update_many()
+            .col_expr(
+                TicketTierColumn::Reserved,
+                Expr::col(TicketTierColumn::Reserved).sub(order.quantity),
+            )
+            .filter(TicketTierColumn::Id.eq(order.tier_id))
+            .exec(&txn)
+            .await?;
+    }
+    txn.commit().await?;
+    Ok(())
+}
+
+/// Whether the order has a refund claimed but not yet settled.
+async fn has_pending_refund(
+    db: &DatabaseConnection,
+    order: &TicketOrderModel,
+) -> Result<bool, Box<dyn Error>> {
+    Ok(LedgerEntry::find()
+        .filter(LedgerEntryColumn::OrderId.eq(order.id))
+        .filter(LedgerEntryColumn::ProviderReference.eq(pending_refund_reference(order)))
+        .filter(LedgerEntryColumn::Pending.eq(true))
+        .count(db)
+        .await?
+        > 0)
+}
+
+/// Stands in for the provider's refund id until the refund is settled.
+fn pending_refund_reference(order: &TicketOrderModel) -> String {
+    format!("pending:{}", order.reference)
+}
+
+/// Like [`pending_refund_reference`] for the refund of a stray payment.
+fn stray_refund_reference(payment_reference: &str) -> String {
+    format!("pending-stray:{}", payment_reference)
+}
+
+/// How much of the order's payment has been given back so far. Other
+/// payments for the order are given back in full, so their charges and
+/// refunds cancel out.
+async fn refunded_amount(
+    db: &DatabaseConnection,
+    order: &TicketOrderModel,
+) -> Result<i64, Box<dyn Error>> {
+    let payment_reference = order.payment_reference.as_deref();
+    Ok(LedgerEntry::find()
+        .filter(LedgerEntryColumn::OrderId.eq(order.id))
+        .all(db)
+        .await?
+        .iter()
+        .map(|entry| match entry.kind {
+            LedgerEntryKind::Refund => -entry.amount,
+            LedgerEntryKind::Charge
+                if Some(entry.provider_reference.as_str()) != payment_reference =>
+            {
+                -entry.amount
+            }
+            _ => 0,
+        })
+        .sum())
+}
+
+/// The order a webhook is about: the one whose payment it reports, else
+/// the one a payment already given back was recorded against, else the
+/// one the provider says the payment was for.
+async fn webhook_order(
+    db: &DatabaseConnection,
+    provider: &str,
+    event: &WebhookEvent,
+) -> Result<Option<TicketOrderModel>, Box<dyn Error>> {
+    let order = TicketOrder::find()
+        .filter(TicketOrderColumn::PaymentProvider.eq(provider))
+        .filter(TicketOrderColumn::PaymentReference.eq(event.payment_reference.as_str()))
+        .one(db)
+        .await?;
+    if order.is_some() {
+        return Ok(order);
+    }
+    let charge = LedgerEntry::find()
+        .filter(LedgerEntryColumn::Provider.eq(provider))
+        .filter(LedgerEntryColumn::Kind.eq(LedgerEntryKind::Charge))
+        .filter(LedgerEntryColumn::ProviderReference.eq(event.payment_reference.as_str()))
+        .one(db)
+        .await?;
+    if let Some(order_id) = charge.and_then(|charge| charge.order_id) {
+        return Ok(TicketOrder::find_by_id(order_id).one(db).await?);
+    }
+    let Some(order_reference) = &event.order_reference else {
+        return Ok(None);
+    };
+    Ok(TicketOrder::find()
+        .filter(TicketOrderColumn::PaymentProvider.eq(provider))
+        .filter(TicketOrderColumn::Reference.eq(order_reference.as_str()))
+        .one(db)
+        .await?)
+}
+
+/// The provider that took the order's payment.
+fn provider_of(order: &TicketOrderModel) -> String {
+    order.payment_provider.clone().unwrap_or_default()
+}
+
+fn ledger_entry(
+    order: &TicketOrderModel,
+    kind: LedgerEntryKind,
+    amount: i64,
+    provider_reference: &str,
+    description: String,
+) -> LedgerEntryActiveModel {
+    LedgerEntryActiveModel {
+        order_id: Set(Some(order.id)),
+        event_id: Set(Some(order.event_id)),
+        kind: Set(kind),
+        amount: Set(amount),
+        currency: Set(order.currency.clone()),
+        provider: Set(provider_of(order)),
+        provider_reference: Set(provider_reference.to_string()),
+        description: Set(description),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+}
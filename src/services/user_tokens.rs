@@ -0,0 +1,74 @@
+use std::error::Error;
+
+use chrono::{Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+};
+
+use crate::entity::prelude::*;
+use crate::entity::user_token::TokenPurpose;
+use crate::utils::utils::{generate_token, hash_api_key};
+
+/// How long an emailed token stays valid.
+pub fn token_lifetime(purpose: TokenPurpose) -> Duration {
+    match purpose {
+        TokenPurpose::VerifyEmail => Duration::days(2),
+        TokenPurpose::PasswordReset => Duration::hours(1),
+    }
+}
+
+/// Create a one-time token for `user_id` and return its plaintext. Only the
+/// hash is stored.
+pub async fn issue_token(
+    db: &DatabaseConnection,
+    user_id: i32,
+    purpose: TokenPurpose,
+) -> Result<String, Box<dyn Error>> {
+    let token = generate_token();
+    let now = Utc::now();
+    UserTokenActiveModel {
+        user_id: Set(user_id),
+        purpose: Set(purpose.as_str().to_string()),
+        token_hash: Set(hash_api_key(&token)),
+        expires_at: Set(now + token_lifetime(purpose)),
+        used_at: Set(None),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(token)
+}
+
+/// Mark a token as used and return its owner. Unknown, expired, already
+/// used and wrong-purpose tokens are rejected.
+pub async fn consume_token(
+    db: &DatabaseConnection,
+    token: &str,
+    purpose: TokenPurpose,
+) -> Result<i32, Box<dyn Error>> {
+    let now = Utc::now();
+    let row = UserToken::find()
+        .filter(UserTokenColumn::TokenHash.eq(hash_api_key(token)))
+        .filter(UserTokenColumn::Purpose.eq(purpose.as_str()))
+        .filter(UserTokenColumn::UsedAt.is_null())
+        .filter(UserTokenColumn::ExpiresAt.gt(now))
+        .one(db)
+        .await?
+        .ok_or("Invalid or expired token")?;
+
+    // Guarded on `used_at` so two concurrent requests cannot both use it
+    let res = UserToken::update_many()
+        .col_expr(UserTokenColumn::UsedAt, Expr::value(Some(now)))
+        .filter(UserTokenColumn::Id.eq(row.id))
+        .filter(UserTokenColumn::UsedAt.is_null())
+        .exec(db)
+        .await?;
+    if res.rows_affected == 0 {
+        return Err("Invalid or expired token".into());
+    }
+
+    Ok(row.user_id)
+}
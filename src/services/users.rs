@@ -1,14 +1,23 @@
 use std::error::Error;
 
+use crate::core::cache::{Cache, USERS};
+use crate::entity::AccountType;
 use crate::entity::prelude::*;
 use crate::schemas::user::{SignShow, SignUp};
+use crate::services::sessions::revoke_all_sessions;
 use crate::utils::utils::{hash_password, verify_password};
+use chrono::{DateTime, Utc};
 use sea_orm::ExprTrait;
-use sea_orm::{ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
 
 pub async fn create_user(
     db: &DatabaseConnection,
     signup: SignUp,
+    hash_rounds: u32,
 ) -> Result<SignShow, Box<dyn Error>> {
     let new_user = UserActiveModel {
         username: Set(signup.username.clone()),
@@ -17,7 +26,7 @@ pub async fn create_user(
         email: Set(signup.email.clone()),
         avatar_url: Set(signup.avatar_url.clone()),
         // Password should be hashed before storing
-        password: Set(hash_password(&signup.password)),
+        password: Set(hash_password(&signup.password, hash_rounds)),
         ..Default::default()
     };
 
@@ -38,21 +47,17 @@ pub async fn authenticate_user(
     password: &str,
 ) -> Result<SignShow, Box<dyn Error>> {
     // Try to find user by email or username
-    let user = User::find()
-        .filter(
-            UserColumn::Email
-                .eq(identifier)
-                .or(UserColumn::Username.eq(identifier)),
-        )
-        .one(db)
-        .await?
-        .ok_or("User not found")?;
+    let user = find_user_by_identifier(db, identifier).await?;
 
     // Verify password
     if !verify_password(password, &user.password) {
         return Err("Invalid password".into());
     }
 
+    if !user.is_active {
+        return Err("User is deactivated".into());
+    }
+
     Ok(SignShow {
         id: user.id,
         username: user.username,
@@ -93,3 +98,233 @@ pub async fn get_user_model_by_id(
 
     Ok(user)
 }
+
+/// What [`get_cached_user`] keeps in Redis: the user without the password
+/// hash, so credential material never leaves the database.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedUser {
+    id: i32,
+    username: String,
+    email: String,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    account_type: AccountType,
+    avatar_url: Option<String>,
+    timezone: Option<String>,
+    is_active: bool,
+    is_admin: bool,
+    email_verified_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<UserModel> for CachedUser {
+    fn from(user: UserModel) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            account_type: user.account_type,
+            avatar_url: user.avatar_url,
+            timezone: user.timezone,
+            is_active: user.is_active,
+            is_admin: user.is_admin,
+            email_verified_at: user.email_verified_at,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+impl From<CachedUser> for UserModel {
+    fn from(user: CachedUser) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            password: String::new(),
+            first_name: user.first_name,
+            last_name: user.last_name,
+            account_type: user.account_type,
+            avatar_url: user.avatar_url,
+            timezone: user.timezone,
+            is_active: user.is_active,
+            is_admin: user.is_admin,
+            email_verified_at: user.email_verified_at,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+/// [`get_user_model_by_id`] through the cache; used on every authenticated
+/// request. Anything that changes a user must call [`invalidate_user`].
+///
+/// The password hash is not cached, so `password` comes back empty; load
+/// the user from the database wherever the hash is checked or changed.
+pub async fn get_cached_user(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    user_id: i32,
+) -> Result<UserModel, Box<dyn Error>> {
+    let user: CachedUser = cache
+        .get_or_load(USERS, &user_id.to_string(), || async {
+            Ok(get_user_model_by_id(db, user_id).await?.into())
+        })
+        .await?;
+    Ok(user.into())
+}
+
+pub async fn invalidate_user(cache: &Cache, user_id: i32) {
+    cache.invalidate(USERS, &user_id.to_string()).await;
+}
+
+/// Find a user by username or email.
+pub async fn find_user_by_identifier(
+    db: &DatabaseConnection,
+    identifier: &str,
+) -> Result<UserModel, Box<dyn Error>> {
+    let user = User::find()
+        .filter(
+            UserColumn::Email
+                .eq(identifier)
+                .or(UserColumn::Username.eq(identifier)),
+        )
+        .one(db)
+        .await?
+        .ok_or("User not found")?;
+
+    Ok(user)
+}
+
+/// Replace the user's password and sign them out everywhere.
+pub async fn set_password(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    user_id: i32,
+    password: &str,
+    hash_rounds: u32,
+) -> Result<UserModel, Box<dyn Error>> {
+    let mut user = get_user_model_by_id(db, user_id).await?.into_active_model();
+    user.password = Set(hash_password(password, hash_rounds));
+    user.updated_at = Set(Utc::now());
+    let user = user.update(db).await?;
+    invalidate_user(cache, user_id).await;
+
+    revoke_all_sessions(db, user_id).await?;
+    Ok(user)
+}
+
+/// Record that the user confirmed their email address. Already verified
+/// users keep their original timestamp.
+pub async fn mark_email_verified(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    user_id: i32,
+) -> Result<UserModel, Box<dyn Error>> {
+    let user = get_user_model_by_id(db, user_id).await?;
+    if user.email_verified_at.is_some() {
+        return Ok(user);
+    }
+    let mut user = user.into_active_model();
+    let now = Utc::now();
+    user.email_verified_at = Set(Some(now));
+    user.updated_at = Set(now);
+    let user = user.update(db).await?;
+    invalidate_user(cache, user_id).await;
+    Ok(user)
+}
+
+/// Activate or deactivate a user. Deactivated users cannot log in and their
+/// existing sessions are revoked.
+pub async fn set_user_active(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    user_id: i32,
+    active: bool,
+) -> Result<UserModel, Box<dyn Error>> {
+    let mut user = get_user_model_by_id(db, user_id).await?.into_active_model();
+    user.is_active = Set(active);
+    user.updated_at = Set(Utc::now());
+    let user = user.update(db).await?;
+    invalidate_user(cache, user_id).await;
+
+    if !active {
+        revoke_all_sessions(db, user_id).await?;
+    }
+    Ok(user)
+}
+
+/// Set or clear the user's preferred time zone. The name must already be
+/// validated.
+pub async fn set_user_timezone(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    user_id: i32,
+    timezone: Option<String>,
+) -> Result<UserModel, Box<dyn Error>> {
+    let mut user = get_user_model_by_id(db, user_id).await?.into_active_model();
+    user.timezone = Set(timezone);
+    user.updated_at = Set(Utc::now());
+    let user = user.update(db).await?;
+    invalidate_user(cache, user_id).await;
+    Ok(user)
+}
+
+pub async fn set_user_admin(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    user_id: i32,
+    admin: bool,
+) -> Result<UserModel, Box<dyn Error>> {
+    let mut user = get_user_model_by_id(db, user_id).await?.into_active_model();
+    user.is_admin = Set(admin);
+    user.updated_at = Set(Utc::now());
+    let user = user.update(db).await?;
+
+    invalidate_user(cache, user_id).await;
+    Ok(user)
+}
+
+/// Turn a user into a host, creating their host profile if needed.
+pub async fn promote_to_host(
+    db: &DatabaseConnection,
+    cache: &Cache,
+    user_id: i32,
+    organization_name: Option<String>,
+) -> Result<HostModel, Box<dyn Error>> {
+    let txn = db.begin().await?;
+
+    let user = User::find_by_id(user_id)
+        .one(&txn)
+        .await?
+        .ok_or("User not found")?;
+    let mut user = user.into_active_model();
+    user.account_type = Set(AccountType::Host);
+    user.updated_at = Set(Utc::now());
+    user.update(&txn).await?;
+
+    let host = match Host::find_by_id(user_id).one(&txn).await? {
+        Some(host) if organization_name.is_none() => host,
+        Some(host) => {
+            let mut host = host.into_active_model();
+            host.organization_name = Set(organization_name);
+            host.update(&txn).await?
+        }
+        None => {
+            HostActiveModel {
+                user_id: Set(user_id),
+                organization_name: Set(organization_name),
+                events_hosted_count: Set(0),
+            }
+            .insert(&txn)
+            .await?
+        }
+    };
+
+    txn.commit().await?;
+    invalidate_user(cache, user_id).await;
+    Ok(host)
+}
@@ -5,17 +5,33 @@ use std::pin::Pin;
 use tracing::error;
 
 use crate::core::configs::AppState;
-use crate::entity::user;
-use crate::services::users::get_user_model_by_id;
-use crate::utils::utils::decode_jwt;
+use crate::entity::api_key::{self, ApiScope};
+use crate::entity::{session, user};
+use crate::services::api_keys::authenticate_api_key;
+use crate::services::sessions::touch_session;
+use crate::services::users::get_cached_user;
+use crate::utils::utils::{API_KEY_PREFIX, decode_jwt};
+
+/// How the current request was authenticated
+#[derive(Debug, Clone)]
+pub enum Credential {
+    /// A JWT issued by `/auth/login` for this session
+    Session(session::Model),
+    /// A personal API key sent as the bearer token
+    ApiKey(api_key::Model),
+}
 
 /// Extractor for the currently authenticated user
 ///
-/// This can be used as a handler parameter to automatically validate JWT
-/// and fetch the user from the database.
+/// This can be used as a handler parameter to automatically validate the
+/// bearer credential and fetch the user (cached in Redis). The bearer may be
+/// either a JWT from `/auth/login` or a personal API key (`here_...`); the
+/// second field records which one was used. JWTs whose session has been
+/// revoked and expired API keys are rejected. The password hash is never
+/// cached, so the user's `password` is empty.
 ///
 /// # Example
-/// ```
+/// ```ignore
 /// #[get("/protected")]
 /// async fn protected_route(current_user: CurrentUser) -> impl Responder {
 ///     HttpResponse::Ok().json(json!({
@@ -24,24 +40,64 @@ use crate::utils::utils::decode_jwt;
 ///     }))
 /// }
 /// ```
-pub struct CurrentUser(pub user::Model);
+pub struct CurrentUser(pub user::Model, pub Credential);
+
+impl CurrentUser {
+    /// Whether the credential grants `scope`. Login sessions grant every scope.
+    pub fn has_scope(&self, scope: ApiScope) -> bool {
+        match &self.1 {
+            Credential::Session(_) => true,
+            Credential::ApiKey(key) => key.has_scope(scope),
+        }
+    }
+
+    /// Fail with 403 unless the request was made with a login session.
+    /// Used for account management that API keys must not reach.
+    pub fn require_session(&self) -> Result<&session::Model, Error> {
+        match &self.1 {
+            Credential::Session(session) => Ok(session),
+            Credential::ApiKey(_) => Err(error::ErrorForbidden(
+                "This endpoint requires a login session, not an API key",
+            )),
+        }
+    }
+
+    /// Fail with 403 unless the user is an administrator.
+    pub fn require_admin(&self) -> Result<(), Error> {
+        if self.0.is_admin {
+            Ok(())
+        } else {
+            Err(error::ErrorForbidden("Administrator access required"))
+        }
+    }
+
+    /// Fail with 403 unless the credential grants `scope`.
+    pub fn require_scope(&self, scope: ApiScope) -> Result<(), Error> {
+        if self.has_scope(scope) {
+            Ok(())
+        } else {
+            Err(error::ErrorForbidden(format!(
+                "API key is missing the `{}` scope",
+                scope.as_str()
+            )))
+        }
+    }
+}
 
 impl FromRequest for CurrentUser {
     type Error = Error;
     type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
 
-    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        // Only headers are needed; leave the body for extractors such as `Json`
         let req = req.clone();
-        let mut payload = payload.take();
 
         Box::pin(async move {
             // Extract Bearer token
-            let auth = BearerAuth::from_request(&req, &mut payload)
-                .await
-                .map_err(|e| {
-                    error!("Failed to extract bearer token: {}", e);
-                    error::ErrorUnauthorized("Missing or invalid authorization header")
-                })?;
+            let auth = BearerAuth::extract(&req).await.map_err(|e| {
+                error!("Failed to extract bearer token: {}", e);
+                error::ErrorUnauthorized("Missing or invalid authorization header")
+            })?;
 
             // Get app state
             let state = req
@@ -51,27 +107,50 @@ impl FromRequest for CurrentUser {
                     error::ErrorInternalServerError("Server configuration error")
                 })?;
 
-            // Decode JWT
-            let claims = decode_jwt(auth.token(), &state.config.secret_key).map_err(|e| {
-                error!("JWT decode error: {}", e);
-                error::ErrorUnauthorized("Invalid or expired token")
-            })?;
+            let (user_id, credential) = if auth.token().starts_with(API_KEY_PREFIX) {
+                // Look up API key
+                let key = authenticate_api_key(&state.db, auth.token())
+                    .await
+                    .map_err(|e| {
+                        error!("API key check failed: {}", e);
+                        error::ErrorUnauthorized("Invalid or expired API key")
+                    })?;
+                (key.user_id, Credential::ApiKey(key))
+            } else {
+                // Decode JWT
+                let claims = decode_jwt(auth.token(), &state.jwt_keys).map_err(|e| {
+                    error!("JWT decode error: {}", e);
+                    error::ErrorUnauthorized("Invalid or expired token")
+                })?;
 
-            // Parse user ID
-            let user_id: i32 = claims.sub.parse().map_err(|e| {
-                error!("Failed to parse user ID from token: {}", e);
-                error::ErrorUnauthorized("Invalid token format")
-            })?;
+                // Parse user ID
+                let user_id: i32 = claims.sub.parse().map_err(|e| {
+                    error!("Failed to parse user ID from token: {}", e);
+                    error::ErrorUnauthorized("Invalid token format")
+                })?;
 
-            // Fetch user from database
-            let user = get_user_model_by_id(&state.db, user_id)
+                // Reject tokens whose session was revoked
+                let session = touch_session(&state.db, user_id, claims.sid)
+                    .await
+                    .map_err(|e| {
+                        error!("Session check failed: {}", e);
+                        error::ErrorUnauthorized("Session expired or revoked")
+                    })?;
+                (user_id, Credential::Session(session))
+            };
+
+            // Fetch user, usually from the cache
+            let user = get_cached_user(&state.db, &state.cache, user_id)
                 .await
                 .map_err(|e| {
                     error!("Failed to fetch user: {}", e);
                     error::ErrorUnauthorized("User not found or database error")
                 })?;
+            if !user.is_active {
+                return Err(error::ErrorUnauthorized("User is deactivated"));
+            }
 
-            Ok(CurrentUser(user))
+            Ok(CurrentUser(user, credential))
         })
     }
 }
@@ -83,13 +162,12 @@ impl FromRequest for MaybeCurrentUser {
     type Error = Error;
     type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
 
-    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
         let req = req.clone();
-        let mut payload = payload.take();
 
         Box::pin(async move {
-            match CurrentUser::from_request(&req, &mut payload).await {
-                Ok(CurrentUser(user)) => Ok(MaybeCurrentUser(Some(user))),
+            match CurrentUser::extract(&req).await {
+                Ok(CurrentUser(user, _)) => Ok(MaybeCurrentUser(Some(user))),
                 Err(_) => Ok(MaybeCurrentUser(None)),
             }
         })
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+
+use crate::core::configs::AppConfig;
+
+/// Key id used for HS256 tokens when `JWT_KID` is not set, and assumed for
+/// tokens issued before `kid` headers were added.
+pub const DEFAULT_KID: &str = "primary";
+
+/// Signing and verification keys for access tokens.
+///
+/// Tokens are signed with a single active key and carry its `kid` in the
+/// header. Verification accepts any configured key, so a new signing key can
+/// be rolled out while tokens signed by the previous one stay valid until
+/// they expire.
+#[derive(Clone)]
+pub struct JwtKeys {
+    pub algorithm: Algorithm,
+    pub kid: String,
+    encoding_key: EncodingKey,
+    verification_keys: HashMap<String, (Algorithm, DecodingKey)>,
+    public_jwks: JwkSet,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    pub leeway_seconds: u64,
+}
+
+impl fmt::Debug for JwtKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtKeys")
+            .field("algorithm", &self.algorithm)
+            .field("kid", &self.kid)
+            .field(
+                "verification_kids",
+                &self.verification_keys.keys().collect::<Vec<_>>(),
+            )
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .field("leeway_seconds", &self.leeway_seconds)
+            .finish_non_exhaustive()
+    }
+}
+
+impl JwtKeys {
+    /// Build the key set from configuration.
+    ///
+    /// - `HS256` signs with `secret_key`; `jwt_previous_secrets`
+    ///   (`kid=secret,kid=secret`) lists retired secrets still accepted.
+    /// - `RS256` / `EdDSA` sign with the PEM in `jwt_private_key` and verify
+    ///   with the public keys in `jwt_public_keys` (a JWK Set that must contain
+    ///   `jwt_kid`). Those public keys are what `/.well-known/jwks.json` serves.
+    pub fn from_config(config: &AppConfig) -> Result<Self, String> {
+        let algorithm = match config.jwt_algorithm.as_str() {
+            "HS256" => Algorithm::HS256,
+            "RS256" => Algorithm::RS256,
+            "EdDSA" => Algorithm::EdDSA,
+            other => return Err(format!("Unsupported JWT algorithm: {}", other)),
+        };
+        let kid = config.jwt_kid.clone();
+        let mut verification_keys = HashMap::new();
+        let mut public_jwks = JwkSet { keys: Vec::new() };
+
+        let encoding_key = if algorithm == Algorithm::HS256 {
+            verification_keys.insert(
+                kid.clone(),
+                (
+                    Algorithm::HS256,
+                    DecodingKey::from_secret(config.secret_key.as_bytes()),
+                ),
+            );
+            EncodingKey::from_secret(config.secret_key.as_bytes())
+        } else {
+            let pem = config
+                .jwt_private_key
+                .as_deref()
+                .ok_or("JWT_PRIVATE_KEY is required for asymmetric JWT algorithms")?;
+            let key = match algorithm {
+                Algorithm::RS256 => EncodingKey::from_rsa_pem(pem.as_bytes()),
+                _ => EncodingKey::from_ed_pem(pem.as_bytes()),
+            };
+            key.map_err(|e| format!("Invalid JWT_PRIVATE_KEY: {}", e))?
+        };
+
+        if let Some(previous) = config.jwt_previous_secrets.as_deref() {
+            for entry in previous.split(',').filter(|e| !e.trim().is_empty()) {
+                let (old_kid, secret) = entry
+                    .trim()
+                    .split_once('=')
+                    .ok_or("JWT_PREVIOUS_SECRETS entries must look like kid=secret")?;
+                verification_keys.insert(
+                    old_kid.to_string(),
+                    (
+                        Algorithm::HS256,
+                        DecodingKey::from_secret(secret.as_bytes()),
+                    ),
+                );
+            }
+        }
+
+        if let Some(jwks) = config.jwt_public_keys.as_deref() {
+            let jwks: JwkSet = serde_json::from_str(jwks)
+                .map_err(|e| format!("JWT_PUBLIC_KEYS is not a valid JWK Set: {}", e))?;
+            for jwk in jwks.keys {
+                if matches!(jwk.algorithm, AlgorithmParameters::OctetKey(_)) {
+                    return Err("JWT_PUBLIC_KEYS must not contain symmetric keys".into());
+                }
+                let jwk_kid = jwk
+                    .common
+                    .key_id
+                    .clone()
+                    .ok_or("Every key in JWT_PUBLIC_KEYS needs a kid")?;
+                let jwk_algorithm = match &jwk.algorithm {
+                    AlgorithmParameters::RSA(_) => Algorithm::RS256,
+                    AlgorithmParameters::OctetKeyPair(_) => Algorithm::EdDSA,
+                    _ => return Err(format!("Unsupported key type for kid {}", jwk_kid)),
+                };
+                let decoding_key = DecodingKey::from_jwk(&jwk)
+                    .map_err(|e| format!("Invalid public key {}: {}", jwk_kid, e))?;
+                verification_keys.insert(jwk_kid, (jwk_algorithm, decoding_key));
+                public_jwks.keys.push(jwk);
+            }
+        }
+
+        if !verification_keys.contains_key(&kid) {
+            return Err(format!(
+                "No verification key configured for the signing kid {}",
+                kid
+            ));
+        }
+
+        Ok(Self {
+            algorithm,
+            kid,
+            encoding_key,
+            verification_keys,
+            public_jwks,
+            issuer: config.jwt_issuer.clone(),
+            audience: config.jwt_audience.clone(),
+            leeway_seconds: config.jwt_leeway_seconds,
+        })
+    }
+
+    pub fn encoding_key(&self) -> &EncodingKey {
+        &self.encoding_key
+    }
+
+    /// Look up the verification key for a token's `kid`.
+    /// Tokens without a `kid` are checked against the current signing key.
+    pub fn verification_key(&self, kid: Option<&str>) -> Option<&(Algorithm, DecodingKey)> {
+        self.verification_keys.get(kid.unwrap_or(self.kid.as_str()))
+    }
+
+    /// Public keys safe to publish at `/.well-known/jwks.json`.
+    pub fn public_jwks(&self) -> &JwkSet {
+        &self.public_jwks
+    }
+}
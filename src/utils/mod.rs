@@ -1,2 +1,11 @@
 pub mod auth_extractor;
+pub mod jwt;
+pub mod payload;
+pub mod recurrence;
+pub mod redact;
+pub mod request_id;
+pub mod tags;
+pub mod tickets;
+pub mod timezone;
+#[allow(clippy::module_inception)]
 pub mod utils;
@@ -0,0 +1,98 @@
+use actix_web::error::{InternalError, JsonPayloadError, PathError, QueryPayloadError};
+use actix_web::http::StatusCode;
+use actix_web::web::{JsonConfig, PathConfig, PayloadConfig, QueryConfig};
+use actix_web::{Error, HttpRequest, HttpResponse};
+use serde_json::error::Category;
+use tracing::warn;
+
+use crate::schemas::error::ErrorResponse;
+
+/// Extractor configs that cap body sizes at `max_body_bytes` and turn
+/// extractor failures into [`ErrorResponse`] bodies instead of actix's
+/// plain-text defaults.
+pub fn json_config(max_body_bytes: usize) -> JsonConfig {
+    JsonConfig::default()
+        .limit(max_body_bytes)
+        .error_handler(json_error_handler)
+}
+
+pub fn payload_config(max_body_bytes: usize) -> PayloadConfig {
+    PayloadConfig::new(max_body_bytes)
+}
+
+pub fn query_config() -> QueryConfig {
+    QueryConfig::default().error_handler(query_error_handler)
+}
+
+pub fn path_config() -> PathConfig {
+    PathConfig::default().error_handler(path_error_handler)
+}
+
+fn reject(err: impl Into<Error>, status: StatusCode, body: ErrorResponse) -> Error {
+    warn!("Rejected request: {}", body.message);
+    InternalError::from_response(err.into(), HttpResponse::build(status).json(body)).into()
+}
+
+fn json_error_handler(err: JsonPayloadError, _req: &HttpRequest) -> Error {
+    let (status, body) = match &err {
+        JsonPayloadError::Overflow { limit }
+        | JsonPayloadError::OverflowKnownLength { limit, .. } => (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorResponse::new(
+                "payload_too_large",
+                format!("Request body exceeds the {} byte limit", limit),
+            ),
+        ),
+        JsonPayloadError::ContentType => (
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ErrorResponse::new(
+                "unsupported_media_type",
+                "Expected Content-Type: application/json",
+            ),
+        ),
+        JsonPayloadError::Deserialize(e) => (
+            StatusCode::BAD_REQUEST,
+            ErrorResponse::new("invalid_json", describe_json_error(e)),
+        ),
+        _ => (
+            StatusCode::BAD_REQUEST,
+            ErrorResponse::new("invalid_body", "Request body could not be read"),
+        ),
+    };
+    reject(err, status, body)
+}
+
+/// Describe a JSON error without echoing values from the body, which may
+/// contain passwords or tokens. Missing/unknown field errors only name the
+/// field, so those are passed through.
+fn describe_json_error(e: &serde_json::Error) -> String {
+    let message = e.to_string();
+    match e.classify() {
+        Category::Data
+            if message.starts_with("missing field") || message.starts_with("unknown field") =>
+        {
+            message
+        }
+        Category::Data => format!(
+            "Request body has a value of the wrong type at line {} column {}",
+            e.line(),
+            e.column()
+        ),
+        Category::Eof => "Request body ended unexpectedly".to_string(),
+        Category::Syntax | Category::Io => format!(
+            "Request body is not valid JSON (line {} column {})",
+            e.line(),
+            e.column()
+        ),
+    }
+}
+
+fn query_error_handler(err: QueryPayloadError, _req: &HttpRequest) -> Error {
+    let body = ErrorResponse::new("invalid_query", format!("Invalid query string: {}", err));
+    reject(err, StatusCode::BAD_REQUEST, body)
+}
+
+fn path_error_handler(err: PathError, _req: &HttpRequest) -> Error {
+    let body = ErrorResponse::new("invalid_path", format!("Invalid path parameter: {}", err));
+    reject(err, StatusCode::BAD_REQUEST, body)
+}
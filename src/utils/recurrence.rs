@@ -0,0 +1,541 @@
+//! RFC 5545 recurrence rules.
+//!
+//! Supports the subset hosts need for regular meetups: `FREQ` of `DAILY`,
+//! `WEEKLY` or `MONTHLY`, with `INTERVAL`, `BYDAY` (ordinals such as `2TU`
+//! or `-1FR` for monthly rules), `BYMONTHDAY`, `COUNT` or `UNTIL`, and
+//! `WKST`. Rules are expanded in the series' local time and each occurrence
+//! is then converted to UTC, so a 19:00 meetup stays at 19:00 local time
+//! across daylight saving changes.
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, Days, Duration, LocalResult, Months, NaiveDate, NaiveDateTime, TimeZone,
+    Utc, Weekday,
+};
+use chrono_tz::Tz;
+
+/// Consecutive periods without a single candidate after which a rule is
+/// treated as exhausted, e.g. `BYMONTHDAY=31` every second February.
+const MAX_EMPTY_PERIODS: u32 = 1000;
+
+/// Format of local date-times in `EXDATE` values and `UNTIL` without `Z`.
+const LOCAL_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// End of a rule given by `UNTIL`; inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Until {
+    /// `UNTIL=20261231`: occurrences on or before this local date
+    Date(NaiveDate),
+    /// `UNTIL=20261231T235959Z`
+    Utc(DateTime<Utc>),
+    /// `UNTIL=20261231T235959`, in the series' time zone
+    Local(NaiveDateTime),
+}
+
+/// A parsed `RRULE` value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    /// Weekdays, each with an optional ordinal within the month
+    pub by_day: Vec<(Option<i8>, Weekday)>,
+    /// Days of the month; negative values count from the end
+    pub by_month_day: Vec<i8>,
+    pub count: Option<u32>,
+    pub until: Option<Until>,
+    pub week_start: Weekday,
+}
+
+impl RecurrenceRule {
+    /// The same rule ending with the last occurrence at or before `until`.
+    pub fn ending_at(&self, until: DateTime<Utc>) -> Self {
+        RecurrenceRule {
+            count: None,
+            until: Some(Until::Utc(until)),
+            ..self.clone()
+        }
+    }
+
+    /// The same rule with a different `COUNT`.
+    pub fn with_count(&self, count: u32) -> Self {
+        RecurrenceRule {
+            count: Some(count),
+            until: None,
+            ..self.clone()
+        }
+    }
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let value = value
+            .strip_prefix("RRULE:")
+            .or_else(|| value.strip_prefix("rrule:"))
+            .unwrap_or(value);
+
+        let mut frequency = None;
+        let mut rule = RecurrenceRule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            count: None,
+            until: None,
+            week_start: Weekday::Mon,
+        };
+
+        for part in value.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Malformed RRULE part '{}'", part))?;
+            let value = value.to_ascii_uppercase();
+            match name.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        other => return Err(format!("Unsupported FREQ '{}'", other)),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|interval| (1..=1000).contains(interval))
+                        .ok_or("INTERVAL must be between 1 and 1000")?
+                }
+                "COUNT" => {
+                    rule.count = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|count| *count > 0)
+                            .ok_or("COUNT must be a positive number")?,
+                    )
+                }
+                "UNTIL" => rule.until = Some(parse_until(&value)?),
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .map(parse_by_day)
+                        .collect::<Result<_, _>>()?
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day = value
+                        .split(',')
+                        .map(|day| {
+                            day.parse::<i8>()
+                                .ok()
+                                .filter(|day| *day != 0 && (-31..=31).contains(day))
+                                .ok_or_else(|| format!("Invalid BYMONTHDAY '{}'", day))
+                        })
+                        .collect::<Result<_, _>>()?
+                }
+                "WKST" => rule.week_start = parse_weekday(&value)?,
+                other => return Err(format!("Unsupported RRULE part '{}'", other)),
+            }
+        }
+
+        rule.frequency = frequency.ok_or("RRULE must have a FREQ")?;
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err("RRULE cannot have both COUNT and UNTIL".to_string());
+        }
+        if rule.frequency != Frequency::Monthly
+            && rule.by_day.iter().any(|(ordinal, _)| ordinal.is_some())
+        {
+            return Err("BYDAY ordinals are only allowed with FREQ=MONTHLY".to_string());
+        }
+        if rule.frequency == Frequency::Weekly && !rule.by_month_day.is_empty() {
+            return Err("BYMONTHDAY is not allowed with FREQ=WEEKLY".to_string());
+        }
+
+        Ok(rule)
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+        };
+        write!(f, "FREQ={}", frequency)?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self
+                .by_day
+                .iter()
+                .map(|(ordinal, day)| match ordinal {
+                    Some(ordinal) => format!("{}{}", ordinal, weekday_code(*day)),
+                    None => weekday_code(*day).to_string(),
+                })
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if !self.by_month_day.is_empty() {
+            let days: Vec<String> = self.by_month_day.iter().map(i8::to_string).collect();
+            write!(f, ";BYMONTHDAY={}", days.join(","))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        match self.until {
+            Some(Until::Date(date)) => write!(f, ";UNTIL={}", date.format("%Y%m%d"))?,
+            Some(Until::Utc(time)) => write!(f, ";UNTIL={}", time.format("%Y%m%dT%H%M%SZ"))?,
+            Some(Until::Local(time)) => write!(f, ";UNTIL={}", time.format(LOCAL_FORMAT))?,
+            None => {}
+        }
+        if self.week_start != Weekday::Mon {
+            write!(f, ";WKST={}", weekday_code(self.week_start))?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_until(value: &str) -> Result<Until, String> {
+    let invalid = || format!("Invalid UNTIL '{}'", value);
+    if let Some(utc) = value.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, LOCAL_FORMAT).map_err(|_| invalid())?;
+        Ok(Until::Utc(time.and_utc()))
+    } else if value.contains('T') {
+        parse_local(value).map(Until::Local).map_err(|_| invalid())
+    } else {
+        NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map(Until::Date)
+            .map_err(|_| invalid())
+    }
+}
+
+fn parse_by_day(value: &str) -> Result<(Option<i8>, Weekday), String> {
+    let invalid = || format!("Invalid BYDAY '{}'", value);
+    let split = value.len().checked_sub(2).ok_or_else(invalid)?;
+    let (ordinal, day) = value.split_at(split);
+    let day = parse_weekday(day).map_err(|_| invalid())?;
+    if ordinal.is_empty() {
+        return Ok((None, day));
+    }
+    let ordinal = ordinal
+        .trim_start_matches('+')
+        .parse::<i8>()
+        .ok()
+        .filter(|ordinal| *ordinal != 0 && (-5..=5).contains(ordinal))
+        .ok_or_else(invalid)?;
+    Ok((Some(ordinal), day))
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    Ok(match value {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        other => return Err(format!("Invalid weekday '{}'", other)),
+    })
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// Parse a local date-time in iCalendar form, e.g. `20261102T190000`.
+pub fn parse_local(value: &str) -> Result<NaiveDateTime, String> {
+    NaiveDateTime::parse_from_str(value, LOCAL_FORMAT)
+        .map_err(|_| format!("Invalid local date-time '{}'", value))
+}
+
+/// Format a local date-time in iCalendar form, e.g. `20261102T190000`.
+pub fn format_local(time: NaiveDateTime) -> String {
+    time.format(LOCAL_FORMAT).to_string()
+}
+
+/// Parse a comma-separated list of local date-times, as stored for EXDATE.
+pub fn parse_exdates(value: &str) -> Result<Vec<NaiveDateTime>, String> {
+    value
+        .split(',')
+        .filter(|value| !value.is_empty())
+        .map(parse_local)
+        .collect()
+}
+
+/// Inverse of [`parse_exdates`].
+pub fn format_exdates(exdates: &[NaiveDateTime]) -> String {
+    exdates
+        .iter()
+        .map(|time| format_local(*time))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The instant a local wall-clock time refers to in `tz`. Times repeated
+/// when clocks go back resolve to the first of the two; times skipped when
+/// clocks go forward are moved past the gap, as RFC 5545 requires.
+pub fn resolve_local(tz: Tz, time: NaiveDateTime) -> DateTime<Utc> {
+    match tz.from_local_datetime(&time) {
+        LocalResult::Single(time) => time.with_timezone(&Utc),
+        LocalResult::Ambiguous(earliest, _) => earliest.with_timezone(&Utc),
+        LocalResult::None => {
+            // Gaps are at most an hour in every zone in use today
+            let shifted = time + Duration::hours(1);
+            tz.from_local_datetime(&shifted)
+                .earliest()
+                .map(|time| time.with_timezone(&Utc))
+                .unwrap_or_else(|| shifted.and_utc())
+        }
+    }
+}
+
+/// A rule anchored at a local start time in a time zone.
+#[derive(Debug, Clone)]
+pub struct Recurrence {
+    pub rule: RecurrenceRule,
+    /// First occurrence, in local time
+    pub start: NaiveDateTime,
+    pub timezone: Tz,
+    /// Local start times removed from the set (`EXDATE`)
+    pub exdates: Vec<NaiveDateTime>,
+}
+
+/// One expanded occurrence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Occurrence {
+    pub local: NaiveDateTime,
+    pub start: DateTime<Utc>,
+}
+
+impl Recurrence {
+    /// Occurrences in order. Unbounded rules never end, so callers must
+    /// stop themselves, e.g. with `take_while`.
+    pub fn occurrences(&self) -> Occurrences<'_> {
+        Occurrences {
+            recurrence: self,
+            period: 0,
+            empty_periods: 0,
+            pending: VecDeque::new(),
+            generated: 0,
+            done: false,
+        }
+    }
+
+    /// Number of occurrences the rule generates before `local`, counting
+    /// ones removed by `EXDATE` as `COUNT` does.
+    pub fn count_before(&self, local: NaiveDateTime) -> u32 {
+        let mut without_exdates = self.clone();
+        without_exdates.exdates.clear();
+        without_exdates
+            .occurrences()
+            .take_while(|occurrence| occurrence.local < local)
+            .count() as u32
+    }
+
+    fn period_dates(&self, period: u32) -> Vec<NaiveDate> {
+        let rule = &self.rule;
+        let step = period.saturating_mul(rule.interval);
+        let start = self.start.date();
+        let mut dates = match rule.frequency {
+            Frequency::Daily => {
+                let Some(date) = start.checked_add_days(Days::new(step.into())) else {
+                    return Vec::new();
+                };
+                let weekday_matches = rule.by_day.is_empty()
+                    || rule.by_day.iter().any(|(_, day)| *day == date.weekday());
+                let month_day_matches = rule.by_month_day.is_empty()
+                    || rule
+                        .by_month_day
+                        .iter()
+                        .any(|day| month_day(date, *day) == Some(date));
+                if weekday_matches && month_day_matches {
+                    vec![date]
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Weekly => {
+                let offset = start.weekday().days_since(rule.week_start);
+                let Some(week) = start
+                    .checked_sub_days(Days::new(offset.into()))
+                    .and_then(|week| week.checked_add_days(Days::new(u64::from(step) * 7)))
+                else {
+                    return Vec::new();
+                };
+                let days: Vec<Weekday> = if rule.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    rule.by_day.iter().map(|(_, day)| *day).collect()
+                };
+                days.into_iter()
+                    .filter_map(|day| {
+                        week.checked_add_days(Days::new(day.days_since(rule.week_start).into()))
+                    })
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let Some(month) = start
+                    .with_day(1)
+                    .and_then(|month| month.checked_add_months(Months::new(step)))
+                else {
+                    return Vec::new();
+                };
+                if !rule.by_month_day.is_empty() {
+                    rule.by_month_day
+                        .iter()
+                        .filter_map(|day| month_day(month, *day))
+                        .filter(|date| {
+                            rule.by_day.is_empty()
+                                || rule
+                                    .by_day
+                                    .iter()
+                                    .any(|by_day| weekday_in_month_matches(*date, *by_day))
+                        })
+                        .collect()
+                } else if !rule.by_day.is_empty() {
+                    rule.by_day
+                        .iter()
+                        .flat_map(|(ordinal, day)| weekdays_in_month(month, *ordinal, *day))
+                        .collect()
+                } else {
+                    month_day(month, start.day() as i8).into_iter().collect()
+                }
+            }
+        };
+        dates.sort();
+        dates.dedup();
+        dates
+    }
+
+    fn past_until(&self, occurrence: &Occurrence) -> bool {
+        match self.rule.until {
+            Some(Until::Date(date)) => occurrence.local.date() > date,
+            Some(Until::Utc(time)) => occurrence.start > time,
+            Some(Until::Local(time)) => occurrence.local > time,
+            None => false,
+        }
+    }
+}
+
+/// Iterator returned by [`Recurrence::occurrences`].
+#[derive(Debug)]
+pub struct Occurrences<'a> {
+    recurrence: &'a Recurrence,
+    period: u32,
+    empty_periods: u32,
+    pending: VecDeque<NaiveDateTime>,
+    /// Occurrences generated so far, including ones removed by `EXDATE`
+    generated: u32,
+    done: bool,
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = Occurrence;
+
+    fn next(&mut self) -> Option<Occurrence> {
+        let recurrence = self.recurrence;
+        loop {
+            if self.done {
+                return None;
+            }
+            let Some(local) = self.pending.pop_front() else {
+                let time = recurrence.start.time();
+                self.pending.extend(
+                    recurrence
+                        .period_dates(self.period)
+                        .into_iter()
+                        .map(|date| date.and_time(time))
+                        .filter(|local| *local >= recurrence.start),
+                );
+                self.period += 1;
+                if self.pending.is_empty() {
+                    self.empty_periods += 1;
+                    self.done = self.empty_periods > MAX_EMPTY_PERIODS;
+                } else {
+                    self.empty_periods = 0;
+                }
+                continue;
+            };
+
+            if recurrence
+                .rule
+                .count
+                .is_some_and(|count| self.generated >= count)
+            {
+                self.done = true;
+                continue;
+            }
+            let occurrence = Occurrence {
+                local,
+                start: resolve_local(recurrence.timezone, local),
+            };
+            if recurrence.past_until(&occurrence) {
+                self.done = true;
+                continue;
+            }
+            self.generated += 1;
+            if recurrence.exdates.contains(&local) {
+                continue;
+            }
+            return Some(occurrence);
+        }
+    }
+}
+
+/// `day` of the month starting at `month`; negative days count from the end.
+fn month_day(month: NaiveDate, day: i8) -> Option<NaiveDate> {
+    let first = month.with_day(1)?;
+    if day > 0 {
+        first.with_day(day as u32)
+    } else {
+        let last = first.checked_add_months(Months::new(1))?.pred_opt()?;
+        let day = last.day() as i32 + 1 + day as i32;
+        (day >= 1).then(|| last.with_day(day as u32)).flatten()
+    }
+}
+
+/// Every `day` in the month, or just the `ordinal`th one (`-1` is the last).
+fn weekdays_in_month(month: NaiveDate, ordinal: Option<i8>, day: Weekday) -> Vec<NaiveDate> {
+    let days: Vec<NaiveDate> = (1..=31)
+        .filter_map(|n| month.with_day(n))
+        .filter(|date| date.weekday() == day)
+        .collect();
+    match ordinal {
+        None => days,
+        Some(n) if n > 0 => days.get(n as usize - 1).copied().into_iter().collect(),
+        Some(n) => days
+            .len()
+            .checked_sub(n.unsigned_abs() as usize)
+            .and_then(|index| days.get(index))
+            .copied()
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn weekday_in_month_matches(date: NaiveDate, (ordinal, day): (Option<i8>, Weekday)) -> bool {
+    date.weekday() == day
+        && (ordinal.is_none() || weekdays_in_month(date, ordinal, day).contains(&date))
+}
@@ -0,0 +1,93 @@
+//! Helpers that keep secrets and personal data out of logs and traces.
+use validator::{ValidationErrors, ValidationErrorsKind};
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Query parameters whose values are never logged.
+const SENSITIVE_PARAMS: [&str; 8] = [
+    "password",
+    "token",
+    "access_token",
+    "refresh_token",
+    "api_key",
+    "key",
+    "secret",
+    "code",
+];
+
+/// Path prefixes whose next segment is a secret, such as the token of a
+/// calendar feed URL.
+const SENSITIVE_PATH_PREFIXES: [&str; 1] = ["/calendar/"];
+
+/// Mask secret path segments and the values of sensitive query parameters
+/// in a path such as `/auth/reset?token=abc&lang=en`.
+pub fn redact_path_and_query(path_and_query: &str) -> String {
+    let (path, query) = match path_and_query.split_once('?') {
+        Some((path, query)) => (redact_path(path), query),
+        None => return redact_path(path_and_query),
+    };
+
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if SENSITIVE_PARAMS.contains(&name.to_ascii_lowercase().as_str()) => {
+                format!("{}={}", name, REDACTED)
+            }
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!("{}?{}", path, query)
+}
+
+/// Mask the secret segment after a [`SENSITIVE_PATH_PREFIXES`] entry,
+/// keeping any file extension: `/calendar/abc.ics` becomes
+/// `/calendar/[REDACTED].ics`.
+fn redact_path(path: &str) -> String {
+    for prefix in SENSITIVE_PATH_PREFIXES {
+        let Some(rest) = path.strip_prefix(prefix) else {
+            continue;
+        };
+        let (segment, tail) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        if segment.is_empty() {
+            break;
+        }
+        let extension = segment.rfind('.').map_or("", |dot| &segment[dot..]);
+        return format!("{}{}{}{}", prefix, REDACTED, extension, tail);
+    }
+    path.to_string()
+}
+
+/// Drop the `user:password@` part of a connection URL.
+pub fn redact_url(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    match rest.split_once('@') {
+        Some((_, host)) => format!("{}://{}@{}", scheme, REDACTED, host),
+        None => url.to_string(),
+    }
+}
+
+/// Describe validation errors by field and rule only.
+///
+/// The `Display` impl of `ValidationErrors` includes the rejected values,
+/// which would put passwords into logs and error responses.
+pub fn validation_summary(errors: &ValidationErrors) -> String {
+    let mut fields: Vec<String> = errors
+        .errors()
+        .iter()
+        .map(|(field, kind)| match kind {
+            ValidationErrorsKind::Field(errors) => {
+                let codes: Vec<&str> = errors.iter().map(|e| e.code.as_ref()).collect();
+                format!("{}: {}", field, codes.join(", "))
+            }
+            ValidationErrorsKind::Struct(_) | ValidationErrorsKind::List(_) => {
+                format!("{}: invalid", field)
+            }
+        })
+        .collect();
+    fields.sort();
+    fields.join("; ")
+}
@@ -0,0 +1,47 @@
+//! Free-form event tags.
+//!
+//! Tags are stored normalized so `#Rust`, `rust` and ` RUST ` are one tag:
+//! a leading `#` is dropped, letters are lowercased and runs of whitespace
+//! become a single `-`. What is left may only hold letters, digits, `-` and
+//! `_`.
+
+/// Longest tag accepted, in characters.
+pub const MAX_TAG_LENGTH: usize = 50;
+
+/// The normalized form of a tag as a user typed it.
+pub fn normalize_tag(raw: &str) -> Result<String, String> {
+    let words: Vec<&str> = raw
+        .trim()
+        .trim_start_matches('#')
+        .split_whitespace()
+        .collect();
+    let tag = words.join("-").to_lowercase();
+    if tag.is_empty() {
+        return Err("Tags cannot be empty".to_string());
+    }
+    if tag.chars().count() > MAX_TAG_LENGTH {
+        return Err(format!(
+            "Tag '{}' is longer than {} characters",
+            tag, MAX_TAG_LENGTH
+        ));
+    }
+    if let Some(c) = tag
+        .chars()
+        .find(|c| !(c.is_alphanumeric() || *c == '-' || *c == '_'))
+    {
+        return Err(format!("Tag '{}' contains '{}'", tag, c));
+    }
+    Ok(tag)
+}
+
+/// Normalize a list of tags, dropping duplicates but keeping their order.
+pub fn normalize_tags<S: AsRef<str>>(raw: &[S]) -> Result<Vec<String>, String> {
+    let mut tags: Vec<String> = Vec::with_capacity(raw.len());
+    for tag in raw {
+        let tag = normalize_tag(tag.as_ref())?;
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    Ok(tags)
+}
@@ -0,0 +1,38 @@
+//! Currency codes and promo codes as people type them.
+//!
+//! Both are stored upper case, so `ngn` and `NGN`, or `early-bird` and
+//! `EARLY-BIRD`, are the same.
+
+/// Longest promo code accepted, in characters.
+pub const MAX_PROMO_CODE_LENGTH: usize = 32;
+
+/// The ISO 4217 code for `raw`, e.g. `NGN` for `ngn`. Only the shape is
+/// checked, not that the currency exists.
+pub fn normalize_currency(raw: &str) -> Result<String, String> {
+    let currency = raw.trim().to_ascii_uppercase();
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(format!(
+            "'{}' is not a three-letter currency code",
+            raw.trim()
+        ));
+    }
+    Ok(currency)
+}
+
+/// The stored form of a promo code: letters, digits, `-` and `_` only.
+pub fn normalize_promo_code(raw: &str) -> Result<String, String> {
+    let code = raw.trim().to_ascii_uppercase();
+    if code.len() < 3 || code.len() > MAX_PROMO_CODE_LENGTH {
+        return Err(format!(
+            "Promo codes are 3 to {} characters long",
+            MAX_PROMO_CODE_LENGTH
+        ));
+    }
+    if let Some(c) = code
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_'))
+    {
+        return Err(format!("Promo code '{}' contains '{}'", code, c));
+    }
+    Ok(code)
+}
@@ -0,0 +1,24 @@
+//! IANA time zones for events and users.
+//!
+//! Times are stored in UTC; the zone name is kept alongside so wall-clock
+//! times can be shown and "today" or "this weekend" worked out where the
+//! event actually happens.
+use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+
+/// An IANA time zone such as `Europe/London`.
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.parse()
+        .map_err(|_| format!("Unknown time zone '{}'", name))
+}
+
+/// A stored zone name. Names are validated before they are saved, so
+/// anything unreadable is treated as UTC rather than failing the request.
+pub fn stored_timezone(name: &str) -> Tz {
+    parse_timezone(name).unwrap_or(Tz::UTC)
+}
+
+/// Wall-clock time of `time` in `tz`.
+pub fn local_time(time: DateTime<Utc>, tz: Tz) -> NaiveDateTime {
+    time.with_timezone(&tz).naive_local()
+}
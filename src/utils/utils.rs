@@ -1,45 +1,129 @@
-use bcrypt::{DEFAULT_COST, hash, verify};
+use bcrypt::{hash, verify};
 use chrono::{Duration, Utc};
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{Header, Validation, decode, decode_header, encode};
+use rand::Rng;
+use rand::distr::Alphanumeric;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-pub fn hash_password(password: &str) -> String {
-    hash(password, DEFAULT_COST).unwrap()
+use crate::utils::jwt::JwtKeys;
+
+/// Marker every API key starts with, used to tell keys apart from JWTs.
+pub const API_KEY_PREFIX: &str = "here_";
+
+/// Hash a password with bcrypt using `rounds` (the `HASH_ROUNDS` setting).
+pub fn hash_password(password: &str, rounds: u32) -> String {
+    hash(password, rounds).unwrap()
 }
 
 pub fn verify_password(password: &str, hash: &str) -> bool {
     verify(password, hash).unwrap_or(false)
 }
 
+fn random_string(len: usize) -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// Generate a new API key, returning `(prefix, full_key)`.
+///
+/// The key looks like `here_<8 chars>_<32 chars>`; the prefix is the part
+/// before the second underscore and is safe to display.
+pub fn generate_api_key() -> (String, String) {
+    let prefix = format!("{}{}", API_KEY_PREFIX, random_string(8).to_lowercase());
+    let key = format!("{}_{}", prefix, random_string(32));
+    (prefix, key)
+}
+
+/// Split the displayable prefix off a full API key.
+pub fn api_key_prefix(key: &str) -> Option<&str> {
+    let rest = key.strip_prefix(API_KEY_PREFIX)?;
+    let (id, _) = rest.split_once('_')?;
+    Some(&key[..API_KEY_PREFIX.len() + id.len()])
+}
+
+/// Random token for emailed links (email verification, password reset).
+pub fn generate_token() -> String {
+    random_string(43)
+}
+
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// How long an access token, and so the login session it belongs to, lasts.
+pub const JWT_LIFETIME: Duration = Duration::hours(24);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // user id
+    pub sid: i32,    // session id
     pub exp: usize,  // expiration time
+    pub iat: usize,  // issued at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
 }
 
-pub fn generate_jwt(user_id: i32, secret: &str) -> Result<String, jsonwebtoken::errors::Error> {
-    let expiration = Utc::now()
-        .checked_add_signed(Duration::hours(24))
+pub fn generate_jwt(
+    user_id: i32,
+    session_id: i32,
+    keys: &JwtKeys,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = Utc::now();
+    let expiration = now
+        .checked_add_signed(JWT_LIFETIME)
         .expect("valid timestamp")
         .timestamp() as usize;
 
     let claims = Claims {
         sub: user_id.to_string(),
+        sid: session_id,
         exp: expiration,
+        iat: now.timestamp() as usize,
+        iss: keys.issuer.clone(),
+        aud: keys.audience.clone(),
     };
 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_ref()),
-    )
+    let mut header = Header::new(keys.algorithm);
+    header.kid = Some(keys.kid.clone());
+
+    encode(&header, &claims, keys.encoding_key())
 }
 
-pub fn decode_jwt(token: &str, secret: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(secret.as_ref()),
-        &Validation::default(),
-    )?;
+/// Verify a token against the key named by its `kid` header and validate
+/// expiry, issuer and audience (with the configured leeway).
+pub fn decode_jwt(token: &str, keys: &JwtKeys) -> Result<Claims, jsonwebtoken::errors::Error> {
+    let header = decode_header(token)?;
+    let (algorithm, decoding_key) = keys
+        .verification_key(header.kid.as_deref())
+        .ok_or(ErrorKind::InvalidKeyFormat)?;
+
+    let mut validation = Validation::new(*algorithm);
+    validation.leeway = keys.leeway_seconds;
+    // `iss`/`aud` are only compared when present, so a configured issuer or
+    // audience must also be a required claim.
+    let mut required = vec!["exp"];
+    if keys.issuer.is_some() {
+        required.push("iss");
+    }
+    if keys.audience.is_some() {
+        required.push("aud");
+    }
+    validation.set_required_spec_claims(&required);
+    if let Some(issuer) = &keys.issuer {
+        validation.set_issuer(&[issuer]);
+    }
+    match &keys.audience {
+        Some(audience) => validation.set_audience(&[audience]),
+        None => validation.validate_aud = false,
+    }
+
+    let token_data = decode::<Claims>(token, decoding_key, &validation)?;
     Ok(token_data.claims)
 }
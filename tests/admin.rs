@@ -0,0 +1,303 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use chrono::{Duration, Utc};
+use here::entity::prelude::*;
+use here::entity::{AccountType, InvitationStatus, LedgerEntryKind, StaffRole};
+use here::services::export::{export_data, import_data};
+use here::services::reference_data::seed_reference_data;
+use here::services::users::{promote_to_host, set_password, set_user_active};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityTrait, PaginatorTrait};
+use serde_json::json;
+
+use common::{create_event, create_host, create_user, init_app, login, test_state};
+
+#[actix_web::test]
+async fn deactivated_user_loses_sessions_and_cannot_log_in() {
+    let state = test_state().await;
+    let db = state.db.clone();
+    let cache = state.cache.clone();
+    let user = create_user(&state, "ada").await;
+    let app = init_app(state).await;
+    let token = login(&app, "ada").await;
+
+    set_user_active(&db, &cache, user.id, false).await.unwrap();
+
+    let req = test::TestRequest::get()
+        .uri("/users/me")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(serde_json::json!({ "identifier": "ada", "password": common::PASSWORD }))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[actix_web::test]
+async fn reset_password_replaces_the_old_one() {
+    let state = test_state().await;
+    let db = state.db.clone();
+    let cache = state.cache.clone();
+    let rounds = state.config.hash_rounds;
+    let user = create_user(&state, "ada").await;
+    let app = init_app(state).await;
+
+    set_password(&db, &cache, user.id, "brand-new-password", rounds)
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(serde_json::json!({ "identifier": "ada", "password": "brand-new-password" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn promote_to_host_creates_host_profile() {
+    let state = test_state().await;
+    let user = create_user(&state, "ada").await;
+
+    let host = promote_to_host(
+        &state.db,
+        &state.cache,
+        user.id,
+        Some("Ada Events".to_string()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(host.organization_name.as_deref(), Some("Ada Events"));
+
+    // Promoting again keeps the existing profile
+    promote_to_host(&state.db, &state.cache, user.id, None)
+        .await
+        .unwrap();
+    let user = User::find_by_id(user.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(user.account_type, AccountType::Host);
+    assert_eq!(Host::find().count(&state.db).await.unwrap(), 1);
+}
+
+#[actix_web::test]
+async fn seeding_reference_data_is_idempotent() {
+    let state = test_state().await;
+
+    let first = seed_reference_data(&state.db, &state.cache).await.unwrap();
+    assert!(!first.event_categories.is_empty());
+    assert!(!first.motivations.is_empty());
+
+    let second = seed_reference_data(&state.db, &state.cache).await.unwrap();
+    assert!(second.event_categories.is_empty());
+    assert!(second.motivations.is_empty());
+}
+
+#[actix_web::test]
+async fn export_round_trips_into_an_empty_database() {
+    let source = test_state().await;
+    let (_, host) = create_host(&source, "grace").await;
+    let ada = create_user(&source, "ada").await;
+    let event = create_event(&source, &host, "Rust Meetup").await;
+    seed_reference_data(&source.db, &source.cache)
+        .await
+        .unwrap();
+    let app = init_app(source.clone()).await;
+    let grace = login(&app, "grace").await;
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/events/series")
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .set_json(json!({
+                "title": "Rust Night",
+                "description": "Weekly Rust meetup",
+                "location": "Lagos",
+                "event_type": "Physical",
+                "category": "Meetup",
+                "visibility": "Public",
+                "rrule": "FREQ=WEEKLY;COUNT=3",
+                "timezone": "Africa/Lagos",
+                "start_time": (Utc::now() + Duration::days(7))
+                    .date_naive()
+                    .and_hms_opt(19, 0, 0),
+                "duration_minutes": 120,
+            }))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let tier: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/ticket-tiers", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .set_json(json!({ "name": "Free", "price": 0, "currency": "NGN", "quantity": 10 }))
+            .to_request(),
+    )
+    .await;
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/checkout", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .set_json(json!({ "tier_id": tier["id"], "quantity": 2 }))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    LedgerEntryActiveModel {
+        event_id: Set(Some(event.id)),
+        kind: Set(LedgerEntryKind::Charge),
+        amount: Set(5000),
+        currency: Set("NGN".to_string()),
+        provider: Set("fake".to_string()),
+        provider_reference: Set("fake_ord_1".to_string()),
+        description: Set("Payment for order ord_1".to_string()),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&source.db)
+    .await
+    .unwrap();
+    NotificationActiveModel {
+        user_id: Set(host.user_id),
+        kind: Set("event_cancelled".to_string()),
+        title: Set("Rust Meetup was cancelled".to_string()),
+        body: Set("Venue flooded".to_string()),
+        event_id: Set(Some(event.id)),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&source.db)
+    .await
+    .unwrap();
+    let link = EventInviteLinkActiveModel {
+        event_id: Set(event.id),
+        created_by: Set(host.user_id),
+        token_hash: Set("0".repeat(64)),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&source.db)
+    .await
+    .unwrap();
+    EventInvitationActiveModel {
+        event_id: Set(event.id),
+        user_id: Set(ada.id),
+        invite_link_id: Set(Some(link.id)),
+        status: Set(InvitationStatus::Accepted),
+        created_at: Set(Utc::now()),
+        updated_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&source.db)
+    .await
+    .unwrap();
+    EventStaffActiveModel {
+        event_id: Set(event.id),
+        user_id: Set(ada.id),
+        role: Set(StaffRole::Staff),
+        status: Set(InvitationStatus::Accepted),
+        invited_by: Set(Some(host.user_id)),
+        created_at: Set(Utc::now()),
+        updated_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&source.db)
+    .await
+    .unwrap();
+    let category = EventCategories::find()
+        .one(&source.db)
+        .await
+        .unwrap()
+        .unwrap();
+    EventCategoryLinkActiveModel {
+        event_id: Set(event.id),
+        category_id: Set(category.id),
+    }
+    .insert(&source.db)
+    .await
+    .unwrap();
+    let tag = TagActiveModel {
+        name: Set("rust".to_string()),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&source.db)
+    .await
+    .unwrap();
+    EventTagActiveModel {
+        event_id: Set(event.id),
+        tag_id: Set(tag.id),
+    }
+    .insert(&source.db)
+    .await
+    .unwrap();
+    PaymentWebhookEventActiveModel {
+        provider: Set("fake".to_string()),
+        event_id: Set("evt_1".to_string()),
+        event_type: Set("payment_succeeded".to_string()),
+        processed_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&source.db)
+    .await
+    .unwrap();
+
+    let data = export_data(&source.db).await.unwrap();
+    let json = serde_json::to_string(&data).unwrap();
+
+    let target = test_state().await;
+    let report = import_data(
+        &target.db,
+        &target.cache,
+        serde_json::from_str(&json).unwrap(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.users, 2);
+    assert_eq!(report.event_series, 1);
+    assert_eq!(report.events, 4);
+    assert_eq!(report.notifications, 1);
+    // Invite links are secrets and stay behind; the invitation does not
+    assert_eq!(report.event_invitations, 1);
+    assert_eq!(report.event_staff, 1);
+    assert_eq!(report.event_category_links, 1);
+    assert_eq!(report.tags, 1);
+    assert_eq!(report.event_tags, 1);
+    assert_eq!(report.ticket_tiers, 1);
+    assert_eq!(report.ticket_orders, 1);
+    let imported = export_data(&target.db).await.unwrap();
+    assert_eq!(imported.ticket_tiers[0].reserved, 2);
+    assert_eq!(imported.ticket_orders, data.ticket_orders);
+    assert_eq!(imported.ledger_entries, data.ledger_entries);
+    assert_eq!(imported.notifications, data.notifications);
+    assert_eq!(imported.event_invitations[0].user_id, ada.id);
+    assert!(imported.event_invitations[0].invite_link_id.is_none());
+    assert_eq!(imported.event_staff, data.event_staff);
+    assert_eq!(report.payment_webhook_events, 1);
+    assert_eq!(export_data(&target.db).await.unwrap().events, data.events);
+
+    // A second import would clash with the rows now present
+    assert!(
+        import_data(
+            &target.db,
+            &target.cache,
+            serde_json::from_str(&json).unwrap()
+        )
+        .await
+        .is_err()
+    );
+}
@@ -0,0 +1,279 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use chrono::{Duration, Utc};
+use here::entity::api_key::ApiScope;
+use here::entity::prelude::*;
+use here::schemas::api_keys::{ApiKeyResponse, CreatedApiKeyResponse};
+use here::services::api_keys::create_api_key;
+use here::utils::utils::hash_api_key;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityTrait, IntoActiveModel};
+use serde_json::{Value, json};
+
+use common::{create_host, create_user, init_app, login, test_state};
+
+fn series_payload() -> Value {
+    json!({
+        "title": "Rust Night",
+        "description": "Weekly Rust meetup",
+        "location": "Lagos",
+        "event_type": "Physical",
+        "category": "Meetup",
+        "visibility": "Public",
+        "rrule": "FREQ=WEEKLY;COUNT=2",
+        "timezone": "Africa/Lagos",
+        "start_time": (Utc::now() + Duration::days(7))
+            .date_naive()
+            .and_hms_opt(19, 0, 0),
+        "duration_minutes": 120,
+    })
+}
+
+#[actix_web::test]
+async fn created_key_is_shown_once_and_stored_hashed() {
+    let state = test_state().await;
+    create_user(&state, "ada").await;
+    let db = state.db.clone();
+    let app = init_app(state).await;
+    let token = login(&app, "ada").await;
+
+    let req = test::TestRequest::post()
+        .uri("/users/me/api-keys")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "name": "Zapier", "scopes": ["profile:read"] }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let created: CreatedApiKeyResponse = test::read_body_json(resp).await;
+    assert!(
+        created
+            .key
+            .starts_with(&format!("{}_", created.api_key.prefix))
+    );
+    assert_eq!(created.api_key.scopes, vec![ApiScope::ProfileRead]);
+
+    let stored = ApiKey::find_by_id(created.api_key.id)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.key_hash, hash_api_key(&created.key));
+    assert!(!stored.key_hash.contains(&created.key));
+
+    // Listing never shows the key again
+    let req = test::TestRequest::get()
+        .uri("/users/me/api-keys")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let keys: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0]["prefix"], created.api_key.prefix.as_str());
+    assert!(keys[0].get("key").is_none());
+    assert!(keys[0].get("key_hash").is_none());
+
+    let delete = || {
+        test::TestRequest::delete()
+            .uri(&format!("/users/me/api-keys/{}", created.api_key.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    let resp = test::call_service(&app, delete()).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    let resp = test::call_service(&app, delete()).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::get()
+        .uri("/users/me/api-keys")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let keys: Vec<ApiKeyResponse> = test::call_and_read_body_json(&app, req).await;
+    assert!(keys.is_empty());
+
+    // A deleted key no longer authenticates
+    let req = test::TestRequest::get()
+        .uri("/users/me")
+        .insert_header(("Authorization", format!("Bearer {}", created.key)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn keys_authenticate_within_their_scopes() {
+    let state = test_state().await;
+    let (user, _) = create_host(&state, "grace").await;
+    let (_, read_only) = create_api_key(
+        &state.db,
+        user.id,
+        "Dashboard".to_string(),
+        &[ApiScope::ProfileRead],
+        None,
+    )
+    .await
+    .unwrap();
+    let (_, writer) = create_api_key(
+        &state.db,
+        user.id,
+        "Publisher".to_string(),
+        &[ApiScope::EventsWrite],
+        None,
+    )
+    .await
+    .unwrap();
+    let app = init_app(state).await;
+
+    let req = test::TestRequest::get()
+        .uri("/users/me")
+        .insert_header(("Authorization", format!("Bearer {}", read_only)))
+        .to_request();
+    let me: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(me["username"], "grace");
+
+    let create_series = |key: &str| {
+        test::TestRequest::post()
+            .uri("/events/series")
+            .insert_header(("Authorization", format!("Bearer {}", key)))
+            .set_json(series_payload())
+            .to_request()
+    };
+    let resp = test::call_service(&app, create_series(&read_only)).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = test::call_service(&app, create_series(&writer)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    // Keys cannot manage keys, whatever their scopes
+    for key in [&read_only, &writer] {
+        let req = test::TestRequest::get()
+            .uri("/users/me/api-keys")
+            .insert_header(("Authorization", format!("Bearer {}", key)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+    let req = test::TestRequest::post()
+        .uri("/users/me/api-keys")
+        .insert_header(("Authorization", format!("Bearer {}", writer)))
+        .set_json(json!({ "name": "Another", "scopes": ["events:write"] }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // The right prefix with the wrong secret
+    let (prefix, _) = read_only.rsplit_once('_').unwrap();
+    let forged = format!("{}_{}", prefix, "x".repeat(32));
+    let req = test::TestRequest::get()
+        .uri("/users/me")
+        .insert_header(("Authorization", format!("Bearer {}", forged)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn expired_keys_are_rejected() {
+    let state = test_state().await;
+    let user = create_user(&state, "ada").await;
+    let (_, key) = create_api_key(
+        &state.db,
+        user.id,
+        "Old script".to_string(),
+        &[ApiScope::ProfileRead],
+        Some(Utc::now() - Duration::minutes(1)),
+    )
+    .await
+    .unwrap();
+    let app = init_app(state).await;
+
+    let req = test::TestRequest::get()
+        .uri("/users/me")
+        .insert_header(("Authorization", format!("Bearer {}", key)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // Keys cannot be created already expired either
+    let token = login(&app, "ada").await;
+    let req = test::TestRequest::post()
+        .uri("/users/me/api-keys")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({
+            "name": "Backdated",
+            "scopes": ["profile:read"],
+            "expires_at": Utc::now() - Duration::days(1),
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[actix_web::test]
+async fn last_used_is_refreshed_at_most_once_a_minute() {
+    let state = test_state().await;
+    let user = create_user(&state, "ada").await;
+    let (stored, key) = create_api_key(
+        &state.db,
+        user.id,
+        "Cron".to_string(),
+        &[ApiScope::ProfileRead],
+        None,
+    )
+    .await
+    .unwrap();
+    let db = state.db.clone();
+    let app = init_app(state).await;
+
+    let last_used = || {
+        let db = db.clone();
+        async move {
+            ApiKey::find_by_id(stored.id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap()
+                .last_used_at
+        }
+    };
+    let set_last_used = |at| {
+        let db = db.clone();
+        async move {
+            let mut row = ApiKey::find_by_id(stored.id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap()
+                .into_active_model();
+            row.last_used_at = Set(Some(at));
+            row.update(&db).await.unwrap();
+        }
+    };
+    let call = || {
+        test::TestRequest::get()
+            .uri("/users/me")
+            .insert_header(("Authorization", format!("Bearer {}", key)))
+            .to_request()
+    };
+
+    assert!(last_used().await.is_none());
+    assert_eq!(
+        test::call_service(&app, call()).await.status(),
+        StatusCode::OK
+    );
+    assert!(last_used().await.is_some());
+
+    let recent = Utc::now() - Duration::seconds(30);
+    set_last_used(recent).await;
+    assert_eq!(
+        test::call_service(&app, call()).await.status(),
+        StatusCode::OK
+    );
+    assert_eq!(last_used().await, Some(recent));
+
+    let stale = Utc::now() - Duration::seconds(120);
+    set_last_used(stale).await;
+    assert_eq!(
+        test::call_service(&app, call()).await.status(),
+        StatusCode::OK
+    );
+    assert!(last_used().await.is_some_and(|at| at > recent));
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use chrono::{Duration, Utc};
+use here::services::sessions::create_session;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, IntoActiveModel};
+use serde_json::{Value, json};
+
+use common::{PASSWORD, create_user, init_app, login, test_state};
+
+#[actix_web::test]
+async fn login_with_username_or_email_returns_token() {
+    let state = test_state().await;
+    let user = create_user(&state, "ada").await;
+    let app = init_app(state).await;
+
+    for identifier in [user.username.as_str(), user.email.as_str()] {
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(json!({ "identifier": identifier, "password": PASSWORD }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["id"], user.id);
+        assert_eq!(body["username"], "ada");
+        assert!(body["access_token"].as_str().is_some_and(|t| !t.is_empty()));
+    }
+}
+
+#[actix_web::test]
+async fn login_with_wrong_password_is_unauthorized() {
+    let state = test_state().await;
+    create_user(&state, "ada").await;
+    let app = init_app(state).await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({ "identifier": "ada", "password": "not-the-password" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn login_with_unknown_user_is_unauthorized() {
+    let app = init_app(test_state().await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({ "identifier": "nobody", "password": PASSWORD }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn revoked_session_token_is_rejected() {
+    let state = test_state().await;
+    create_user(&state, "ada").await;
+    let app = init_app(state).await;
+    let token = login(&app, "ada").await;
+
+    let req = test::TestRequest::get()
+        .uri("/auth/sessions")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let sessions: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0]["current"], true);
+    let session_id = sessions[0]["id"].as_i64().unwrap();
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/auth/sessions/{}", session_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let req = test::TestRequest::get()
+        .uri("/users/me")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn sessions_with_expired_tokens_are_not_listed() {
+    let state = test_state().await;
+    let user = create_user(&state, "ada").await;
+    let old = create_session(&state.db, user.id, None, None)
+        .await
+        .unwrap();
+    let mut old = old.into_active_model();
+    old.created_at = Set(Utc::now() - Duration::days(2));
+    old.update(&state.db).await.unwrap();
+    let app = init_app(state).await;
+    let token = login(&app, "ada").await;
+
+    let req = test::TestRequest::get()
+        .uri("/auth/sessions")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let sessions: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0]["current"], true);
+}
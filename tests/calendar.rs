@@ -0,0 +1,188 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::http::header;
+use actix_web::test;
+use here::entity::EventVisibility;
+use here::schemas::calendar::CalendarFeedResponse;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, IntoActiveModel};
+use serde_json::json;
+
+use common::{create_event, create_host, create_user, init_app, login, test_state};
+
+/// Unfolded content lines of an iCalendar body.
+fn content_lines(body: &str) -> Vec<String> {
+    body.replace("\r\n ", "")
+        .split("\r\n")
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[actix_web::test]
+async fn events_download_as_ics() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Rust, Coffee; and Chat").await;
+    let mut long = event.clone().into_active_model();
+    long.description = Set("A very long description that has to be folded because it is far longer than seventy-five octets".to_string());
+    long.update(&state.db).await.unwrap();
+    let private = create_event(&state, &host, "Board Meeting").await;
+    let mut hidden = private.clone().into_active_model();
+    hidden.visibility = Set(EventVisibility::Private);
+    hidden.update(&state.db).await.unwrap();
+    let app = init_app(state).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/events/{}.ics", event.id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get(header::CONTENT_TYPE).unwrap(),
+        "text/calendar; charset=utf-8"
+    );
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+
+    assert!(body.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(body.ends_with("END:VCALENDAR\r\n"));
+    assert!(body.split("\r\n").all(|line| line.len() <= 75));
+    let lines = content_lines(&body);
+    for expected in [
+        format!("UID:event-{}@here", event.id),
+        "SUMMARY:Rust\\, Coffee\\; and Chat".to_string(),
+        "LOCATION:Lagos".to_string(),
+        "STATUS:CONFIRMED".to_string(),
+        "ORGANIZER;CN=\"Test grace\":mailto:grace@example.com".to_string(),
+        format!("DTSTART:{}", event.start_time.format("%Y%m%dT%H%M%SZ")),
+    ] {
+        assert!(lines.contains(&expected), "missing {}", expected);
+    }
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.ends_with("seventy-five octets"))
+    );
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/events/{}.ics", private.id))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[actix_web::test]
+async fn feeds_list_registered_events_and_follow_cancellations() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let registered = create_event(&state, &host, "Rust Meetup").await;
+    let other = create_event(&state, &host, "Go Meetup").await;
+    create_user(&state, "ada").await;
+    let app = init_app(state).await;
+
+    let ada = login(&app, "ada").await;
+    let req = test::TestRequest::post()
+        .uri(&format!("/events/{}/rsvp", registered.id))
+        .insert_header(("Authorization", format!("Bearer {}", ada)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::CREATED
+    );
+
+    let create_feed = || {
+        test::TestRequest::post()
+            .uri("/users/me/calendar-feed")
+            .insert_header(("Authorization", format!("Bearer {}", ada)))
+            .to_request()
+    };
+    let resp = test::call_service(&app, create_feed()).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let feed: CalendarFeedResponse = test::read_body_json(resp).await;
+    assert!(feed.webcal_url.starts_with("webcal://"));
+    let path = feed.url.split_once("/calendar/").unwrap().1;
+    let path = format!("/calendar/{}", path);
+
+    let fetch = |path: &str| test::TestRequest::get().uri(path).to_request();
+    let body = test::call_and_read_body(&app, fetch(&path)).await;
+    let lines = content_lines(std::str::from_utf8(&body).unwrap());
+    assert!(lines.contains(&format!("UID:event-{}@here", registered.id)));
+    assert!(!lines.contains(&format!("UID:event-{}@here", other.id)));
+    assert!(lines.contains(&"STATUS:CONFIRMED".to_string()));
+
+    let grace = login(&app, "grace").await;
+    let req = test::TestRequest::post()
+        .uri(&format!("/events/{}/cancel", registered.id))
+        .insert_header(("Authorization", format!("Bearer {}", grace)))
+        .set_json(json!({ "reason": "Venue flooded" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let body = test::call_and_read_body(&app, fetch(&path)).await;
+    let lines = content_lines(std::str::from_utf8(&body).unwrap());
+    assert!(lines.contains(&"STATUS:CANCELLED".to_string()));
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.starts_with("DESCRIPTION:Cancelled: Venue flooded"))
+    );
+
+    // A new feed URL replaces the old one
+    let rotated: CalendarFeedResponse = test::call_and_read_body_json(&app, create_feed()).await;
+    assert_ne!(rotated.url, feed.url);
+    assert_eq!(
+        test::call_service(&app, fetch(&path)).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    let delete = || {
+        test::TestRequest::delete()
+            .uri("/users/me/calendar-feed")
+            .insert_header(("Authorization", format!("Bearer {}", ada)))
+            .to_request()
+    };
+    assert_eq!(
+        test::call_service(&app, delete()).await.status(),
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        test::call_service(&app, delete()).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[actix_web::test]
+async fn calendar_links_ignore_the_request_host() {
+    let mut state = test_state().await;
+    state.config.api_url = Some("https://api.here.example/".to_string());
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Rust Meetup").await;
+    create_user(&state, "ada").await;
+    let app = init_app(state).await;
+
+    let spoofed = |req: test::TestRequest| {
+        req.insert_header((header::HOST, "evil.example"))
+            .insert_header(("X-Forwarded-Host", "evil.example"))
+            .insert_header(("X-Forwarded-Proto", "https"))
+    };
+
+    let ada = login(&app, "ada").await;
+    let req = spoofed(test::TestRequest::post().uri("/users/me/calendar-feed"))
+        .insert_header(("Authorization", format!("Bearer {}", ada)))
+        .to_request();
+    let feed: CalendarFeedResponse = test::call_and_read_body_json(&app, req).await;
+    assert!(feed.url.starts_with("https://api.here.example/calendar/"));
+    assert!(
+        feed.webcal_url
+            .starts_with("webcal://api.here.example/calendar/")
+    );
+
+    let req =
+        spoofed(test::TestRequest::get().uri(&format!("/events/{}.ics", event.id))).to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    let lines = content_lines(std::str::from_utf8(&body).unwrap());
+    assert!(lines.contains(&format!("URL:https://api.here.example/events/{}", event.id)));
+    assert!(!lines.iter().any(|line| line.contains("evil.example")));
+}
@@ -0,0 +1,194 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use here::core::configs::AppState;
+use here::entity::prelude::*;
+use here::entity::{AttendanceStatus, EventStatus, EventType};
+use here::schemas::event::EventResponse;
+use here::schemas::notification::NotificationResponse;
+use here::services::email::event_cancelled_email;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set};
+use serde_json::json;
+
+use common::{create_event, create_host, create_user, init_app, login, test_state};
+
+async fn register(state: &AppState, event_id: i32, username: &str, status: AttendanceStatus) {
+    let user = create_user(state, username).await;
+    AttendeeActiveModel {
+        user_id: Set(user.id),
+        preferred_event_type: Set(EventType::Physical),
+    }
+    .insert(&state.db)
+    .await
+    .unwrap();
+    AttendanceActiveModel {
+        event_id: Set(event_id),
+        attendee_id: Set(user.id),
+        status: Set(status),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await
+    .unwrap();
+}
+
+#[actix_web::test]
+async fn cancelling_notifies_attendees_and_closes_rsvps() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Rust Meetup").await;
+    register(&state, event.id, "ada", AttendanceStatus::Registered).await;
+    register(&state, event.id, "alan", AttendanceStatus::Waitlisted).await;
+    register(&state, event.id, "barbara", AttendanceStatus::NoShow).await;
+    create_user(&state, "linus").await;
+    let app = init_app(state).await;
+    let cancel_uri = format!("/events/{}/cancel", event.id);
+
+    // Somebody else's event does not exist as far as they are concerned
+    let token = login(&app, "linus").await;
+    let req = test::TestRequest::post()
+        .uri(&cancel_uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "reason": "Mine now" }))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    let host_token = login(&app, "grace").await;
+    let cancel = || {
+        test::TestRequest::post()
+            .uri(&cancel_uri)
+            .insert_header(("Authorization", format!("Bearer {}", host_token)))
+            .set_json(json!({ "reason": "Venue flooded" }))
+            .to_request()
+    };
+    let cancelled: EventResponse = test::call_and_read_body_json(&app, cancel()).await;
+    assert_eq!(cancelled.status, EventStatus::Cancelled);
+    assert!(cancelled.cancelled_at.is_some());
+    assert_eq!(cancelled.cancelled_by, Some(host.user_id));
+    assert_eq!(
+        cancelled.cancellation_reason.as_deref(),
+        Some("Venue flooded")
+    );
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/events/{}", event.id))
+        .to_request();
+    let detail: EventResponse = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(detail.cancellation_reason.as_deref(), Some("Venue flooded"));
+
+    assert_eq!(
+        test::call_service(&app, cancel()).await.status(),
+        StatusCode::CONFLICT
+    );
+
+    for (username, expected) in [("ada", 1), ("alan", 1), ("barbara", 0), ("linus", 0)] {
+        let token = login(&app, username).await;
+        let req = test::TestRequest::get()
+            .uri("/notifications")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let notifications: Vec<NotificationResponse> =
+            test::call_and_read_body_json(&app, req).await;
+        assert_eq!(notifications.len(), expected, "{}", username);
+        if let Some(notification) = notifications.first() {
+            assert_eq!(notification.kind, "event_cancelled");
+            assert_eq!(notification.event_id, Some(event.id));
+            assert!(notification.body.contains("Venue flooded"));
+        }
+    }
+
+    let token = login(&app, "linus").await;
+    let req = test::TestRequest::post()
+        .uri(&format!("/events/{}/rsvp", event.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::CONFLICT
+    );
+}
+
+#[actix_web::test]
+async fn rsvp_registers_once_and_notifications_can_be_read() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Rust Meetup").await;
+    create_user(&state, "ada").await;
+    let app = init_app(state).await;
+
+    let token = login(&app, "ada").await;
+    let rsvp = || {
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/rsvp", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    assert_eq!(
+        test::call_service(&app, rsvp()).await.status(),
+        StatusCode::CREATED
+    );
+    assert_eq!(
+        test::call_service(&app, rsvp()).await.status(),
+        StatusCode::OK
+    );
+
+    let host_token = login(&app, "grace").await;
+    let req = test::TestRequest::post()
+        .uri(&format!("/events/{}/cancel", event.id))
+        .insert_header(("Authorization", format!("Bearer {}", host_token)))
+        .set_json(json!({ "reason": "Speaker ill" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let list = |unread: bool| {
+        test::TestRequest::get()
+            .uri(&format!("/notifications?unread={}", unread))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    let notifications: Vec<NotificationResponse> =
+        test::call_and_read_body_json(&app, list(true)).await;
+    assert_eq!(notifications.len(), 1);
+
+    let read_uri = format!("/notifications/{}/read", notifications[0].id);
+    let req = test::TestRequest::post()
+        .uri(&read_uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NO_CONTENT
+    );
+    let unread: Vec<NotificationResponse> = test::call_and_read_body_json(&app, list(true)).await;
+    assert!(unread.is_empty());
+    let all: Vec<NotificationResponse> = test::call_and_read_body_json(&app, list(false)).await;
+    assert!(all[0].read_at.is_some());
+
+    // Other people's notifications are not found
+    let req = test::TestRequest::post()
+        .uri(&read_uri)
+        .insert_header(("Authorization", format!("Bearer {}", host_token)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[actix_web::test]
+async fn cancellation_email_includes_the_reason() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let user = create_user(&state, "ada").await;
+    let mut event = create_event(&state, &host, "Rust Meetup").await;
+    event.cancellation_reason = Some("Venue flooded".to_string());
+
+    let email = event_cancelled_email(&user, &event);
+    assert_eq!(email.to, "ada@example.com");
+    assert_eq!(email.subject, "Cancelled: Rust Meetup");
+    assert!(email.body.contains("Reason: Venue flooded"));
+}
@@ -0,0 +1,149 @@
+//! Shared harness for the integration tests.
+//!
+//! Every test gets its own in-memory SQLite database with all migrations
+//! applied, plus a Redis pool pointed at an address nothing
+//! listens on. deadpool only connects when a connection is checked out, so
+//! the pool stands in for Redis until a test actually needs it.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::test;
+use chrono::{Duration, Utc};
+use here::core::configs::{AppConfig, AppState};
+use here::core::startup::build_app_state;
+use here::entity::prelude::*;
+use here::entity::{EventCategory, EventStatus, EventType, EventVisibility};
+use here::migration;
+use here::utils::utils::hash_password;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ConnectOptions, Database};
+use serde_json::{Value, json};
+
+pub const PASSWORD: &str = "password123";
+pub const SECRET_KEY: &str = "integration-test-secret-key-0123456789";
+
+pub fn test_config() -> AppConfig {
+    let secrets: HashMap<String, String> = [
+        ("SECRET_KEY", SECRET_KEY),
+        ("HASH_ROUNDS", "4"),
+        ("REDIS_URL", "redis://127.0.0.1:1"),
+        ("SMTP_HOST", "localhost"),
+        ("SMTP_PORT", "2525"),
+        ("SMTP_USERNAME", "test"),
+        ("SMTP_PASSWORD", "test"),
+        ("SMTP_FROM_EMAIL", "noreply@example.com"),
+        ("DATABASE_URL", "sqlite::memory:"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+
+    AppConfig::from_secrets(secrets).expect("valid test configuration")
+}
+
+/// Fresh application state backed by an in-memory SQLite database.
+pub async fn test_state() -> AppState {
+    let config = test_config();
+
+    // A single connection keeps every query on the same in-memory database.
+    let mut options = ConnectOptions::new(config.database_url.clone());
+    options
+        .max_connections(1)
+        .min_connections(1)
+        .sqlx_logging(false);
+    let db = Database::connect(options)
+        .await
+        .expect("in-memory SQLite connection");
+    migration::up(&db, None).await.expect("migrations");
+
+    build_app_state(db, config).expect("application state")
+}
+
+/// Initialise the real application around `state`.
+pub async fn init_app(
+    state: AppState,
+) -> impl Service<
+    actix_http::Request,
+    Response = ServiceResponse<impl MessageBody>,
+    Error = actix_web::Error,
+> {
+    test::init_service(here::build_app(state)).await
+}
+
+// --- Factories ---
+
+pub async fn create_user(state: &AppState, username: &str) -> UserModel {
+    let now = Utc::now();
+    UserActiveModel {
+        username: Set(username.to_string()),
+        email: Set(format!("{}@example.com", username)),
+        password: Set(hash_password(PASSWORD, state.config.hash_rounds)),
+        first_name: Set(Some("Test".to_string())),
+        last_name: Set(Some(username.to_string())),
+        account_type: Set(here::entity::AccountType::Attendee),
+        avatar_url: Set(None),
+        is_active: Set(true),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await
+    .expect("insert user")
+}
+
+pub async fn create_host(state: &AppState, username: &str) -> (UserModel, HostModel) {
+    let user = create_user(state, username).await;
+    let host = HostActiveModel {
+        user_id: Set(user.id),
+        organization_name: Set(Some(format!("{} Events", username))),
+        events_hosted_count: Set(0),
+    }
+    .insert(&state.db)
+    .await
+    .expect("insert host");
+    (user, host)
+}
+
+pub async fn create_event(state: &AppState, host: &HostModel, title: &str) -> EventModel {
+    let now = Utc::now();
+    EventActiveModel {
+        title: Set(title.to_string()),
+        description: Set(format!("{} description", title)),
+        location: Set("Lagos".to_string()),
+        event_type: Set(EventType::Physical),
+        category: Set(EventCategory::Meetup),
+        status: Set(EventStatus::Scheduled),
+        visibility: Set(EventVisibility::Public),
+        host_id: Set(host.user_id),
+        start_time: Set(now + Duration::days(7)),
+        end_time: Set(now + Duration::days(7) + Duration::hours(2)),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await
+    .expect("insert event")
+}
+
+// --- Request helpers ---
+
+/// Log `username` in through the API and return the access token.
+pub async fn login<S, B>(app: &S, username: &str) -> String
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({ "identifier": username, "password": PASSWORD }))
+        .to_request();
+    let body: Value = test::call_and_read_body_json(app, req).await;
+    body["access_token"]
+        .as_str()
+        .expect("access token in login response")
+        .to_string()
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use here::core::configs::AppConfig;
+
+use common::{SECRET_KEY, test_config};
+
+fn secrets(overrides: &[(&str, &str)]) -> HashMap<String, String> {
+    let mut secrets: HashMap<String, String> = [
+        ("SECRET_KEY", SECRET_KEY),
+        ("REDIS_URL", "redis://127.0.0.1:1"),
+        ("SMTP_HOST", "localhost"),
+        ("SMTP_USERNAME", "test"),
+        ("SMTP_PASSWORD", "test"),
+        ("SMTP_FROM_EMAIL", "noreply@example.com"),
+        ("DATABASE_URL", "sqlite::memory:"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    for (key, value) in overrides {
+        secrets.insert(key.to_string(), value.to_string());
+    }
+    secrets
+}
+
+fn load_error(overrides: &[(&str, &str)]) -> String {
+    AppConfig::from_secrets(secrets(overrides))
+        .expect_err("configuration should be rejected")
+        .to_string()
+}
+
+#[actix_web::test]
+async fn invalid_values_are_reported_together() {
+    let message = load_error(&[
+        ("SECRET_KEY", "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+        ("REDIS_URL", "http://localhost:6379"),
+        ("DATABASE_URL", "not a url"),
+        ("SMTP_FROM_EMAIL", "nobody"),
+        ("JWT_ALGORITHM", "none"),
+    ]);
+
+    assert!(message.contains("SECRET_KEY is too predictable"));
+    assert!(message.contains("REDIS_URL has unsupported scheme `http`"));
+    assert!(message.contains("DATABASE_URL is not a valid URL"));
+    assert!(message.contains("SMTP_FROM_EMAIL must be an email address"));
+    assert!(message.contains("JWT_ALGORITHM must be HS256, RS256 or EdDSA"));
+    assert!(!message.contains("aaaaaaaa"));
+
+    let message = load_error(&[("SECRET_KEY", "short")]);
+    assert!(message.contains("SECRET_KEY must be at least 32 characters"));
+}
+
+#[actix_web::test]
+async fn unparsable_numbers_are_rejected_instead_of_defaulted() {
+    let message = load_error(&[("HASH_ROUNDS", "abc")]);
+    assert!(message.contains("hash_rounds"));
+
+    let message = load_error(&[("SMTP_PORT", "70000")]);
+    assert!(message.contains("smtp_port"));
+
+    let message = load_error(&[("DEBUG", "maybe")]);
+    assert!(message.contains("debug"));
+}
+
+#[actix_web::test]
+async fn profile_file_is_layered_below_secrets() {
+    let dir = std::env::temp_dir().join(format!("here-config-{}", std::process::id()));
+    fs::create_dir_all(dir.join("config")).unwrap();
+    fs::write(
+        dir.join("config/staging.toml"),
+        "hash_rounds = 6\nlog_format = \"json\"\nsmtp_port = 2525\n",
+    )
+    .unwrap();
+    fs::write(dir.join("Secrets.toml"), "SMTP_PORT = \"2526\"\n").unwrap();
+
+    let config = AppConfig::load_from(
+        &dir,
+        Some(secrets(&[("APP_PROFILE", "staging"), ("HASH_ROUNDS", "5")])),
+    )
+    .unwrap();
+    assert_eq!(config.profile, "staging");
+    assert_eq!(config.hash_rounds, 5);
+    assert_eq!(config.log_format, "json");
+    assert_eq!(config.smtp_port, 2526);
+
+    let missing = AppConfig::load_from(&dir, Some(secrets(&[("APP_PROFILE", "nope")])));
+    assert!(missing.is_err());
+
+    fs::remove_dir_all(PathBuf::from(&dir)).unwrap();
+}
+
+#[actix_web::test]
+async fn prod_profile_forbids_debug() {
+    let message = load_error(&[("APP_PROFILE", "prod"), ("DEBUG", "true")]);
+    assert!(message.contains("DEBUG must be false in the prod profile"));
+
+    let config = AppConfig::from_secrets(secrets(&[("APP_PROFILE", "prod")])).unwrap();
+    assert!(!config.debug);
+    assert_eq!(config.log_format, "json");
+
+    // Deployments that set no profile get production settings
+    let config = AppConfig::from_secrets(secrets(&[])).unwrap();
+    assert_eq!(config.profile, "prod");
+    assert_eq!(config.hash_rounds, 12);
+    assert!(load_error(&[("DEBUG", "true")]).contains("DEBUG must be false in the prod profile"));
+}
+
+#[actix_web::test]
+async fn debug_output_redacts_secrets() {
+    let mut config = test_config();
+    config.redis_url = "redis://:hunter2@cache:6379".to_string();
+    let output = format!("{:?}", config);
+
+    assert!(!output.contains(SECRET_KEY));
+    assert!(!output.contains("hunter2"));
+    assert!(output.contains("secret_key: \"[REDACTED]\""));
+    assert!(
+        config
+            .redacted_entries()
+            .iter()
+            .any(|(name, value)| *name == "smtp_password" && value == "[REDACTED]")
+    );
+}
+
+#[actix_web::test]
+async fn only_prefixed_environment_variables_are_read() {
+    // SAFETY: no other test in this binary reads or writes these variables,
+    // and none of the settings they touch are asserted elsewhere.
+    unsafe {
+        std::env::set_var("PORT", "not-a-port");
+        std::env::set_var("SHUTDOWN_TIMEOUT_SECONDS", "5");
+        std::env::set_var("HERE_SHUTDOWN_TIMEOUT_SECONDS", "45");
+    }
+
+    let config = AppConfig::from_secrets(secrets(&[])).unwrap();
+    assert_eq!(config.port, 8000);
+    assert_eq!(config.shutdown_timeout_seconds, 45);
+
+    unsafe {
+        std::env::remove_var("PORT");
+        std::env::remove_var("SHUTDOWN_TIMEOUT_SECONDS");
+        std::env::remove_var("HERE_SHUTDOWN_TIMEOUT_SECONDS");
+    }
+}
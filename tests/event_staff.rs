@@ -0,0 +1,432 @@
+mod common;
+
+use actix_web::http::{Method, StatusCode};
+use actix_web::test;
+use chrono::{Duration, Utc};
+use here::entity::{AttendanceStatus, EventRole, EventVisibility, InvitationStatus, StaffRole};
+use here::schemas::event::{AttendanceResponse, EventSeriesResponse};
+use here::schemas::event_staff::{ManagedEventResponse, StaffResponse, UserStaffRoleResponse};
+use here::schemas::notification::NotificationResponse;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, IntoActiveModel};
+use serde_json::json;
+
+use common::{create_event, create_host, create_user, init_app, login, test_state};
+
+fn request(
+    method: Method,
+    uri: &str,
+    token: &str,
+    body: Option<serde_json::Value>,
+) -> test::TestRequest {
+    let req = test::TestRequest::default()
+        .method(method)
+        .uri(uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)));
+    match body {
+        Some(body) => req.set_json(body),
+        None => req,
+    }
+}
+
+#[actix_web::test]
+async fn co_hosts_edit_and_staff_check_in() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Launch Party").await;
+    for username in ["ada", "bob", "carol"] {
+        create_user(&state, username).await;
+    }
+    let mallory_user = create_user(&state, "mallory").await;
+    let app = init_app(state).await;
+    let grace = login(&app, "grace").await;
+    let ada = login(&app, "ada").await;
+    let bob = login(&app, "bob").await;
+    let carol = login(&app, "carol").await;
+    let mallory = login(&app, "mallory").await;
+
+    let staff_uri = format!("/events/{}/staff", event.id);
+    let call = |method: Method, uri: &str, token: &str, body: Option<serde_json::Value>| {
+        request(method, uri, token, body).to_request()
+    };
+
+    // Only people who exist, and not the host
+    assert_eq!(
+        test::call_service(
+            &app,
+            call(
+                Method::POST,
+                &staff_uri,
+                &grace,
+                Some(json!({ "user": "nobody", "role": "Staff" }))
+            )
+        )
+        .await
+        .status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    assert_eq!(
+        test::call_service(
+            &app,
+            call(
+                Method::POST,
+                &staff_uri,
+                &grace,
+                Some(json!({ "user": "grace", "role": "CoHost" }))
+            )
+        )
+        .await
+        .status(),
+        StatusCode::CONFLICT
+    );
+    let resp = test::call_service(
+        &app,
+        call(
+            Method::POST,
+            &staff_uri,
+            &grace,
+            Some(json!({ "user": "ada", "role": "CoHost" })),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let ada_role: StaffResponse = test::read_body_json(resp).await;
+    assert_eq!(ada_role.status, InvitationStatus::Pending);
+    let bob_role: StaffResponse = test::call_and_read_body_json(
+        &app,
+        call(
+            Method::POST,
+            &staff_uri,
+            &grace,
+            Some(json!({ "user": "bob@example.com", "role": "Staff" })),
+        ),
+    )
+    .await;
+    assert_eq!(bob_role.role, StaffRole::Staff);
+
+    // Nothing changes hands until the role is accepted
+    assert_eq!(
+        test::call_service(&app, call(Method::GET, &staff_uri, &ada, None))
+            .await
+            .status(),
+        StatusCode::NOT_FOUND
+    );
+    let roles: Vec<UserStaffRoleResponse> =
+        test::call_and_read_body_json(&app, call(Method::GET, "/event-roles", &ada, None)).await;
+    assert_eq!(roles.len(), 1);
+    assert_eq!(roles[0].event.id, event.id);
+    let notifications: Vec<NotificationResponse> =
+        test::call_and_read_body_json(&app, call(Method::GET, "/notifications", &ada, None)).await;
+    assert_eq!(notifications[0].kind, "staff_invitation");
+    assert_eq!(
+        test::call_service(
+            &app,
+            call(
+                Method::POST,
+                &format!("/event-roles/{}/accept", ada_role.id),
+                &mallory,
+                None
+            )
+        )
+        .await
+        .status(),
+        StatusCode::NOT_FOUND
+    );
+    for (token, role) in [(&ada, &ada_role), (&bob, &bob_role)] {
+        let accepted: StaffResponse = test::call_and_read_body_json(
+            &app,
+            call(
+                Method::POST,
+                &format!("/event-roles/{}/accept", role.id),
+                token,
+                None,
+            ),
+        )
+        .await;
+        assert_eq!(accepted.status, InvitationStatus::Accepted);
+    }
+
+    // Co-hosts edit and see the team; staff do neither; only the host adds people
+    let event_uri = format!("/events/{}", event.id);
+    let edit = json!({ "title": "Launch Night" });
+    assert_eq!(
+        test::call_service(
+            &app,
+            call(Method::PATCH, &event_uri, &ada, Some(edit.clone()))
+        )
+        .await
+        .status(),
+        StatusCode::OK
+    );
+    assert_eq!(
+        test::call_service(&app, call(Method::PATCH, &event_uri, &bob, Some(edit)))
+            .await
+            .status(),
+        StatusCode::FORBIDDEN
+    );
+    let team: Vec<StaffResponse> =
+        test::call_and_read_body_json(&app, call(Method::GET, &staff_uri, &ada, None)).await;
+    assert_eq!(team.len(), 2);
+    assert_eq!(
+        test::call_service(&app, call(Method::GET, &staff_uri, &bob, None))
+            .await
+            .status(),
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        test::call_service(
+            &app,
+            call(
+                Method::POST,
+                &staff_uri,
+                &ada,
+                Some(json!({ "user": "carol", "role": "Staff" }))
+            )
+        )
+        .await
+        .status(),
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        test::call_service(
+            &app,
+            call(
+                Method::POST,
+                &format!("{}/cancel", event_uri),
+                &ada,
+                Some(json!({ "reason": "Venue flooded" }))
+            )
+        )
+        .await
+        .status(),
+        StatusCode::FORBIDDEN
+    );
+
+    // Staff check registered attendees in
+    assert_eq!(
+        test::call_service(
+            &app,
+            call(
+                Method::POST,
+                &format!("{}/rsvp", event_uri),
+                &carol,
+                Some(json!({}))
+            )
+        )
+        .await
+        .status(),
+        StatusCode::CREATED
+    );
+    let attendees_uri = format!("{}/attendees", event_uri);
+    let attendees: Vec<AttendanceResponse> =
+        test::call_and_read_body_json(&app, call(Method::GET, &attendees_uri, &bob, None)).await;
+    assert_eq!(attendees.len(), 1);
+    let carol_id = attendees[0].attendee_id;
+    let checked_in: AttendanceResponse = test::call_and_read_body_json(
+        &app,
+        call(
+            Method::POST,
+            &format!("{}/{}/check-in", attendees_uri, carol_id),
+            &bob,
+            None,
+        ),
+    )
+    .await;
+    assert_eq!(checked_in.status, AttendanceStatus::CheckedIn);
+    assert_eq!(
+        test::call_service(
+            &app,
+            call(
+                Method::POST,
+                &format!("{}/{}/check-in", attendees_uri, mallory_user.id),
+                &bob,
+                None
+            )
+        )
+        .await
+        .status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    assert_eq!(
+        test::call_service(&app, call(Method::GET, &attendees_uri, &mallory, None))
+            .await
+            .status(),
+        StatusCode::NOT_FOUND
+    );
+
+    // Everyone's dashboard shows their role
+    for (token, role) in [
+        (&grace, EventRole::Host),
+        (&ada, EventRole::CoHost),
+        (&bob, EventRole::Staff),
+    ] {
+        let managed: Vec<ManagedEventResponse> =
+            test::call_and_read_body_json(&app, call(Method::GET, "/events/managed", token, None))
+                .await;
+        assert_eq!(managed.len(), 1);
+        assert_eq!(managed[0].event.id, event.id);
+        assert_eq!(managed[0].role, role);
+    }
+    let managed: Vec<ManagedEventResponse> =
+        test::call_and_read_body_json(&app, call(Method::GET, "/events/managed", &mallory, None))
+            .await;
+    assert!(managed.is_empty());
+
+    // Stepping down or being removed ends access
+    let declined: StaffResponse = test::call_and_read_body_json(
+        &app,
+        call(
+            Method::POST,
+            &format!("/event-roles/{}/decline", ada_role.id),
+            &ada,
+            None,
+        ),
+    )
+    .await;
+    assert_eq!(declined.status, InvitationStatus::Declined);
+    assert_eq!(
+        test::call_service(&app, call(Method::GET, &staff_uri, &ada, None))
+            .await
+            .status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        test::call_service(
+            &app,
+            call(
+                Method::DELETE,
+                &format!("{}/{}", staff_uri, bob_role.id),
+                &grace,
+                None
+            )
+        )
+        .await
+        .status(),
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        test::call_service(&app, call(Method::GET, &attendees_uri, &bob, None))
+            .await
+            .status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[actix_web::test]
+async fn staff_can_see_private_events() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let mut event = create_event(&state, &host, "Board Meeting")
+        .await
+        .into_active_model();
+    event.visibility = Set(EventVisibility::Private);
+    let event = event.update(&state.db).await.unwrap();
+    create_user(&state, "bob").await;
+    let app = init_app(state).await;
+    let grace = login(&app, "grace").await;
+    let bob = login(&app, "bob").await;
+
+    let event_uri = format!("/events/{}", event.id);
+    let get_event = || request(Method::GET, &event_uri, &bob, None).to_request();
+    assert_eq!(
+        test::call_service(&app, get_event()).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    let role: StaffResponse = test::call_and_read_body_json(
+        &app,
+        request(
+            Method::POST,
+            &format!("{}/staff", event_uri),
+            &grace,
+            Some(json!({ "user": "bob", "role": "Staff" })),
+        )
+        .to_request(),
+    )
+    .await;
+    // Asked is not enough
+    assert_eq!(
+        test::call_service(&app, get_event()).await.status(),
+        StatusCode::NOT_FOUND
+    );
+    let req = request(
+        Method::POST,
+        &format!("/event-roles/{}/accept", role.id),
+        &bob,
+        None,
+    )
+    .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    assert_eq!(
+        test::call_service(&app, get_event()).await.status(),
+        StatusCode::OK
+    );
+}
+
+#[actix_web::test]
+async fn co_hosts_can_see_private_series() {
+    let state = test_state().await;
+    create_host(&state, "grace").await;
+    create_user(&state, "bob").await;
+    let app = init_app(state).await;
+    let grace = login(&app, "grace").await;
+    let bob = login(&app, "bob").await;
+
+    let series: EventSeriesResponse = test::call_and_read_body_json(
+        &app,
+        request(
+            Method::POST,
+            "/events/series",
+            &grace,
+            Some(json!({
+                "title": "Board Meeting",
+                "description": "Monthly board meeting",
+                "location": "Lagos",
+                "event_type": "Physical",
+                "category": "Meetup",
+                "visibility": "Private",
+                "rrule": "FREQ=MONTHLY;COUNT=2",
+                "timezone": "Africa/Lagos",
+                "start_time": (Utc::now() + Duration::days(7))
+                    .date_naive()
+                    .and_hms_opt(10, 0, 0),
+                "duration_minutes": 60,
+            })),
+        )
+        .to_request(),
+    )
+    .await;
+    let series_uri = format!("/events/series/{}", series.id);
+    let get_series = || request(Method::GET, &series_uri, &bob, None).to_request();
+    assert_eq!(
+        test::call_service(&app, get_series()).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    // Co-hosting one occurrence is enough, as it is for the event itself
+    let event_uri = format!("/events/{}", series.occurrences[1].id);
+    let role: StaffResponse = test::call_and_read_body_json(
+        &app,
+        request(
+            Method::POST,
+            &format!("{}/staff", event_uri),
+            &grace,
+            Some(json!({ "user": "bob", "role": "CoHost" })),
+        )
+        .to_request(),
+    )
+    .await;
+    let req = request(
+        Method::POST,
+        &format!("/event-roles/{}/accept", role.id),
+        &bob,
+        None,
+    )
+    .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    let req = request(Method::GET, &event_uri, &bob, None).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    let resp = test::call_service(&app, get_series()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let shown: EventSeriesResponse = test::read_body_json(resp).await;
+    assert_eq!(shown.occurrences.len(), 2);
+}
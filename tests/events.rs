@@ -0,0 +1,87 @@
+mod common;
+
+use std::cell::Cell;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use here::core::cache::{Cache, EVENTS};
+use here::entity::EventVisibility;
+use here::entity::prelude::*;
+use here::schemas::event::{EventResponse, ReferenceDataResponse};
+use here::services::reference_data::seed_reference_data;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, IntoActiveModel};
+
+use common::{create_event, create_host, init_app, test_state};
+
+#[actix_web::test]
+async fn public_events_are_listed_and_private_ones_hidden() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let public = create_event(&state, &host, "Rust Meetup").await;
+    let private = create_event(&state, &host, "Board Meeting").await;
+    let mut hidden = private.clone().into_active_model();
+    hidden.visibility = Set(EventVisibility::Private);
+    hidden.update(&state.db).await.unwrap();
+    let app = init_app(state).await;
+
+    let req = test::TestRequest::get().uri("/events").to_request();
+    let events: Vec<EventResponse> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].id, public.id);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/events/{}", public.id))
+        .to_request();
+    let event: EventResponse = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(event.title, "Rust Meetup");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/events/{}", private.id))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    let req = test::TestRequest::get().uri("/events?limit=0").to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+}
+
+#[actix_web::test]
+async fn reads_fall_back_to_the_database_when_redis_is_down() {
+    // The test config points Redis at a closed port
+    let state = test_state().await;
+    assert!(state.cache.is_enabled());
+    seed_reference_data(&state.db, &state.cache).await.unwrap();
+    let app = init_app(state).await;
+
+    let req = test::TestRequest::get().uri("/reference-data").to_request();
+    let body: ReferenceDataResponse = test::call_and_read_body_json(&app, req).await;
+    assert!(!body.event_categories.is_empty());
+    assert!(!body.motivations.is_empty());
+
+    let req = test::TestRequest::get().uri("/metrics").to_request();
+    let metrics = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(metrics.contains(r#"here_cache_requests_total{cache="reference",result="error"} 1"#));
+}
+
+#[actix_web::test]
+async fn disabled_cache_always_loads() {
+    let cache = Cache::disabled();
+    let loads = Cell::new(0);
+
+    for _ in 0..2 {
+        let value: Option<EventModel> = cache
+            .get_or_load(EVENTS, "1", || async {
+                loads.set(loads.get() + 1);
+                Ok(None)
+            })
+            .await
+            .unwrap();
+        assert!(value.is_none());
+    }
+    assert_eq!(loads.get(), 2);
+}
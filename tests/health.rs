@@ -0,0 +1,38 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use serde_json::Value;
+
+use common::{init_app, test_state};
+
+#[actix_web::test]
+async fn live_reports_version_and_uptime() {
+    let app = init_app(test_state().await).await;
+
+    let req = test::TestRequest::get().uri("/health/live").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert!(body["uptime_seconds"].as_i64().is_some_and(|s| s >= 0));
+}
+
+#[actix_web::test]
+async fn ready_is_unavailable_while_redis_is_down() {
+    // The test harness points Redis at a port nothing listens on
+    let app = init_app(test_state().await).await;
+
+    let req = test::TestRequest::get().uri("/health/ready").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["status"], "unavailable");
+    assert_eq!(body["checks"]["database"]["status"], "up");
+    assert_eq!(body["checks"]["redis"]["status"], "down");
+    assert_eq!(body["checks"]["redis"]["critical"], true);
+    assert!(body["checks"].get("smtp").is_none());
+}
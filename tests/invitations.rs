@@ -0,0 +1,286 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use chrono::{Duration, Utc};
+use here::core::configs::AppState;
+use here::entity::prelude::*;
+use here::entity::{EventVisibility, InvitationStatus};
+use here::schemas::invitation::{
+    CreatedInviteLinkResponse, InvitationResponse, InviteLinkResponse, UserInvitationResponse,
+};
+use here::schemas::notification::NotificationResponse;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, IntoActiveModel};
+use serde_json::json;
+
+use common::{create_event, create_host, create_user, init_app, login, test_state};
+
+async fn create_private_event(state: &AppState, title: &str) -> EventModel {
+    let (_, host) = create_host(state, "grace").await;
+    let mut event = create_event(state, &host, title).await.into_active_model();
+    event.visibility = Set(EventVisibility::Private);
+    event.update(&state.db).await.unwrap()
+}
+
+#[actix_web::test]
+async fn private_events_are_visible_only_to_invitees() {
+    let state = test_state().await;
+    let event = create_private_event(&state, "Board Meeting").await;
+    for username in ["ada", "bob", "mallory"] {
+        create_user(&state, username).await;
+    }
+    let app = init_app(state).await;
+    let grace = login(&app, "grace").await;
+    let ada = login(&app, "ada").await;
+    let bob = login(&app, "bob").await;
+    let mallory = login(&app, "mallory").await;
+
+    let get = |uri: String, token: Option<&str>| {
+        let req = test::TestRequest::get().uri(&uri);
+        match token {
+            Some(token) => req.insert_header(("Authorization", format!("Bearer {}", token))),
+            None => req,
+        }
+        .to_request()
+    };
+    let post = |uri: String, token: &str, body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(body)
+            .to_request()
+    };
+    let event_uri = format!("/events/{}", event.id);
+
+    // Uninvited callers cannot read, download or RSVP
+    for token in [None, Some(ada.as_str())] {
+        assert_eq!(
+            test::call_service(&app, get(event_uri.clone(), token))
+                .await
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            test::call_service(&app, get(format!("{}.ics", event_uri), token))
+                .await
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+    }
+    let rsvp_uri = format!("/events/{}/rsvp", event.id);
+    assert_eq!(
+        test::call_service(&app, post(rsvp_uri.clone(), &ada, json!({})))
+            .await
+            .status(),
+        StatusCode::NOT_FOUND
+    );
+
+    // Only the host can invite, and only people who exist
+    let invitations_uri = format!("/events/{}/invitations", event.id);
+    let invite = |token: &str, invitees: serde_json::Value| {
+        post(
+            invitations_uri.clone(),
+            token,
+            json!({ "invitees": invitees }),
+        )
+    };
+    assert_eq!(
+        test::call_service(&app, invite(&mallory, json!(["mallory"])))
+            .await
+            .status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        test::call_service(&app, invite(&grace, json!(["ada", "nobody"])))
+            .await
+            .status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    let invited: Vec<InvitationResponse> =
+        test::call_and_read_body_json(&app, invite(&grace, json!(["ada", "bob@example.com"])))
+            .await;
+    assert_eq!(invited.len(), 2);
+    assert!(
+        invited
+            .iter()
+            .all(|invitation| invitation.status == InvitationStatus::Pending)
+    );
+    let ada_invitation = invited[0].id;
+
+    // Invitees see the event, their invitation and a notification
+    assert_eq!(
+        test::call_service(&app, get(event_uri.clone(), Some(&ada)))
+            .await
+            .status(),
+        StatusCode::OK
+    );
+    let mine: Vec<UserInvitationResponse> =
+        test::call_and_read_body_json(&app, get("/invitations".to_string(), Some(&ada))).await;
+    assert_eq!(mine.len(), 1);
+    assert_eq!(mine[0].event.id, event.id);
+    let notifications: Vec<NotificationResponse> =
+        test::call_and_read_body_json(&app, get("/notifications".to_string(), Some(&ada))).await;
+    assert_eq!(notifications[0].kind, "event_invitation");
+    assert_eq!(notifications[0].event_id, Some(event.id));
+
+    // Accepting registers; declining is remembered
+    let accepted: InvitationResponse = test::call_and_read_body_json(
+        &app,
+        post(
+            format!("/invitations/{}/accept", ada_invitation),
+            &ada,
+            json!({}),
+        ),
+    )
+    .await;
+    assert_eq!(accepted.status, InvitationStatus::Accepted);
+    assert_eq!(
+        test::call_service(&app, post(rsvp_uri.clone(), &ada, json!({})))
+            .await
+            .status(),
+        StatusCode::OK
+    );
+    let declined: InvitationResponse = test::call_and_read_body_json(
+        &app,
+        post(
+            format!("/invitations/{}/decline", invited[1].id),
+            &bob,
+            json!({}),
+        ),
+    )
+    .await;
+    assert_eq!(declined.status, InvitationStatus::Declined);
+    // Nobody else can answer for them
+    assert_eq!(
+        test::call_service(
+            &app,
+            post(
+                format!("/invitations/{}/accept", invited[1].id),
+                &mallory,
+                json!({})
+            )
+        )
+        .await
+        .status(),
+        StatusCode::NOT_FOUND
+    );
+
+    // Withdrawing the invitation takes the RSVP and access with it
+    let req = test::TestRequest::delete()
+        .uri(&format!("{}/{}", invitations_uri, ada_invitation))
+        .insert_header(("Authorization", format!("Bearer {}", grace)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        test::call_service(&app, get(event_uri.clone(), Some(&ada)))
+            .await
+            .status(),
+        StatusCode::NOT_FOUND
+    );
+    let listed: Vec<InvitationResponse> =
+        test::call_and_read_body_json(&app, get(invitations_uri.clone(), Some(&grace))).await;
+    assert_eq!(listed.len(), 1);
+}
+
+#[actix_web::test]
+async fn invite_links_respect_limits_and_revocation() {
+    let state = test_state().await;
+    let event = create_private_event(&state, "Board Meeting").await;
+    for username in ["ada", "bob", "carol"] {
+        create_user(&state, username).await;
+    }
+    let app = init_app(state).await;
+    let grace = login(&app, "grace").await;
+
+    let links_uri = format!("/events/{}/invite-links", event.id);
+    let create_link = |body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&links_uri)
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .set_json(body)
+            .to_request()
+    };
+    assert_eq!(
+        test::call_service(
+            &app,
+            create_link(json!({ "expires_at": Utc::now() - Duration::hours(1) }))
+        )
+        .await
+        .status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    let resp = test::call_service(&app, create_link(json!({ "max_uses": 1 }))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let link: CreatedInviteLinkResponse = test::read_body_json(resp).await;
+    assert!(link.url.ends_with(&format!("/invite/{}", link.token)));
+
+    let redeem = |token: &str, link_token: &str| {
+        test::TestRequest::post()
+            .uri("/invitations/redeem")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({ "token": link_token }))
+            .to_request()
+    };
+    let ada = login(&app, "ada").await;
+    let first: InvitationResponse =
+        test::call_and_read_body_json(&app, redeem(&ada, &link.token)).await;
+    assert_eq!(first.invite_link_id, Some(link.link.id));
+    assert_eq!(first.status, InvitationStatus::Pending);
+    // Redeeming again uses nothing up
+    let again: InvitationResponse =
+        test::call_and_read_body_json(&app, redeem(&ada, &link.token)).await;
+    assert_eq!(again.id, first.id);
+
+    let bob = login(&app, "bob").await;
+    assert_eq!(
+        test::call_service(&app, redeem(&bob, &link.token))
+            .await
+            .status(),
+        StatusCode::GONE
+    );
+    let req = test::TestRequest::get()
+        .uri(&format!("/events/{}", event.id))
+        .insert_header(("Authorization", format!("Bearer {}", bob)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    // Revoked links stop working; unknown ones were never there
+    let unlimited: CreatedInviteLinkResponse =
+        test::call_and_read_body_json(&app, create_link(json!({}))).await;
+    let req = test::TestRequest::delete()
+        .uri(&format!("{}/{}", links_uri, unlimited.link.id))
+        .insert_header(("Authorization", format!("Bearer {}", grace)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NO_CONTENT
+    );
+    let carol = login(&app, "carol").await;
+    assert_eq!(
+        test::call_service(&app, redeem(&carol, &unlimited.token))
+            .await
+            .status(),
+        StatusCode::GONE
+    );
+    assert_eq!(
+        test::call_service(&app, redeem(&carol, "not-a-real-token"))
+            .await
+            .status(),
+        StatusCode::NOT_FOUND
+    );
+
+    let req = test::TestRequest::get()
+        .uri(&links_uri)
+        .insert_header(("Authorization", format!("Bearer {}", grace)))
+        .to_request();
+    let links: Vec<InviteLinkResponse> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(links.len(), 2);
+    assert!(links.iter().all(|link| !link.active));
+    assert_eq!(links[1].use_count, 1);
+}
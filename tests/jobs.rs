@@ -0,0 +1,221 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use chrono::Duration;
+use here::entity::prelude::*;
+use here::jobs::queue::retry_delay;
+use here::jobs::{Job, perform};
+use here::services::email::RecordingMailer;
+use here::services::users::set_user_admin;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityTrait, IntoActiveModel};
+use serde_json::json;
+
+use common::{PASSWORD, create_event, create_host, create_user, init_app, login, test_state};
+
+/// The token from the link in an email body.
+fn token_from(body: &str) -> String {
+    let start = body.find("token=").expect("link with a token") + "token=".len();
+    body[start..]
+        .split_whitespace()
+        .next()
+        .expect("token")
+        .to_string()
+}
+
+#[actix_web::test]
+async fn password_reset_email_link_sets_a_new_password() {
+    let mut state = test_state().await;
+    let mailer = Arc::new(RecordingMailer::default());
+    state.mailer = mailer.clone();
+    let user = create_user(&state, "ada").await;
+
+    perform(&Job::SendPasswordResetEmail { user_id: user.id }, &state)
+        .await
+        .unwrap();
+    let sent = mailer.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].to, "ada@example.com");
+    assert!(
+        sent[0]
+            .body
+            .contains("http://localhost:8000/reset-password?token=")
+    );
+    let token = token_from(&sent[0].body);
+
+    let app = init_app(state).await;
+    let reset = |token: String| {
+        test::TestRequest::post()
+            .uri("/auth/password-reset")
+            .set_json(json!({ "token": token, "password": "a-brand-new-password" }))
+            .to_request()
+    };
+    assert_eq!(
+        test::call_service(&app, reset(token.clone()))
+            .await
+            .status(),
+        StatusCode::NO_CONTENT
+    );
+    // Tokens are single use
+    assert_eq!(
+        test::call_service(&app, reset(token)).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({ "identifier": "ada", "password": PASSWORD }))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({ "identifier": "ada", "password": "a-brand-new-password" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn verification_email_link_verifies_the_address() {
+    let mut state = test_state().await;
+    let mailer = Arc::new(RecordingMailer::default());
+    state.mailer = mailer.clone();
+    let user = create_user(&state, "grace").await;
+    let job = Job::SendVerificationEmail { user_id: user.id };
+
+    perform(&job, &state).await.unwrap();
+    let token = token_from(&mailer.sent()[0].body);
+
+    let app = init_app(state.clone()).await;
+    let req = test::TestRequest::post()
+        .uri("/auth/verify-email")
+        .set_json(json!({ "token": token }))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NO_CONTENT
+    );
+    let user = User::find_by_id(user.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(user.email_verified_at.is_some());
+
+    // Running the job again, e.g. after a retry, sends nothing once verified
+    perform(&job, &state).await.unwrap();
+    assert_eq!(mailer.sent().len(), 1);
+}
+
+#[actix_web::test]
+async fn job_admin_requires_an_administrator() {
+    let state = test_state().await;
+    let admin = create_user(&state, "root").await;
+    set_user_admin(&state.db, &state.cache, admin.id, true)
+        .await
+        .unwrap();
+    create_user(&state, "alan").await;
+    let app = init_app(state).await;
+
+    let token = login(&app, "alan").await;
+    let req = test::TestRequest::get()
+        .uri("/admin/jobs")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    // The test config points Redis at a closed port
+    let token = login(&app, "root").await;
+    let req = test::TestRequest::get()
+        .uri("/admin/jobs")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+}
+
+#[actix_web::test]
+async fn signup_and_reset_requests_succeed_while_the_queue_is_down() {
+    let state = test_state().await;
+    let app = init_app(state).await;
+
+    let req = test::TestRequest::post()
+        .uri("/users/signup")
+        .set_json(json!({
+            "username": "linus",
+            "email": "linus@example.com",
+            "password": PASSWORD,
+        }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    // Same response for known and unknown addresses
+    for email in ["linus@example.com", "nobody@example.com"] {
+        let req = test::TestRequest::post()
+            .uri("/auth/password-reset/request")
+            .set_json(json!({ "email": email }))
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::ACCEPTED
+        );
+    }
+}
+
+#[actix_web::test]
+async fn retries_back_off_exponentially_up_to_an_hour() {
+    assert_eq!(retry_delay(1), Duration::seconds(30));
+    assert_eq!(retry_delay(2), Duration::seconds(60));
+    assert_eq!(retry_delay(5), Duration::seconds(480));
+    assert_eq!(retry_delay(8), Duration::hours(1));
+    assert_eq!(retry_delay(u32::MAX), Duration::hours(1));
+}
+
+#[actix_web::test]
+async fn reminders_for_an_old_start_time_are_skipped() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Rust Conf").await;
+    create_user(&state, "ada").await;
+    let app = init_app(state.clone()).await;
+    let ada = login(&app, "ada").await;
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/rsvp", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", ada)))
+            .set_json(json!({}))
+            .to_request(),
+    )
+    .await;
+    assert!(resp.status().is_success());
+
+    // Moved a day later after the first reminder was scheduled
+    let old_start = event.start_time;
+    let mut moved = event.clone().into_active_model();
+    moved.start_time = Set(old_start + Duration::days(1));
+    moved.end_time = Set(event.end_time + Duration::days(1));
+    let moved = moved.update(&state.db).await.unwrap();
+
+    // The stale reminder returns without queueing any email, while the
+    // current one goes on to queue them (and fails, as Redis is down)
+    let stale = Job::EventReminder {
+        event_id: event.id,
+        start_time: old_start,
+    };
+    assert!(perform(&stale, &state).await.is_ok());
+    let current = Job::EventReminder {
+        event_id: event.id,
+        start_time: moved.start_time,
+    };
+    assert!(perform(&current, &state).await.is_err());
+}
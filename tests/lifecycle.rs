@@ -0,0 +1,181 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use chrono::{Duration, Utc};
+use here::core::configs::AppState;
+use here::core::domain_events::DomainEvent;
+use here::entity::prelude::*;
+use here::entity::{AttendanceStatus, EventStatus, EventType};
+use here::schemas::event::EventResponse;
+use here::services::event_lifecycle::advance_due_events;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityTrait, IntoActiveModel};
+use serde_json::json;
+
+use common::{create_event, create_host, create_user, init_app, login, test_state};
+
+async fn register(
+    state: &AppState,
+    event_id: i32,
+    username: &str,
+    status: AttendanceStatus,
+) -> AttendanceModel {
+    let user = create_user(state, username).await;
+    AttendeeActiveModel {
+        user_id: Set(user.id),
+        preferred_event_type: Set(EventType::Physical),
+    }
+    .insert(&state.db)
+    .await
+    .unwrap();
+    AttendanceActiveModel {
+        event_id: Set(event_id),
+        attendee_id: Set(user.id),
+        status: Set(status),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await
+    .unwrap()
+}
+
+#[actix_web::test]
+async fn events_advance_with_time_and_mark_no_shows() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let past = create_event(&state, &host, "Yesterday's Meetup").await;
+    let mut ended = past.clone().into_active_model();
+    ended.start_time = Set(Utc::now() - Duration::days(1));
+    ended.end_time = Set(Utc::now() - Duration::days(1) + Duration::hours(2));
+    ended.update(&state.db).await.unwrap();
+    let future = create_event(&state, &host, "Next Week").await;
+
+    let absent = register(&state, past.id, "ada", AttendanceStatus::Registered).await;
+    let present = register(&state, past.id, "alan", AttendanceStatus::CheckedIn).await;
+    let mut events = state.domain_events.subscribe();
+
+    let transitions = advance_due_events(&state.db, &state.cache, &state.domain_events)
+        .await
+        .unwrap();
+    assert_eq!(transitions, 2);
+
+    let past = Event::find_by_id(past.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(past.status, EventStatus::Completed);
+    let future = Event::find_by_id(future.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(future.status, EventStatus::Scheduled);
+
+    for (attendance, expected) in [
+        (absent, AttendanceStatus::NoShow),
+        (present, AttendanceStatus::CheckedIn),
+    ] {
+        let attendance = Attendance::find_by_id(attendance.id)
+            .one(&state.db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(attendance.status, expected);
+    }
+
+    let mut published = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        published.push(event);
+    }
+    assert!(matches!(
+        published.as_slice(),
+        [
+            DomainEvent::EventStatusChanged {
+                from: EventStatus::Scheduled,
+                to: EventStatus::Ongoing,
+                changed_by: None,
+                ..
+            },
+            DomainEvent::EventStatusChanged {
+                from: EventStatus::Ongoing,
+                to: EventStatus::Completed,
+                ..
+            },
+            DomainEvent::AttendeesMarkedNoShow { count: 1, .. },
+        ]
+    ));
+
+    // Nothing left to do
+    let transitions = advance_due_events(&state.db, &state.cache, &state.domain_events)
+        .await
+        .unwrap();
+    assert_eq!(transitions, 0);
+}
+
+#[actix_web::test]
+async fn hosts_change_status_within_the_state_machine() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    create_user(&state, "mallory").await;
+    let event = create_event(&state, &host, "Rust Meetup").await;
+    let app = init_app(state).await;
+    let uri = format!("/events/{}/status", event.id);
+
+    let token = login(&app, "mallory").await;
+    let req = test::TestRequest::patch()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "status": "Ongoing" }))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    let token = login(&app, "grace").await;
+    let set_status = |status: &str| {
+        test::TestRequest::patch()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({ "status": status }))
+            .to_request()
+    };
+
+    let updated: EventResponse = test::call_and_read_body_json(&app, set_status("Ongoing")).await;
+    assert_eq!(updated.status, EventStatus::Ongoing);
+    let updated: EventResponse = test::call_and_read_body_json(&app, set_status("Completed")).await;
+    assert_eq!(updated.status, EventStatus::Completed);
+
+    for status in ["Scheduled", "Ongoing"] {
+        assert_eq!(
+            test::call_service(&app, set_status(status)).await.status(),
+            StatusCode::CONFLICT
+        );
+    }
+    // Cancelling has its own endpoint so attendees are told
+    assert_eq!(
+        test::call_service(&app, set_status("Cancelled"))
+            .await
+            .status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+}
+
+#[actix_web::test]
+async fn completed_and_cancelled_are_final() {
+    use EventStatus::*;
+
+    assert!(Scheduled.can_transition_to(Ongoing));
+    assert!(Scheduled.can_transition_to(Cancelled));
+    assert!(Ongoing.can_transition_to(Completed));
+    assert!(Ongoing.can_transition_to(Cancelled));
+    assert!(!Scheduled.can_transition_to(Completed));
+    assert!(!Ongoing.can_transition_to(Scheduled));
+    for from in [Completed, Cancelled] {
+        assert!(from.is_final());
+        for to in [Scheduled, Ongoing, Completed, Cancelled] {
+            assert!(!from.can_transition_to(to));
+        }
+    }
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use serde_json::json;
+
+use common::{create_user, init_app, test_config, test_state};
+
+async fn scrape<S, B>(app: &S) -> String
+where
+    S: actix_web::dev::Service<
+            actix_http::Request,
+            Response = actix_web::dev::ServiceResponse<B>,
+            Error = actix_web::Error,
+        >,
+    B: actix_web::body::MessageBody,
+{
+    let req = test::TestRequest::get().uri("/metrics").to_request();
+    let body = test::call_and_read_body(app, req).await;
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[actix_web::test]
+async fn requests_are_counted_by_route_template() {
+    let app = init_app(test_state().await).await;
+
+    let req = test::TestRequest::delete()
+        .uri("/auth/sessions/42")
+        .to_request();
+    test::call_service(&app, req).await;
+
+    let body = scrape(&app).await;
+    assert!(body.contains(
+        r#"here_http_requests_total{method="DELETE",route="/auth/sessions/{id}",status="401"} 1"#
+    ));
+    assert!(body.contains("here_http_request_duration_seconds_bucket"));
+    assert!(body.contains("here_db_query_duration_seconds_bucket"));
+    assert!(body.contains(r#"here_redis_pool_connections{state="max"}"#));
+}
+
+#[actix_web::test]
+async fn login_outcomes_are_counted() {
+    let state = test_state().await;
+    create_user(&state, "ada").await;
+    let app = init_app(state).await;
+
+    for password in [common::PASSWORD, "wrong-password"] {
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(json!({ "identifier": "ada", "password": password }))
+            .to_request();
+        test::call_service(&app, req).await;
+    }
+
+    let body = scrape(&app).await;
+    assert!(body.contains(r#"here_login_attempts_total{outcome="success"} 1"#));
+    assert!(body.contains(r#"here_login_attempts_total{outcome="failure"} 1"#));
+    assert!(body.contains("here_active_users 1"));
+}
+
+#[actix_web::test]
+async fn metrics_token_is_enforced_when_configured() {
+    let mut config = test_config();
+    config.metrics_token = Some("scrape-secret".to_string());
+    let mut state = test_state().await;
+    state.config = config;
+    let app = init_app(state).await;
+
+    let req = test::TestRequest::get().uri("/metrics").to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/metrics")
+        .insert_header(("Authorization", "Bearer scrape-secret"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use here::core::startup::check_migrations;
+use here::migration;
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection};
+
+async fn empty_db() -> DatabaseConnection {
+    let mut options = ConnectOptions::new("sqlite::memory:");
+    options
+        .max_connections(1)
+        .min_connections(1)
+        .sqlx_logging(false);
+    Database::connect(options).await.unwrap()
+}
+
+async fn table_exists(db: &DatabaseConnection, table: &str) -> bool {
+    db.query_one_raw(sea_orm::Statement::from_sql_and_values(
+        db.get_database_backend(),
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = ?",
+        [table.into()],
+    ))
+    .await
+    .unwrap()
+    .is_some()
+}
+
+#[actix_web::test]
+async fn fresh_database_is_not_up_to_date() {
+    let db = empty_db().await;
+
+    let pending = migration::pending(&db).await.unwrap();
+    assert_eq!(pending.len(), migration::migrations().len());
+    assert!(migration::ensure_up_to_date(&db).await.is_err());
+}
+
+#[actix_web::test]
+async fn up_applies_every_migration_once() {
+    let db = empty_db().await;
+
+    let ran = migration::up(&db, None).await.unwrap();
+    assert_eq!(ran.len(), migration::migrations().len());
+    assert!(migration::ensure_up_to_date(&db).await.is_ok());
+    assert!(table_exists(&db, "users").await);
+    assert!(table_exists(&db, "sessions").await);
+
+    // Running again is a no-op
+    assert!(migration::up(&db, None).await.unwrap().is_empty());
+    assert!(
+        migration::status(&db)
+            .await
+            .unwrap()
+            .iter()
+            .all(|s| s.applied_at.is_some())
+    );
+}
+
+#[actix_web::test]
+async fn up_and_down_respect_steps() {
+    let db = empty_db().await;
+
+    let ran = migration::up(&db, Some(1)).await.unwrap();
+    assert_eq!(ran, vec![migration::migrations()[0].name()]);
+    assert!(table_exists(&db, "users").await);
+    assert!(!table_exists(&db, "sessions").await);
+
+    migration::up(&db, None).await.unwrap();
+    let reverted = migration::down(&db, 1).await.unwrap();
+    assert_eq!(
+        reverted,
+        vec![migration::migrations().last().unwrap().name()]
+    );
+    assert_eq!(migration::pending(&db).await.unwrap(), reverted);
+}
+
+#[actix_web::test]
+async fn down_rolls_back_everything() {
+    let db = empty_db().await;
+    migration::up(&db, None).await.unwrap();
+
+    let reverted = migration::down(&db, usize::MAX).await.unwrap();
+    assert_eq!(reverted.len(), migration::migrations().len());
+    assert!(!table_exists(&db, "users").await);
+
+    // And the schema can be rebuilt afterwards
+    migration::up(&db, None).await.unwrap();
+    assert!(table_exists(&db, "users").await);
+}
+
+/// A database as the server left it when it synced the schema from the
+/// entity registry on startup: the base tables with a user, but no
+/// migrations table.
+async fn synced_db() -> DatabaseConnection {
+    let db = empty_db().await;
+    migration::up(&db, Some(1)).await.unwrap();
+    db.execute_unprepared("DROP TABLE schema_migrations")
+        .await
+        .unwrap();
+    db.execute_unprepared(
+        "INSERT INTO users (username, email, password) VALUES ('ada', 'ada@example.com', 'x')",
+    )
+    .await
+    .unwrap();
+    db
+}
+
+#[actix_web::test]
+async fn synced_schema_is_baselined_and_migrated() {
+    let db = synced_db().await;
+    assert!(migration::up(&db, None).await.is_err());
+
+    let adopted = migration::baseline(&db).await.unwrap();
+    assert_eq!(adopted, vec![migration::migrations()[0].name()]);
+    assert!(migration::baseline(&db).await.unwrap().is_empty());
+    migration::up(&db, None).await.unwrap();
+    assert!(migration::ensure_up_to_date(&db).await.is_ok());
+    assert!(table_exists(&db, "sessions").await);
+    let users = db
+        .query_one_raw(sea_orm::Statement::from_string(
+            db.get_database_backend(),
+            "SELECT COUNT(*) AS n FROM users",
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(users.try_get::<i64>("", "n").unwrap(), 1);
+}
+
+#[actix_web::test]
+async fn startup_adopts_a_synced_schema() {
+    let db = synced_db().await;
+    assert!(check_migrations(&db, false).await.is_err());
+    assert!(check_migrations(&db, true).await.is_ok());
+
+    // New databases need no baseline, and broken ones are refused
+    assert!(
+        migration::baseline(&empty_db().await)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let partial = empty_db().await;
+    partial
+        .execute_unprepared("CREATE TABLE users (id INTEGER PRIMARY KEY)")
+        .await
+        .unwrap();
+    assert!(migration::baseline(&partial).await.is_err());
+}
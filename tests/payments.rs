@@ -0,0 +1,699 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use chrono::{Duration, Utc};
+use here::core::startup::build_app_state;
+use here::entity::prelude::*;
+use here::entity::{LedgerEntryKind, OrderStatus};
+use here::jobs::{Job, perform};
+use here::schemas::ticket::{LedgerEntryResponse, OrderResponse, TicketTierResponse};
+use here::services::payments::{
+    FakePaymentProvider, PaymentProvider, WebhookError, WebhookKind, sign_webhook,
+};
+use here::services::paystack::PaystackProvider;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, Database, EntityTrait, IntoActiveModel};
+use serde_json::json;
+
+use common::{create_event, create_host, create_user, init_app, login, test_config, test_state};
+
+#[actix_web::test]
+async fn unknown_payment_providers_stop_startup() {
+    let mut config = test_config();
+    config.payment_provider = "stripe".to_string();
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+
+    let error = build_app_state(db, config).expect_err("startup refused");
+    assert!(error.contains("Unknown payment provider: stripe"));
+}
+
+#[actix_web::test]
+async fn paystack_webhooks_are_verified_and_read() {
+    let paystack = PaystackProvider::new("http://127.0.0.1:1", "sk_test_secret", "http://app");
+    let charge = json!({
+        "event": "charge.success",
+        "data": {
+            "id": 302961,
+            "reference": "ord_1",
+            "amount": 500000,
+            "currency": "NGN",
+            "metadata": { "order_reference": "ORD-1" }
+        }
+    })
+    .to_string();
+    let signature = sign_webhook("sk_test_secret", charge.as_bytes());
+
+    let event = paystack
+        .parse_webhook(charge.as_bytes(), Some(&signature))
+        .unwrap()
+        .expect("acted on");
+    assert_eq!(event.kind, WebhookKind::PaymentSucceeded);
+    assert_eq!(event.id, "charge.success:302961");
+    assert_eq!(event.payment_reference, "ord_1");
+    assert_eq!(event.order_reference.as_deref(), Some("ORD-1"));
+    assert_eq!(event.amount, 500000);
+
+    // Signed with another key, tampered with or unsigned
+    let forged = sign_webhook("sk_test_other", charge.as_bytes());
+    assert_eq!(
+        paystack.parse_webhook(charge.as_bytes(), Some(&forged)),
+        Err(WebhookError::InvalidSignature)
+    );
+    let tampered = charge.replace("500000", "5");
+    assert_eq!(
+        paystack.parse_webhook(tampered.as_bytes(), Some(&signature)),
+        Err(WebhookError::InvalidSignature)
+    );
+    assert_eq!(
+        paystack.parse_webhook(charge.as_bytes(), None),
+        Err(WebhookError::InvalidSignature)
+    );
+
+    // Refund amounts may come as strings; other events are acknowledged
+    let refund = json!({
+        "event": "refund.processed",
+        "data": { "id": "88", "transaction_reference": "ord_1", "amount": "500000", "currency": "NGN" }
+    })
+    .to_string();
+    let event = paystack
+        .parse_webhook(
+            refund.as_bytes(),
+            Some(&sign_webhook("sk_test_secret", refund.as_bytes())),
+        )
+        .unwrap()
+        .expect("acted on");
+    assert_eq!(event.kind, WebhookKind::Refunded);
+    assert_eq!(event.refund_reference.as_deref(), Some("88"));
+    assert_eq!(event.amount, 500000);
+    let transfer = json!({ "event": "transfer.success", "data": {} }).to_string();
+    assert_eq!(
+        paystack.parse_webhook(
+            transfer.as_bytes(),
+            Some(&sign_webhook("sk_test_secret", transfer.as_bytes())),
+        ),
+        Ok(None)
+    );
+}
+
+#[actix_web::test]
+async fn webhooks_settle_orders_once_and_fill_the_ledger() {
+    let mut state = test_state().await;
+    let payments = Arc::new(FakePaymentProvider::default());
+    state.payments = payments.clone();
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Rust Conf").await;
+    create_user(&state, "ada").await;
+    create_user(&state, "alan").await;
+    let app = init_app(state.clone()).await;
+    let grace = login(&app, "grace").await;
+    let ada = login(&app, "ada").await;
+    let alan = login(&app, "alan").await;
+
+    let tier: TicketTierResponse = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/ticket-tiers", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .set_json(json!({ "name": "General", "price": 5000, "currency": "NGN", "quantity": 5 }))
+            .to_request(),
+    )
+    .await;
+    let checkout = |token: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/checkout", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({ "tier_id": tier.id, "quantity": 2 }))
+            .to_request()
+    };
+    let deliver = |(body, signature): (String, String)| {
+        test::TestRequest::post()
+            .uri("/payments/webhook")
+            .insert_header(("content-type", "application/json"))
+            .insert_header(("x-fake-signature", signature))
+            .set_payload(body)
+            .to_request()
+    };
+    let ledger = || async {
+        let entries: Vec<LedgerEntryResponse> = test::call_and_read_body_json(
+            &app,
+            test::TestRequest::get()
+                .uri(&format!("/events/{}/ledger", event.id))
+                .insert_header(("Authorization", format!("Bearer {}", grace)))
+                .to_request(),
+        )
+        .await;
+        entries
+    };
+    let order = |id: i32| {
+        let db = state.db.clone();
+        async move { TicketOrder::find_by_id(id).one(&db).await.unwrap().unwrap() }
+    };
+
+    let adas: OrderResponse = test::call_and_read_body_json(&app, checkout(&ada)).await;
+    let alans: OrderResponse = test::call_and_read_body_json(&app, checkout(&alan)).await;
+
+    // Forged webhooks are turned away
+    let (body, _) = payments.webhook(WebhookKind::PaymentSucceeded, &adas.reference);
+    let resp = test::call_service(&app, deliver((body, "00ff".to_string()))).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // A payment pays the order once, however often it is reported
+    let paid = payments.webhook(WebhookKind::PaymentSucceeded, &adas.reference);
+    for _ in 0..2 {
+        let resp = test::call_service(&app, deliver(paid.clone())).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+    let adas = order(adas.id).await;
+    assert_eq!(adas.status, OrderStatus::Paid);
+    assert!(adas.attendance_id.is_some());
+    let entries = ledger().await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].kind, LedgerEntryKind::Charge);
+    assert_eq!(entries[0].amount, 10000);
+    assert_eq!(entries[0].provider, "fake");
+
+    // A declined payment gives the tickets back
+    let resp = test::call_service(
+        &app,
+        deliver(payments.webhook(WebhookKind::PaymentFailed, &alans.reference)),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(order(alans.id).await.status, OrderStatus::Failed);
+
+    // A refund from the provider's dashboard drops the registration
+    let resp = test::call_service(
+        &app,
+        deliver(payments.webhook(WebhookKind::Refunded, &adas.reference)),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let refunded = order(adas.id).await;
+    assert_eq!(refunded.status, OrderStatus::Refunded);
+    assert!(refunded.refunded_at.is_some());
+    assert!(refunded.attendance_id.is_none());
+    let entries = ledger().await;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].kind, LedgerEntryKind::Refund);
+    assert_eq!(entries.iter().map(|entry| entry.amount).sum::<i64>(), 0);
+    let tier = TicketTier::find_by_id(tier.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tier.reserved, 0);
+
+    // Money for an order whose hold ran out goes straight back
+    let late: OrderResponse = test::call_and_read_body_json(&app, checkout(&alan)).await;
+    let mut expired = order(late.id).await.into_active_model();
+    expired.expires_at = Set(Utc::now() - Duration::minutes(1));
+    expired.update(&state.db).await.unwrap();
+    perform(&Job::ExpireTicketHold { order_id: late.id }, &state)
+        .await
+        .unwrap();
+    let resp = test::call_service(
+        &app,
+        deliver(payments.webhook(WebhookKind::PaymentSucceeded, &late.reference)),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(order(late.id).await.status, OrderStatus::Expired);
+    let refunds = payments.refunds();
+    assert_eq!(refunds.len(), 2);
+    assert_eq!(refunds[1], (format!("fake_{}", late.reference), 10000));
+    assert_eq!(ledger().await.len(), 4);
+}
+
+#[actix_web::test]
+async fn hosts_refund_orders_and_cancelling_refunds_the_rest() {
+    let mut state = test_state().await;
+    let payments = Arc::new(FakePaymentProvider::default());
+    state.payments = payments.clone();
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Rust Conf").await;
+    create_user(&state, "ada").await;
+    create_user(&state, "alan").await;
+    let app = init_app(state.clone()).await;
+    let grace = login(&app, "grace").await;
+    let ada = login(&app, "ada").await;
+    let alan = login(&app, "alan").await;
+
+    let tier: TicketTierResponse = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/ticket-tiers", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .set_json(json!({ "name": "General", "price": 2500, "currency": "NGN", "quantity": 5 }))
+            .to_request(),
+    )
+    .await;
+    let mut orders = Vec::new();
+    for token in [&ada, &alan] {
+        let order: OrderResponse = test::call_and_read_body_json(
+            &app,
+            test::TestRequest::post()
+                .uri(&format!("/events/{}/checkout", event.id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(json!({ "tier_id": tier.id, "quantity": 1 }))
+                .to_request(),
+        )
+        .await;
+        payments.succeed(&order.reference);
+        let order: OrderResponse = test::call_and_read_body_json(
+            &app,
+            test::TestRequest::post()
+                .uri(&format!("/orders/{}/confirm", order.id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(order.status, OrderStatus::Paid);
+        orders.push(order);
+    }
+
+    let refund = |order_id: i32, token: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/orders/{}/refund", event.id, order_id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    // Only the host gives money back, and only once
+    let resp = test::call_service(&app, refund(orders[0].id, &ada)).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = test::call_service(&app, refund(orders[0].id, &grace)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let refunded: OrderResponse = test::read_body_json(resp).await;
+    assert_eq!(refunded.status, OrderStatus::Refunded);
+    assert!(refunded.attendance_id.is_none());
+    let resp = test::call_service(&app, refund(orders[0].id, &grace)).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    assert_eq!(payments.refunds().len(), 1);
+
+    // A refund reported in another currency is not counted against the order
+    let (body, signature) = payments.refund_webhook_in(&orders[1].reference, 2500, "USD");
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/payments/webhook")
+            .insert_header(("x-fake-signature", signature))
+            .set_payload(body)
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let untouched: TicketOrderModel = TicketOrder::find_by_id(orders[1].id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(untouched.status, OrderStatus::Paid);
+    assert!(untouched.attendance_id.is_some());
+
+    // Part of the money back from the dashboard leaves the order paid
+    let (body, signature) = payments.partial_refund_webhook(&orders[1].reference, 1000);
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/payments/webhook")
+            .insert_header(("x-fake-signature", signature))
+            .set_payload(body)
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let partly: TicketOrderModel = TicketOrder::find_by_id(orders[1].id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(partly.status, OrderStatus::Paid);
+    assert!(partly.attendance_id.is_some());
+
+    // Cancelling refunds everyone else but keeps their registrations, so
+    // they still hear about it
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/cancel", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .set_json(json!({ "reason": "Venue flooded" }))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    for _ in 0..2 {
+        perform(&Job::RefundEventOrders { event_id: event.id }, &state)
+            .await
+            .unwrap();
+    }
+    let listed: Vec<OrderResponse> = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/events/{}/orders", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .to_request(),
+    )
+    .await;
+    assert!(
+        listed
+            .iter()
+            .all(|order| order.status == OrderStatus::Refunded)
+    );
+    assert!(listed[0].attendance_id.is_some());
+    // Only what the partial refund left is given back
+    let refunds = payments.refunds();
+    assert_eq!(refunds.len(), 4);
+    assert_eq!(refunds[3].1, 1500);
+
+    let entries: Vec<LedgerEntryResponse> = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/events/{}/ledger", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(entries.len(), 5);
+    assert_eq!(entries.iter().map(|entry| entry.amount).sum::<i64>(), 0);
+    assert!(entries.iter().all(|entry| !entry.pending));
+}
+
+#[actix_web::test]
+async fn refunds_cut_short_are_settled_by_the_provider_webhook() {
+    let mut state = test_state().await;
+    let payments = Arc::new(FakePaymentProvider::default());
+    state.payments = payments.clone();
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Rust Conf").await;
+    create_user(&state, "ada").await;
+    let app = init_app(state.clone()).await;
+    let grace = login(&app, "grace").await;
+    let ada = login(&app, "ada").await;
+
+    let tier: TicketTierResponse = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/ticket-tiers", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .set_json(json!({ "name": "General", "price": 2500, "currency": "NGN", "quantity": 5 }))
+            .to_request(),
+    )
+    .await;
+    let order: OrderResponse = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/checkout", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", ada)))
+            .set_json(json!({ "tier_id": tier.id, "quantity": 1 }))
+            .to_request(),
+    )
+    .await;
+    payments.succeed(&order.reference);
+    let order: OrderResponse = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/orders/{}/confirm", order.id))
+            .insert_header(("Authorization", format!("Bearer {}", ada)))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(order.status, OrderStatus::Paid);
+
+    // What a host refund leaves behind when the process dies after the
+    // provider has given the money back
+    let model = TicketOrder::find_by_id(order.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut claimed = model.clone().into_active_model();
+    claimed.status = Set(OrderStatus::Refunded);
+    claimed.refunded_at = Set(Some(Utc::now()));
+    claimed.update(&state.db).await.unwrap();
+    LedgerEntryActiveModel {
+        order_id: Set(Some(order.id)),
+        event_id: Set(Some(event.id)),
+        kind: Set(LedgerEntryKind::Refund),
+        amount: Set(-2500),
+        currency: Set("NGN".to_string()),
+        provider: Set("fake".to_string()),
+        provider_reference: Set(format!("pending:{}", order.reference)),
+        description: Set(format!("Refund for order {}", order.reference)),
+        pending: Set(true),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await
+    .unwrap();
+
+    let (body, signature) = payments.webhook(WebhookKind::Refunded, &order.reference);
+    for _ in 0..2 {
+        let resp = test::call_service(
+            &app,
+            test::TestRequest::post()
+                .uri("/payments/webhook")
+                .insert_header(("x-fake-signature", signature.clone()))
+                .set_payload(body.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    let settled = TicketOrder::find_by_id(order.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(settled.status, OrderStatus::Refunded);
+    assert!(settled.attendance_id.is_none());
+    let entries: Vec<LedgerEntryResponse> = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/events/{}/ledger", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(entries.len(), 2);
+    assert!(!entries[1].pending);
+    assert!(entries[1].provider_reference.starts_with("fake_refund_"));
+    assert_eq!(entries.iter().map(|entry| entry.amount).sum::<i64>(), 0);
+    let tier = TicketTier::find_by_id(tier.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tier.reserved, 0);
+}
+
+#[actix_web::test]
+async fn late_payments_are_refunded_once_even_when_cut_short() {
+    let mut state = test_state().await;
+    let payments = Arc::new(FakePaymentProvider::default());
+    state.payments = payments.clone();
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Rust Conf").await;
+    create_user(&state, "ada").await;
+    let app = init_app(state.clone()).await;
+    let grace = login(&app, "grace").await;
+    let ada = login(&app, "ada").await;
+
+    let tier: TicketTierResponse = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/ticket-tiers", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .set_json(json!({ "name": "General", "price": 2500, "currency": "NGN", "quantity": 5 }))
+            .to_request(),
+    )
+    .await;
+    let order: OrderResponse = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/checkout", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", ada)))
+            .set_json(json!({ "tier_id": tier.id, "quantity": 1 }))
+            .to_request(),
+    )
+    .await;
+    let mut expired = TicketOrder::find_by_id(order.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap()
+        .into_active_model();
+    expired.expires_at = Set(Utc::now() - Duration::minutes(1));
+    expired.update(&state.db).await.unwrap();
+    perform(&Job::ExpireTicketHold { order_id: order.id }, &state)
+        .await
+        .unwrap();
+
+    // What refunding a late payment leaves behind when the process dies
+    // after the provider has given the money back
+    let payment_reference = format!("fake_{}", order.reference);
+    for (kind, amount, reference, pending) in [
+        (
+            LedgerEntryKind::Charge,
+            2500,
+            payment_reference.clone(),
+            false,
+        ),
+        (
+            LedgerEntryKind::Refund,
+            -2500,
+            format!("pending-stray:{}", payment_reference),
+            true,
+        ),
+    ] {
+        LedgerEntryActiveModel {
+            order_id: Set(Some(order.id)),
+            event_id: Set(Some(event.id)),
+            kind: Set(kind),
+            amount: Set(amount),
+            currency: Set("NGN".to_string()),
+            provider: Set("fake".to_string()),
+            provider_reference: Set(reference),
+            description: Set(format!("Payment not taken by order {}", order.reference)),
+            pending: Set(pending),
+            ..Default::default()
+        }
+        .insert(&state.db)
+        .await
+        .unwrap();
+    }
+    let deliver = |(body, signature): (String, String)| {
+        test::TestRequest::post()
+            .uri("/payments/webhook")
+            .insert_header(("x-fake-signature", signature))
+            .set_payload(body)
+            .to_request()
+    };
+
+    // The payment's webhook is redelivered: the money is not sent back again
+    let resp = test::call_service(
+        &app,
+        deliver(payments.webhook(WebhookKind::PaymentSucceeded, &order.reference)),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(payments.refunds().is_empty());
+
+    // The provider's refund webhook completes the pending refund
+    let resp = test::call_service(
+        &app,
+        deliver(payments.webhook(WebhookKind::Refunded, &order.reference)),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let entries: Vec<LedgerEntryResponse> = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/events/{}/ledger", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|entry| !entry.pending));
+    assert!(entries[1].provider_reference.starts_with("fake_refund_"));
+    assert_eq!(entries.iter().map(|entry| entry.amount).sum::<i64>(), 0);
+    let order = TicketOrder::find_by_id(order.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Expired);
+}
+
+#[actix_web::test]
+async fn second_payments_for_an_order_are_given_back() {
+    let mut state = test_state().await;
+    let payments = Arc::new(FakePaymentProvider::default());
+    state.payments = payments.clone();
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Rust Conf").await;
+    create_user(&state, "ada").await;
+    let app = init_app(state.clone()).await;
+    let grace = login(&app, "grace").await;
+    let ada = login(&app, "ada").await;
+
+    let tier: TicketTierResponse = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/ticket-tiers", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .set_json(json!({ "name": "General", "price": 2500, "currency": "NGN", "quantity": 5 }))
+            .to_request(),
+    )
+    .await;
+    let order: OrderResponse = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/checkout", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", ada)))
+            .set_json(json!({ "tier_id": tier.id, "quantity": 1 }))
+            .to_request(),
+    )
+    .await;
+    let deliver = |(body, signature): (String, String)| {
+        test::TestRequest::post()
+            .uri("/payments/webhook")
+            .insert_header(("x-fake-signature", signature))
+            .set_payload(body)
+            .to_request()
+    };
+    let resp = test::call_service(
+        &app,
+        deliver(payments.webhook(WebhookKind::PaymentSucceeded, &order.reference)),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // The buyer paid twice; the second payment goes back, however often
+    // it is reported, and the order stays paid
+    let second = payments.second_payment_webhook(&order.reference);
+    for _ in 0..2 {
+        let resp = test::call_service(&app, deliver(second.clone())).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+    assert_eq!(
+        payments.refunds(),
+        vec![(format!("fake_{}_2", order.reference), 2500)]
+    );
+    let paid = TicketOrder::find_by_id(order.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(paid.status, OrderStatus::Paid);
+    assert!(paid.attendance_id.is_some());
+
+    // Refunding the order still gives back all of its own payment
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/orders/{}/refund", event.id, order.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        payments.refunds()[1],
+        (format!("fake_{}", order.reference), 2500)
+    );
+    let entries: Vec<LedgerEntryResponse> = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/events/{}/ledger", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(entries.len(), 4);
+    assert!(entries.iter().all(|entry| !entry.pending));
+    assert_eq!(entries.iter().map(|entry| entry.amount).sum::<i64>(), 0);
+}
@@ -0,0 +1,355 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use here::schemas::event::{EventResponse, EventSeriesResponse};
+use here::utils::recurrence::{Recurrence, RecurrenceRule};
+use serde_json::json;
+
+use common::{create_host, create_user, init_app, login, test_state};
+
+fn local(value: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M").unwrap()
+}
+
+fn utc(value: &str) -> DateTime<Utc> {
+    local(value).and_utc()
+}
+
+fn expand(rule: &str, start: &str, timezone: Tz, exdates: &[&str]) -> Vec<DateTime<Utc>> {
+    Recurrence {
+        rule: rule.parse().unwrap(),
+        start: local(start),
+        timezone,
+        exdates: exdates.iter().map(|exdate| local(exdate)).collect(),
+    }
+    .occurrences()
+    .take(50)
+    .map(|occurrence| occurrence.start)
+    .collect()
+}
+
+#[actix_web::test]
+async fn weekly_rules_keep_local_time_across_dst() {
+    // Clocks go back in London on 25 October 2026
+    let starts = expand(
+        "FREQ=WEEKLY;BYDAY=TU;COUNT=4",
+        "2026-10-20T19:00",
+        chrono_tz::Europe::London,
+        &["2026-11-03T19:00"],
+    );
+    // The excluded occurrence still counts towards COUNT
+    assert_eq!(
+        starts,
+        vec![
+            utc("2026-10-20T18:00"),
+            utc("2026-10-27T19:00"),
+            utc("2026-11-10T19:00"),
+        ]
+    );
+
+    // 02:30 does not exist in New York on 14 March 2027
+    let starts = expand(
+        "FREQ=DAILY;COUNT=3",
+        "2027-03-13T02:30",
+        chrono_tz::America::New_York,
+        &[],
+    );
+    assert_eq!(
+        starts,
+        vec![
+            utc("2027-03-13T07:30"),
+            utc("2027-03-14T07:30"),
+            utc("2027-03-15T06:30"),
+        ]
+    );
+}
+
+#[actix_web::test]
+async fn monthly_and_weekly_rules_pick_the_right_days() {
+    let london = chrono_tz::Europe::London;
+    let last_fridays = expand(
+        "FREQ=MONTHLY;BYDAY=-1FR;UNTIL=20270301",
+        "2026-11-27T18:00",
+        london,
+        &[],
+    );
+    let days: Vec<NaiveDate> = last_fridays
+        .iter()
+        .map(|start| start.date_naive())
+        .collect();
+    assert_eq!(
+        days,
+        ["2026-11-27", "2026-12-25", "2027-01-29", "2027-02-26"]
+            .map(|day| day.parse::<NaiveDate>().unwrap())
+    );
+
+    // Months without a 31st are skipped
+    let month_ends = expand(
+        "FREQ=MONTHLY;BYMONTHDAY=31;COUNT=3",
+        "2027-01-31T12:00",
+        london,
+        &[],
+    );
+    assert_eq!(
+        month_ends,
+        vec![
+            utc("2027-01-31T12:00"),
+            utc("2027-03-31T11:00"),
+            utc("2027-05-31T11:00"),
+        ]
+    );
+
+    let fortnightly = expand(
+        "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;COUNT=4",
+        "2026-11-02T09:00",
+        chrono_tz::UTC,
+        &[],
+    );
+    assert_eq!(
+        fortnightly,
+        vec![
+            utc("2026-11-02T09:00"),
+            utc("2026-11-05T09:00"),
+            utc("2026-11-16T09:00"),
+            utc("2026-11-19T09:00"),
+        ]
+    );
+}
+
+#[actix_web::test]
+async fn rules_round_trip_and_reject_unsupported_parts() {
+    let rule: RecurrenceRule =
+        "RRULE:FREQ=monthly;INTERVAL=2;BYDAY=2TU,-1FR;UNTIL=20271231T235959Z"
+            .parse()
+            .unwrap();
+    assert_eq!(
+        rule.to_string(),
+        "FREQ=MONTHLY;INTERVAL=2;BYDAY=2TU,-1FR;UNTIL=20271231T235959Z"
+    );
+    assert_eq!(rule.to_string().parse::<RecurrenceRule>().unwrap(), rule);
+
+    for invalid in [
+        "BYDAY=MO",
+        "FREQ=YEARLY",
+        "FREQ=WEEKLY;COUNT=3;UNTIL=20270101",
+        "FREQ=WEEKLY;BYDAY=1MO",
+        "FREQ=DAILY;BYHOUR=9",
+        "FREQ=DAILY;INTERVAL=0",
+    ] {
+        assert!(invalid.parse::<RecurrenceRule>().is_err(), "{}", invalid);
+    }
+}
+
+/// Wall-clock 19:00 `days` from now, in the format the API takes.
+fn days_ahead(days: i64) -> NaiveDateTime {
+    (Utc::now() + Duration::days(days))
+        .date_naive()
+        .and_hms_opt(19, 0, 0)
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn hosts_edit_one_occurrence_or_all_following() {
+    let state = test_state().await;
+    create_host(&state, "grace").await;
+    create_user(&state, "ada").await;
+    let app = init_app(state).await;
+    let token = login(&app, "grace").await;
+    let start = days_ahead(7);
+
+    let req = test::TestRequest::post()
+        .uri("/events/series")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({
+            "title": "Rust Night",
+            "description": "Weekly Rust meetup",
+            "location": "Lagos",
+            "event_type": "Physical",
+            "category": "Meetup",
+            "visibility": "Public",
+            "rrule": "FREQ=WEEKLY;COUNT=5",
+            "timezone": "Africa/Lagos",
+            "start_time": start,
+            "duration_minutes": 120,
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let series: EventSeriesResponse = test::read_body_json(resp).await;
+    assert_eq!(series.occurrences.len(), 5);
+    let ids: Vec<i32> = series.occurrences.iter().map(|event| event.id).collect();
+    // Lagos is UTC+1 all year
+    assert_eq!(
+        series.occurrences[0].start_time,
+        (start - Duration::hours(1)).and_utc()
+    );
+
+    // Each occurrence takes its own RSVPs
+    let ada = login(&app, "ada").await;
+    for id in [ids[1], ids[2]] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/events/{}/rsvp", id))
+            .insert_header(("Authorization", format!("Bearer {}", ada)))
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::CREATED
+        );
+    }
+
+    let edit = |id: i32, body: serde_json::Value| {
+        test::TestRequest::patch()
+            .uri(&format!("/events/{}", id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(body)
+            .to_request()
+    };
+
+    let changed: Vec<EventResponse> = test::call_and_read_body_json(
+        &app,
+        edit(ids[1], json!({ "title": "Rust Night: Async Special" })),
+    )
+    .await;
+    assert_eq!(changed.len(), 1);
+    assert!(changed[0].is_exception);
+
+    // Moving later occurrences to another day is refused
+    let next_day = series.occurrences[2].start_time + Duration::days(1);
+    let resp = test::call_service(
+        &app,
+        edit(
+            ids[2],
+            json!({ "start_time": next_day, "scope": "following" }),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let later = series.occurrences[2].start_time + Duration::hours(1);
+    let changed: Vec<EventResponse> = test::call_and_read_body_json(
+        &app,
+        edit(
+            ids[2],
+            json!({ "start_time": later, "location": "Abuja", "scope": "following" }),
+        ),
+    )
+    .await;
+    assert_eq!(
+        changed.iter().map(|event| event.id).collect::<Vec<_>>(),
+        ids[2..].to_vec()
+    );
+    let new_series_id = changed[0].series_id.unwrap();
+    assert_ne!(new_series_id, series.id);
+    for (event, original) in changed.iter().zip(&series.occurrences[2..]) {
+        assert_eq!(event.location, "Abuja");
+        assert_eq!(event.start_time, original.start_time + Duration::hours(1));
+        assert_eq!(event.end_time, original.end_time + Duration::hours(1));
+        assert!(!event.is_exception);
+    }
+
+    // The original series now ends before the split; the exception kept its title
+    let req = test::TestRequest::get()
+        .uri(&format!("/events/series/{}", series.id))
+        .to_request();
+    let original: EventSeriesResponse = test::call_and_read_body_json(&app, req).await;
+    assert!(original.rrule.contains("UNTIL="));
+    assert_eq!(original.occurrences.len(), 2);
+    assert_eq!(original.occurrences[1].title, "Rust Night: Async Special");
+    assert_eq!(original.occurrences[0].location, "Lagos");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/events/series/{}", new_series_id))
+        .to_request();
+    let split: EventSeriesResponse = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(split.rrule, "FREQ=WEEKLY;COUNT=3");
+    assert_eq!(
+        split.start_time,
+        start + Duration::days(14) + Duration::hours(1)
+    );
+    assert_eq!(split.occurrences.len(), 3);
+
+    // Ada's RSVP moved with the occurrence
+    let req = test::TestRequest::post()
+        .uri(&format!("/events/{}/rsvp", ids[2]))
+        .insert_header(("Authorization", format!("Bearer {}", ada)))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn only_hosts_create_series_with_valid_rules() {
+    let state = test_state().await;
+    create_host(&state, "grace").await;
+    create_user(&state, "ada").await;
+    let app = init_app(state).await;
+
+    let body = |rrule: &str, timezone: &str| {
+        json!({
+            "title": "Rust Night",
+            "description": "Weekly Rust meetup",
+            "location": "Lagos",
+            "event_type": "Physical",
+            "category": "Meetup",
+            "visibility": "Private",
+            "rrule": rrule,
+            "timezone": timezone,
+            "start_time": days_ahead(3),
+            "duration_minutes": 90,
+            "exdates": [days_ahead(10)],
+        })
+    };
+    let create = |token: &str, body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri("/events/series")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(body)
+            .to_request()
+    };
+
+    let ada = login(&app, "ada").await;
+    assert_eq!(
+        test::call_service(&app, create(&ada, body("FREQ=WEEKLY", "UTC")))
+            .await
+            .status(),
+        StatusCode::FORBIDDEN
+    );
+
+    let grace = login(&app, "grace").await;
+    for (rrule, timezone) in [
+        ("FREQ=YEARLY", "UTC"),
+        ("FREQ=WEEKLY", "Mars/Olympus_Mons"),
+        ("FREQ=DAILY;UNTIL=20200101", "UTC"),
+    ] {
+        assert_eq!(
+            test::call_service(&app, create(&grace, body(rrule, timezone)))
+                .await
+                .status(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "{}",
+            rrule
+        );
+    }
+
+    // Unbounded rules are created up to the horizon, without the exdate
+    let resp = test::call_service(&app, create(&grace, body("FREQ=WEEKLY", "UTC"))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let series: EventSeriesResponse = test::read_body_json(resp).await;
+    assert!((25..=27).contains(&series.occurrences.len()));
+    assert_eq!(series.occurrences[1].start_time, days_ahead(17).and_utc());
+
+    // Private series are hidden from everyone but the host
+    let uri = format!("/events/series/{}", series.id);
+    let req = test::TestRequest::get().uri(&uri).to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", grace)))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+}
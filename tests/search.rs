@@ -0,0 +1,153 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use here::entity::{EventStatus, EventVisibility};
+use here::schemas::search::SearchResponse;
+use here::services::search::{search_terms, word_similarity};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, IntoActiveModel};
+use serde_json::json;
+
+use common::{create_event, create_host, create_user, init_app, login, test_state};
+
+#[actix_web::test]
+async fn queries_are_split_into_words() {
+    assert_eq!(
+        search_terms("Rust-Meetup!! rust \"OR\"* café"),
+        vec!["rust", "meetup", "or", "café"]
+    );
+    assert!(search_terms(" *:& ").is_empty());
+    assert_eq!(search_terms("a b c d e f g h i j").len(), 8);
+
+    // Typos keep most of a word's trigrams; other words share almost none
+    assert!(word_similarity("meetp", "Rust Meetup") >= 0.6);
+    assert!(word_similarity("lagso meetup", "Rust Meetup in Lagos") >= 0.6);
+    assert!(word_similarity("python", "Rust Meetup") < 0.6);
+    assert_eq!(word_similarity("rust", "Rust"), 1.0);
+}
+
+#[actix_web::test]
+async fn search_ranks_events_hosts_and_users() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let meetup = create_event(&state, &host, "Rust Meetup").await;
+    let mut workshop = create_event(&state, &host, "Python Workshop")
+        .await
+        .into_active_model();
+    workshop.description = Set("Bring your Rust questions".to_string());
+    let workshop = workshop.update(&state.db).await.unwrap();
+    let mut secret = create_event(&state, &host, "Rust Board Meeting")
+        .await
+        .into_active_model();
+    secret.visibility = Set(EventVisibility::Private);
+    secret.update(&state.db).await.unwrap();
+    let mut picnic = create_event(&state, &host, "Rust Picnic")
+        .await
+        .into_active_model();
+    picnic.status = Set(EventStatus::Cancelled);
+    picnic.update(&state.db).await.unwrap();
+    create_user(&state, "ada").await;
+    let mut adam = create_user(&state, "adam").await.into_active_model();
+    adam.is_active = Set(false);
+    adam.update(&state.db).await.unwrap();
+    let app = init_app(state).await;
+    let grace = login(&app, "grace").await;
+
+    let search = |query: &str, token: Option<&str>| {
+        let req = test::TestRequest::get().uri(&format!("/search?q={}", query));
+        match token {
+            Some(token) => req
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request(),
+            None => req.to_request(),
+        }
+    };
+    let event_ids = |results: &SearchResponse| -> Vec<i32> {
+        results.events.iter().map(|event| event.id).collect()
+    };
+
+    // Title matches rank above description matches; private and cancelled
+    // events never show up
+    let results: SearchResponse = test::call_and_read_body_json(&app, search("rust", None)).await;
+    assert_eq!(event_ids(&results), vec![meetup.id, workshop.id]);
+    let results: SearchResponse = test::call_and_read_body_json(&app, search("lagos", None)).await;
+    assert_eq!(results.events.len(), 2);
+    // Words match by prefix, all of them must match, and typos are forgiven
+    for (query, expected) in [
+        ("meet", vec![meetup.id]),
+        ("rust%20work", vec![workshop.id]),
+        ("meetp", vec![meetup.id]),
+        ("pythn", vec![workshop.id]),
+        ("board", vec![]),
+    ] {
+        let results: SearchResponse =
+            test::call_and_read_body_json(&app, search(query, None)).await;
+        assert_eq!(event_ids(&results), expected, "{}", query);
+    }
+
+    // Hosts by organization name
+    let results: SearchResponse = test::call_and_read_body_json(&app, search("events", None)).await;
+    assert_eq!(results.hosts.len(), 1);
+    assert_eq!(results.hosts[0].username, "grace");
+    assert_eq!(
+        results.hosts[0].organization_name.as_deref(),
+        Some("grace Events")
+    );
+
+    // People only for signed-in callers, and only active accounts
+    let results: SearchResponse = test::call_and_read_body_json(&app, search("ada", None)).await;
+    assert!(results.users.is_empty());
+    let results: SearchResponse =
+        test::call_and_read_body_json(&app, search("ada", Some(&grace))).await;
+    let usernames: Vec<&str> = results
+        .users
+        .iter()
+        .map(|user| user.username.as_str())
+        .collect();
+    assert_eq!(usernames, vec!["ada"]);
+
+    // Edits are picked up straight away
+    let req = test::TestRequest::patch()
+        .uri(&format!("/events/{}", workshop.id))
+        .insert_header(("Authorization", format!("Bearer {}", grace)))
+        .set_json(json!({ "title": "Go Workshop" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    let results: SearchResponse = test::call_and_read_body_json(&app, search("go", None)).await;
+    assert_eq!(event_ids(&results), vec![workshop.id]);
+    let results: SearchResponse = test::call_and_read_body_json(&app, search("python", None)).await;
+    assert!(results.events.is_empty());
+
+    for query in ["", "%21%21%21"] {
+        assert_eq!(
+            test::call_service(&app, search(query, None)).await.status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+}
+
+#[actix_web::test]
+async fn hidden_matches_do_not_crowd_out_visible_ones() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    // More private title matches than are ever ranked, all scoring above
+    // the one public description match
+    for i in 0..210 {
+        let mut private = create_event(&state, &host, &format!("Rust Rust {}", i))
+            .await
+            .into_active_model();
+        private.visibility = Set(EventVisibility::Private);
+        private.update(&state.db).await.unwrap();
+    }
+    let mut public = create_event(&state, &host, "Weekly Meetup")
+        .await
+        .into_active_model();
+    public.description = Set("Rust and coffee".to_string());
+    let public = public.update(&state.db).await.unwrap();
+    let app = init_app(state).await;
+
+    let req = test::TestRequest::get().uri("/search?q=rust").to_request();
+    let results: SearchResponse = test::call_and_read_body_json(&app, req).await;
+    let ids: Vec<i32> = results.events.iter().map(|event| event.id).collect();
+    assert_eq!(ids, vec![public.id]);
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use here::schemas::error::ErrorResponse;
+use serde_json::json;
+
+use common::{init_app, test_config, test_state};
+
+#[actix_web::test]
+async fn security_headers_are_set() {
+    let app = init_app(test_state().await).await;
+
+    let req = test::TestRequest::get().uri("/health/live").to_request();
+    let resp = test::call_service(&app, req).await;
+    let headers = resp.headers();
+    assert_eq!(headers.get("x-content-type-options").unwrap(), "nosniff");
+    assert_eq!(headers.get("x-frame-options").unwrap(), "DENY");
+    assert_eq!(headers.get("referrer-policy").unwrap(), "no-referrer");
+    assert_eq!(
+        headers.get("strict-transport-security").unwrap(),
+        "max-age=31536000; includeSubDomains"
+    );
+    assert!(
+        headers
+            .get("content-security-policy")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .starts_with("default-src 'none'")
+    );
+
+    let req = test::TestRequest::get().uri("/docs/").to_request();
+    let resp = test::call_service(&app, req).await;
+    let csp = resp.headers().get("content-security-policy").unwrap();
+    assert!(
+        csp.to_str()
+            .unwrap()
+            .contains("style-src 'self' 'unsafe-inline'")
+    );
+}
+
+#[actix_web::test]
+async fn cors_allows_only_configured_origins() {
+    let mut state = test_state().await;
+    let mut config = test_config();
+    config.cors_allowed_origins = Some("https://app.example.com".to_string());
+    config.cors_allow_credentials = true;
+    state.config = config;
+    let app = init_app(state).await;
+
+    let req = test::TestRequest::default()
+        .method(actix_web::http::Method::OPTIONS)
+        .uri("/auth/login")
+        .insert_header(("Origin", "https://app.example.com"))
+        .insert_header(("Access-Control-Request-Method", "POST"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let headers = resp.headers();
+    assert_eq!(
+        headers.get("access-control-allow-origin").unwrap(),
+        "https://app.example.com"
+    );
+    assert_eq!(
+        headers.get("access-control-allow-credentials").unwrap(),
+        "true"
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/health/live")
+        .insert_header(("Origin", "https://evil.example.com"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.headers().get("access-control-allow-origin").is_none());
+}
+
+#[actix_web::test]
+async fn malformed_and_oversized_json_get_structured_errors() {
+    let mut state = test_state().await;
+    state.config.max_body_bytes = 64;
+    let app = init_app(state).await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .insert_header(("Content-Type", "application/json"))
+        .set_payload(r#"{"username": "ada", "password": "#)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: ErrorResponse = test::read_body_json(resp).await;
+    assert_eq!(body.error, "invalid_json");
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .insert_header(("Content-Type", "application/json"))
+        .set_payload(r#"{"username": 1, "password": "hunter2-secret"}"#)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: ErrorResponse = test::read_body_json(resp).await;
+    assert!(!body.message.contains("hunter2"));
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"username": "ada", "password": "x".repeat(100)}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let body: ErrorResponse = test::read_body_json(resp).await;
+    assert_eq!(body.error, "payload_too_large");
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .insert_header(("Content-Type", "text/plain"))
+        .set_payload("hello")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use here::entity::{EventCategory, EventVisibility};
+use here::schemas::event::EventResponse;
+use here::schemas::tag::TagResponse;
+use here::utils::tags::{normalize_tag, normalize_tags};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, IntoActiveModel};
+use serde_json::json;
+
+use common::{create_event, create_host, init_app, login, test_state};
+
+#[actix_web::test]
+async fn tags_are_normalized() {
+    assert_eq!(normalize_tag("#Rust").unwrap(), "rust");
+    assert_eq!(normalize_tag("  Open   Source ").unwrap(), "open-source");
+    assert_eq!(normalize_tag("Lagos_Tech").unwrap(), "lagos_tech");
+    assert_eq!(normalize_tag("Café").unwrap(), "café");
+    assert!(normalize_tag("c++").is_err());
+    assert!(normalize_tag("#").is_err());
+    assert!(normalize_tag(&"a".repeat(51)).is_err());
+    assert_eq!(
+        normalize_tags(&["Rust", "#rust", "web"]).unwrap(),
+        vec!["rust", "web"]
+    );
+}
+
+#[actix_web::test]
+async fn events_filter_by_categories_and_tags() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let rust = create_event(&state, &host, "Rust Meetup").await;
+    let web = create_event(&state, &host, "Web Workshop").await;
+    let plain = create_event(&state, &host, "Coffee Morning").await;
+    let mut secret = create_event(&state, &host, "Board Meeting")
+        .await
+        .into_active_model();
+    secret.visibility = Set(EventVisibility::Private);
+    let secret = secret.update(&state.db).await.unwrap();
+    let app = init_app(state).await;
+    let grace = login(&app, "grace").await;
+
+    let edit = |id: i32, body: serde_json::Value| {
+        test::TestRequest::patch()
+            .uri(&format!("/events/{}", id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .set_json(body)
+            .to_request()
+    };
+    let edited: Vec<EventResponse> = test::call_and_read_body_json(
+        &app,
+        edit(
+            rust.id,
+            json!({ "categories": ["Meetup", "Workshop"], "tags": ["#Rust", "Open Source"] }),
+        ),
+    )
+    .await;
+    assert_eq!(
+        edited[0].categories,
+        vec![EventCategory::Meetup, EventCategory::Workshop]
+    );
+    assert_eq!(edited[0].tags, vec!["open-source", "rust"]);
+    // The first category becomes the primary one
+    let edited: Vec<EventResponse> = test::call_and_read_body_json(
+        &app,
+        edit(
+            web.id,
+            json!({ "categories": ["Workshop"], "tags": ["rust", "web"] }),
+        ),
+    )
+    .await;
+    assert_eq!(edited[0].category, EventCategory::Workshop);
+    for body in [json!({ "tags": ["c++"] }), json!({ "categories": [] })] {
+        assert_eq!(
+            test::call_service(&app, edit(plain.id, body))
+                .await
+                .status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+    let req = test::TestRequest::patch()
+        .uri(&format!("/events/{}", secret.id))
+        .insert_header(("Authorization", format!("Bearer {}", grace)))
+        .set_json(json!({ "tags": ["rumours"] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let list = |query: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/events?{}", query))
+            .to_request()
+    };
+    let ids = |events: Vec<EventResponse>| {
+        let mut ids: Vec<i32> = events.into_iter().map(|event| event.id).collect();
+        ids.sort();
+        ids
+    };
+    for (query, expected) in [
+        ("tags=rust", vec![rust.id, web.id]),
+        ("tags=%23Rust,web", vec![rust.id, web.id]),
+        ("tags=rust,open-source&match=all", vec![rust.id]),
+        ("categories=Workshop", vec![rust.id, web.id]),
+        ("categories=Meetup", vec![rust.id, plain.id]),
+        ("categories=Workshop&tags=web&match=all", vec![web.id]),
+        (
+            "categories=Meetup&tags=web",
+            vec![rust.id, web.id, plain.id],
+        ),
+        ("categories=Conference", vec![]),
+        ("tags=rumours", vec![]),
+    ] {
+        let events: Vec<EventResponse> = test::call_and_read_body_json(&app, list(query)).await;
+        assert_eq!(ids(events), expected, "{}", query);
+    }
+    assert_eq!(
+        test::call_service(&app, list("categories=Picnic"))
+            .await
+            .status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+
+    // Details carry the labels too
+    let req = test::TestRequest::get()
+        .uri(&format!("/events/{}", rust.id))
+        .to_request();
+    let event: EventResponse = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(event.tags, vec!["open-source", "rust"]);
+
+    // Autocomplete ranks by use and never reveals private events' tags
+    let suggest = |prefix: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/tags?prefix={}", prefix))
+            .to_request()
+    };
+    let tags: Vec<TagResponse> = test::call_and_read_body_json(&app, suggest("r")).await;
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].name, "rust");
+    assert_eq!(tags[0].event_count, 2);
+    let tags: Vec<TagResponse> = test::call_and_read_body_json(&app, suggest("%23Open")).await;
+    assert_eq!(tags[0].name, "open-source");
+    let tags: Vec<TagResponse> = test::call_and_read_body_json(&app, suggest("o_")).await;
+    assert!(tags.is_empty());
+    assert_eq!(
+        test::call_service(&app, suggest("")).await.status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+}
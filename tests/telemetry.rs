@@ -0,0 +1,104 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use here::schemas::user::SignUp;
+use here::utils::redact::{redact_path_and_query, redact_url, validation_summary};
+use serde_json::json;
+use validator::Validate;
+
+use common::{init_app, test_state};
+
+#[actix_web::test]
+async fn request_id_is_generated_and_echoed() {
+    let app = init_app(test_state().await).await;
+
+    let req = test::TestRequest::get().uri("/health/live").to_request();
+    let resp = test::call_service(&app, req).await;
+    let generated = resp.headers().get("x-request-id").unwrap();
+    assert_eq!(generated.len(), 36);
+
+    let req = test::TestRequest::get()
+        .uri("/health/live")
+        .insert_header(("X-Request-Id", "client-supplied-123"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers().get("x-request-id").unwrap(),
+        "client-supplied-123"
+    );
+}
+
+#[actix_web::test]
+async fn error_responses_carry_the_request_id_and_no_passwords() {
+    let app = init_app(test_state().await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/users/signup")
+        .insert_header(("X-Request-Id", "signup-1"))
+        .set_json(json!({
+            "username": "ada",
+            "email": "not-an-email",
+            "password": "short"
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "signup-1");
+
+    let body = test::read_body(resp).await;
+    let body = String::from_utf8_lossy(&body);
+    assert!(body.contains("password: length"));
+    assert!(!body.contains("short"));
+}
+
+#[actix_web::test]
+async fn invalid_request_ids_are_replaced() {
+    let app = init_app(test_state().await).await;
+
+    let req = test::TestRequest::get()
+        .uri("/health/live")
+        .insert_header(("X-Request-Id", "bad id with spaces"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_ne!(
+        resp.headers().get("x-request-id").unwrap(),
+        "bad id with spaces"
+    );
+}
+
+#[actix_web::test]
+async fn secrets_are_redacted() {
+    assert_eq!(
+        redact_path_and_query("/reset?token=abc&lang=en"),
+        "/reset?token=[REDACTED]&lang=en"
+    );
+    // Calendar feed URLs carry their token in the path
+    assert_eq!(
+        redact_path_and_query("/calendar/s3cr3t.ics"),
+        "/calendar/[REDACTED].ics"
+    );
+    assert_eq!(
+        redact_path_and_query("/calendar/s3cr3t.ics?token=abc"),
+        "/calendar/[REDACTED].ics?token=[REDACTED]"
+    );
+    assert_eq!(redact_path_and_query("/events/7.ics"), "/events/7.ics");
+    assert_eq!(
+        redact_url("postgres://app:hunter2@db:5432/here"),
+        "postgres://[REDACTED]@db:5432/here"
+    );
+
+    let signup = SignUp {
+        username: "ada".to_string(),
+        first_name: None,
+        last_name: None,
+        email: "ada@example.com".to_string(),
+        avatar_url: None,
+        password: "hunter2".to_string(),
+    };
+    assert!(!format!("{:?}", signup).contains("hunter2"));
+    assert_eq!(
+        validation_summary(&signup.validate().unwrap_err()),
+        "password: length"
+    );
+}
@@ -0,0 +1,254 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use chrono::{Duration, Utc};
+use here::entity::prelude::*;
+use here::entity::{AttendanceStatus, DiscountType, OrderStatus};
+use here::jobs::{Job, perform};
+use here::schemas::ticket::{OrderResponse, TicketTierResponse};
+use here::services::payments::FakePaymentProvider;
+use here::services::tickets::price_order;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityTrait, IntoActiveModel};
+use serde_json::json;
+
+use common::{create_event, create_host, create_user, init_app, login, test_state};
+
+#[actix_web::test]
+async fn orders_are_priced_with_their_discount() {
+    let promo = |discount_type, amount| PromoCodeModel {
+        id: 1,
+        event_id: 1,
+        code: "SAVE".to_string(),
+        discount_type,
+        amount,
+        max_uses: None,
+        uses: 0,
+        expires_at: None,
+        created_at: Utc::now(),
+    };
+
+    assert_eq!(price_order(5000, 2, None), (10000, 0, 10000));
+    assert_eq!(
+        price_order(5000, 2, Some(&promo(DiscountType::Percentage, 25))),
+        (10000, 2500, 7500)
+    );
+    assert_eq!(
+        price_order(5000, 2, Some(&promo(DiscountType::Fixed, 3000))),
+        (10000, 3000, 7000)
+    );
+    // Discounts never make an order pay out
+    assert_eq!(
+        price_order(5000, 1, Some(&promo(DiscountType::Fixed, 8000))),
+        (5000, 5000, 0)
+    );
+}
+
+#[actix_web::test]
+async fn tickets_are_held_paid_for_and_released() {
+    let mut state = test_state().await;
+    let payments = Arc::new(FakePaymentProvider::default());
+    state.payments = payments.clone();
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Rust Conf").await;
+    create_user(&state, "ada").await;
+    create_user(&state, "alan").await;
+    let app = init_app(state.clone()).await;
+    let grace = login(&app, "grace").await;
+    let ada = login(&app, "ada").await;
+    let alan = login(&app, "alan").await;
+
+    let post = |uri: String, token: &str, body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(body)
+            .to_request()
+    };
+
+    // Only managers sell tickets
+    let tier = json!({ "name": "General", "price": 5000, "currency": "ngn", "quantity": 3 });
+    let resp = test::call_service(
+        &app,
+        post(
+            format!("/events/{}/ticket-tiers", event.id),
+            &ada,
+            tier.clone(),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = test::call_service(
+        &app,
+        post(format!("/events/{}/ticket-tiers", event.id), &grace, tier),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let general: TicketTierResponse = test::read_body_json(resp).await;
+    assert_eq!(general.currency, "NGN");
+    assert_eq!(general.available, 3);
+    assert!(general.on_sale);
+    let resp = test::call_service(
+        &app,
+        post(
+            format!("/events/{}/promo-codes", event.id),
+            &grace,
+            json!({ "code": "early", "discount_type": "Percentage", "amount": 20, "max_uses": 1 }),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    // Ticketed events take no RSVPs
+    let resp = test::call_service(
+        &app,
+        post(format!("/events/{}/rsvp", event.id), &ada, json!({})),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    let checkout = |token: &str, quantity: i32, promo: Option<&str>| {
+        post(
+            format!("/events/{}/checkout", event.id),
+            token,
+            json!({ "tier_id": general.id, "quantity": quantity, "promo_code": promo }),
+        )
+    };
+    let resp = test::call_service(&app, checkout(&ada, 2, Some("EARLY"))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let order: OrderResponse = test::read_body_json(resp).await;
+    assert_eq!(order.status, OrderStatus::Pending);
+    assert_eq!(
+        (order.subtotal, order.discount, order.total),
+        (10000, 2000, 8000)
+    );
+    assert!(order.checkout_url.is_some());
+    let requested = payments.requests();
+    assert_eq!(requested.len(), 1);
+    assert_eq!(requested[0].amount, 8000);
+    assert_eq!(requested[0].email, "ada@example.com");
+
+    // The code is used up and only one ticket is left while Ada pays
+    let resp = test::call_service(&app, checkout(&alan, 1, Some("early"))).await;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let resp = test::call_service(&app, checkout(&alan, 2, None)).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    let resp = test::call_service(&app, checkout(&alan, 1, None)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let alans: OrderResponse = test::read_body_json(resp).await;
+
+    // Unpaid orders stay pending until the payment goes through
+    let confirm = |id: i32, token: &str| post(format!("/orders/{}/confirm", id), token, json!({}));
+    let pending: OrderResponse = test::call_and_read_body_json(&app, confirm(order.id, &ada)).await;
+    assert_eq!(pending.status, OrderStatus::Pending);
+    let resp = test::call_service(&app, confirm(order.id, &alan)).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    payments.succeed(&order.reference);
+    let paid: OrderResponse = test::call_and_read_body_json(&app, confirm(order.id, &ada)).await;
+    assert_eq!(paid.status, OrderStatus::Paid);
+    let attendance = Attendance::find_by_id(paid.attendance_id.expect("registration"))
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(attendance.event_id, event.id);
+    assert_eq!(attendance.status, AttendanceStatus::Registered);
+
+    // Cancelling gives the ticket back, and only pending orders cancel
+    let cancel = |id: i32, token: &str| post(format!("/orders/{}/cancel", id), token, json!({}));
+    let cancelled: OrderResponse =
+        test::call_and_read_body_json(&app, cancel(alans.id, &alan)).await;
+    assert_eq!(cancelled.status, OrderStatus::Cancelled);
+    let resp = test::call_service(&app, cancel(order.id, &ada)).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    let tiers: Vec<TicketTierResponse> = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/events/{}/ticket-tiers", event.id))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(tiers[0].available, 1);
+
+    // Holds that run out expire and free their tickets
+    let resp = test::call_service(&app, checkout(&alan, 1, None)).await;
+    let held: OrderResponse = test::read_body_json(resp).await;
+    let mut expired = TicketOrder::find_by_id(held.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap()
+        .into_active_model();
+    expired.expires_at = Set(Utc::now() - Duration::minutes(1));
+    expired.update(&state.db).await.unwrap();
+    perform(&Job::ExpireTicketHold { order_id: held.id }, &state)
+        .await
+        .unwrap();
+    let orders: Vec<OrderResponse> = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::get()
+            .uri("/orders")
+            .insert_header(("Authorization", format!("Bearer {}", alan)))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(orders.len(), 2);
+    assert_eq!(orders[0].status, OrderStatus::Expired);
+    let tier = TicketTier::find_by_id(general.id)
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tier.reserved, 2);
+
+    // A tier with orders cannot be deleted
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::delete()
+            .uri(&format!("/events/{}/ticket-tiers/{}", event.id, general.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+}
+
+#[actix_web::test]
+async fn free_tickets_are_paid_straight_away() {
+    let mut state = test_state().await;
+    let payments = Arc::new(FakePaymentProvider::default());
+    state.payments = payments.clone();
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Open Day").await;
+    create_user(&state, "ada").await;
+    let app = init_app(state).await;
+    let grace = login(&app, "grace").await;
+    let ada = login(&app, "ada").await;
+
+    let tier: TicketTierResponse = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/ticket-tiers", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", grace)))
+            .set_json(json!({ "name": "Free", "price": 0, "currency": "NGN", "quantity": 10 }))
+            .to_request(),
+    )
+    .await;
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/events/{}/checkout", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", ada)))
+            .set_json(json!({ "tier_id": tier.id, "quantity": 1 }))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let order: OrderResponse = test::read_body_json(resp).await;
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert!(order.attendance_id.is_some());
+    assert!(order.checkout_url.is_none());
+    assert!(payments.requests().is_empty());
+}
@@ -0,0 +1,186 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use chrono::{DateTime, Days, Duration, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+use here::schemas::auth::UserMeResponse;
+use here::schemas::event::{EventResponse, EventWindow};
+use here::utils::recurrence::resolve_local;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, IntoActiveModel};
+use serde_json::json;
+
+use common::{create_event, create_host, create_user, init_app, login, test_state};
+
+#[actix_web::test]
+async fn windows_cover_the_right_days() {
+    let day = |value: &str| value.parse::<NaiveDate>().unwrap();
+    let wednesday = day("2026-10-21");
+
+    assert_eq!(EventWindow::Today.dates(wednesday), (wednesday, wednesday));
+    assert_eq!(
+        EventWindow::Tomorrow.dates(wednesday),
+        (day("2026-10-22"), day("2026-10-22"))
+    );
+    assert_eq!(
+        EventWindow::ThisWeek.dates(wednesday),
+        (wednesday, day("2026-10-25"))
+    );
+    assert_eq!(
+        EventWindow::ThisWeekend.dates(wednesday),
+        (day("2026-10-24"), day("2026-10-25"))
+    );
+    // Once the weekend has started only the rest of it is left
+    assert_eq!(
+        EventWindow::ThisWeekend.dates(day("2026-10-24")),
+        (day("2026-10-24"), day("2026-10-25"))
+    );
+    assert_eq!(
+        EventWindow::ThisWeekend.dates(day("2026-10-25")),
+        (day("2026-10-25"), day("2026-10-25"))
+    );
+}
+
+/// Noon, `days` after today, in `tz`.
+fn local_noon(tz: Tz, days: u64) -> DateTime<Utc> {
+    let today = Utc::now().with_timezone(&tz).date_naive();
+    resolve_local(
+        tz,
+        (today + Days::new(days)).and_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap()),
+    )
+}
+
+#[actix_web::test]
+async fn events_list_by_local_day_and_show_local_times() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    create_user(&state, "ada").await;
+    let kiritimati: Tz = chrono_tz::Pacific::Kiritimati;
+    let lagos: Tz = chrono_tz::Africa::Lagos;
+
+    // Already under way, so it is "today" wherever the clock is
+    let now = Utc::now();
+    let mut under_way = create_event(&state, &host, "Sunrise Run")
+        .await
+        .into_active_model();
+    under_way.timezone = Set(kiritimati.name().to_string());
+    under_way.start_time = Set(now - Duration::minutes(1));
+    under_way.end_time = Set(now + Duration::hours(3));
+    let under_way = under_way.update(&state.db).await.unwrap();
+
+    let mut tomorrow = create_event(&state, &host, "Rust Meetup")
+        .await
+        .into_active_model();
+    tomorrow.timezone = Set(lagos.name().to_string());
+    tomorrow.start_time = Set(local_noon(lagos, 1));
+    tomorrow.end_time = Set(local_noon(lagos, 1) + Duration::hours(2));
+    let tomorrow = tomorrow.update(&state.db).await.unwrap();
+
+    // A week out in UTC, so in neither window
+    create_event(&state, &host, "Go Meetup").await;
+    let app = init_app(state).await;
+
+    let list = |when: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/events?when={}", when))
+            .to_request()
+    };
+    let today: Vec<EventResponse> = test::call_and_read_body_json(&app, list("today")).await;
+    assert_eq!(
+        today.iter().map(|event| event.id).collect::<Vec<_>>(),
+        vec![under_way.id]
+    );
+    let next: Vec<EventResponse> = test::call_and_read_body_json(&app, list("tomorrow")).await;
+    assert_eq!(
+        next.iter().map(|event| event.id).collect::<Vec<_>>(),
+        vec![tomorrow.id]
+    );
+    let req = test::TestRequest::get()
+        .uri("/events?when=next_year")
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+
+    let meetup = &next[0];
+    assert_eq!(meetup.timezone, "Africa/Lagos");
+    assert_eq!(
+        meetup.local_start_time.time(),
+        NaiveTime::from_hms_opt(12, 0, 0).unwrap()
+    );
+    assert_eq!(
+        meetup.local_end_time.time(),
+        NaiveTime::from_hms_opt(14, 0, 0).unwrap()
+    );
+    assert!(meetup.viewer_timezone.is_none());
+
+    // With a preferred zone, times are also given in it
+    let ada = login(&app, "ada").await;
+    let set_timezone = |timezone: serde_json::Value| {
+        test::TestRequest::put()
+            .uri("/users/me/timezone")
+            .insert_header(("Authorization", format!("Bearer {}", ada)))
+            .set_json(json!({ "timezone": timezone }))
+            .to_request()
+    };
+    assert_eq!(
+        test::call_service(&app, set_timezone(json!("Mars/Olympus_Mons")))
+            .await
+            .status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    let me: UserMeResponse =
+        test::call_and_read_body_json(&app, set_timezone(json!("Asia/Tokyo"))).await;
+    assert_eq!(me.timezone.as_deref(), Some("Asia/Tokyo"));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/events/{}", tomorrow.id))
+        .insert_header(("Authorization", format!("Bearer {}", ada)))
+        .to_request();
+    let event: EventResponse = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(event.viewer_timezone.as_deref(), Some("Asia/Tokyo"));
+    // Tokyo is eight hours ahead of Lagos all year
+    assert_eq!(
+        event.viewer_start_time,
+        Some(event.local_start_time + Duration::hours(8))
+    );
+
+    let me: UserMeResponse = test::call_and_read_body_json(&app, set_timezone(json!(null))).await;
+    assert!(me.timezone.is_none());
+}
+
+#[actix_web::test]
+async fn changing_an_events_zone_keeps_its_wall_clock_time() {
+    let state = test_state().await;
+    let (_, host) = create_host(&state, "grace").await;
+    let event = create_event(&state, &host, "Rust Meetup").await;
+    let app = init_app(state).await;
+    let token = login(&app, "grace").await;
+
+    let edit = |body: serde_json::Value| {
+        test::TestRequest::patch()
+            .uri(&format!("/events/{}", event.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(body)
+            .to_request()
+    };
+    assert_eq!(
+        test::call_service(&app, edit(json!({ "timezone": "Africa/Atlantis" })))
+            .await
+            .status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+
+    let changed: Vec<EventResponse> =
+        test::call_and_read_body_json(&app, edit(json!({ "timezone": "Africa/Lagos" }))).await;
+    let changed = &changed[0];
+    assert_eq!(changed.timezone, "Africa/Lagos");
+    // Same local time as before in UTC, so an hour earlier in absolute terms
+    assert_eq!(changed.local_start_time, event.start_time.naive_utc());
+    assert_eq!(changed.start_time, event.start_time - Duration::hours(1));
+    assert_eq!(
+        changed.end_time - changed.start_time,
+        event.end_time - event.start_time
+    );
+}
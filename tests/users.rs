@@ -0,0 +1,117 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use here::entity::prelude::*;
+use sea_orm::ModelTrait;
+use serde_json::{Value, json};
+
+use common::{create_event, create_host, create_user, init_app, login, test_state};
+
+#[actix_web::test]
+async fn signup_creates_user() {
+    let app = init_app(test_state().await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/users/signup")
+        .set_json(json!({
+            "username": "grace",
+            "email": "grace@example.com",
+            "password": "password123",
+            "first_name": "Grace",
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["username"], "grace");
+    assert_eq!(body["email"], "grace@example.com");
+    assert_eq!(body["first_name"], "Grace");
+    assert!(body.get("password").is_none());
+
+    // The new account can log in straight away
+    let token = login(&app, "grace").await;
+    assert!(!token.is_empty());
+}
+
+#[actix_web::test]
+async fn signup_rejects_invalid_payload() {
+    let app = init_app(test_state().await).await;
+
+    let req = test::TestRequest::post()
+        .uri("/users/signup")
+        .set_json(json!({
+            "username": "grace",
+            "email": "not-an-email",
+            "password": "short",
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[actix_web::test]
+async fn signup_with_taken_username_fails() {
+    let state = test_state().await;
+    create_user(&state, "grace").await;
+    let app = init_app(state).await;
+
+    let req = test::TestRequest::post()
+        .uri("/users/signup")
+        .set_json(json!({
+            "username": "grace",
+            "email": "other@example.com",
+            "password": "password123",
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(!resp.status().is_success());
+}
+
+#[actix_web::test]
+async fn me_returns_current_user() {
+    let state = test_state().await;
+    let user = create_user(&state, "grace").await;
+    let app = init_app(state).await;
+    let token = login(&app, "grace").await;
+
+    let req = test::TestRequest::get()
+        .uri("/users/me")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["id"], user.id);
+    assert_eq!(body["username"], "grace");
+    assert_eq!(body["email"], user.email);
+}
+
+#[actix_web::test]
+async fn me_requires_authentication() {
+    let app = init_app(test_state().await).await;
+
+    let req = test::TestRequest::get().uri("/users/me").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::get()
+        .uri("/users/me")
+        .insert_header(("Authorization", "Bearer not-a-jwt"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn host_and_event_factories_are_linked() {
+    let state = test_state().await;
+    let (user, host) = create_host(&state, "organiser").await;
+    let event = create_event(&state, &host, "Rust Meetup").await;
+
+    assert_eq!(event.host_id, user.id);
+    let events = host.find_related(Event).all(&state.db).await.unwrap();
+    assert_eq!(events, vec![event]);
+}